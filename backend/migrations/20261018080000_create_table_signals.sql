@@ -0,0 +1,48 @@
+-- Add migration script here
+CREATE TABLE signals (
+    ticker TEXT NOT NULL,
+    period TEXT NOT NULL,
+    bar_date INTEGER NOT NULL,
+    kdj_k REAL NOT NULL,
+    kdj_d REAL NOT NULL,
+    boll_dist REAL NOT NULL,
+    PRIMARY KEY (ticker, period, bar_date)
+);
+
+CREATE INDEX idx_signals_period_date ON signals (period, bar_date);
+
+-- Carry over the last snapshots, dated on the last stored kline of the ticker.
+-- Snapshots of tickers without klines cannot be dated and are dropped.
+CREATE TEMP VIEW last_kline_dates AS
+SELECT k_ticker AS ticker, MAX(k_date) AS bar_date FROM (
+    SELECT k_ticker, k_date FROM klines
+    UNION ALL
+    SELECT k_ticker, k_date FROM klines_us
+)
+GROUP BY k_ticker;
+
+INSERT OR REPLACE INTO signals (ticker, period, bar_date, kdj_k, kdj_d, boll_dist)
+SELECT
+    s.ticker,
+    'day',
+    d.bar_date,
+    s.kdj_k,
+    s.kdj_d,
+    s.boll_dist
+FROM signals_d s
+JOIN last_kline_dates d ON d.ticker = s.ticker;
+
+INSERT OR REPLACE INTO signals (ticker, period, bar_date, kdj_k, kdj_d, boll_dist)
+SELECT
+    s.ticker,
+    'week',
+    d.bar_date,
+    s.kdj_k,
+    s.kdj_d,
+    s.boll_dist
+FROM signals_w s
+JOIN last_kline_dates d ON d.ticker = s.ticker;
+
+DROP VIEW last_kline_dates;
+DROP TABLE signals_d;
+DROP TABLE signals_w;
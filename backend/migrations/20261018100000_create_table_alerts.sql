@@ -0,0 +1,21 @@
+-- Add migration script here
+CREATE TABLE alert_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    condition TEXT NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    triggered BOOLEAN NOT NULL DEFAULT FALSE,
+    last_fired_at TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE alert_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL,
+    rule_name TEXT NOT NULL,
+    ticker TEXT NOT NULL,
+    message TEXT NOT NULL,
+    fired_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_alert_events_fired_at ON alert_events (fired_at);
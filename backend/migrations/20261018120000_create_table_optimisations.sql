@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE optimisations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ticker TEXT NOT NULL,
+    mode TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    space TEXT NOT NULL,
+    method TEXT NOT NULL,
+    objective TEXT NOT NULL,
+    report TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE backtest_runs ADD COLUMN optimisation_id INTEGER;
+
+CREATE INDEX idx_backtest_runs_optimisation ON backtest_runs (optimisation_id);
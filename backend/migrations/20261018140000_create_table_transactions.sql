@@ -0,0 +1,29 @@
+-- Add migration script here
+CREATE TABLE transactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account TEXT NOT NULL,
+    trade_date INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    ticker TEXT,
+    quantity REAL NOT NULL DEFAULT 0,
+    price REAL NOT NULL DEFAULT 0,
+    amount REAL NOT NULL DEFAULT 0,
+    fee REAL NOT NULL DEFAULT 0,
+    currency TEXT NOT NULL,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_transactions_account_date ON transactions (account, trade_date);
+
+CREATE TABLE fx_rates (
+    currency TEXT NOT NULL PRIMARY KEY,
+    cny_rate REAL NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Rough defaults, kept current through the API.
+INSERT INTO fx_rates (currency, cny_rate) VALUES
+    ('CNY', 1.0),
+    ('HKD', 0.91),
+    ('USD', 7.12);
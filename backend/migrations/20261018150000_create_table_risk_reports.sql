@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE risk_reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account TEXT NOT NULL,
+    base_currency TEXT NOT NULL,
+    report TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_risk_reports_account ON risk_reports (account, id);
@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE sector_members (
+    sector TEXT NOT NULL,
+    ticker TEXT NOT NULL,
+    realname TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (sector, ticker)
+);
+
+CREATE INDEX idx_sector_members_ticker ON sector_members (ticker);
@@ -0,0 +1,64 @@
+-- Add migration script here
+CREATE TABLE tracked_sectors (
+    ticker TEXT PRIMARY KEY,
+    realname TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO tracked_sectors (ticker, realname, position) VALUES
+    ('90.BK0475', '银行', 0),
+    ('90.BK1036', '半导体', 1),
+    ('90.BK0464', '石油行业', 2),
+    ('90.BK0473', '证券', 3),
+    ('90.BK1037', '消费电子', 4),
+    ('90.BK0428', '电力行业', 5),
+    ('90.BK0736', '通信服务', 6),
+    ('90.BK0474', '保险', 7),
+    ('90.BK0459', '电子元件', 8),
+    ('90.BK1033', '电池', 9),
+    ('90.BK0448', '通信设备', 10),
+    ('90.BK0737', '软件开发', 11),
+    ('90.BK0481', '汽车零部件', 12),
+    ('90.BK0478', '有色金属', 13),
+    ('90.BK1029', '汽车整车', 14),
+    ('90.BK0465', '化学制药', 15),
+    ('90.BK0910', '专用设备', 16),
+    ('90.BK0437', '煤炭行业', 17),
+    ('90.BK0447', '互联网服务', 18),
+    ('90.BK1031', '光伏设备', 19),
+    ('90.BK0438', '食品饮料', 20),
+    ('90.BK1044', '生物制品', 21),
+    ('90.BK0545', '通用设备', 22),
+    ('90.BK0538', '化学制品', 23),
+    ('90.BK0457', '电网设备', 24),
+    ('90.BK0425', '工程建设', 25),
+    ('90.BK1041', '医疗器械', 26),
+    ('90.BK0480', '航天航空', 27),
+    ('90.BK1027', '小金属', 28),
+    ('90.BK1038', '光学光电子', 29),
+    ('90.BK0735', '计算机设备', 30),
+    ('90.BK0486', '文化传媒', 31),
+    ('90.BK0433', '农牧饲渔', 32),
+    ('90.BK0450', '航运港口', 33),
+    ('90.BK0479', '钢铁行业', 34),
+    ('90.BK1019', '化学原料', 35),
+    ('90.BK0422', '物流行业', 36),
+    ('90.BK0727', '医疗服务', 37),
+    ('90.BK0739', '工程机械', 38),
+    ('90.BK0429', '交运设备', 39),
+    ('90.BK1034', '电源设备', 40),
+    ('90.BK0454', '塑料制品', 41),
+    ('90.BK1046', '游戏', 42),
+    ('90.BK1015', '能源金属', 43),
+    ('90.BK0471', '化纤行业', 44),
+    ('90.BK0732', '贵金属', 45),
+    ('90.BK0731', '化肥行业', 46),
+    ('90.BK1020', '非金属材料', 47),
+    ('90.BK0729', '船舶制造', 48),
+    ('90.BK0546', '玻璃玻纤', 49),
+    ('90.BK0458', '仪器仪表', 50),
+    ('90.BK0424', '水泥建材', 51),
+    ('90.BK1039', '电子化学品', 52),
+    ('90.BK1017', '采掘行业', 53),
+    ('90.BK1030', '电机', 54);
@@ -0,0 +1,33 @@
+-- Add migration script here
+-- Retention moves to the application, rows are keyed by the Shanghai trading date.
+DROP TRIGGER IF EXISTS maintain_30_records_per_ticker;
+
+CREATE TABLE moneyflow_sector_new (
+    trade_date TEXT NOT NULL,
+    ticker TEXT NOT NULL,
+    realname TEXT NOT NULL,
+    lead_value REAL NOT NULL,
+    lead_share REAL NOT NULL,
+    super_value REAL NOT NULL,
+    super_share REAL NOT NULL,
+    large_value REAL NOT NULL,
+    large_share REAL NOT NULL,
+    mid_value REAL NOT NULL,
+    mid_share REAL NOT NULL,
+    small_value REAL NOT NULL,
+    small_share REAL NOT NULL,
+    PRIMARY KEY (trade_date, ticker)
+);
+
+-- The latest row of a day wins.
+INSERT OR REPLACE INTO moneyflow_sector_new
+SELECT substr(date_time, 1, 10), ticker, realname, lead_value, lead_share, super_value,
+    super_share, large_value, large_share, mid_value, mid_share, small_value, small_share
+FROM moneyflow_sector
+ORDER BY rowid;
+
+DROP TABLE moneyflow_sector;
+
+ALTER TABLE moneyflow_sector_new RENAME TO moneyflow_sector;
+
+CREATE INDEX idx_moneyflow_sector_ticker_date ON moneyflow_sector (ticker, trade_date);
@@ -0,0 +1,20 @@
+-- Add migration script here
+CREATE TABLE moneyflow_stock (
+    trade_date TEXT NOT NULL,
+    ticker TEXT NOT NULL,
+    lead_value REAL NOT NULL,
+    lead_share REAL NOT NULL,
+    super_value REAL NOT NULL,
+    super_share REAL NOT NULL,
+    large_value REAL NOT NULL,
+    large_share REAL NOT NULL,
+    mid_value REAL NOT NULL,
+    mid_share REAL NOT NULL,
+    small_value REAL NOT NULL,
+    small_share REAL NOT NULL,
+    close REAL NOT NULL,
+    change_pct REAL NOT NULL,
+    PRIMARY KEY (trade_date, ticker)
+);
+
+CREATE INDEX idx_moneyflow_stock_ticker_date ON moneyflow_stock (ticker, trade_date);
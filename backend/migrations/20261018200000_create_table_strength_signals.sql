@@ -0,0 +1,24 @@
+-- Add migration script here
+CREATE TABLE strength_signals (
+    ticker TEXT NOT NULL,
+    bar_date INTEGER NOT NULL,
+    benchmark TEXT NOT NULL,
+    sector TEXT,
+    ret_1w REAL,
+    ret_1m REAL,
+    ret_3m REAL,
+    ret_6m REAL,
+    rs_1w REAL,
+    rs_1m REAL,
+    rs_3m REAL,
+    rs_6m REAL,
+    sector_rs_1w REAL,
+    sector_rs_1m REAL,
+    sector_rs_3m REAL,
+    sector_rs_6m REAL,
+    score REAL,
+    rating INTEGER,
+    PRIMARY KEY (ticker, bar_date)
+);
+
+CREATE INDEX idx_strength_signals_date ON strength_signals (bar_date);
@@ -0,0 +1,27 @@
+-- Add migration script here
+CREATE TABLE fundamentals_snapshots (
+    ticker TEXT NOT NULL,
+    snapshot_date TEXT NOT NULL,
+    total_cap REAL,
+    pe REAL,
+    pb REAL,
+    revenue REAL,
+    net REAL,
+    margin REAL,
+    debt REAL,
+    PRIMARY KEY (ticker, snapshot_date)
+);
+
+-- Carry over the current figures, dated on the day of migration.
+INSERT INTO fundamentals_snapshots (ticker, snapshot_date, total_cap, pe, pb, revenue, net, margin, debt)
+SELECT
+    ticker,
+    DATE('now'),
+    total_cap,
+    pe,
+    pb,
+    revenue,
+    net,
+    margin,
+    debt
+FROM stocks;
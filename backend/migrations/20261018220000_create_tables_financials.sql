@@ -0,0 +1,53 @@
+-- Add migration script here
+CREATE TABLE income_statements (
+    ticker TEXT NOT NULL,
+    period TEXT NOT NULL,
+    report_type TEXT NOT NULL,
+    revenue REAL,
+    operating_cost REAL,
+    operating_profit REAL,
+    total_profit REAL,
+    net_profit REAL,
+    parent_net_profit REAL,
+    eps REAL,
+    PRIMARY KEY (ticker, period, report_type)
+);
+
+CREATE TABLE balance_sheets (
+    ticker TEXT NOT NULL,
+    period TEXT NOT NULL,
+    report_type TEXT NOT NULL,
+    total_assets REAL,
+    total_liabilities REAL,
+    total_equity REAL,
+    parent_equity REAL,
+    cash REAL,
+    current_assets REAL,
+    current_liabilities REAL,
+    PRIMARY KEY (ticker, period, report_type)
+);
+
+CREATE TABLE cashflow_statements (
+    ticker TEXT NOT NULL,
+    period TEXT NOT NULL,
+    report_type TEXT NOT NULL,
+    operating_cf REAL,
+    investing_cf REAL,
+    financing_cf REAL,
+    capex REAL,
+    PRIMARY KEY (ticker, period, report_type)
+);
+
+CREATE TABLE financial_ratios (
+    ticker TEXT NOT NULL,
+    period TEXT NOT NULL,
+    report_type TEXT NOT NULL,
+    roe REAL,
+    roa REAL,
+    gross_margin REAL,
+    fcf REAL,
+    debt_to_equity REAL,
+    revenue_yoy REAL,
+    net_profit_yoy REAL,
+    PRIMARY KEY (ticker, period, report_type)
+);
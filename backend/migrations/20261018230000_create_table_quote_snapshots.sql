@@ -0,0 +1,12 @@
+-- Add migration script here
+CREATE TABLE quote_snapshots (
+    ticker TEXT NOT NULL,
+    quoted_at INTEGER NOT NULL,
+    realname TEXT NOT NULL,
+    last REAL,
+    bid REAL,
+    ask REAL,
+    volume REAL,
+    change_pct REAL,
+    PRIMARY KEY (ticker, quoted_at)
+);
@@ -0,0 +1,7 @@
+-- ISINs of stored stocks, an ISIN resolves to the stock it is linked to.
+CREATE TABLE stock_isins (
+    ticker TEXT NOT NULL PRIMARY KEY,
+    isin TEXT NOT NULL
+);
+
+CREATE INDEX idx_stock_isins_isin ON stock_isins (isin);
@@ -0,0 +1,273 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    model::{AlertCondition, AlertEvent, AlertRule, SignalPeriod},
+    repository::{DomainRepository, FinancialRepository},
+    service_alert::{AlertInputs, Evaluation, evaluate_condition, next_state},
+    service_market::Ticker,
+};
+
+/// Port for delivering fired alerts, e.g. webhook, email or a local file.
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    fn name(&self) -> &str;
+    async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error>;
+}
+
+// ---------------------------------------------------------------
+// Alert Service
+// - Evaluate active rules after signals or moneyflow are refreshed
+// - Fire on false -> true transitions only, persisting rule state
+// - Store the event and fan out to all sinks off the caller's path
+// ---------------------------------------------------------------
+pub struct AlertService {
+    pub repo: Arc<dyn DomainRepository>,
+    pub repo_financial: Arc<dyn FinancialRepository>,
+    pub sinks: Vec<Arc<dyn NotificationSink>>,
+}
+
+impl AlertService {
+    /// Evaluates signal rules of a ticker and period, returns the number of alerts fired.
+    pub async fn on_signal(
+        &self,
+        ticker: &Ticker,
+        period: SignalPeriod,
+    ) -> Result<usize, anyhow::Error> {
+        let rules: Vec<AlertRule> = self
+            .repo
+            .get_alert_rules()
+            .await?
+            .into_iter()
+            .filter(|r| {
+                r.active && r.condition.ticker() == ticker && r.condition.period() == Some(period)
+            })
+            .collect();
+        if rules.is_empty() {
+            return Ok(0);
+        }
+
+        // Crosses compare the last two bars, the other conditions only the last.
+        let signals = self.repo.get_signals_tail(ticker, period, 2).await?;
+        let stock = self.repo.get_stock(ticker).await.ok();
+
+        // Pattern rules see the same fields as the screener.
+        let (mut sectors, mut strength, mut ratios) = (vec![], None, None);
+        if rules
+            .iter()
+            .any(|r| matches!(r.condition, AlertCondition::Pattern { .. }))
+        {
+            sectors = self
+                .repo
+                .get_stock_sectors(ticker)
+                .await?
+                .into_iter()
+                .map(|m| m.sector)
+                .collect();
+            strength = self
+                .repo
+                .get_strength_latest()
+                .await?
+                .into_iter()
+                .find(|s| s.ticker == ticker.as_str());
+            ratios = self
+                .repo_financial
+                .get_financial_ratios(ticker)
+                .await?
+                .pop();
+        }
+
+        let inputs = AlertInputs {
+            signals: &signals,
+            stock: stock.as_ref(),
+            sectors: &sectors,
+            strength: strength.as_ref(),
+            ratios: ratios.as_ref(),
+            flows: &[],
+        };
+
+        let mut fired = 0;
+        for rule in rules {
+            let evaluation = evaluate_condition(&rule.condition, &inputs);
+            if self.apply(&rule, evaluation).await? {
+                fired += 1;
+            }
+        }
+
+        Ok(fired)
+    }
+
+    /// Evaluates moneyflow rules, returns the number of alerts fired.
+    pub async fn on_moneyflow(&self) -> Result<usize, anyhow::Error> {
+        let rules: Vec<AlertRule> = self
+            .repo
+            .get_alert_rules()
+            .await?
+            .into_iter()
+            .filter(|r| r.active && r.condition.period().is_none())
+            .collect();
+        if rules.is_empty() {
+            return Ok(0);
+        }
+
+        let mut flows = self.repo.get_mf_sector().await?;
+        flows.sort_by(|a, b| a.trade_date.cmp(&b.trade_date));
+
+        let mut fired = 0;
+        for rule in rules {
+            let ticker_flows: Vec<_> = flows
+                .iter()
+                .filter(|f| f.ticker == rule.condition.ticker().as_str())
+                .cloned()
+                .collect();
+            let inputs = AlertInputs {
+                flows: &ticker_flows,
+                ..Default::default()
+            };
+            let evaluation = evaluate_condition(&rule.condition, &inputs);
+            if self.apply(&rule, evaluation).await? {
+                fired += 1;
+            }
+        }
+
+        Ok(fired)
+    }
+
+    async fn apply(&self, rule: &AlertRule, evaluation: Evaluation) -> Result<bool, anyhow::Error> {
+        let (triggered, fire) = next_state(rule.triggered, &evaluation);
+        let Evaluation::Hit(message) = evaluation else {
+            if triggered != rule.triggered {
+                self.repo
+                    .update_alert_state(rule.id, triggered, None)
+                    .await?;
+            }
+            return Ok(false);
+        };
+        if !fire {
+            return Ok(false);
+        }
+
+        let event = AlertEvent {
+            id: 0,
+            rule_id: rule.id,
+            rule_name: rule.name.clone(),
+            ticker: rule.condition.ticker().to_string(),
+            message,
+            fired_at: chrono::Utc::now().to_rfc3339(),
+        };
+        self.repo
+            .update_alert_state(rule.id, true, Some(&event.fired_at))
+            .await?;
+        self.repo.create_alert_event(&event).await?;
+
+        // NOTE: Sinks deliver in the background so a slow one stalls neither the others nor
+        // the job, the event is stored regardless.
+        for sink in self.sinks.iter() {
+            let sink = sink.clone();
+            let event = event.clone();
+            tokio::spawn(async move {
+                if let Err(e) = sink.send(&event).await {
+                    tracing::error!("alert sink {} failed: {}", sink.name(), e);
+                }
+            });
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use sqlx::SqlitePool;
+
+    use super::*;
+    use crate::{
+        domain::model::{AlertCondition, AlertDirection, AlertField, Signal},
+        infra::storage::{
+            repo_domain_sqlite::SqliteDomainRepository,
+            repo_financial_sqlite::SqliteFinancialRepository,
+        },
+    };
+
+    #[derive(Default)]
+    struct MemorySink(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl NotificationSink for MemorySink {
+        fn name(&self) -> &str {
+            "memory"
+        }
+
+        async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+            self.0.lock().unwrap().push(event.message.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_on_signal_fires_once() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let repo = Arc::new(SqliteDomainRepository::new(pool.clone()));
+        let sink = Arc::new(MemorySink::default());
+        let service = AlertService {
+            repo: repo.clone(),
+            repo_financial: Arc::new(SqliteFinancialRepository::new(pool)),
+            sinks: vec![sink.clone()],
+        };
+
+        repo.create_alert_rule(
+            "oversold",
+            &AlertCondition::Threshold {
+                ticker: "1.600635".parse().unwrap(),
+                period: SignalPeriod::Day,
+                field: AlertField::KdjK,
+                direction: AlertDirection::Below,
+                value: 20.0,
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut fired = vec![];
+        for (date, k) in [
+            (20251103, 15.0),
+            (20251104, 10.0),
+            (20251105, 30.0),
+            (20251106, 5.0),
+        ] {
+            repo.create_signal(Signal {
+                ticker: "1.600635".to_string(),
+                period: SignalPeriod::Day,
+                bar_date: date,
+                kdj_k: k,
+                kdj_d: 50.0,
+                boll_dist: 1.0,
+            })
+            .await
+            .unwrap();
+            fired.push(
+                service
+                    .on_signal(&"1.600635".parse().unwrap(), SignalPeriod::Day)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        assert_eq!(fired, vec![1, 0, 0, 1]);
+        // Deliveries run on spawned tasks.
+        tokio::task::yield_now().await;
+        assert_eq!(sink.0.lock().unwrap().len(), 2);
+        assert_eq!(repo.get_alert_events(10).await.unwrap().len(), 2);
+        assert_eq!(
+            service
+                .on_signal(&"1.600635".parse().unwrap(), SignalPeriod::Week)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+use crate::{
+    application::{
+        alerts::NotificationSink,
+        model::{Job, JobStatus, JobType},
+    },
+    domain::{
+        model::{AlertEvent, Quote},
+        service_market::Ticker,
+    },
+};
+
+/// Events buffered per subscriber, a slower one skips the oldest.
+const CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Topic {
+    Job,
+    Quote,
+    Alert,
+}
+
+impl Topic {
+    /// Also the SSE event name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Topic::Job => "job",
+            Topic::Quote => "quote",
+            Topic::Alert => "alert",
+        }
+    }
+}
+
+/// Job state transition as seen by the runner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct JobEvent {
+    pub job_id: i64,
+    pub job_type: JobType,
+    pub job_status: JobStatus,
+    /// Ticker of the payload, if any.
+    pub ticker: Option<String>,
+    pub error_message: Option<String>,
+}
+
+impl JobEvent {
+    pub fn new(job: &Job, job_status: JobStatus, error_message: Option<String>) -> Self {
+        Self {
+            job_id: job.id,
+            job_type: job.job_type.clone(),
+            job_status,
+            ticker: job
+                .payload
+                .get("ticker")
+                .and_then(|t| t.as_str())
+                .map(String::from),
+            error_message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "topic", rename_all = "lowercase")]
+pub enum Event {
+    Job(JobEvent),
+    Quote(Quote),
+    Alert(AlertEvent),
+}
+
+impl Event {
+    pub fn topic(&self) -> Topic {
+        match self {
+            Event::Job(_) => Topic::Job,
+            Event::Quote(_) => Topic::Quote,
+            Event::Alert(_) => Topic::Alert,
+        }
+    }
+
+    pub fn ticker(&self) -> Option<&str> {
+        match self {
+            Event::Job(e) => e.ticker.as_deref(),
+            Event::Quote(q) => Some(&q.ticker),
+            Event::Alert(a) => Some(&a.ticker),
+        }
+    }
+}
+
+/// Subscription filter, an empty list lets everything through.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventFilter {
+    pub topics: Vec<Topic>,
+    /// Events without a ticker are kept.
+    pub tickers: Vec<Ticker>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &Event) -> bool {
+        let topic = self.topics.is_empty() || self.topics.contains(&event.topic());
+        let ticker = match event.ticker() {
+            Some(ticker) => {
+                self.tickers.is_empty() || self.tickers.iter().any(|t| t.as_str() == ticker)
+            }
+            None => true,
+        };
+        topic && ticker
+    }
+}
+
+// ---------------------------------------------------------------
+// Event Bus
+// - In-process broadcast of job transitions, quotes and fired alerts
+// - Publishing never blocks nor fails, events without subscribers are dropped
+// ---------------------------------------------------------------
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: Event) {
+        // NOTE: errs only when nobody is subscribed.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus::new()
+    }
+}
+
+/// Forwards fired alerts onto the bus.
+pub struct EventSink {
+    pub events: EventBus,
+}
+
+#[async_trait]
+impl NotificationSink for EventSink {
+    fn name(&self) -> &str {
+        "events"
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+        self.events.publish(Event::Alert(event.clone()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn quote(ticker: &str) -> Event {
+        Event::Quote(Quote {
+            ticker: ticker.to_string(),
+            realname: ticker.to_string(),
+            last: Some(1.0),
+            bid: None,
+            ask: None,
+            volume: None,
+            change_pct: None,
+            quoted_at: 0,
+        })
+    }
+
+    fn job(payload: serde_json::Value) -> Event {
+        let job = Job::new(JobType::ComputeRisk, payload);
+        Event::Job(JobEvent::new(&job, JobStatus::Done, None))
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let all = EventFilter::default();
+        assert!(all.matches(&quote("1.600635")));
+
+        let filter = EventFilter {
+            topics: vec![Topic::Quote, Topic::Job],
+            tickers: vec!["1.600635".parse().unwrap()],
+        };
+        assert!(filter.matches(&quote("1.600635")));
+        assert!(!filter.matches(&quote("105.TSLA")));
+        assert!(filter.matches(&job(json!({ "ticker": "1.600635" }))));
+        assert!(!filter.matches(&job(json!({ "ticker": "105.TSLA" }))));
+        // Jobs of no ticker are kept.
+        assert!(filter.matches(&job(json!({ "account": "main" }))));
+
+        let alerts = EventFilter {
+            topics: vec![Topic::Alert],
+            tickers: vec![],
+        };
+        assert!(!alerts.matches(&quote("1.600635")));
+    }
+
+    #[tokio::test]
+    async fn test_event_bus() {
+        let bus = EventBus::new();
+        // Nobody listens yet.
+        bus.publish(quote("1.600635"));
+
+        let mut rx = bus.subscribe();
+        let sink = EventSink {
+            events: bus.clone(),
+        };
+        sink.send(&AlertEvent {
+            id: 1,
+            rule_id: 1,
+            rule_name: "oversold".to_string(),
+            ticker: "1.600635".to_string(),
+            message: "rsi below 30".to_string(),
+            fired_at: "2025-10-15 10:00:00".to_string(),
+        })
+        .await
+        .unwrap();
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.topic(), Topic::Alert);
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["topic"], "alert");
+        assert_eq!(value["rule_name"], "oversold");
+    }
+}
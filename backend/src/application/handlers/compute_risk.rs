@@ -0,0 +1,165 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        handlers::JobHandler,
+        model::{Job, JobError, JobResult, JobType},
+    },
+    domain::{
+        model::{Currency, Transaction},
+        repository::DomainRepository,
+        service_market::Ticker,
+        service_portfolio::{FxRates, Ledger},
+        service_risk::{RiskParams, RiskPosition, compute_risk, sectors_of},
+    },
+};
+
+/// CSI 300.
+pub const DEFAULT_BENCHMARK: &str = "1.000300";
+
+// ---------------------------------------------------------------
+// Compute Risk
+// - Replay the ledger of an account, value open positions at the latest closes
+// - Risk from stored daily klines of the positions and the benchmark
+// - Store the report
+// NOTE: returns are in each ticker's own currency, FX moves are not part of the risk.
+// ---------------------------------------------------------------
+#[derive(Clone)]
+pub struct ComputeRiskHandler {
+    pub repo: Arc<dyn DomainRepository>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ComputeRiskPayload {
+    pub account: String,
+    /// Defaults to CSI 300, beta is left out when it has no stored klines.
+    pub benchmark: Option<Ticker>,
+    /// Defaults to CNY.
+    pub base: Option<Currency>,
+    #[serde(default)]
+    pub params: RiskParams,
+}
+
+/// Transactions of an account, daily closes of its tickers and FX rates.
+pub async fn load_portfolio(
+    repo: &dyn DomainRepository,
+    account: &str,
+) -> Result<(Vec<Transaction>, HashMap<String, Vec<(i64, f64)>>, FxRates), anyhow::Error> {
+    let (txs, rates) = tokio::try_join!(repo.get_transactions(account), repo.get_fx_rates())?;
+
+    // NOTE: tickers that no longer parse keep no closes, as if nothing was stored.
+    let mut tickers: Vec<Ticker> = txs
+        .iter()
+        .filter_map(|t| t.ticker.as_deref()?.parse().ok())
+        .collect();
+    tickers.sort_unstable();
+    tickers.dedup();
+
+    let mut closes = HashMap::new();
+    for ticker in tickers {
+        let klines = repo.get_klines(&ticker).await?;
+        closes.insert(
+            ticker.to_string(),
+            klines.iter().map(|k| (k.k_date, k.k_close)).collect(),
+        );
+    }
+
+    Ok((txs, closes, FxRates::new(&rates)))
+}
+
+#[async_trait]
+impl JobHandler for ComputeRiskHandler {
+    fn job_type(&self) -> JobType {
+        JobType::ComputeRisk
+    }
+
+    async fn handle(&self, job: &Job) -> Result<JobResult, JobError> {
+        let payload: ComputeRiskPayload =
+            serde_json::from_value(job.payload.clone()).map_err(JobError::Serialization)?;
+        if let Err(e) = payload.params.validate() {
+            return Ok(JobResult {
+                success: false,
+                output: None,
+                error: Some(e),
+            });
+        }
+
+        let (txs, mut closes, fx) = load_portfolio(self.repo.as_ref(), &payload.account).await?;
+        let ledger = match Ledger::replay(&txs) {
+            Ok(ledger) => ledger,
+            Err(e) => {
+                return Ok(JobResult {
+                    success: false,
+                    output: None,
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+
+        let base = payload.base.unwrap_or(Currency::Cny);
+        let latest: HashMap<String, (i64, f64)> = closes
+            .iter()
+            .filter_map(|(ticker, series)| series.last().map(|last| (ticker.clone(), *last)))
+            .collect();
+        let date = latest
+            .values()
+            .map(|(date, _)| *date)
+            .max()
+            .unwrap_or_default();
+        let snapshot = ledger.snapshot(&payload.account, date, &latest, &fx, base);
+
+        let members = self.repo.get_sector_membership().await?;
+        let positions: Vec<RiskPosition> = snapshot
+            .positions
+            .iter()
+            .filter(|p| p.quantity > 0.0)
+            .map(|p| RiskPosition {
+                ticker: p.ticker.clone(),
+                market_value: fx.convert(p.market_value, p.currency, base),
+                closes: closes.remove(&p.ticker).unwrap_or_default(),
+                sectors: sectors_of(&p.ticker, &members),
+            })
+            .collect();
+
+        let benchmark = payload
+            .benchmark
+            .unwrap_or_else(|| DEFAULT_BENCHMARK.parse().expect("valid benchmark"));
+        let bench: Vec<(i64, f64)> = self
+            .repo
+            .get_klines(&benchmark)
+            .await?
+            .iter()
+            .map(|k| (k.k_date, k.k_close))
+            .collect();
+
+        let report = compute_risk(
+            &positions,
+            (!bench.is_empty()).then_some((benchmark.as_str(), bench.as_slice())),
+            &payload.params,
+        );
+        if !report.is_finite() {
+            return Ok(JobResult {
+                success: false,
+                output: None,
+                error: Some("risk report has non-finite values, not stored".to_string()),
+            });
+        }
+        let id = self
+            .repo
+            .create_risk_report(&payload.account, base, &report)
+            .await?;
+
+        Ok(JobResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "risk report": id,
+                "account": payload.account,
+                "positions": report.positions.len(),
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        handlers::JobHandler,
+        model::{Job, JobError, JobResult, JobType},
+    },
+    domain::{
+        model::Currency,
+        repository::DomainRepository,
+        service_market::Ticker,
+        service_strength::{
+            StrengthSignal, compute_strength, market_benchmark, pick_sector, rate_strength,
+        },
+    },
+};
+
+// ---------------------------------------------------------------
+// Compute Strength
+// - Daily closes of all stored stocks, their market's benchmark and BK sectors
+// - Ratios against the benchmark and the first sector with klines
+// - Rate stocks by percentile of weighted performance within each market
+// - Markets whose benchmark has no klines are skipped
+// - Store as strength signals of each stock's latest bar
+// ---------------------------------------------------------------
+#[derive(Clone)]
+pub struct ComputeStrengthHandler {
+    pub repo: Arc<dyn DomainRepository>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ComputeStrengthPayload {
+    /// Benchmark of every market, defaults to each market's own, see [market_benchmark].
+    pub benchmark: Option<Ticker>,
+}
+
+#[async_trait]
+impl JobHandler for ComputeStrengthHandler {
+    fn job_type(&self) -> JobType {
+        JobType::ComputeStrength
+    }
+
+    async fn handle(&self, job: &Job) -> Result<JobResult, JobError> {
+        let payload: ComputeStrengthPayload =
+            serde_json::from_value(job.payload.clone()).map_err(JobError::Serialization)?;
+
+        let mut markets: BTreeMap<Currency, Vec<Ticker>> = BTreeMap::new();
+        for ticker in self.repo.get_stock_tickers().await? {
+            if let Some(currency) = ticker.currency() {
+                markets.entry(currency).or_default().push(ticker);
+            }
+        }
+
+        let members = self.repo.get_sector_membership().await?;
+        let mut sector_closes: HashMap<String, Vec<(i64, f64)>> = HashMap::new();
+        for sector in members.values().flatten() {
+            if !sector_closes.contains_key(sector)
+                && let Ok(ticker) = sector.parse::<Ticker>()
+            {
+                sector_closes.insert(sector.clone(), self.closes(&ticker).await?);
+            }
+        }
+
+        let mut signals: Vec<StrengthSignal> = vec![];
+        let mut benchmarks: BTreeMap<Currency, Ticker> = BTreeMap::new();
+        let mut missing: Vec<Ticker> = vec![];
+        for (currency, tickers) in markets {
+            let benchmark = payload
+                .benchmark
+                .clone()
+                .unwrap_or_else(|| market_benchmark(currency).parse().expect("valid benchmark"));
+            let bench = self.closes(&benchmark).await?;
+            if bench.is_empty() {
+                missing.push(benchmark);
+                continue;
+            }
+
+            let mut market: Vec<StrengthSignal> = vec![];
+            for ticker in tickers {
+                let closes = self.closes(&ticker).await?;
+                let sector = members
+                    .get(ticker.as_str())
+                    .and_then(|sectors| pick_sector(sectors, &sector_closes))
+                    .map(|s| (s, sector_closes[s].as_slice()));
+                market.extend(compute_strength(
+                    &ticker,
+                    &closes,
+                    (&benchmark, &bench),
+                    sector,
+                ));
+            }
+            rate_strength(&mut market);
+            signals.extend(market);
+            benchmarks.insert(currency, benchmark);
+        }
+        missing.sort_unstable();
+        missing.dedup();
+        if benchmarks.is_empty() && !missing.is_empty() {
+            let missing: Vec<&str> = missing.iter().map(|t| t.as_str()).collect();
+            return Ok(JobResult {
+                success: false,
+                output: None,
+                error: Some(format!(
+                    "no stored klines of benchmark {}",
+                    missing.join(", ")
+                )),
+            });
+        }
+
+        self.repo.create_strength_signals(&signals).await?;
+
+        Ok(JobResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "benchmarks": benchmarks,
+                "missing benchmarks": missing,
+                "strength signals": signals.len(),
+                "rated": signals.iter().filter(|s| s.rating.is_some()).count(),
+            })),
+            error: None,
+        })
+    }
+}
+
+impl ComputeStrengthHandler {
+    async fn closes(&self, ticker: &Ticker) -> Result<Vec<(i64, f64)>, anyhow::Error> {
+        let klines = self.repo.get_klines(ticker).await?;
+
+        Ok(klines.iter().map(|k| (k.k_date, k.k_close)).collect())
+    }
+}
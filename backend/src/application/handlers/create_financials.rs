@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        handlers::JobHandler,
+        model::{Job, JobError, JobResult, JobType},
+    },
+    domain::{
+        repository::FinancialRepository, service_financials::compute_ratios, service_market::Ticker,
+    },
+    infra::data::financial::crawl_financials_eastmoney,
+};
+
+// ---------------------------------------------------------------
+// Create Financials
+// - (ticker) -> crawl income, balance sheet and cash flow statements
+// - Derive ratios per period
+// - Upsert both, restated periods are overwritten
+// NOTE: A-shares only.
+// ---------------------------------------------------------------
+#[derive(Clone)]
+pub struct CreateFinancialsHandler {
+    pub repo: Arc<dyn FinancialRepository>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateFinancialsPayload {
+    pub ticker: Ticker,
+}
+
+#[async_trait]
+impl JobHandler for CreateFinancialsHandler {
+    fn job_type(&self) -> JobType {
+        JobType::CreateFinancials
+    }
+
+    async fn handle(&self, job: &Job) -> Result<JobResult, JobError> {
+        let payload: CreateFinancialsPayload =
+            serde_json::from_value(job.payload.clone()).map_err(JobError::Serialization)?;
+
+        let statements = match crawl_financials_eastmoney(&payload.ticker).await {
+            Ok(statements) => statements,
+            Err(e) => {
+                return Ok(JobResult {
+                    success: false,
+                    output: None,
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+
+        let ratios = compute_ratios(&statements);
+        self.repo.create_financials(&statements, &ratios).await?;
+
+        Ok(JobResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "ticker": payload.ticker,
+                "periods": ratios.len(),
+                "latest": ratios.last().map(|r| r.period.clone()),
+            })),
+            error: None,
+        })
+    }
+}
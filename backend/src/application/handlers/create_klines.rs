@@ -8,12 +8,13 @@ use crate::{
         handlers::JobHandler,
         model::{Job, JobError, JobResult, JobType},
     },
-    domain::repository::DomainRepository,
+    domain::{repository::DomainRepository, service_market::Ticker},
     infra::data::kline::{UrlKlineEastmoney, crawl_kline_eastmoney},
 };
 
 // ---------------------------------------------------------------
 // Crawl Price
+// NOTE: daily bars only, weekly views are resampled from these.
 // - (ticker, url) -> [Kline] and write db
 // ---------------------------------------------------------------
 #[derive(Clone)]
@@ -23,7 +24,7 @@ pub struct CreateKlineHandler {
 
 #[derive(Serialize, Deserialize)]
 pub struct CreateKlinePayload {
-    pub ticker: String,
+    pub ticker: Ticker,
     pub start: String,
     pub end: String,
 }
@@ -43,7 +44,7 @@ impl JobHandler for CreateKlineHandler {
             &payload.ticker,
             &payload.start,
             &payload.end,
-            true,
+            false,
         ))
         .await
         {
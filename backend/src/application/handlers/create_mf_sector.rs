@@ -5,22 +5,29 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     application::{
+        alerts::AlertService,
         handlers::JobHandler,
         model::{Job, JobError, JobResult, JobType},
     },
     domain::repository::DomainRepository,
-    infra::data::moneyflow::{UrlMoneyflowSectorEastmoney, crawl_moneyflow_sector_eastmoney},
+    infra::data::moneyflow::{
+        UrlMoneyflowSectorEastmoney, crawl_moneyflow_sector_eastmoney, trade_date_shanghai,
+    },
 };
 
 // ---------------------------------------------------------------
 // Create Moneyflow Sector
-// NOTE: decide on days to keep record.
-// - () -> crawl data and save
+// - () -> crawl data of the tracked sectors and save, keyed by trading date
+// - Drop records older than retention, if any
+// - Evaluate moneyflow alert rules
 // ---------------------------------------------------------------
 
 #[derive(Clone)]
 pub struct CreateMfSectorHandler {
     pub repo: Arc<dyn DomainRepository>,
+    /// Days of moneyflow history to keep, `None` keeps all.
+    pub retention_days: Option<u32>,
+    pub alerts: Arc<AlertService>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -34,8 +41,16 @@ impl JobHandler for CreateMfSectorHandler {
 
     // FIX: later abstract data sourcing into port.
     async fn handle(&self, _: &Job) -> Result<JobResult, JobError> {
+        let tracked: Vec<String> = self
+            .repo
+            .get_tracked_sectors()
+            .await?
+            .into_iter()
+            .map(|s| s.ticker)
+            .collect();
+
         let url = UrlMoneyflowSectorEastmoney::default();
-        let ml_records = match crawl_moneyflow_sector_eastmoney(url).await {
+        let ml_records = match crawl_moneyflow_sector_eastmoney(url, &tracked).await {
             Ok(res) => res,
             Err(e) => {
                 return Ok(JobResult {
@@ -48,6 +63,18 @@ impl JobHandler for CreateMfSectorHandler {
 
         self.repo.create_mf_sector(&ml_records).await?;
 
+        if let Some(days) = self.retention_days {
+            let cutoff =
+                trade_date_shanghai(chrono::Utc::now()) - chrono::Duration::days(days as i64);
+            self.repo
+                .delete_mf_sector_before(&cutoff.to_string())
+                .await?;
+        }
+
+        if let Err(e) = self.alerts.on_moneyflow().await {
+            tracing::error!("alerts failed for moneyflow sector: {}", e);
+        }
+
         Ok(JobResult {
             success: true,
             output: Some(serde_json::json!({
@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        handlers::JobHandler,
+        model::{Job, JobError, JobResult, JobType},
+    },
+    domain::{repository::DomainRepository, service_market::Ticker},
+    infra::data::moneyflow::{
+        UrlMoneyflowStockEastmoney, crawl_moneyflow_stock_eastmoney, trade_date_shanghai,
+    },
+};
+
+// ---------------------------------------------------------------
+// Create Moneyflow Stock
+// - (ticker) -> crawl daily moneyflow history and upsert, overlapping days are refreshed
+// - Drop records older than retention, if any
+// ---------------------------------------------------------------
+
+#[derive(Clone)]
+pub struct CreateMfStockHandler {
+    pub repo: Arc<dyn DomainRepository>,
+    /// Days of moneyflow history to keep, `None` keeps all.
+    pub retention_days: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateMfStockPayload {
+    pub ticker: Ticker,
+}
+
+#[async_trait]
+impl JobHandler for CreateMfStockHandler {
+    fn job_type(&self) -> JobType {
+        JobType::CreateMfStock
+    }
+
+    async fn handle(&self, job: &Job) -> Result<JobResult, JobError> {
+        let payload: CreateMfStockPayload =
+            serde_json::from_value(job.payload.clone()).map_err(JobError::Serialization)?;
+
+        let url = UrlMoneyflowStockEastmoney::daily(&payload.ticker);
+        let flows = match crawl_moneyflow_stock_eastmoney(url).await {
+            Ok(flows) if flows.is_empty() => {
+                return Ok(JobResult {
+                    success: false,
+                    output: None,
+                    error: Some(format!("no moneyflow for {}", payload.ticker)),
+                });
+            }
+            Ok(flows) => flows,
+            Err(e) => {
+                return Ok(JobResult {
+                    success: false,
+                    output: None,
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+
+        self.repo.create_mf_stock(&flows).await?;
+
+        if let Some(days) = self.retention_days {
+            let cutoff =
+                trade_date_shanghai(chrono::Utc::now()) - chrono::Duration::days(days as i64);
+            self.repo
+                .delete_mf_stock_before(&cutoff.to_string())
+                .await?;
+        }
+
+        Ok(JobResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "ticker": payload.ticker,
+                "days": flows.len(),
+            })),
+            error: None,
+        })
+    }
+}
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        handlers::JobHandler,
+        model::{Job, JobError, JobResult, JobType},
+    },
+    domain::{repository::DomainRepository, service_market::Ticker},
+    infra::data::sector::crawl_sector_members_eastmoney,
+};
+
+// ---------------------------------------------------------------
+// Create Sector Members
+// - (sector) -> crawl board constituents and replace the stored ones
+// - An empty crawl keeps the stored constituents
+// ---------------------------------------------------------------
+
+#[derive(Clone)]
+pub struct CreateSectorMembersHandler {
+    pub repo: Arc<dyn DomainRepository>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateSectorMembersPayload {
+    pub sector: Ticker,
+}
+
+#[async_trait]
+impl JobHandler for CreateSectorMembersHandler {
+    fn job_type(&self) -> JobType {
+        JobType::CreateSectorMembers
+    }
+
+    async fn handle(&self, job: &Job) -> Result<JobResult, JobError> {
+        let payload: CreateSectorMembersPayload =
+            serde_json::from_value(job.payload.clone()).map_err(JobError::Serialization)?;
+
+        let members = match crawl_sector_members_eastmoney(&payload.sector).await {
+            Ok(members) if members.is_empty() => {
+                return Ok(JobResult {
+                    success: false,
+                    output: None,
+                    error: Some(format!("no constituents for {}", payload.sector)),
+                });
+            }
+            Ok(members) => members,
+            Err(e) => {
+                return Ok(JobResult {
+                    success: false,
+                    output: None,
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+
+        self.repo
+            .set_sector_members(&payload.sector, &members)
+            .await?;
+
+        Ok(JobResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "sector": payload.sector,
+                "members": members.len(),
+            })),
+            error: None,
+        })
+    }
+}
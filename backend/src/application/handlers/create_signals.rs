@@ -5,12 +5,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     application::{
+        alerts::AlertService,
         handlers::JobHandler,
         model::{Job, JobError, JobResult, JobType},
     },
     domain::{
-        model::Signal,
+        model::{Signal, SignalPeriod},
         repository::DomainRepository,
+        service_calendar::{Exchange, TradingCalendar},
+        service_market::Ticker,
         service_signal::{compute_boll_dist, compute_kdj},
     },
     infra::data::kline::{UrlKlineEastmoney, crawl_kline_eastmoney},
@@ -20,16 +23,21 @@ use crate::{
 // Create Signals
 // - Crawl klines
 // - Compute signals
-// - Save signals to db
+// - Upsert signals to db, keyed by the last bar date, one row per week
+// - Drop signals older than retention, if any
+// - Evaluate alert rules of the ticker
 // ---------------------------------------------------------------
 #[derive(Clone)]
 pub struct CreateSignalHandler {
     pub repo: Arc<dyn DomainRepository>,
+    /// Days of signal history to keep, `None` keeps all.
+    pub retention_days: Option<u32>,
+    pub alerts: Arc<AlertService>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct CreateSignalPayload {
-    pub ticker: String,
+    pub ticker: Ticker,
     pub week: bool,
 }
 
@@ -47,20 +55,48 @@ impl JobHandler for CreateSignalHandler {
 
         let klines = crawl_kline_eastmoney(url).await?;
 
+        let Some(last_kline) = klines.last() else {
+            return Ok(JobResult {
+                success: false,
+                output: None,
+                error: Some(format!("no klines for {}", payload.ticker)),
+            });
+        };
+
+        let period = if payload.week {
+            SignalPeriod::Week
+        } else {
+            SignalPeriod::Day
+        };
         let kdjs = compute_kdj(&klines);
         let last_kdj = kdjs.last().unwrap();
         let boll_dist = compute_boll_dist(&klines);
         let signal = Signal {
-            ticker: payload.ticker.clone(),
+            ticker: payload.ticker.to_string(),
+            period,
+            bar_date: last_kline.k_date,
             kdj_k: last_kdj.k,
             kdj_d: last_kdj.d,
             boll_dist,
         };
 
-        if payload.week {
-            self.repo.create_signals_w(signal).await?;
-        } else {
-            self.repo.create_signals_d(signal).await?;
+        self.repo.create_signal(signal).await?;
+
+        if let Some(days) = self.retention_days {
+            // NOTE: Global indexes have no calendar, they fall back to SSE as stocks do.
+            let today = TradingCalendar::for_ticker(&payload.ticker)
+                .unwrap_or(TradingCalendar::get(Exchange::Sse))
+                .local_date(chrono::Utc::now());
+            let cutoff = today - chrono::Duration::days(days as i64);
+            let cutoff = cutoff.format("%Y%m%d").to_string().parse::<i64>().unwrap();
+            self.repo
+                .delete_signals_before(&payload.ticker, period, cutoff)
+                .await?;
+        }
+
+        // NOTE: Alerts are best effort, the signal is already stored.
+        if let Err(e) = self.alerts.on_signal(&payload.ticker, period).await {
+            tracing::error!("alerts failed for {}: {}", payload.ticker, e);
         }
 
         Ok(JobResult {
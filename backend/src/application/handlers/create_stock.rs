@@ -8,14 +8,20 @@ use crate::{
         handlers::JobHandler,
         model::{Job, JobError, JobResult, JobType},
     },
-    domain::repository::DomainRepository,
+    domain::{
+        model::FundamentalsSnapshot,
+        repository::DomainRepository,
+        service_calendar::{Exchange, TradingCalendar},
+        service_market::Ticker,
+    },
     infra::data::stock::{UrlStockEastmoney, crawl_stock_eastmoney},
 };
 
 // ---------------------------------------------------------------
 // Create Stock
-// NOTE: extra flag on DELETE existing records for update purpose.
-// - (ticker) -> crawl meta and save
+// - (ticker) -> crawl meta and save, existing tickers are skipped unless `refresh`
+// - Snapshot the fundamentals under the current trading date of the ticker's exchange
+// NOTE: tickers of no known exchange follow SSE.
 // ---------------------------------------------------------------
 
 #[derive(Clone)]
@@ -25,7 +31,10 @@ pub struct CreateStockHandler {
 
 #[derive(Serialize, Deserialize)]
 pub struct CreateStockPayload {
-    pub ticker: String,
+    pub ticker: Ticker,
+    /// Re-crawls a stored ticker to update its fundamentals.
+    #[serde(default)]
+    pub refresh: bool,
 }
 
 #[async_trait]
@@ -42,11 +51,12 @@ impl JobHandler for CreateStockHandler {
         // NOTE: Else, skip the job if ticker exists in stocks table.
         // `ticker` is indexed so it's fast lookup. e.g. 105.TSLA
         // (SELECT 1 FROM table_name WHERE column_name = ? LIMIT 1;)
-        if self
-            .repo
-            .get_stock(&payload.ticker)
-            .await
-            .is_ok_and(|s| s.ticker == payload.ticker)
+        if !payload.refresh
+            && self
+                .repo
+                .get_stock(&payload.ticker)
+                .await
+                .is_ok_and(|s| s.ticker == payload.ticker)
         {
             return Ok(JobResult {
                 success: true,
@@ -69,6 +79,13 @@ impl JobHandler for CreateStockHandler {
             }
         };
 
+        let trade_date = TradingCalendar::for_ticker(&payload.ticker)
+            .unwrap_or(TradingCalendar::get(Exchange::Sse))
+            .trade_date(chrono::Utc::now())
+            .to_string();
+        self.repo
+            .create_fundamentals_snapshot(&FundamentalsSnapshot::new(&stock, &trade_date))
+            .await?;
         self.repo.create_stock(stock).await?;
 
         // // Step 2: crawl klines of the stock.
@@ -107,6 +124,7 @@ impl JobHandler for CreateStockHandler {
             success: true,
             output: Some(serde_json::json!({
                 "Stock created": format!("{}", payload.ticker),
+                "snapshot": trade_date,
             })),
             error: None,
         })
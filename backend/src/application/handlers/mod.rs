@@ -4,10 +4,17 @@ use std::sync::Arc;
 
 use crate::application::model::{Job, JobError, JobResult, JobType};
 
-//pub mod create_klines;
+pub mod compute_risk;
+pub mod compute_strength;
+pub mod create_financials;
+pub mod create_klines;
 pub mod create_mf_sector;
+pub mod create_mf_stock;
+pub mod create_sector_members;
 pub mod create_signals;
 pub mod create_stock;
+pub mod run_backtest;
+pub mod walk_forward;
 
 #[async_trait]
 pub trait JobHandler: Send + Sync {
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        handlers::JobHandler,
+        model::{Job, JobError, JobResult, JobType},
+    },
+    domain::{
+        backtest::{BacktestConfig, rules::MarketRules, run_backtest, strategy::StrategyConfig},
+        repository::DomainRepository,
+        service_market::Ticker,
+    },
+};
+
+// ---------------------------------------------------------------
+// Run Backtest
+// - Read stored daily klines of the ticker
+// - Pick market rules by ticker unless given
+// - Run the strategy through the simulated broker
+// - Store the report
+// ---------------------------------------------------------------
+#[derive(Clone)]
+pub struct RunBacktestHandler {
+    pub repo: Arc<dyn DomainRepository>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RunBacktestPayload {
+    pub ticker: Ticker,
+    pub strategy: StrategyConfig,
+    #[serde(default)]
+    pub config: BacktestConfig,
+    /// Set when the run is a trial of a parameter search.
+    #[serde(default)]
+    pub optimisation_id: Option<i64>,
+}
+
+#[async_trait]
+impl JobHandler for RunBacktestHandler {
+    fn job_type(&self) -> JobType {
+        JobType::RunBacktest
+    }
+
+    async fn handle(&self, job: &Job) -> Result<JobResult, JobError> {
+        let payload: RunBacktestPayload =
+            serde_json::from_value(job.payload.clone()).map_err(JobError::Serialization)?;
+        if let Err(e) = payload.config.validate() {
+            return Ok(JobResult {
+                success: false,
+                output: None,
+                error: Some(e),
+            });
+        }
+
+        let klines = self.repo.get_klines(&payload.ticker).await?;
+        if klines.is_empty() {
+            return Ok(JobResult {
+                success: false,
+                output: None,
+                error: Some(format!(
+                    "no klines for {}, create klines first",
+                    payload.ticker
+                )),
+            });
+        }
+
+        let mut config = payload.config;
+        if config.rules.is_none() {
+            // ST names trade under tighter limits.
+            let realname = self
+                .repo
+                .get_stock(&payload.ticker)
+                .await
+                .ok()
+                .map(|s| s.realname);
+            config.rules = Some(MarketRules::for_ticker(
+                &payload.ticker,
+                realname.as_deref(),
+            ));
+        }
+
+        let strategy = payload.strategy.clone();
+        // NOTE: CPU bound, keep it off the async workers.
+        let report = tokio::task::spawn_blocking(move || {
+            let mut strategy = strategy.build();
+            run_backtest(&klines, strategy.as_mut(), &config)
+        })
+        .await
+        .map_err(anyhow::Error::from)?;
+
+        let id = self
+            .repo
+            .create_backtest_run(
+                &payload.ticker,
+                &payload.strategy,
+                &config,
+                &report,
+                payload.optimisation_id,
+            )
+            .await?;
+
+        Ok(JobResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "backtest run": id,
+                "metrics": report.metrics,
+            })),
+            error: None,
+        })
+    }
+}
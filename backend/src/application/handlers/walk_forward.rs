@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        handlers::JobHandler,
+        model::{Job, JobError, JobResult, JobType},
+    },
+    domain::{
+        backtest::{
+            BacktestConfig,
+            optimize::{search_space, walk_forward},
+            rules::MarketRules,
+        },
+        repository::DomainRepository,
+        service_market::Ticker,
+    },
+};
+
+// ---------------------------------------------------------------
+// Walk Forward
+// - Read the optimisation and stored daily klines of its ticker
+// - Optimise on rolling in-sample windows, trade the winner out-of-sample
+// - Store the report on the optimisation
+// ---------------------------------------------------------------
+#[derive(Clone)]
+pub struct WalkForwardHandler {
+    pub repo: Arc<dyn DomainRepository>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WalkForwardPayload {
+    pub optimisation_id: i64,
+    /// Bars per in-sample window.
+    pub in_sample: usize,
+    /// Bars per out-of-sample window, also the step between windows.
+    pub out_sample: usize,
+    #[serde(default)]
+    pub config: BacktestConfig,
+}
+
+#[async_trait]
+impl JobHandler for WalkForwardHandler {
+    fn job_type(&self) -> JobType {
+        JobType::WalkForward
+    }
+
+    async fn handle(&self, job: &Job) -> Result<JobResult, JobError> {
+        let payload: WalkForwardPayload =
+            serde_json::from_value(job.payload.clone()).map_err(JobError::Serialization)?;
+
+        let Some(optimisation) = self.repo.get_optimisation(payload.optimisation_id).await? else {
+            return Ok(JobResult {
+                success: false,
+                output: None,
+                error: Some(format!("no optimisation {}", payload.optimisation_id)),
+            });
+        };
+
+        let candidates = match search_space(
+            &optimisation.strategy,
+            &optimisation.space,
+            optimisation.method,
+        ) {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                return Ok(JobResult {
+                    success: false,
+                    output: None,
+                    error: Some(e),
+                });
+            }
+        };
+
+        let ticker: Ticker = match optimisation.ticker.parse() {
+            Ok(ticker) => ticker,
+            Err(e) => {
+                return Ok(JobResult {
+                    success: false,
+                    output: None,
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+        let klines = self.repo.get_klines(&ticker).await?;
+        if klines.len() < payload.in_sample + payload.out_sample {
+            return Ok(JobResult {
+                success: false,
+                output: None,
+                error: Some(format!(
+                    "{} klines for {}, need at least {}",
+                    klines.len(),
+                    optimisation.ticker,
+                    payload.in_sample + payload.out_sample
+                )),
+            });
+        }
+
+        let mut config = payload.config;
+        if config.rules.is_none() {
+            let realname = self.repo.get_stock(&ticker).await.ok().map(|s| s.realname);
+            config.rules = Some(MarketRules::for_ticker(&ticker, realname.as_deref()));
+        }
+
+        let objective = optimisation.objective;
+        let (in_sample, out_sample) = (payload.in_sample, payload.out_sample);
+        // NOTE: CPU bound, keep it off the async workers.
+        let report = tokio::task::spawn_blocking(move || {
+            walk_forward(
+                &klines,
+                &candidates,
+                in_sample,
+                out_sample,
+                objective,
+                &config,
+            )
+        })
+        .await
+        .map_err(anyhow::Error::from)?;
+
+        self.repo
+            .set_walk_forward_report(optimisation.id, &report)
+            .await?;
+
+        Ok(JobResult {
+            success: true,
+            output: Some(serde_json::json!({
+                "walk forward": optimisation.id,
+                "windows": report.windows.len(),
+                "efficiency": report.efficiency,
+            })),
+            error: None,
+        })
+    }
+}
@@ -1,29 +1,63 @@
-use std::sync::Arc;
+use std::{env, sync::Arc, time::Duration};
 
 use crate::{
     application::{
+        alerts::{AlertService, NotificationSink},
+        events::{EventBus, EventSink},
         handlers::{
-            JobHandlerRegistry, create_mf_sector::CreateMfSectorHandler,
-            create_signals::CreateSignalHandler, create_stock::CreateStockHandler,
+            JobHandlerRegistry, compute_risk::ComputeRiskHandler,
+            compute_strength::ComputeStrengthHandler, create_financials::CreateFinancialsHandler,
+            create_klines::CreateKlineHandler, create_mf_sector::CreateMfSectorHandler,
+            create_mf_stock::CreateMfStockHandler,
+            create_sector_members::CreateSectorMembersHandler, create_signals::CreateSignalHandler,
+            create_stock::CreateStockHandler, run_backtest::RunBacktestHandler,
+            walk_forward::WalkForwardHandler,
         },
+        quotes::QuoteService,
         runner::JobRunner,
     },
-    infra::storage::{
-        Database, repo_domain_sqlite::SqliteDomainRepository, repo_job_sqlite::SqliteJobRepository,
+    infra::{
+        data::quote::EastmoneyQuoteSource,
+        notify::{FileSink, SmtpSink, WebhookSink},
+        storage::{
+            Database, repo_domain_sqlite::SqliteDomainRepository,
+            repo_financial_sqlite::SqliteFinancialRepository, repo_job_sqlite::SqliteJobRepository,
+        },
     },
 };
 
+pub mod alerts;
+pub mod events;
 pub mod handlers;
 pub mod model;
+pub mod quotes;
 pub mod repository;
 pub mod runner;
 
-pub fn init_runner(db: &Database) -> JobRunner {
+pub fn init_runner(db: &Database, events: &EventBus) -> JobRunner {
     let repo_domain = Arc::new(SqliteDomainRepository::new(db.pool.clone()));
+    let repo_financial = Arc::new(SqliteFinancialRepository::new(db.pool.clone()));
     let repo_job = Arc::new(SqliteJobRepository::new(db.pool.clone()));
 
+    // NOTE: unset keeps the full history.
+    let signals_retention_days = env::var("SIGNALS_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse::<u32>().ok());
+
+    let moneyflow_retention_days = env::var("MONEYFLOW_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse::<u32>().ok());
+
+    let alerts = Arc::new(AlertService {
+        repo: repo_domain.clone(),
+        repo_financial: repo_financial.clone(),
+        sinks: alert_sinks(events),
+    });
+
     let create_signal_handler = CreateSignalHandler {
         repo: repo_domain.clone(),
+        retention_days: signals_retention_days,
+        alerts: alerts.clone(),
     };
     let create_stock_handler = CreateStockHandler {
         repo: repo_domain.clone(),
@@ -31,6 +65,41 @@ pub fn init_runner(db: &Database) -> JobRunner {
 
     let create_mf_sector_handler = CreateMfSectorHandler {
         repo: repo_domain.clone(),
+        retention_days: moneyflow_retention_days,
+        alerts: alerts.clone(),
+    };
+
+    let create_mf_stock_handler = CreateMfStockHandler {
+        repo: repo_domain.clone(),
+        retention_days: moneyflow_retention_days,
+    };
+
+    let create_kline_handler = CreateKlineHandler {
+        repo: repo_domain.clone(),
+    };
+
+    let run_backtest_handler = RunBacktestHandler {
+        repo: repo_domain.clone(),
+    };
+
+    let walk_forward_handler = WalkForwardHandler {
+        repo: repo_domain.clone(),
+    };
+
+    let compute_risk_handler = ComputeRiskHandler {
+        repo: repo_domain.clone(),
+    };
+
+    let create_sector_members_handler = CreateSectorMembersHandler {
+        repo: repo_domain.clone(),
+    };
+
+    let compute_strength_handler = ComputeStrengthHandler {
+        repo: repo_domain.clone(),
+    };
+
+    let create_financials_handler = CreateFinancialsHandler {
+        repo: repo_financial.clone(),
     };
 
     let mut handler_registry = JobHandlerRegistry::new();
@@ -38,6 +107,14 @@ pub fn init_runner(db: &Database) -> JobRunner {
         Arc::new(create_signal_handler),
         Arc::new(create_stock_handler),
         Arc::new(create_mf_sector_handler),
+        Arc::new(create_mf_stock_handler),
+        Arc::new(create_kline_handler),
+        Arc::new(run_backtest_handler),
+        Arc::new(walk_forward_handler),
+        Arc::new(compute_risk_handler),
+        Arc::new(create_sector_members_handler),
+        Arc::new(compute_strength_handler),
+        Arc::new(create_financials_handler),
     ]);
 
     let concurrency = 3;
@@ -46,10 +123,71 @@ pub fn init_runner(db: &Database) -> JobRunner {
 
     JobRunner::new(
         repo_domain,
+        repo_financial,
         repo_job,
         Arc::new(handler_registry),
         concurrency,
         wait_sec,
         batch_size,
     )
+    .with_events(events.clone())
+}
+
+/// Quote polling is off unless `QUOTE_POLL_SECS` is set, snapshots are stored every minute by
+/// default.
+pub fn init_quotes(db: &Database, events: &EventBus) -> Arc<QuoteService> {
+    let repo_domain = Arc::new(SqliteDomainRepository::new(db.pool.clone()));
+
+    let interval = env::var("QUOTE_POLL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs);
+
+    let persist_every = env::var("QUOTE_PERSIST_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(60));
+
+    // NOTE: unset keeps the full history.
+    let retention_days = env::var("QUOTE_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse::<u32>().ok());
+
+    Arc::new(QuoteService::new(
+        repo_domain,
+        Arc::new(EastmoneyQuoteSource),
+        interval,
+        persist_every,
+        retention_days,
+        events.clone(),
+    ))
+}
+
+/// Notification sinks configured by env, fired alerts are stored and published either way.
+fn alert_sinks(events: &EventBus) -> Vec<Arc<dyn NotificationSink>> {
+    let mut sinks: Vec<Arc<dyn NotificationSink>> = vec![Arc::new(EventSink {
+        events: events.clone(),
+    })];
+
+    if let Ok(url) = env::var("ALERT_WEBHOOK_URL") {
+        sinks.push(Arc::new(WebhookSink { url }));
+    }
+    if let (Ok(addr), Ok(from), Ok(to)) = (
+        env::var("ALERT_SMTP_ADDR"),
+        env::var("ALERT_SMTP_FROM"),
+        env::var("ALERT_SMTP_TO"),
+    ) {
+        sinks.push(Arc::new(SmtpSink {
+            addr,
+            from,
+            to: to.split(',').map(|s| s.trim().to_string()).collect(),
+        }));
+    }
+    if let Ok(path) = env::var("ALERT_FILE") {
+        sinks.push(Arc::new(FileSink { path: path.into() }));
+    }
+
+    sinks
 }
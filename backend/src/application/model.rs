@@ -49,6 +49,13 @@ pub enum JobType {
     CreateKline,
     CreateSignal,
     CreateMfSector,
+    RunBacktest,
+    WalkForward,
+    ComputeRisk,
+    CreateSectorMembers,
+    CreateMfStock,
+    ComputeStrength,
+    CreateFinancials,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
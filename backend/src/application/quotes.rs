@@ -0,0 +1,236 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::{
+    application::events::{Event, EventBus},
+    domain::{
+        model::Quote, repository::DomainRepository, service_calendar::TradingCalendar,
+        service_market::Ticker,
+    },
+};
+
+/// Port for fetching quotes of many tickers, batching is up to the source.
+#[async_trait]
+pub trait QuoteSource: Send + Sync {
+    async fn fetch(&self, tickers: &[Ticker]) -> Result<Vec<Quote>, anyhow::Error>;
+}
+
+// ---------------------------------------------------------------
+// Quote Service
+// - Poll quotes of all watchlist tickers whose market is in session
+// - Keep the latest quote per ticker in memory and publish each on the bus
+// - Persist the cache as snapshots every `persist_every` while polling
+// NOTE: the cache starts empty, nothing is loaded back from snapshots.
+// ---------------------------------------------------------------
+pub struct QuoteService {
+    pub repo: Arc<dyn DomainRepository>,
+    pub source: Arc<dyn QuoteSource>,
+    /// None leaves polling off.
+    pub interval: Option<Duration>,
+    pub persist_every: Duration,
+    /// Unset keeps all snapshots.
+    pub retention_days: Option<u32>,
+    pub events: EventBus,
+    cache: RwLock<HashMap<String, Quote>>,
+}
+
+impl QuoteService {
+    pub fn new(
+        repo: Arc<dyn DomainRepository>,
+        source: Arc<dyn QuoteSource>,
+        interval: Option<Duration>,
+        persist_every: Duration,
+        retention_days: Option<u32>,
+        events: EventBus,
+    ) -> Self {
+        Self {
+            repo,
+            source,
+            interval,
+            persist_every,
+            retention_days,
+            events,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Cached quotes of `tickers` in the given order, tickers never polled are left out.
+    pub fn latest(&self, tickers: &[Ticker]) -> Vec<Quote> {
+        let cache = self.cache.read().unwrap();
+        tickers
+            .iter()
+            .filter_map(|t| cache.get(t.as_str()).cloned())
+            .collect()
+    }
+
+    /// Polls the watchlist tickers in session at `now`, returns the quotes fetched.
+    pub async fn poll(&self, now: DateTime<Utc>) -> Result<Vec<Quote>, anyhow::Error> {
+        let tickers: Vec<Ticker> = self
+            .repo
+            .get_watchlists()
+            .await?
+            .into_iter()
+            .flat_map(|w| w.items.into_iter().filter_map(|i| i.ticker.parse().ok()))
+            .collect::<BTreeSet<Ticker>>()
+            .into_iter()
+            .filter(|t| TradingCalendar::for_ticker(t).is_some_and(|c| c.is_open(now)))
+            .collect();
+        if tickers.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let quotes = self.source.fetch(&tickers).await?;
+        let mut cache = self.cache.write().unwrap();
+        for quote in quotes.iter() {
+            cache.insert(quote.ticker.clone(), quote.clone());
+            self.events.publish(Event::Quote(quote.clone()));
+        }
+
+        Ok(quotes)
+    }
+
+    /// Stores the cache as snapshots and prunes those past retention, returns the number stored.
+    pub async fn persist(&self, now: DateTime<Utc>) -> Result<usize, anyhow::Error> {
+        let quotes: Vec<Quote> = self.cache.read().unwrap().values().cloned().collect();
+        self.repo.create_quote_snapshots(&quotes).await?;
+
+        if let Some(days) = self.retention_days {
+            let cutoff = now.timestamp() - i64::from(days) * 86_400;
+            self.repo.delete_quote_snapshots_before(cutoff).await?;
+        }
+
+        Ok(quotes.len())
+    }
+
+    /// Polls until the process exits, returns at once if polling is off.
+    pub async fn run(self: Arc<Self>) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let mut ticks = tokio::time::interval(interval);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut persisted_at = Instant::now();
+        let mut dirty = false;
+
+        loop {
+            ticks.tick().await;
+            match self.poll(Utc::now()).await {
+                Ok(quotes) => dirty |= !quotes.is_empty(),
+                Err(e) => tracing::error!("quote poll failed: {}", e),
+            }
+
+            if dirty && persisted_at.elapsed() >= self.persist_every {
+                if let Err(e) = self.persist(Utc::now()).await {
+                    tracing::error!("quote snapshots failed: {}", e);
+                }
+                persisted_at = Instant::now();
+                dirty = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use sqlx::SqlitePool;
+
+    use super::*;
+    use crate::{
+        domain::service_market::parse_tickers,
+        infra::storage::repo_domain_sqlite::SqliteDomainRepository,
+    };
+
+    fn tickers(s: &str) -> Vec<Ticker> {
+        parse_tickers(s).unwrap()
+    }
+
+    /// Quotes every ticker asked for at a fixed price, recording each request.
+    #[derive(Default)]
+    struct FixedSource(std::sync::Mutex<Vec<Vec<Ticker>>>);
+
+    #[async_trait]
+    impl QuoteSource for FixedSource {
+        async fn fetch(&self, tickers: &[Ticker]) -> Result<Vec<Quote>, anyhow::Error> {
+            self.0.lock().unwrap().push(tickers.to_vec());
+            Ok(tickers
+                .iter()
+                .map(|t| Quote {
+                    ticker: t.to_string(),
+                    realname: t.to_string(),
+                    last: Some(10.0),
+                    bid: Some(9.99),
+                    ask: Some(10.0),
+                    volume: Some(100.0),
+                    change_pct: Some(1.0),
+                    quoted_at: 1_760_500_800,
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_in_session() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let repo = Arc::new(SqliteDomainRepository::new(pool));
+        let source = Arc::new(FixedSource::default());
+        let service = QuoteService::new(
+            repo.clone(),
+            source.clone(),
+            None,
+            Duration::from_secs(60),
+            Some(1),
+            EventBus::default(),
+        );
+        let mut events = service.events.subscribe();
+
+        let a = repo.create_watchlist("a", None).await.unwrap();
+        let b = repo.create_watchlist("b", None).await.unwrap();
+        repo.add_watchlist_tickers(a, &tickers("1.600635,105.TSLA"))
+            .await
+            .unwrap();
+        repo.add_watchlist_tickers(b, &tickers("1.600635,0.000001"))
+            .await
+            .unwrap();
+
+        // 10:00 Shanghai on a Wednesday, New York is closed.
+        let now = Utc.with_ymd_and_hms(2025, 10, 15, 2, 0, 0).unwrap();
+        let quotes = service.poll(now).await.unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(source.0.lock().unwrap()[0], tickers("0.000001,1.600635"));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events.recv().await.unwrap().ticker(), Some("0.000001"));
+
+        let latest = service.latest(&tickers("105.TSLA,1.600635"));
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].ticker, "1.600635");
+
+        // Saturday, nothing is fetched.
+        let weekend = Utc.with_ymd_and_hms(2025, 10, 18, 2, 0, 0).unwrap();
+        assert!(service.poll(weekend).await.unwrap().is_empty());
+        assert_eq!(source.0.lock().unwrap().len(), 1);
+
+        assert_eq!(service.persist(now).await.unwrap(), 2);
+        let ticker = "1.600635".parse().unwrap();
+        let snapshots = repo.get_quote_snapshots(&ticker, None).await.unwrap();
+        assert_eq!(snapshots, vec![latest[0].clone()]);
+
+        // Two days on the stale cache is stored again but falls out of the one day retention.
+        let later = now + chrono::Duration::days(2);
+        service.persist(later).await.unwrap();
+        assert!(
+            repo.get_quote_snapshots(&ticker, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
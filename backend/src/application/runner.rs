@@ -5,11 +5,12 @@ use tokio::{sync::Semaphore, time::sleep};
 
 use crate::{
     application::{
+        events::{Event, EventBus, JobEvent},
         handlers::JobHandlerRegistry,
         model::{Job, JobStatus},
         repository::JobRepository,
     },
-    domain::repository::DomainRepository,
+    domain::repository::{DomainRepository, FinancialRepository},
 };
 
 /// Main engine of the application that drives query/command.
@@ -17,7 +18,10 @@ use crate::{
 #[derive(Clone)]
 pub struct JobRunner {
     pub repo_domain: Arc<dyn DomainRepository>,
+    pub repo_financial: Arc<dyn FinancialRepository>,
     pub repo_job: Arc<dyn JobRepository>,
+    /// Job transitions are published here.
+    pub events: EventBus,
     handler_registry: Arc<JobHandlerRegistry>,
     concurrency_limit: Arc<Semaphore>,
     wait_sec: u64,
@@ -37,6 +41,7 @@ pub enum RunnerError {
 impl JobRunner {
     pub fn new(
         repo_domain: Arc<dyn DomainRepository>,
+        repo_financial: Arc<dyn FinancialRepository>,
         repo_job: Arc<dyn JobRepository>,
         handler_registry: Arc<JobHandlerRegistry>,
         max_concurrent_jobs: usize,
@@ -45,7 +50,9 @@ impl JobRunner {
     ) -> Self {
         Self {
             repo_domain,
+            repo_financial,
             repo_job,
+            events: EventBus::default(),
             handler_registry,
             concurrency_limit: Arc::new(Semaphore::new(max_concurrent_jobs)),
             wait_sec,
@@ -53,6 +60,12 @@ impl JobRunner {
         }
     }
 
+    /// Publishes job transitions on a shared bus instead of a private one.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
     // NOTE: This is handled by the initiator in a server handler.
     // If err, it's non-task error and should be logged.
     pub async fn run(&self) -> Result<(), RunnerError> {
@@ -134,15 +147,17 @@ impl JobRunner {
     async fn process_job(&self, job: Job) -> Result<(), RunnerError> {
         // Mark job as running
         self.repo_job.mark_job_running(job.id).await?;
+        self.publish(&job, JobStatus::Running, None);
 
         // Find appropriate handler
-        let handler = self
-            .handler_registry
-            .get_handler(&job.job_type)
-            .ok_or_else(|| RunnerError::Execution {
+        let Some(handler) = self.handler_registry.get_handler(&job.job_type) else {
+            let message = format!("No handler for job type: {:?}", job.job_type);
+            self.publish(&job, JobStatus::Error, Some(message.clone()));
+            return Err(RunnerError::Execution {
                 job_id: job.id,
-                message: format!("No handler for job type: {:?}", job.job_type),
-            })?;
+                message,
+            });
+        };
 
         // Execute the job
         // FIXME: refine the logic in error
@@ -151,10 +166,12 @@ impl JobRunner {
             Ok(result) => {
                 if result.success {
                     self.repo_job.mark_job_done(job.id, result.output).await?;
+                    self.publish(&job, JobStatus::Done, None);
                 } else {
                     self.repo_job
-                        .update_job_status(job.id, JobStatus::Error, result.error)
+                        .update_job_status(job.id, JobStatus::Error, result.error.clone())
                         .await?;
+                    self.publish(&job, JobStatus::Error, result.error);
                 }
                 Ok(())
             }
@@ -163,10 +180,16 @@ impl JobRunner {
                 self.repo_job
                     .update_job_status(job.id, JobStatus::Error, Some(e.to_string()))
                     .await?;
+                self.publish(&job, JobStatus::Error, Some(e.to_string()));
                 Ok(())
             }
         }
     }
+
+    fn publish(&self, job: &Job, status: JobStatus, error_message: Option<String>) {
+        self.events
+            .publish(Event::Job(JobEvent::new(job, status, error_message)));
+    }
 }
 
 #[cfg(test)]
@@ -178,16 +201,19 @@ mod tests {
 
     use crate::{
         application::{
+            alerts::AlertService,
+            events::{Event, EventBus},
             handlers::{
                 JobHandlerRegistry,
                 create_signals::CreateSignalHandler,
                 create_stock::{CreateStockHandler, CreateStockPayload},
             },
-            model::{Job, JobType},
+            model::{Job, JobStatus, JobType},
             runner::JobRunner,
         },
         infra::storage::{
-            repo_domain_sqlite::SqliteDomainRepository, repo_job_sqlite::SqliteJobRepository,
+            repo_domain_sqlite::SqliteDomainRepository,
+            repo_financial_sqlite::SqliteFinancialRepository, repo_job_sqlite::SqliteJobRepository,
         },
     };
 
@@ -200,10 +226,17 @@ mod tests {
 
     async fn setup_runner(pool: SqlitePool) -> Result<JobRunner, anyhow::Error> {
         let repo_domain = Arc::new(SqliteDomainRepository::new(pool.clone()));
+        let repo_financial = Arc::new(SqliteFinancialRepository::new(pool.clone()));
         let repo_job = Arc::new(SqliteJobRepository::new(pool.clone()));
 
         let create_signal_handler = CreateSignalHandler {
             repo: repo_domain.clone(),
+            retention_days: None,
+            alerts: Arc::new(AlertService {
+                repo: repo_domain.clone(),
+                repo_financial: repo_financial.clone(),
+                sinks: vec![],
+            }),
         };
         let create_stock_handler = CreateStockHandler {
             repo: repo_domain.clone(),
@@ -221,6 +254,7 @@ mod tests {
 
         Ok(JobRunner::new(
             repo_domain,
+            repo_financial,
             repo_job,
             Arc::new(handler_registry),
             concurrency,
@@ -242,7 +276,8 @@ mod tests {
                 Job::new(
                     JobType::CreateStock,
                     json!(CreateStockPayload {
-                        ticker: ticker.to_string(),
+                        ticker: ticker.parse().unwrap(),
+                        refresh: false,
                     }),
                 )
             })
@@ -254,4 +289,29 @@ mod tests {
         let stocks = runner.repo_domain.get_stock_all().await.unwrap();
         assert_eq!(stocks.len(), tickers.len());
     }
+
+    #[tokio::test]
+    async fn test_job_events() {
+        let pool = setup_test_db().await.unwrap();
+        let events = EventBus::new();
+        let runner = setup_runner(pool.clone())
+            .await
+            .unwrap()
+            .with_events(events.clone());
+        let mut rx = events.subscribe();
+
+        // No handler is registered for risk jobs.
+        let job = Job::new(JobType::ComputeRisk, json!({ "account": "main" }));
+        runner.repo_job.create_jobs(vec![job]).await.unwrap();
+        assert!(runner.run().await.is_err());
+
+        let statuses: Vec<JobStatus> = [rx.recv().await.unwrap(), rx.recv().await.unwrap()]
+            .into_iter()
+            .map(|event| match event {
+                Event::Job(e) => e.job_status,
+                other => panic!("unexpected event {other:?}"),
+            })
+            .collect();
+        assert_eq!(statuses, vec![JobStatus::Running, JobStatus::Error]);
+    }
 }
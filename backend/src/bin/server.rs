@@ -1,7 +1,9 @@
 use backend::{
-    application::init_runner,
+    application::{events::EventBus, init_quotes, init_runner},
+    domain::service_calendar::{Exchange, TradingCalendar},
     infra::{http::init_server, storage::Database},
 };
+use chrono::{Duration, Utc};
 use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
@@ -12,9 +14,23 @@ async fn main() -> anyhow::Result<()> {
         .with_env_filter(EnvFilter::from_default_env())
         .init();
 
+    // Holidays are bundled per year, flag calendars that run out within a quarter.
+    for exchange in Exchange::ALL {
+        let calendar = TradingCalendar::get(exchange);
+        if calendar.local_date(Utc::now()) + Duration::days(90) > calendar.covered_until {
+            tracing::warn!(
+                "{:?} holidays are bundled until {}, add the next year to data/calendars",
+                exchange,
+                calendar.covered_until
+            );
+        }
+    }
+
     let database = Database::new().await?;
 
-    let runner = init_runner(&database);
+    let events = EventBus::new();
+    let runner = init_runner(&database, &events);
+    let quotes = init_quotes(&database, &events);
 
-    init_server(database, runner).await
+    init_server(database, runner, quotes).await
 }
@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::{
+    backtest::{
+        portfolio::Portfolio,
+        rules::MarketRules,
+        strategy::{Order, Side},
+    },
+    model::Kline,
+};
+
+/// Proportional commission with a floor per fill.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CommissionModel {
+    pub rate: f64,
+    pub minimum: f64,
+}
+
+impl Default for CommissionModel {
+    fn default() -> Self {
+        Self {
+            rate: 0.00025,
+            minimum: 5.0,
+        }
+    }
+}
+
+impl CommissionModel {
+    pub fn fee(&self, notional: f64) -> f64 {
+        if notional <= 0.0 {
+            return 0.0;
+        }
+        (notional * self.rate).max(self.minimum)
+    }
+}
+
+/// Fixed slippage in basis points against the order.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SlippageModel {
+    pub bps: f64,
+}
+
+impl Default for SlippageModel {
+    fn default() -> Self {
+        Self { bps: 5.0 }
+    }
+}
+
+impl SlippageModel {
+    pub fn apply(&self, price: f64, side: Side) -> f64 {
+        match side {
+            Side::Buy => price * (1.0 + self.bps / 10_000.0),
+            Side::Sell => price * (1.0 - self.bps / 10_000.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Fill {
+    pub date: i64,
+    pub side: Side,
+    pub quantity: f64,
+    pub price: f64,
+    /// Commission and taxes.
+    pub fee: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum Rejection {
+    #[error("insufficient cash")]
+    InsufficientCash,
+    #[error("no position to sell")]
+    NoPosition,
+    #[error("shares bought today settle tomorrow")]
+    Unsettled,
+    #[error("order is below one lot")]
+    BelowLot,
+    #[error("locked at limit-up")]
+    LimitUp,
+    #[error("locked at limit-down")]
+    LimitDown,
+}
+
+/// Fills market orders at the bar's open under the market's rules.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimBroker {
+    pub commission: CommissionModel,
+    pub slippage: SlippageModel,
+    pub rules: MarketRules,
+}
+
+impl SimBroker {
+    /// Commission plus stamp duty on sells and transfer fee on both sides.
+    pub fn fee(&self, side: Side, notional: f64) -> f64 {
+        let tax = match side {
+            Side::Buy => 0.0,
+            Side::Sell => notional * self.rules.stamp_duty,
+        };
+        self.commission.fee(notional) + tax + notional * self.rules.transfer_fee
+    }
+
+    /// Buys are cut down to whole lots cash affords after fees, sells to the settled position.
+    /// Buys at limit-up and sells at limit-down are refused, as no counterparty would fill them.
+    pub fn execute(
+        &self,
+        order: &Order,
+        bar: &Kline,
+        prev_close: Option<f64>,
+        portfolio: &Portfolio,
+    ) -> Result<Fill, Rejection> {
+        let mut price = self.slippage.apply(bar.k_open, order.side);
+
+        if let Some((limit_up, limit_down)) = prev_close.and_then(|c| self.rules.limits(c)) {
+            match order.side {
+                Side::Buy if bar.k_open >= limit_up - 1e-9 => return Err(Rejection::LimitUp),
+                Side::Sell if bar.k_open <= limit_down + 1e-9 => return Err(Rejection::LimitDown),
+                _ => {}
+            }
+            // Slippage can not push a fill beyond the limits.
+            price = price.clamp(limit_down, limit_up);
+        }
+
+        let quantity = match order.side {
+            Side::Buy => {
+                let lot = self.rules.lot_size;
+                let mut quantity = self
+                    .rules
+                    .round_lot(order.quantity.min(portfolio.cash / price));
+                if order.quantity < lot {
+                    return Err(Rejection::BelowLot);
+                }
+                while quantity > 0.0
+                    && quantity * price + self.fee(Side::Buy, quantity * price) > portfolio.cash
+                {
+                    quantity -= lot;
+                }
+                if quantity <= 0.0 {
+                    return Err(Rejection::InsufficientCash);
+                }
+                quantity
+            }
+            Side::Sell => {
+                if portfolio.position <= 0.0 {
+                    return Err(Rejection::NoPosition);
+                }
+                let settled = if self.rules.t_plus_one {
+                    portfolio.settled(bar.k_date)
+                } else {
+                    portfolio.position
+                };
+                if settled <= 0.0 {
+                    return Err(Rejection::Unsettled);
+                }
+                let quantity = order.quantity.min(settled);
+                // Odd lots can only go out together with the whole position.
+                let quantity = if quantity >= portfolio.position {
+                    portfolio.position
+                } else {
+                    self.rules.round_lot(quantity)
+                };
+                if quantity <= 0.0 {
+                    return Err(Rejection::BelowLot);
+                }
+                quantity
+            }
+        };
+
+        Ok(Fill {
+            date: bar.k_date,
+            side: order.side,
+            quantity,
+            price,
+            fee: self.fee(order.side, quantity * price),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(open: f64) -> Kline {
+        bar_on(20251105, open)
+    }
+
+    fn bar_on(date: i64, open: f64) -> Kline {
+        Kline {
+            k_ticker: "1.600635".to_string(),
+            k_date: date,
+            k_open: open,
+            k_high: open,
+            k_low: open,
+            k_close: open,
+            k_volume: 0.0,
+            k_value: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_execute_caps_by_cash_and_position() {
+        let broker = SimBroker::default();
+        let portfolio = Portfolio::new(10_000.0);
+
+        let order = Order {
+            side: Side::Buy,
+            quantity: 1_000.0,
+        };
+        let fill = broker
+            .execute(&order, &bar(10.0), None, &portfolio)
+            .unwrap();
+        assert!((fill.price - 10.005).abs() < 1e-9);
+        assert!(fill.quantity * fill.price + fill.fee <= 10_000.0);
+        assert_eq!(fill.fee, 5.0);
+
+        let order = Order {
+            side: Side::Sell,
+            quantity: 100.0,
+        };
+        assert_eq!(
+            broker.execute(&order, &bar(10.0), None, &portfolio),
+            Err(Rejection::NoPosition)
+        );
+    }
+
+    #[test]
+    fn test_execute_a_share_rules() {
+        let broker = SimBroker {
+            rules: MarketRules::a_share(0.1),
+            ..Default::default()
+        };
+        let mut portfolio = Portfolio::new(10_000.0);
+        let buy = Order {
+            side: Side::Buy,
+            quantity: 1_000.0,
+        };
+
+        // Whole lots only.
+        let fill = broker
+            .execute(&buy, &bar_on(20251103, 10.0), Some(10.0), &portfolio)
+            .unwrap();
+        assert_eq!(fill.quantity, 900.0);
+        portfolio.apply(fill);
+
+        // Locked at limit-up.
+        assert_eq!(
+            broker.execute(&buy, &bar_on(20251104, 11.0), Some(10.0), &portfolio),
+            Err(Rejection::LimitUp)
+        );
+        let below = Order {
+            side: Side::Buy,
+            quantity: 50.0,
+        };
+        assert_eq!(
+            broker.execute(&below, &bar_on(20251104, 10.0), Some(10.0), &portfolio),
+            Err(Rejection::BelowLot)
+        );
+
+        // T+1: not on the day of purchase.
+        let sell = Order {
+            side: Side::Sell,
+            quantity: 900.0,
+        };
+        assert_eq!(
+            broker.execute(&sell, &bar_on(20251103, 10.0), Some(10.0), &portfolio),
+            Err(Rejection::Unsettled)
+        );
+        assert_eq!(
+            broker.execute(&sell, &bar_on(20251104, 9.0), Some(10.0), &portfolio),
+            Err(Rejection::LimitDown)
+        );
+
+        let fill = broker
+            .execute(&sell, &bar_on(20251104, 10.0), Some(10.0), &portfolio)
+            .unwrap();
+        assert_eq!(fill.quantity, 900.0);
+        // Commission floor, stamp duty and transfer fee.
+        let notional = fill.quantity * fill.price;
+        let expected = 5.0 + notional * 0.0005 + notional * 0.00001;
+        assert!((fill.fee - expected).abs() < 1e-9);
+    }
+}
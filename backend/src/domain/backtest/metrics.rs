@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::{backtest::portfolio::Trade, service_level::date_from_i64};
+
+const PERIODS_PER_YEAR: f64 = 252.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EquityPoint {
+    pub date: i64,
+    pub equity: f64,
+    pub position: f64,
+}
+
+/// Ratios are fractions, e.g. a max drawdown of 0.25 is 25%.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Metrics {
+    pub total_return: f64,
+    pub cagr: f64,
+    pub sharpe: f64,
+    pub sortino: f64,
+    pub max_drawdown: f64,
+    pub win_rate: f64,
+    /// Share of bars with a position held.
+    pub exposure: f64,
+    pub trades: usize,
+}
+
+/// Metrics of a daily equity curve, risk-free rate taken as zero.
+pub fn compute_metrics(equity: &[EquityPoint], trades: &[Trade]) -> Metrics {
+    let (Some(first), Some(last)) = (equity.first(), equity.last()) else {
+        return Metrics::default();
+    };
+
+    let total_return = if first.equity > 0.0 {
+        last.equity / first.equity - 1.0
+    } else {
+        0.0
+    };
+
+    let days = match (date_from_i64(first.date), date_from_i64(last.date)) {
+        (Some(a), Some(b)) => (b - a).num_days() as f64,
+        _ => 0.0,
+    };
+    let cagr = if days > 0.0 && total_return > -1.0 {
+        (1.0 + total_return).powf(365.25 / days) - 1.0
+    } else {
+        0.0
+    };
+
+    let returns: Vec<f64> = equity
+        .windows(2)
+        .filter(|w| w[0].equity > 0.0)
+        .map(|w| w[1].equity / w[0].equity - 1.0)
+        .collect();
+    let (sharpe, sortino) = if returns.len() > 1 {
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let std = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+        let downside = (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n).sqrt();
+        let annualize = PERIODS_PER_YEAR.sqrt();
+        (
+            if std > 0.0 {
+                mean / std * annualize
+            } else {
+                0.0
+            },
+            if downside > 0.0 {
+                mean / downside * annualize
+            } else {
+                0.0
+            },
+        )
+    } else {
+        (0.0, 0.0)
+    };
+
+    let mut peak = f64::MIN;
+    let mut max_drawdown: f64 = 0.0;
+    for point in equity {
+        peak = peak.max(point.equity);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max(1.0 - point.equity / peak);
+        }
+    }
+
+    let wins = trades.iter().filter(|t| t.pnl > 0.0).count();
+    let win_rate = if trades.is_empty() {
+        0.0
+    } else {
+        wins as f64 / trades.len() as f64
+    };
+
+    let exposure = equity.iter().filter(|p| p.position > 0.0).count() as f64 / equity.len() as f64;
+
+    Metrics {
+        total_return,
+        cagr,
+        sharpe,
+        sortino,
+        max_drawdown,
+        win_rate,
+        exposure,
+        trades: trades.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(date: i64, equity: f64, position: f64) -> EquityPoint {
+        EquityPoint {
+            date,
+            equity,
+            position,
+        }
+    }
+
+    #[test]
+    fn test_compute_metrics() {
+        let equity = vec![
+            point(20240101, 100.0, 0.0),
+            point(20240601, 120.0, 1.0),
+            point(20240901, 90.0, 1.0),
+            point(20241231, 110.0, 0.0),
+        ];
+        let trade = |pnl: f64| Trade {
+            entry_date: 20240101,
+            exit_date: 20241231,
+            quantity: 1.0,
+            entry_price: 1.0,
+            exit_price: 1.0,
+            pnl,
+            return_pct: 0.0,
+        };
+        let m = compute_metrics(&equity, &[trade(10.0), trade(-5.0)]);
+
+        assert!((m.total_return - 0.1).abs() < 1e-12);
+        assert!((m.cagr - 0.1).abs() < 0.001);
+        assert!((m.max_drawdown - 0.25).abs() < 1e-12);
+        assert_eq!(m.win_rate, 0.5);
+        assert_eq!(m.exposure, 0.5);
+        assert_eq!(m.trades, 2);
+        assert!(m.sharpe > 0.0);
+        assert!(m.sortino > m.sharpe);
+
+        assert_eq!(compute_metrics(&[], &[]), Metrics::default());
+    }
+}
@@ -0,0 +1,312 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::{
+    backtest::{
+        broker::{CommissionModel, Fill, SimBroker, SlippageModel},
+        metrics::{EquityPoint, Metrics, compute_metrics},
+        portfolio::{Portfolio, Trade},
+        rules::MarketRules,
+        strategy::{BarContext, Order, Strategy},
+    },
+    model::Kline,
+};
+
+pub mod broker;
+pub mod metrics;
+pub mod montecarlo;
+pub mod optimize;
+pub mod portfolio;
+pub mod rng;
+pub mod rules;
+pub mod strategy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct BacktestConfig {
+    pub initial_cash: f64,
+    pub commission: CommissionModel,
+    pub slippage: SlippageModel,
+    /// Market rules, derived from the ticker when not given.
+    pub rules: Option<MarketRules>,
+}
+
+impl BacktestConfig {
+    /// Positive cash, non-negative finite costs, and sane market rules when given.
+    pub fn validate(&self) -> Result<(), String> {
+        let non_negative = |name: &str, value: f64| match value.is_finite() && value >= 0.0 {
+            true => Ok(()),
+            false => Err(format!(
+                "{name} = {value}, expected a finite value of 0 or more"
+            )),
+        };
+
+        if !(self.initial_cash.is_finite() && self.initial_cash > 0.0) {
+            return Err(format!(
+                "initial_cash = {}, expected a positive amount",
+                self.initial_cash
+            ));
+        }
+        non_negative("commission.rate", self.commission.rate)?;
+        non_negative("commission.minimum", self.commission.minimum)?;
+        non_negative("slippage.bps", self.slippage.bps)?;
+        if self.slippage.bps >= 10_000.0 {
+            return Err(format!(
+                "slippage.bps = {}, expected below 10000",
+                self.slippage.bps
+            ));
+        }
+
+        if let Some(rules) = &self.rules {
+            if !(rules.lot_size.is_finite() && rules.lot_size > 0.0) {
+                return Err(format!(
+                    "rules.lot_size = {}, expected a positive size",
+                    rules.lot_size
+                ));
+            }
+            if let Some(limit) = rules.price_limit
+                && !(limit > 0.0 && limit < 1.0)
+            {
+                return Err(format!(
+                    "rules.price_limit = {limit}, expected between 0 and 1"
+                ));
+            }
+            non_negative("rules.stamp_duty", rules.stamp_duty)?;
+            non_negative("rules.transfer_fee", rules.transfer_fee)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            initial_cash: 100_000.0,
+            commission: CommissionModel::default(),
+            slippage: SlippageModel::default(),
+            rules: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BacktestReport {
+    pub metrics: Metrics,
+    pub trades: Vec<Trade>,
+    pub fills: Vec<Fill>,
+    /// Orders the broker refused, e.g. for lack of cash.
+    pub rejected: usize,
+    pub equity: Vec<EquityPoint>,
+}
+
+// ---------------------------------------------------------------
+// Event loop, per bar:
+// - fill orders pending from the previous close at this open
+// - mark equity at this close
+// - hand the closed bar to the strategy for new orders
+// Orders placed on the last bar are dropped, open positions stay marked to market.
+// ---------------------------------------------------------------
+pub fn run_backtest(
+    klines: &[Kline],
+    strategy: &mut dyn Strategy,
+    config: &BacktestConfig,
+) -> BacktestReport {
+    run_backtest_range(klines, 0..klines.len(), strategy, config)
+}
+
+/// Trades only bars in `range`, earlier bars serve as indicator warm-up.
+pub fn run_backtest_range(
+    klines: &[Kline],
+    range: Range<usize>,
+    strategy: &mut dyn Strategy,
+    config: &BacktestConfig,
+) -> BacktestReport {
+    let range = range.start.min(klines.len())..range.end.min(klines.len());
+    let klines = &klines[..range.end];
+    let broker = SimBroker {
+        commission: config.commission,
+        slippage: config.slippage,
+        rules: config.rules.unwrap_or_else(|| {
+            klines
+                .first()
+                .and_then(|k| k.k_ticker.parse().ok())
+                .map(|ticker| MarketRules::for_ticker(&ticker, None))
+                .unwrap_or_default()
+        }),
+    };
+    let mut portfolio = Portfolio::new(config.initial_cash);
+    let mut equity = Vec::with_capacity(range.len());
+    let mut pending: Vec<Order> = vec![];
+    let mut rejected = 0;
+
+    strategy.init(klines);
+
+    for (index, bar) in klines.iter().enumerate().skip(range.start) {
+        let prev_close = index.checked_sub(1).map(|i| klines[i].k_close);
+        for order in pending.drain(..) {
+            match broker.execute(&order, bar, prev_close, &portfolio) {
+                Ok(fill) => portfolio.apply(fill),
+                Err(_) => rejected += 1,
+            }
+        }
+
+        let value = portfolio.equity(bar.k_close);
+        equity.push(EquityPoint {
+            date: bar.k_date,
+            equity: value,
+            position: portfolio.position,
+        });
+
+        let ctx = BarContext {
+            index,
+            klines: &klines[..=index],
+            cash: portfolio.cash,
+            position: portfolio.position,
+            equity: value,
+        };
+        pending = strategy.on_bar(&ctx);
+    }
+
+    BacktestReport {
+        metrics: compute_metrics(&equity, &portfolio.trades),
+        trades: portfolio.trades,
+        fills: portfolio.fills,
+        rejected,
+        equity,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::*;
+    use crate::domain::backtest::strategy::{Side, StrategyConfig};
+
+    /// Oscillating daily series so KDJ crosses both ways.
+    pub(crate) fn wave_klines(count: usize) -> Vec<Kline> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        (0..count)
+            .map(|i| {
+                let close = 10.0 + 2.0 * (i as f64 / 8.0).sin() + i as f64 * 0.01;
+                let date = start + Duration::days(i as i64);
+                Kline {
+                    k_ticker: "1.600635".to_string(),
+                    k_date: date.format("%Y%m%d").to_string().parse().unwrap(),
+                    k_open: close - 0.05,
+                    k_high: close + 0.2,
+                    k_low: close - 0.2,
+                    k_close: close,
+                    k_volume: 1e6,
+                    k_value: 1e7,
+                }
+            })
+            .collect()
+    }
+
+    struct BuyAndHold;
+
+    impl Strategy for BuyAndHold {
+        fn on_bar(&mut self, ctx: &BarContext) -> Vec<Order> {
+            if ctx.position > 0.0 {
+                return vec![];
+            }
+            vec![Order {
+                side: Side::Buy,
+                quantity: (ctx.cash / ctx.bar().k_close).floor(),
+            }]
+        }
+    }
+
+    #[test]
+    fn test_run_backtest_buy_and_hold() {
+        let klines = wave_klines(50);
+        let config = BacktestConfig {
+            slippage: SlippageModel { bps: 0.0 },
+            ..Default::default()
+        };
+        let report = run_backtest(&klines, &mut BuyAndHold, &config);
+
+        // Order placed at the first close fills at the second open.
+        assert_eq!(report.fills.len(), 1);
+        assert_eq!(report.fills[0].date, klines[1].k_date);
+        assert_eq!(report.fills[0].price, klines[1].k_open);
+        assert_eq!(report.equity.len(), 50);
+        assert_eq!(report.equity[0].equity, config.initial_cash);
+        assert!(report.trades.is_empty());
+        assert!((report.metrics.exposure - 49.0 / 50.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_run_backtest_range() {
+        let klines = wave_klines(100);
+        let report = run_backtest_range(
+            &klines,
+            60..100,
+            &mut BuyAndHold,
+            &BacktestConfig::default(),
+        );
+        assert_eq!(report.equity.len(), 40);
+        assert_eq!(report.equity[0].date, klines[60].k_date);
+        assert_eq!(report.fills[0].date, klines[61].k_date);
+    }
+
+    #[test]
+    fn test_run_backtest_kdj() {
+        let klines = wave_klines(400);
+        let mut strategy = StrategyConfig::default().build();
+        let report = run_backtest(&klines, strategy.as_mut(), &BacktestConfig::default());
+
+        assert!(!report.trades.is_empty());
+        assert!(report.fills.len() >= report.trades.len() * 2);
+        let fees: f64 = report.fills.iter().map(|f| f.fee).sum();
+        let pnl: f64 = report.trades.iter().map(|t| t.pnl).sum();
+        let last = report.equity.last().unwrap();
+        if last.position == 0.0 {
+            assert!((last.equity - (100_000.0 + pnl)).abs() < 1e-6);
+        }
+        assert!(fees > 0.0);
+        // 1.600635 trades in 100-share lots.
+        assert!(report.fills.iter().all(|f| f.quantity % 100.0 == 0.0));
+        assert!(report.metrics.max_drawdown >= 0.0 && report.metrics.max_drawdown < 1.0);
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert!(BacktestConfig::default().validate().is_ok());
+
+        let invalid = [
+            BacktestConfig {
+                initial_cash: 0.0,
+                ..Default::default()
+            },
+            BacktestConfig {
+                initial_cash: f64::NAN,
+                ..Default::default()
+            },
+            BacktestConfig {
+                commission: CommissionModel {
+                    rate: -0.001,
+                    minimum: 5.0,
+                },
+                ..Default::default()
+            },
+            BacktestConfig {
+                slippage: SlippageModel { bps: f64::NAN },
+                ..Default::default()
+            },
+            BacktestConfig {
+                rules: Some(MarketRules {
+                    lot_size: 0.0,
+                    ..MarketRules::a_share(0.1)
+                }),
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::backtest::{BacktestReport, rng::Rng};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResampleMethod {
+    /// Reorder closed trades, total P&L is kept and only the path changes.
+    #[default]
+    Shuffle,
+    /// Draw blocks of consecutive daily returns with replacement.
+    Bootstrap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct MonteCarloConfig {
+    pub method: ResampleMethod,
+    pub iterations: usize,
+    pub seed: u64,
+    /// Two-sided confidence level of the intervals, e.g. 0.95.
+    pub confidence: f64,
+    /// Bars per block of the bootstrap, keeps short-range autocorrelation.
+    pub block: usize,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self {
+            method: ResampleMethod::default(),
+            iterations: 1_000,
+            seed: 0,
+            confidence: 0.95,
+            block: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Distribution {
+    pub mean: f64,
+    pub std: f64,
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+    /// Confidence interval bounds.
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MonteCarloReport {
+    pub config: MonteCarloConfig,
+    /// Values of the backtest itself.
+    pub observed_return: f64,
+    pub observed_drawdown: f64,
+    pub total_return: Distribution,
+    pub max_drawdown: Distribution,
+    /// Share of samples ending below the initial equity.
+    pub prob_loss: f64,
+}
+
+/// Resamples a backtest `iterations` times, the same seed gives the same report.
+pub fn monte_carlo(
+    report: &BacktestReport,
+    config: &MonteCarloConfig,
+) -> Result<MonteCarloReport, String> {
+    if config.iterations == 0 {
+        return Err("iterations must be positive".to_string());
+    }
+    if !(config.confidence > 0.0 && config.confidence < 1.0) {
+        return Err("confidence must be in (0, 1)".to_string());
+    }
+    let initial = match report.equity.first() {
+        Some(point) if point.equity > 0.0 => point.equity,
+        _ => return Err("backtest has no equity curve".to_string()),
+    };
+
+    let mut rng = Rng::new(config.seed);
+    let mut returns = Vec::with_capacity(config.iterations);
+    let mut drawdowns = Vec::with_capacity(config.iterations);
+
+    match config.method {
+        ResampleMethod::Shuffle => {
+            if report.trades.is_empty() {
+                return Err("backtest has no closed trades".to_string());
+            }
+            let mut pnls: Vec<f64> = report.trades.iter().map(|t| t.pnl).collect();
+            for _ in 0..config.iterations {
+                shuffle(&mut pnls, &mut rng);
+                let path = pnls.iter().scan(initial, |equity, pnl| {
+                    *equity += pnl;
+                    Some(*equity)
+                });
+                let (ret, dd) = path_stats(initial, path);
+                returns.push(ret);
+                drawdowns.push(dd);
+            }
+        }
+        ResampleMethod::Bootstrap => {
+            let daily: Vec<f64> = report
+                .equity
+                .windows(2)
+                .filter(|w| w[0].equity > 0.0)
+                .map(|w| w[1].equity / w[0].equity - 1.0)
+                .collect();
+            if daily.is_empty() {
+                return Err("backtest has fewer than two bars".to_string());
+            }
+            let block = config.block.clamp(1, daily.len());
+            let mut sample = Vec::with_capacity(daily.len());
+            for _ in 0..config.iterations {
+                sample.clear();
+                while sample.len() < daily.len() {
+                    let start = rng.below(daily.len() - block + 1);
+                    let take = block.min(daily.len() - sample.len());
+                    sample.extend_from_slice(&daily[start..start + take]);
+                }
+                let path = sample.iter().scan(initial, |equity, r| {
+                    *equity *= 1.0 + r;
+                    Some(*equity)
+                });
+                let (ret, dd) = path_stats(initial, path);
+                returns.push(ret);
+                drawdowns.push(dd);
+            }
+        }
+    }
+
+    let observed_return = report.metrics.total_return;
+    let prob_loss = returns.iter().filter(|r| **r < 0.0).count() as f64 / returns.len() as f64;
+
+    Ok(MonteCarloReport {
+        config: *config,
+        observed_return,
+        observed_drawdown: report.metrics.max_drawdown,
+        total_return: distribution(returns, config.confidence),
+        max_drawdown: distribution(drawdowns, config.confidence),
+        prob_loss,
+    })
+}
+
+/// Fisher-Yates.
+fn shuffle<T>(items: &mut [T], rng: &mut Rng) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, rng.below(i + 1));
+    }
+}
+
+/// Total return and max drawdown of an equity path starting at `initial`.
+fn path_stats(initial: f64, path: impl Iterator<Item = f64>) -> (f64, f64) {
+    let mut peak = initial;
+    let mut last = initial;
+    let mut max_drawdown: f64 = 0.0;
+    for equity in path {
+        peak = peak.max(equity);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max(1.0 - equity / peak);
+        }
+        last = equity;
+    }
+    (last / initial - 1.0, max_drawdown)
+}
+
+fn distribution(mut values: Vec<f64>, confidence: f64) -> Distribution {
+    values.sort_by(f64::total_cmp);
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std = if values.len() > 1 {
+        (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+    } else {
+        0.0
+    };
+    let tail = (1.0 - confidence) / 2.0;
+    Distribution {
+        mean,
+        std,
+        min: values[0],
+        median: percentile(&values, 0.5),
+        max: values[values.len() - 1],
+        lower: percentile(&values, tail),
+        upper: percentile(&values, 1.0 - tail),
+    }
+}
+
+/// Linear interpolation between closest ranks of sorted values.
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::backtest::{
+        BacktestConfig, run_backtest, strategy::StrategyConfig, tests::wave_klines,
+    };
+
+    #[test]
+    fn test_percentile() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&values, 0.0), 1.0);
+        assert_eq!(percentile(&values, 0.5), 3.0);
+        assert_eq!(percentile(&values, 0.625), 3.5);
+        assert_eq!(percentile(&values, 1.0), 5.0);
+    }
+
+    #[test]
+    fn test_monte_carlo() {
+        let klines = wave_klines(400);
+        let mut strategy = StrategyConfig::default().build();
+        let report = run_backtest(&klines, strategy.as_mut(), &BacktestConfig::default());
+        assert!(report.trades.len() > 2);
+
+        let config = MonteCarloConfig {
+            iterations: 200,
+            seed: 42,
+            ..Default::default()
+        };
+        let shuffled = monte_carlo(&report, &config).unwrap();
+        assert_eq!(shuffled, monte_carlo(&report, &config).unwrap());
+
+        // Reordering keeps the sum of trade P&L.
+        let pnl: f64 = report.trades.iter().map(|t| t.pnl).sum();
+        let initial = report.equity[0].equity;
+        assert!((shuffled.total_return.min - pnl / initial).abs() < 1e-9);
+        assert!((shuffled.total_return.max - pnl / initial).abs() < 1e-9);
+        let dd = &shuffled.max_drawdown;
+        assert!(dd.min <= dd.lower && dd.lower <= dd.median);
+        assert!(dd.median <= dd.upper && dd.upper <= dd.max);
+
+        let bootstrap = monte_carlo(
+            &report,
+            &MonteCarloConfig {
+                method: ResampleMethod::Bootstrap,
+                ..config
+            },
+        )
+        .unwrap();
+        assert!(bootstrap.total_return.std > 0.0);
+        assert!(bootstrap.total_return.lower < bootstrap.total_return.upper);
+        assert!((0.0..=1.0).contains(&bootstrap.prob_loss));
+
+        let other = monte_carlo(
+            &report,
+            &MonteCarloConfig {
+                method: ResampleMethod::Bootstrap,
+                seed: 7,
+                ..config
+            },
+        )
+        .unwrap();
+        assert_ne!(bootstrap.total_return, other.total_return);
+
+        assert!(
+            monte_carlo(
+                &report,
+                &MonteCarloConfig {
+                    iterations: 0,
+                    ..config
+                }
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,461 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::{
+    backtest::{
+        BacktestConfig,
+        metrics::{EquityPoint, Metrics, compute_metrics},
+        rng::Rng,
+        run_backtest_range,
+        strategy::StrategyConfig,
+    },
+    model::Kline,
+};
+
+/// Inclusive range of a parameter, sampled at `step`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ParamRange {
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+}
+
+impl ParamRange {
+    /// Number of values, saturating, without building them.
+    pub fn count(&self) -> Result<usize, String> {
+        if !(self.start.is_finite() && self.end.is_finite() && self.step.is_finite()) {
+            return Err("range bounds and step must be finite".to_string());
+        }
+        if self.step <= 0.0 {
+            return Err(format!("step = {}, expected positive", self.step));
+        }
+        if self.end < self.start {
+            return Err(format!("end = {} before start = {}", self.end, self.start));
+        }
+        // NOTE: float to int casts saturate.
+        Ok(((self.end - self.start) / self.step + 1e-9).floor() as usize + 1)
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        let n = self.count().unwrap_or(1);
+        (0..n).map(|i| self.start + i as f64 * self.step).collect()
+    }
+}
+
+/// Max trials of one parameter search.
+pub const MAX_TRIALS: usize = 1000;
+
+/// Trials a search would run, checked before any is built.
+pub fn trial_count(space: &ParamSpace, method: SearchMethod) -> Result<usize, String> {
+    let mut grid: usize = 1;
+    for (name, range) in space {
+        let n = range.count().map_err(|e| format!("{name}: {e}"))?;
+        grid = grid.saturating_mul(n);
+    }
+
+    Ok(match method {
+        SearchMethod::Grid => grid,
+        SearchMethod::Random { samples, .. } => samples,
+    })
+}
+
+/// Parameter name to range, names as in [StrategyConfig::params].
+pub type ParamSpace = BTreeMap<String, ParamRange>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SearchMethod {
+    Grid,
+    Random { samples: usize, seed: u64 },
+}
+
+/// Metric to maximise, drawdown is minimised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    Sharpe,
+    Sortino,
+    Cagr,
+    TotalReturn,
+    MaxDrawdown,
+    WinRate,
+}
+
+impl Objective {
+    /// Higher is better.
+    pub fn score(&self, m: &Metrics) -> f64 {
+        match self {
+            Objective::Sharpe => m.sharpe,
+            Objective::Sortino => m.sortino,
+            Objective::Cagr => m.cagr,
+            Objective::TotalReturn => m.total_return,
+            Objective::MaxDrawdown => -m.max_drawdown,
+            Objective::WinRate => m.win_rate,
+        }
+    }
+
+    /// The metric as reported.
+    pub fn value(&self, m: &Metrics) -> f64 {
+        match self {
+            Objective::MaxDrawdown => m.max_drawdown,
+            _ => self.score(m),
+        }
+    }
+}
+
+/// Strategy configs to try, in a stable order. Errs beyond [MAX_TRIALS].
+pub fn search_space(
+    base: &StrategyConfig,
+    space: &ParamSpace,
+    method: SearchMethod,
+) -> Result<Vec<StrategyConfig>, String> {
+    let n = trial_count(space, method)?;
+    if n == 0 || n > MAX_TRIALS {
+        return Err(format!("{n} trials, expected 1 to {MAX_TRIALS}"));
+    }
+    let axes: Vec<(&String, Vec<f64>)> = space.iter().map(|(k, r)| (k, r.values())).collect();
+
+    let points: Vec<Vec<f64>> = match method {
+        SearchMethod::Grid => axes.iter().fold(vec![vec![]], |acc, (_, values)| {
+            acc.iter()
+                .flat_map(|prefix| {
+                    values.iter().map(move |v| {
+                        let mut point = prefix.clone();
+                        point.push(*v);
+                        point
+                    })
+                })
+                .collect()
+        }),
+        SearchMethod::Random { samples, seed } => {
+            let mut rng = Rng::new(seed);
+            (0..samples)
+                .map(|_| {
+                    axes.iter()
+                        .map(|(_, values)| values[rng.below(values.len())])
+                        .collect()
+                })
+                .collect()
+        }
+    };
+
+    points
+        .into_iter()
+        .map(|point| {
+            axes.iter()
+                .zip(point)
+                .try_fold(base.clone(), |config, ((name, _), v)| {
+                    config.with_param(name, v)
+                })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Heatmap {
+    pub x_param: String,
+    pub y_param: String,
+    pub objective: Objective,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// `z[i][j]` is the best objective value at `y[i]`, `x[j]`, over all other parameters.
+    pub z: Vec<Vec<Option<f64>>>,
+}
+
+/// Lays out trial results on two parameters for overfitting inspection, a lone sharp
+/// peak among poor neighbours is suspect.
+pub fn heatmap(
+    trials: &[(StrategyConfig, Metrics)],
+    x_param: &str,
+    y_param: &str,
+    objective: Objective,
+) -> Heatmap {
+    let param = |config: &StrategyConfig, name: &str| {
+        config
+            .params()
+            .into_iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v)
+    };
+    let axis = |name: &str| {
+        let mut values: Vec<f64> = trials.iter().filter_map(|(c, _)| param(c, name)).collect();
+        values.sort_by(f64::total_cmp);
+        values.dedup();
+        values
+    };
+    let x = axis(x_param);
+    let y = axis(y_param);
+
+    let mut z: Vec<Vec<Option<(f64, f64)>>> = vec![vec![None; x.len()]; y.len()];
+    for (config, metrics) in trials {
+        let (Some(xv), Some(yv)) = (param(config, x_param), param(config, y_param)) else {
+            continue;
+        };
+        let j = x.iter().position(|v| *v == xv).unwrap();
+        let i = y.iter().position(|v| *v == yv).unwrap();
+        let score = objective.score(metrics);
+        if z[i][j].is_none_or(|(best, _)| score > best) {
+            z[i][j] = Some((score, objective.value(metrics)));
+        }
+    }
+
+    Heatmap {
+        x_param: x_param.to_string(),
+        y_param: y_param.to_string(),
+        objective,
+        x,
+        y,
+        z: z.into_iter()
+            .map(|row| row.into_iter().map(|c| c.map(|(_, v)| v)).collect())
+            .collect(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WalkForwardWindow {
+    pub in_sample_start: i64,
+    pub in_sample_end: i64,
+    pub out_sample_start: i64,
+    pub out_sample_end: i64,
+    pub best: StrategyConfig,
+    pub in_sample_score: f64,
+    pub out_sample_score: f64,
+    pub out_sample: Metrics,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WalkForwardReport {
+    pub objective: Objective,
+    pub windows: Vec<WalkForwardWindow>,
+    /// Metrics of the out-of-sample segments chained together.
+    pub out_sample: Metrics,
+    /// Mean out-of-sample over mean in-sample objective, far below 1 hints at overfitting.
+    pub efficiency: f64,
+}
+
+/// Rolling windows of bar indices, the out-of-sample part follows the in-sample part
+/// and windows advance by the out-of-sample length.
+pub fn walk_forward_windows(
+    bars: usize,
+    in_sample: usize,
+    out_sample: usize,
+) -> Vec<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+    let mut windows = vec![];
+    if in_sample == 0 || out_sample == 0 {
+        return windows;
+    }
+    let mut start = 0;
+    while start + in_sample + out_sample <= bars {
+        let split = start + in_sample;
+        windows.push((start..split, split..split + out_sample));
+        start += out_sample;
+    }
+    windows
+}
+
+/// Optimises on each in-sample window and trades the winner on the following out-of-sample window.
+pub fn walk_forward(
+    klines: &[Kline],
+    candidates: &[StrategyConfig],
+    in_sample: usize,
+    out_sample: usize,
+    objective: Objective,
+    config: &BacktestConfig,
+) -> WalkForwardReport {
+    let mut windows = vec![];
+    let mut chained: Vec<EquityPoint> = vec![];
+    let mut trades = vec![];
+
+    for (is, oos) in walk_forward_windows(klines.len(), in_sample, out_sample) {
+        let best = candidates
+            .iter()
+            .map(|c| {
+                let report = run_backtest_range(klines, is.clone(), c.build().as_mut(), config);
+                (c, objective.score(&report.metrics))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((best, in_sample_score)) = best else {
+            break;
+        };
+
+        let report = run_backtest_range(klines, oos.clone(), best.build().as_mut(), config);
+
+        // Chain segments, each scaled to start where the previous ended.
+        let scale = chained
+            .last()
+            .map(|p| p.equity)
+            .unwrap_or(config.initial_cash)
+            / config.initial_cash;
+        chained.extend(report.equity.iter().map(|p| EquityPoint {
+            equity: p.equity * scale,
+            ..*p
+        }));
+        trades.extend(report.trades.iter().cloned());
+
+        windows.push(WalkForwardWindow {
+            in_sample_start: klines[is.start].k_date,
+            in_sample_end: klines[is.end - 1].k_date,
+            out_sample_start: klines[oos.start].k_date,
+            out_sample_end: klines[oos.end - 1].k_date,
+            best: best.clone(),
+            in_sample_score,
+            out_sample_score: objective.score(&report.metrics),
+            out_sample: report.metrics,
+        });
+    }
+
+    let mean = |f: fn(&WalkForwardWindow) -> f64| {
+        windows.iter().map(f).sum::<f64>() / windows.len().max(1) as f64
+    };
+    let (is_mean, oos_mean) = (mean(|w| w.in_sample_score), mean(|w| w.out_sample_score));
+
+    WalkForwardReport {
+        objective,
+        out_sample: compute_metrics(&chained, &trades),
+        efficiency: if is_mean.abs() > f64::EPSILON {
+            oos_mean / is_mean
+        } else {
+            0.0
+        },
+        windows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::backtest::tests::wave_klines;
+
+    fn space() -> ParamSpace {
+        ParamSpace::from([
+            (
+                "buy_below".to_string(),
+                ParamRange {
+                    start: 20.0,
+                    end: 40.0,
+                    step: 10.0,
+                },
+            ),
+            (
+                "sell_above".to_string(),
+                ParamRange {
+                    start: 60.0,
+                    end: 80.0,
+                    step: 20.0,
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_search_space() {
+        let base = StrategyConfig::default();
+
+        let grid = search_space(&base, &space(), SearchMethod::Grid).unwrap();
+        assert_eq!(grid.len(), 6);
+        assert_eq!(
+            grid[0],
+            StrategyConfig::Kdj {
+                buy_below: 20.0,
+                sell_above: 60.0
+            }
+        );
+
+        let method = SearchMethod::Random {
+            samples: 4,
+            seed: 1,
+        };
+        let a = search_space(&base, &space(), method).unwrap();
+        let b = search_space(&base, &space(), method).unwrap();
+        assert_eq!(a.len(), 4);
+        assert_eq!(a, b);
+        assert!(a.iter().all(|c| grid.contains(c)));
+
+        let bad = ParamSpace::from([(
+            "k".to_string(),
+            ParamRange {
+                start: 1.0,
+                end: 2.0,
+                step: 1.0,
+            },
+        )]);
+        assert!(search_space(&base, &bad, SearchMethod::Grid).is_err());
+    }
+
+    #[test]
+    fn test_trial_count() {
+        assert_eq!(trial_count(&space(), SearchMethod::Grid), Ok(6));
+
+        let range = |start: f64, end: f64, step: f64| ParamRange { start, end, step };
+        let huge = ParamSpace::from([
+            ("buy_below".to_string(), range(0.0, 1e12, 1e-6)),
+            ("sell_above".to_string(), range(0.0, 1e12, 1e-6)),
+        ]);
+        assert_eq!(trial_count(&huge, SearchMethod::Grid), Ok(usize::MAX));
+        assert!(search_space(&StrategyConfig::default(), &huge, SearchMethod::Grid).is_err());
+
+        let method = SearchMethod::Random {
+            samples: usize::MAX,
+            seed: 1,
+        };
+        assert!(search_space(&StrategyConfig::default(), &space(), method).is_err());
+
+        for bad in [
+            range(0.0, 10.0, 0.0),
+            range(0.0, 10.0, -1.0),
+            range(10.0, 0.0, 1.0),
+            range(0.0, f64::INFINITY, 1.0),
+            range(f64::NAN, 10.0, 1.0),
+        ] {
+            let space = ParamSpace::from([("buy_below".to_string(), bad)]);
+            assert!(trial_count(&space, SearchMethod::Grid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let metrics = |sharpe: f64| Metrics {
+            sharpe,
+            ..Default::default()
+        };
+        let trials: Vec<(StrategyConfig, Metrics)> =
+            search_space(&StrategyConfig::default(), &space(), SearchMethod::Grid)
+                .unwrap()
+                .into_iter()
+                .enumerate()
+                .map(|(i, c)| (c, metrics(i as f64)))
+                .collect();
+
+        let map = heatmap(&trials, "buy_below", "sell_above", Objective::Sharpe);
+        assert_eq!(map.x, vec![20.0, 30.0, 40.0]);
+        assert_eq!(map.y, vec![60.0, 80.0]);
+        // Grid order is buy_below major.
+        assert_eq!(map.z[0], vec![Some(0.0), Some(2.0), Some(4.0)]);
+        assert_eq!(map.z[1], vec![Some(1.0), Some(3.0), Some(5.0)]);
+    }
+
+    #[test]
+    fn test_walk_forward() {
+        assert_eq!(
+            walk_forward_windows(100, 50, 20),
+            vec![(0..50, 50..70), (20..70, 70..90)]
+        );
+
+        let klines = wave_klines(300);
+        let candidates =
+            search_space(&StrategyConfig::default(), &space(), SearchMethod::Grid).unwrap();
+        let report = walk_forward(
+            &klines,
+            &candidates,
+            120,
+            60,
+            Objective::TotalReturn,
+            &BacktestConfig::default(),
+        );
+        assert_eq!(report.windows.len(), 3);
+        assert_eq!(report.windows[0].out_sample_start, klines[120].k_date);
+        assert!(candidates.contains(&report.windows[0].best));
+    }
+}
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::backtest::{broker::Fill, strategy::Side};
+
+/// Closed (or partially closed) round trip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Trade {
+    pub entry_date: i64,
+    pub exit_date: i64,
+    pub quantity: f64,
+    /// Average cost per share, fees included.
+    pub entry_price: f64,
+    pub exit_price: f64,
+    /// Net of fees.
+    pub pnl: f64,
+    pub return_pct: f64,
+}
+
+/// Cash and a single-instrument long position.
+#[derive(Debug, Clone)]
+pub struct Portfolio {
+    pub cash: f64,
+    pub position: f64,
+    pub avg_cost: f64,
+    pub entry_date: Option<i64>,
+    /// Date and quantity of the latest day with buys, for T+1.
+    last_buy: Option<(i64, f64)>,
+    pub fills: Vec<Fill>,
+    pub trades: Vec<Trade>,
+}
+
+impl Portfolio {
+    pub fn new(cash: f64) -> Self {
+        Self {
+            cash,
+            position: 0.0,
+            avg_cost: 0.0,
+            entry_date: None,
+            last_buy: None,
+            fills: vec![],
+            trades: vec![],
+        }
+    }
+
+    pub fn equity(&self, price: f64) -> f64 {
+        self.cash + self.position * price
+    }
+
+    /// Shares that may be sold on `date` under T+1.
+    pub fn settled(&self, date: i64) -> f64 {
+        match self.last_buy {
+            Some((bought, quantity)) if bought == date => (self.position - quantity).max(0.0),
+            _ => self.position,
+        }
+    }
+
+    pub fn apply(&mut self, fill: Fill) {
+        let notional = fill.quantity * fill.price;
+        match fill.side {
+            Side::Buy => {
+                self.cash -= notional + fill.fee;
+                self.avg_cost = (self.avg_cost * self.position + notional + fill.fee)
+                    / (self.position + fill.quantity);
+                self.position += fill.quantity;
+                self.entry_date.get_or_insert(fill.date);
+                self.last_buy = match self.last_buy {
+                    Some((date, quantity)) if date == fill.date => {
+                        Some((date, quantity + fill.quantity))
+                    }
+                    _ => Some((fill.date, fill.quantity)),
+                };
+            }
+            Side::Sell => {
+                let proceeds = notional - fill.fee;
+                let cost = self.avg_cost * fill.quantity;
+                self.cash += proceeds;
+                self.position -= fill.quantity;
+                self.trades.push(Trade {
+                    entry_date: self.entry_date.unwrap_or(fill.date),
+                    exit_date: fill.date,
+                    quantity: fill.quantity,
+                    entry_price: self.avg_cost,
+                    exit_price: fill.price,
+                    pnl: proceeds - cost,
+                    return_pct: if cost > 0.0 {
+                        (proceeds - cost) / cost * 100.0
+                    } else {
+                        0.0
+                    },
+                });
+                if self.position <= 0.0 {
+                    self.position = 0.0;
+                    self.avg_cost = 0.0;
+                    self.entry_date = None;
+                }
+            }
+        }
+        self.fills.push(fill);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_round_trip() {
+        let mut p = Portfolio::new(10_000.0);
+        p.apply(Fill {
+            date: 20251103,
+            side: Side::Buy,
+            quantity: 100.0,
+            price: 50.0,
+            fee: 5.0,
+        });
+        assert_eq!(p.cash, 4_995.0);
+        assert_eq!(p.avg_cost, 50.05);
+        assert_eq!(p.equity(55.0), 10_495.0);
+
+        p.apply(Fill {
+            date: 20251105,
+            side: Side::Sell,
+            quantity: 100.0,
+            price: 55.0,
+            fee: 5.0,
+        });
+        assert_eq!(p.position, 0.0);
+        assert_eq!(p.cash, 10_490.0);
+        let trade = &p.trades[0];
+        assert_eq!(trade.entry_date, 20251103);
+        assert!((trade.pnl - 490.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::{service_calendar::Exchange, service_market::Ticker};
+
+/// Exchange rules the simulated broker enforces on top of commission and slippage.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MarketRules {
+    /// Shares bought on a day can only be sold from the next day.
+    pub t_plus_one: bool,
+    /// Buys are rounded down to whole lots, sells too unless closing the position.
+    pub lot_size: f64,
+    /// Daily price limit as a fraction of the previous close, e.g. 0.1 for ±10%.
+    pub price_limit: Option<f64>,
+    /// Tax on sell notional.
+    pub stamp_duty: f64,
+    /// Fee on both sides' notional.
+    pub transfer_fee: f64,
+}
+
+impl Default for MarketRules {
+    /// Unrestricted: T+0, single shares, no limits or taxes.
+    fn default() -> Self {
+        Self {
+            t_plus_one: false,
+            lot_size: 1.0,
+            price_limit: None,
+            stamp_duty: 0.0,
+            transfer_fee: 0.0,
+        }
+    }
+}
+
+impl MarketRules {
+    /// Mainland A-share rules with the given daily limit.
+    pub fn a_share(price_limit: f64) -> Self {
+        Self {
+            t_plus_one: true,
+            lot_size: 100.0,
+            price_limit: Some(price_limit),
+            stamp_duty: 0.0005,
+            transfer_fee: 0.00001,
+        }
+    }
+
+    /// Rules by the exchange of a ticker (`1.600635`, `0.300750`, `105.TSLA`), `realname` flags
+    /// ST names.
+    pub fn for_ticker(ticker: &Ticker, realname: Option<&str>) -> Self {
+        let code = ticker.code();
+        let is_st = realname.is_some_and(|name| name.to_ascii_uppercase().contains("ST"));
+
+        match ticker.exchange() {
+            // SSE, SZSE and the BK sector boards, BSE listings share the SZSE market.
+            Some(Exchange::Sse | Exchange::Szse) => {
+                // NOTE: STAR, ChiNext and BSE keep their limits for ST names too.
+                if ["4", "8", "920"].iter().any(|p| code.starts_with(p)) {
+                    Self::a_share(0.3)
+                } else if ["688", "689", "300", "301"]
+                    .iter()
+                    .any(|p| code.starts_with(p))
+                {
+                    Self::a_share(0.2)
+                } else if is_st {
+                    Self::a_share(0.05)
+                } else {
+                    Self::a_share(0.1)
+                }
+            }
+            // NASDAQ, NYSE, AMEX and HKEX: T+0, whole shares, no limits.
+            _ => Self::default(),
+        }
+    }
+
+    /// Limit-up and limit-down prices, rounded to the cent as the exchanges do.
+    pub fn limits(&self, prev_close: f64) -> Option<(f64, f64)> {
+        self.price_limit.map(|limit| {
+            (
+                (prev_close * (1.0 + limit) * 100.0).round() / 100.0,
+                (prev_close * (1.0 - limit) * 100.0).round() / 100.0,
+            )
+        })
+    }
+
+    pub fn round_lot(&self, quantity: f64) -> f64 {
+        (quantity / self.lot_size).floor() * self.lot_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(s: &str) -> Ticker {
+        s.parse().unwrap()
+    }
+
+    fn limit(secid: &str, realname: Option<&str>) -> Option<f64> {
+        MarketRules::for_ticker(&ticker(secid), realname).price_limit
+    }
+
+    #[test]
+    fn test_main_board() {
+        assert_eq!(limit("1.600635", Some("大众公用")), Some(0.1));
+        assert_eq!(limit("0.000001", Some("平安银行")), Some(0.1));
+        assert_eq!(limit("0.000004", Some("*ST国华")), Some(0.05));
+        assert!(MarketRules::for_ticker(&ticker("90.BK0475"), None).t_plus_one);
+    }
+
+    #[test]
+    fn test_star_board() {
+        assert_eq!(limit("1.688981", None), Some(0.2));
+        assert_eq!(limit("1.689009", None), Some(0.2));
+        assert_eq!(limit("1.688086", Some("*ST紫晶")), Some(0.2));
+    }
+
+    #[test]
+    fn test_chinext_board() {
+        assert_eq!(limit("0.300750", None), Some(0.2));
+        assert_eq!(limit("0.301269", None), Some(0.2));
+        assert_eq!(limit("0.300313", Some("*ST天山")), Some(0.2));
+    }
+
+    #[test]
+    fn test_bse_board() {
+        assert_eq!(limit("0.430047", None), Some(0.3));
+        assert_eq!(limit("0.830799", None), Some(0.3));
+        assert_eq!(limit("0.920118", None), Some(0.3));
+        assert!(MarketRules::for_ticker(&ticker("0.920118"), None).t_plus_one);
+    }
+
+    #[test]
+    fn test_us_and_hk() {
+        let us = MarketRules::for_ticker(&ticker("105.TSLA"), Some("特斯拉"));
+        assert!(!us.t_plus_one);
+        assert_eq!(us.lot_size, 1.0);
+        assert_eq!(us.price_limit, None);
+        assert_eq!(limit("116.00700", None), None);
+    }
+
+    #[test]
+    fn test_limits() {
+        let (up, down) = MarketRules::a_share(0.1).limits(12.34).unwrap();
+        assert_eq!(up, 13.57);
+        assert_eq!(down, 11.11);
+        assert_eq!(MarketRules::a_share(0.1).round_lot(1_299.0), 1_200.0);
+    }
+}
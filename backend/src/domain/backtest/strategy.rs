@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::{
+    model::{BOLL, KDJ, Kline},
+    service_signal::{compute_boll, compute_kdj},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// Market order in shares, filled at the next bar's open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Order {
+    pub side: Side,
+    pub quantity: f64,
+}
+
+/// State handed to a strategy at the close of a bar.
+pub struct BarContext<'a> {
+    pub index: usize,
+    /// History up to and including the current bar.
+    pub klines: &'a [Kline],
+    pub cash: f64,
+    pub position: f64,
+    pub equity: f64,
+}
+
+impl BarContext<'_> {
+    pub fn bar(&self) -> &Kline {
+        &self.klines[self.index]
+    }
+}
+
+pub trait Strategy: Send {
+    /// Called once with the full series before the first bar, e.g. to precompute indicators.
+    /// Only values up to the current bar may be read in `on_bar`.
+    fn init(&mut self, _klines: &[Kline]) {}
+    /// Called at the close of every bar.
+    fn on_bar(&mut self, ctx: &BarContext) -> Vec<Order>;
+}
+
+/// Serializable strategy selection for jobs and the API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StrategyConfig {
+    /// Buy on a KDJ golden cross with K below `buy_below`, sell on K above `sell_above` or a dead cross.
+    Kdj { buy_below: f64, sell_above: f64 },
+    /// Buy on a close below the lower band of `period` bars and `k` std devs, sell above the middle band.
+    Boll { period: usize, k: f64 },
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        StrategyConfig::Kdj {
+            buy_below: 20.0,
+            sell_above: 80.0,
+        }
+    }
+}
+
+impl StrategyConfig {
+    /// Tunable parameters by name.
+    pub fn params(&self) -> Vec<(&'static str, f64)> {
+        match self {
+            StrategyConfig::Kdj {
+                buy_below,
+                sell_above,
+            } => vec![("buy_below", *buy_below), ("sell_above", *sell_above)],
+            StrategyConfig::Boll { period, k } => vec![("period", *period as f64), ("k", *k)],
+        }
+    }
+
+    /// Copy with one parameter replaced.
+    pub fn with_param(&self, name: &str, value: f64) -> Result<Self, String> {
+        let mut config = self.clone();
+        match (&mut config, name) {
+            (StrategyConfig::Kdj { buy_below, .. }, "buy_below") => *buy_below = value,
+            (StrategyConfig::Kdj { sell_above, .. }, "sell_above") => *sell_above = value,
+            (StrategyConfig::Boll { period, .. }, "period") if value >= 1.0 => {
+                *period = value.round() as usize
+            }
+            (StrategyConfig::Boll { k, .. }, "k") => *k = value,
+            _ => return Err(format!("invalid parameter {name} = {value}")),
+        }
+        Ok(config)
+    }
+
+    pub fn build(&self) -> Box<dyn Strategy> {
+        match self {
+            StrategyConfig::Kdj {
+                buy_below,
+                sell_above,
+            } => Box::new(KdjStrategy {
+                buy_below: *buy_below,
+                sell_above: *sell_above,
+                kdjs: vec![],
+            }),
+            StrategyConfig::Boll { period, k } => Box::new(BollStrategy {
+                period: *period,
+                k: *k,
+                bands: vec![],
+            }),
+        }
+    }
+}
+
+/// All-in/all-out long only KDJ strategy.
+pub struct KdjStrategy {
+    pub buy_below: f64,
+    pub sell_above: f64,
+    kdjs: Vec<KDJ>,
+}
+
+impl Strategy for KdjStrategy {
+    fn init(&mut self, klines: &[Kline]) {
+        // KDJ is causal, each value reads only past bars.
+        self.kdjs = compute_kdj(klines);
+    }
+
+    fn on_bar(&mut self, ctx: &BarContext) -> Vec<Order> {
+        let i = ctx.index;
+        if i == 0 || i >= self.kdjs.len() {
+            return vec![];
+        }
+        let (prev, now) = (self.kdjs[i - 1], self.kdjs[i]);
+        let golden = prev.k <= prev.d && now.k > now.d;
+        let dead = prev.k >= prev.d && now.k < now.d;
+
+        if ctx.position <= 0.0 && golden && now.k < self.buy_below {
+            let quantity = (ctx.cash / ctx.bar().k_close).floor();
+            if quantity > 0.0 {
+                return vec![Order {
+                    side: Side::Buy,
+                    quantity,
+                }];
+            }
+        } else if ctx.position > 0.0 && (now.k > self.sell_above || dead) {
+            return vec![Order {
+                side: Side::Sell,
+                quantity: ctx.position,
+            }];
+        }
+
+        vec![]
+    }
+}
+
+/// All-in/all-out long only Bollinger mean reversion.
+pub struct BollStrategy {
+    pub period: usize,
+    pub k: f64,
+    bands: Vec<BOLL>,
+}
+
+impl Strategy for BollStrategy {
+    fn init(&mut self, klines: &[Kline]) {
+        self.bands = compute_boll(klines, self.period, self.k);
+    }
+
+    fn on_bar(&mut self, ctx: &BarContext) -> Vec<Order> {
+        let i = ctx.index;
+        if i + 1 < self.period || i >= self.bands.len() {
+            return vec![];
+        }
+        let close = ctx.bar().k_close;
+        let band = self.bands[i];
+
+        if ctx.position <= 0.0 && close < band.lower {
+            let quantity = (ctx.cash / close).floor();
+            if quantity > 0.0 {
+                return vec![Order {
+                    side: Side::Buy,
+                    quantity,
+                }];
+            }
+        } else if ctx.position > 0.0 && close > band.middle {
+            return vec![Order {
+                side: Side::Sell,
+                quantity: ctx.position,
+            }];
+        }
+
+        vec![]
+    }
+}
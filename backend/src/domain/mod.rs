@@ -1,3 +1,16 @@
+pub mod backtest;
 pub mod model;
 pub mod repository;
+pub mod service_alert;
+pub mod service_calendar;
+pub mod service_confluence;
+pub mod service_financials;
+pub mod service_level;
+pub mod service_market;
+pub mod service_portfolio;
+pub mod service_risk;
+pub mod service_rotation;
+pub mod service_screener;
 pub mod service_signal;
+pub mod service_sizing;
+pub mod service_strength;
@@ -1,7 +1,18 @@
 use serde::{Deserialize, Serialize};
-use sqlx::prelude::FromRow;
+use sqlx::{Type, prelude::FromRow};
 use utoipa::ToSchema;
 
+use crate::domain::{
+    backtest::{
+        BacktestConfig,
+        metrics::Metrics,
+        optimize::{Objective, ParamSpace, SearchMethod},
+        strategy::StrategyConfig,
+    },
+    service_market::Ticker,
+    service_risk::RiskReport,
+};
+
 // HealthCheck record for serialization
 #[derive(Serialize, Debug, FromRow)]
 pub struct User {
@@ -24,6 +35,52 @@ pub struct Stock {
     pub debt: Option<f64>,
 }
 
+/// Fundamentals of a stock as of a refresh, one per ticker and date.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct FundamentalsSnapshot {
+    pub ticker: String,
+    /// yyyy-mm-dd
+    pub snapshot_date: String,
+    pub total_cap: Option<f64>,
+    pub pe: Option<f64>,
+    pub pb: Option<f64>,
+    pub revenue: Option<f64>,
+    pub net: Option<f64>,
+    pub margin: Option<f64>,
+    pub debt: Option<f64>,
+}
+
+impl FundamentalsSnapshot {
+    pub fn new(stock: &Stock, snapshot_date: &str) -> Self {
+        Self {
+            ticker: stock.ticker.clone(),
+            snapshot_date: snapshot_date.to_string(),
+            total_cap: stock.total_cap,
+            pe: stock.pe,
+            pb: stock.pb,
+            revenue: stock.revenue,
+            net: stock.net,
+            margin: stock.margin,
+            debt: stock.debt,
+        }
+    }
+}
+
+/// Latest quote of a ticker, also the row of a persisted snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct Quote {
+    pub ticker: String,
+    pub realname: String,
+    pub last: Option<f64>,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    /// Session volume in lots.
+    pub volume: Option<f64>,
+    pub change_pct: Option<f64>,
+    /// Unix seconds of the exchange quote.
+    pub quoted_at: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Kline {
     pub k_ticker: String,
@@ -36,14 +93,393 @@ pub struct Kline {
     pub k_value: f64,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, Hash, Eq, PartialEq, ToSchema)]
+#[sqlx(type_name = "signal_period")]
+#[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum SignalPeriod {
+    Day,
+    Week,
+}
+
+/// Indicator snapshot of a ticker as of the close of `bar_date` (yyyymmdd).
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Signal {
     pub ticker: String,
+    pub period: SignalPeriod,
+    pub bar_date: i64,
     pub kdj_k: f64,
     pub kdj_d: f64,
     pub boll_dist: f64,
 }
 
+/// Named screener rule, `expression` in the language of `service_screener`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScreenerRule {
+    pub id: i64,
+    pub name: String,
+    pub expression: String,
+    pub rank_by: Option<String>,
+    pub descending: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertField {
+    KdjK,
+    KdjD,
+    BollDist,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertDirection {
+    Above,
+    Below,
+}
+
+/// What an alert rule watches, stored as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Signal field is above/below a value.
+    Threshold {
+        ticker: Ticker,
+        period: SignalPeriod,
+        field: AlertField,
+        direction: AlertDirection,
+        value: f64,
+    },
+    /// KDJ K crossed D on the latest bar, `above` for a golden cross.
+    Cross {
+        ticker: Ticker,
+        period: SignalPeriod,
+        direction: AlertDirection,
+    },
+    /// Screener expression matches the latest signal of the ticker.
+    Pattern {
+        ticker: Ticker,
+        period: SignalPeriod,
+        expression: String,
+    },
+    /// Latest main net inflow of a sector is `multiple` times its average absolute inflow.
+    MoneyflowSpike { ticker: Ticker, multiple: f64 },
+}
+
+impl AlertCondition {
+    pub fn ticker(&self) -> &Ticker {
+        match self {
+            AlertCondition::Threshold { ticker, .. }
+            | AlertCondition::Cross { ticker, .. }
+            | AlertCondition::Pattern { ticker, .. }
+            | AlertCondition::MoneyflowSpike { ticker, .. } => ticker,
+        }
+    }
+
+    /// Signal period the condition reads, `None` for moneyflow.
+    pub fn period(&self) -> Option<SignalPeriod> {
+        match self {
+            AlertCondition::Threshold { period, .. }
+            | AlertCondition::Cross { period, .. }
+            | AlertCondition::Pattern { period, .. } => Some(*period),
+            AlertCondition::MoneyflowSpike { .. } => None,
+        }
+    }
+}
+
+/// Alert rule, `triggered` holds whether the condition was true on the last evaluation.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AlertRule {
+    pub id: i64,
+    pub name: String,
+    #[sqlx(json)]
+    pub condition: AlertCondition,
+    pub active: bool,
+    pub triggered: bool,
+    pub last_fired_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AlertEvent {
+    pub id: i64,
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub ticker: String,
+    pub message: String,
+    pub fired_at: String,
+}
+
+/// Stored backtest summary, the full report is fetched by id.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct BacktestRun {
+    pub id: i64,
+    pub ticker: String,
+    #[sqlx(json)]
+    pub strategy: StrategyConfig,
+    #[sqlx(json)]
+    pub config: BacktestConfig,
+    #[sqlx(json)]
+    pub metrics: Metrics,
+    /// Set for trials of a parameter search.
+    pub optimisation_id: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "optimisation_mode")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum OptimisationMode {
+    /// Trials run as separate backtest jobs.
+    Search,
+    WalkForward,
+}
+
+/// Parameter search or walk-forward analysis of a strategy on a ticker.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Optimisation {
+    pub id: i64,
+    pub ticker: String,
+    pub mode: OptimisationMode,
+    /// Base config, searched parameters are overridden.
+    #[sqlx(json)]
+    pub strategy: StrategyConfig,
+    #[sqlx(json)]
+    #[schema(value_type = Object)]
+    pub space: ParamSpace,
+    #[sqlx(json)]
+    pub method: SearchMethod,
+    #[sqlx(json)]
+    pub objective: Objective,
+    pub created_at: String,
+}
+
+/// Named, ordered group of tickers.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Watchlist {
+    pub id: i64,
+    pub name: String,
+    pub notes: Option<String>,
+    pub created_at: String,
+    /// In list order.
+    #[sqlx(skip)]
+    pub items: Vec<WatchlistItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WatchlistItem {
+    pub ticker: String,
+    pub position: i64,
+    pub notes: Option<String>,
+    pub added_at: String,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Type, ToSchema,
+)]
+#[sqlx(type_name = "currency")]
+#[sqlx(rename_all = "UPPERCASE")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    Cny,
+    Hkd,
+    Usd,
+}
+
+impl Currency {
+    /// Trading currency of a stored ticker from the market registry, CNY if unknown.
+    pub fn for_ticker(ticker: &str) -> Self {
+        ticker
+            .parse::<Ticker>()
+            .ok()
+            .and_then(|t| t.currency())
+            .unwrap_or(Currency::Cny)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "transaction_kind")]
+#[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionKind {
+    Buy,
+    Sell,
+    Dividend,
+    Fee,
+    Deposit,
+    Withdrawal,
+}
+
+/// Ledger entry of an account. Buys and sells use `quantity` and `price`,
+/// the cash-only kinds use `amount`. `fee` is charged on top of any kind.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Transaction {
+    pub id: i64,
+    pub account: String,
+    /// yyyymmdd
+    pub trade_date: i64,
+    pub kind: TransactionKind,
+    pub ticker: Option<String>,
+    pub quantity: f64,
+    pub price: f64,
+    pub amount: f64,
+    pub fee: f64,
+    pub currency: Currency,
+    pub notes: Option<String>,
+    pub created_at: String,
+}
+
+/// CNY per unit of `currency`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct FxRate {
+    pub currency: Currency,
+    pub cny_rate: f64,
+    pub updated_at: String,
+}
+
+/// Stored risk report of an account.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RiskRun {
+    pub id: i64,
+    pub account: String,
+    pub base_currency: Currency,
+    #[sqlx(json)]
+    pub report: RiskReport,
+    pub created_at: String,
+}
+
+/// BK sector in the tracked universe, read by moneyflow and sector signal jobs.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct TrackedSector {
+    #[schema(example = "90.BK1036")]
+    pub ticker: String,
+    #[schema(example = "半导体")]
+    pub realname: String,
+    pub position: i64,
+    pub created_at: String,
+}
+
+/// Constituent of a BK sector board.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct SectorMember {
+    #[schema(example = "90.BK1036")]
+    pub sector: String,
+    #[schema(example = "1.600584")]
+    pub ticker: String,
+    pub realname: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// Reporting period of a statement, A-share figures are cumulative from the start of the year.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, Hash, Eq, PartialEq, ToSchema)]
+#[sqlx(type_name = "report_type")]
+#[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ReportType {
+    Q1,
+    H1,
+    Q3,
+    Annual,
+}
+
+impl ReportType {
+    /// From a period end such as `2025-06-30`, None off the quarter ends.
+    pub fn from_period(period: &str) -> Option<Self> {
+        match period.get(5..10)? {
+            "03-31" => Some(Self::Q1),
+            "06-30" => Some(Self::H1),
+            "09-30" => Some(Self::Q3),
+            "12-31" => Some(Self::Annual),
+            _ => None,
+        }
+    }
+
+    /// Months covered by the cumulative figures.
+    pub fn months(&self) -> u32 {
+        match self {
+            Self::Q1 => 3,
+            Self::H1 => 6,
+            Self::Q3 => 9,
+            Self::Annual => 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct IncomeStatement {
+    pub ticker: String,
+    /// Period end, yyyy-mm-dd.
+    pub period: String,
+    pub report_type: ReportType,
+    pub revenue: Option<f64>,
+    pub operating_cost: Option<f64>,
+    pub operating_profit: Option<f64>,
+    pub total_profit: Option<f64>,
+    pub net_profit: Option<f64>,
+    /// Attributable to shareholders of the parent.
+    pub parent_net_profit: Option<f64>,
+    pub eps: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct BalanceSheet {
+    pub ticker: String,
+    /// Period end, yyyy-mm-dd.
+    pub period: String,
+    pub report_type: ReportType,
+    pub total_assets: Option<f64>,
+    pub total_liabilities: Option<f64>,
+    pub total_equity: Option<f64>,
+    /// Attributable to shareholders of the parent.
+    pub parent_equity: Option<f64>,
+    pub cash: Option<f64>,
+    pub current_assets: Option<f64>,
+    pub current_liabilities: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct CashflowStatement {
+    pub ticker: String,
+    /// Period end, yyyy-mm-dd.
+    pub period: String,
+    pub report_type: ReportType,
+    pub operating_cf: Option<f64>,
+    pub investing_cf: Option<f64>,
+    pub financing_cf: Option<f64>,
+    /// Cash paid for fixed, intangible and other long-term assets.
+    pub capex: Option<f64>,
+}
+
+/// Statements of a ticker, each in chronological order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct FinancialStatements {
+    pub income: Vec<IncomeStatement>,
+    pub balance: Vec<BalanceSheet>,
+    pub cashflow: Vec<CashflowStatement>,
+}
+
+/// Ratios of a period, as fractions. Returns on interim periods are annualised.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct FinancialRatios {
+    pub ticker: String,
+    /// Period end, yyyy-mm-dd.
+    pub period: String,
+    pub report_type: ReportType,
+    pub roe: Option<f64>,
+    pub roa: Option<f64>,
+    pub gross_margin: Option<f64>,
+    /// Operating cash flow less capex, cumulative over the period.
+    pub fcf: Option<f64>,
+    pub debt_to_equity: Option<f64>,
+    /// Against the same period of the previous year.
+    pub revenue_yoy: Option<f64>,
+    /// Against the same period of the previous year.
+    pub net_profit_yoy: Option<f64>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct KDJ {
     pub k: f64,
@@ -57,3 +493,47 @@ pub struct BOLL {
     pub middle: f64,
     pub lower: f64,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelKind {
+    Support,
+    Resistance,
+}
+
+/// Horizontal price zone derived from clustered swing points.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Level {
+    pub price: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub kind: LevelKind,
+    pub touches: usize,
+    pub volume_share: f64,
+    pub strength: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PivotMethod {
+    #[default]
+    Classic,
+    Fibonacci,
+    Camarilla,
+}
+
+/// Pivot points for the session following the bar they are derived from.
+/// `r4`/`s4` are only set by Camarilla.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Pivots {
+    pub method: PivotMethod,
+    pub pivot: f64,
+    pub r1: f64,
+    pub r2: f64,
+    pub r3: f64,
+    pub r4: Option<f64>,
+    pub s1: f64,
+    pub s2: f64,
+    pub s3: f64,
+    pub s4: Option<f64>,
+}
@@ -1,35 +1,303 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 
 use crate::{
-    domain::model::{Signal, Stock},
-    infra::data::moneyflow::MoneyflowEastmoney,
+    domain::{
+        backtest::{
+            BacktestConfig, BacktestReport,
+            optimize::{Objective, ParamSpace, SearchMethod, WalkForwardReport},
+            strategy::StrategyConfig,
+        },
+        model::{
+            AlertCondition, AlertEvent, AlertRule, BacktestRun, Currency, FinancialRatios,
+            FinancialStatements, FundamentalsSnapshot, FxRate, Kline, Optimisation,
+            OptimisationMode, Quote, RiskRun, ScreenerRule, SectorMember, Signal, SignalPeriod,
+            Stock, TrackedSector, Transaction, Watchlist,
+        },
+        service_market::{Isin, Ticker},
+        service_risk::RiskReport,
+        service_strength::StrengthSignal,
+    },
+    infra::data::moneyflow::{MoneyflowEastmoney, MoneyflowStock},
 };
 
 /// Repository for the main domain of candlescyther, it includes stock analysis related persistence.
 /// READ/WRITE stocks, klines, signals, etc.
 #[async_trait]
 pub trait DomainRepository: Send + Sync {
+    /// Upsert on ticker.
     async fn create_stock(&self, stock: Stock) -> Result<(), anyhow::Error>;
-    async fn get_stock(&self, ticker: &str) -> Result<Stock, anyhow::Error>;
+    async fn get_stock(&self, ticker: &Ticker) -> Result<Stock, anyhow::Error>;
     async fn get_stock_all(&self) -> Result<Vec<Stock>, anyhow::Error>;
-    async fn delete_stocks(&self, tickers: &[&str]) -> Result<(), anyhow::Error>;
+    async fn delete_stocks(&self, tickers: &[Ticker]) -> Result<(), anyhow::Error>;
+    /// Upsert on ticker, a stock carries one ISIN.
+    async fn create_stock_isin(&self, ticker: &Ticker, isin: &Isin) -> Result<(), anyhow::Error>;
+    /// Stored stocks linked to an ISIN.
+    async fn get_isin_tickers(&self, isin: &Isin) -> Result<Vec<Ticker>, anyhow::Error>;
+
+    /// Upsert on (ticker, snapshot_date).
+    async fn create_fundamentals_snapshot(
+        &self,
+        snapshot: &FundamentalsSnapshot,
+    ) -> Result<(), anyhow::Error>;
+    /// Snapshots of a ticker in chronological order, from `start` (yyyy-mm-dd) if given.
+    async fn get_fundamentals(
+        &self,
+        ticker: &Ticker,
+        start: Option<&str>,
+    ) -> Result<Vec<FundamentalsSnapshot>, anyhow::Error>;
+
+    async fn create_klines(&self, ticker: &Ticker, klines: &[Kline]) -> Result<(), anyhow::Error>;
+    async fn get_klines(&self, ticker: &Ticker) -> Result<Vec<Kline>, anyhow::Error>;
+
+    /// Upsert on (ticker, period, bar_date), a weekly signal replaces the others of its ISO week.
+    async fn create_signal(&self, signal: Signal) -> Result<(), anyhow::Error>;
+    /// Latest signal per ticker, of sectors (`90.*`) or stocks.
+    async fn get_signals_latest(
+        &self,
+        period: SignalPeriod,
+        sector: bool,
+    ) -> Result<Vec<Signal>, anyhow::Error>;
+    /// Last `n` signals per ticker, of sectors (`90.*`) or stocks, ordered by ticker then date.
+    async fn get_signals_recent(
+        &self,
+        period: SignalPeriod,
+        sector: bool,
+        n: i64,
+    ) -> Result<Vec<Signal>, anyhow::Error>;
+    /// Signals of a ticker in chronological order, bounds inclusive (yyyymmdd).
+    async fn get_signal_series(
+        &self,
+        ticker: &Ticker,
+        period: SignalPeriod,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> Result<Vec<Signal>, anyhow::Error>;
+    /// Last `n` signals of a ticker in chronological order.
+    async fn get_signals_tail(
+        &self,
+        ticker: &Ticker,
+        period: SignalPeriod,
+        n: i64,
+    ) -> Result<Vec<Signal>, anyhow::Error>;
+    /// Deletes signals of a ticker dated before `bar_date` (yyyymmdd).
+    async fn delete_signals_before(
+        &self,
+        ticker: &Ticker,
+        period: SignalPeriod,
+        bar_date: i64,
+    ) -> Result<(), anyhow::Error>;
+
+    /// Upsert on name.
+    async fn create_screener_rule(
+        &self,
+        name: &str,
+        expression: &str,
+        rank_by: Option<&str>,
+        descending: bool,
+    ) -> Result<(), anyhow::Error>;
+    async fn get_screener_rule(&self, name: &str) -> Result<Option<ScreenerRule>, anyhow::Error>;
+    async fn get_screener_rules(&self) -> Result<Vec<ScreenerRule>, anyhow::Error>;
+    async fn delete_screener_rule(&self, name: &str) -> Result<(), anyhow::Error>;
+
+    /// Upsert on name, re-arming the rule.
+    async fn create_alert_rule(
+        &self,
+        name: &str,
+        condition: &AlertCondition,
+    ) -> Result<(), anyhow::Error>;
+    async fn get_alert_rules(&self) -> Result<Vec<AlertRule>, anyhow::Error>;
+    async fn delete_alert_rule(&self, name: &str) -> Result<(), anyhow::Error>;
+    async fn update_alert_state(
+        &self,
+        id: i64,
+        triggered: bool,
+        last_fired_at: Option<&str>,
+    ) -> Result<(), anyhow::Error>;
+    async fn create_alert_event(&self, event: &AlertEvent) -> Result<(), anyhow::Error>;
+    /// Most recent first.
+    async fn get_alert_events(&self, limit: i64) -> Result<Vec<AlertEvent>, anyhow::Error>;
+
+    /// Returns the id of the stored run.
+    async fn create_backtest_run(
+        &self,
+        ticker: &Ticker,
+        strategy: &StrategyConfig,
+        config: &BacktestConfig,
+        report: &BacktestReport,
+        optimisation_id: Option<i64>,
+    ) -> Result<i64, anyhow::Error>;
+    /// Most recent first, of all tickers if `ticker` is None.
+    async fn get_backtest_runs(
+        &self,
+        ticker: Option<&Ticker>,
+    ) -> Result<Vec<BacktestRun>, anyhow::Error>;
+    async fn get_backtest_report(&self, id: i64) -> Result<Option<BacktestReport>, anyhow::Error>;
+
+    /// Returns the id of the stored optimisation.
+    async fn create_optimisation(
+        &self,
+        ticker: &Ticker,
+        mode: OptimisationMode,
+        strategy: &StrategyConfig,
+        space: &ParamSpace,
+        method: &SearchMethod,
+        objective: Objective,
+    ) -> Result<i64, anyhow::Error>;
+    /// Most recent first.
+    async fn get_optimisations(&self) -> Result<Vec<Optimisation>, anyhow::Error>;
+    async fn get_optimisation(&self, id: i64) -> Result<Option<Optimisation>, anyhow::Error>;
+    /// Trials of a search.
+    async fn get_optimisation_runs(&self, id: i64) -> Result<Vec<BacktestRun>, anyhow::Error>;
+    async fn set_walk_forward_report(
+        &self,
+        id: i64,
+        report: &WalkForwardReport,
+    ) -> Result<(), anyhow::Error>;
+    async fn get_walk_forward_report(
+        &self,
+        id: i64,
+    ) -> Result<Option<WalkForwardReport>, anyhow::Error>;
+
+    /// Returns the id of the new watchlist, names are unique.
+    async fn create_watchlist(&self, name: &str, notes: Option<&str>)
+    -> Result<i64, anyhow::Error>;
+    /// Ordered by name, with items.
+    async fn get_watchlists(&self) -> Result<Vec<Watchlist>, anyhow::Error>;
+    async fn get_watchlist(&self, id: i64) -> Result<Option<Watchlist>, anyhow::Error>;
+    /// Fields left `None` are kept, returns false if the watchlist does not exist.
+    async fn update_watchlist(
+        &self,
+        id: i64,
+        name: Option<&str>,
+        notes: Option<&str>,
+    ) -> Result<bool, anyhow::Error>;
+    async fn delete_watchlist(&self, id: i64) -> Result<bool, anyhow::Error>;
+    /// Appends tickers not yet in the list, in the given order.
+    async fn add_watchlist_tickers(&self, id: i64, tickers: &[Ticker])
+    -> Result<(), anyhow::Error>;
+    async fn remove_watchlist_ticker(
+        &self,
+        id: i64,
+        ticker: &Ticker,
+    ) -> Result<bool, anyhow::Error>;
+    async fn set_watchlist_item_notes(
+        &self,
+        id: i64,
+        ticker: &Ticker,
+        notes: Option<&str>,
+    ) -> Result<bool, anyhow::Error>;
+    /// Moves `tickers` to the front in the given order, the rest keep their relative order.
+    async fn reorder_watchlist(&self, id: i64, tickers: &[Ticker]) -> Result<(), anyhow::Error>;
+    /// Tickers in list order.
+    async fn get_watchlist_tickers(&self, id: i64) -> Result<Vec<Ticker>, anyhow::Error>;
 
-    // async fn create_klines(&self, ticker: &str, klines: &[Kline]) -> Result<(), anyhow::Error>;
-    // async fn get_klines(&self, ticker: &str) -> Result<Vec<Kline>, anyhow::Error>;
+    /// Returns the id of the stored transaction, `id` and `created_at` are ignored.
+    async fn create_transaction(&self, tx: &Transaction) -> Result<i64, anyhow::Error>;
+    async fn get_transaction(&self, id: i64) -> Result<Option<Transaction>, anyhow::Error>;
+    /// Ordered by trade date, then id.
+    async fn get_transactions(&self, account: &str) -> Result<Vec<Transaction>, anyhow::Error>;
+    async fn delete_transaction(&self, id: i64) -> Result<bool, anyhow::Error>;
+    async fn get_accounts(&self) -> Result<Vec<String>, anyhow::Error>;
+    async fn get_fx_rates(&self) -> Result<Vec<FxRate>, anyhow::Error>;
+    async fn set_fx_rate(&self, currency: Currency, cny_rate: f64) -> Result<(), anyhow::Error>;
 
-    async fn create_signals_d(&self, signal: Signal) -> Result<(), anyhow::Error>;
-    async fn create_signals_w(&self, signal: Signal) -> Result<(), anyhow::Error>;
-    async fn get_signals_stock_d(&self) -> Result<Vec<Signal>, anyhow::Error>;
-    async fn get_signals_stock_w(&self) -> Result<Vec<Signal>, anyhow::Error>;
-    async fn get_signals_sector_d(&self) -> Result<Vec<Signal>, anyhow::Error>;
-    async fn get_signals_sector_w(&self) -> Result<Vec<Signal>, anyhow::Error>;
-    async fn delete_signals_d(&self) -> Result<(), anyhow::Error>;
-    async fn delete_signals_w(&self) -> Result<(), anyhow::Error>;
+    async fn create_risk_report(
+        &self,
+        account: &str,
+        base: Currency,
+        report: &RiskReport,
+    ) -> Result<i64, anyhow::Error>;
+    /// Most recent report of the account.
+    async fn get_risk_report(&self, account: &str) -> Result<Option<RiskRun>, anyhow::Error>;
 
+    /// Ordered by position.
+    async fn get_tracked_sectors(&self) -> Result<Vec<TrackedSector>, anyhow::Error>;
+    /// Appends a sector, false if already tracked.
+    async fn add_tracked_sector(
+        &self,
+        ticker: &Ticker,
+        realname: &str,
+    ) -> Result<bool, anyhow::Error>;
+    async fn delete_tracked_sector(&self, ticker: &Ticker) -> Result<bool, anyhow::Error>;
+
+    /// Replaces the constituents of a sector.
+    async fn set_sector_members(
+        &self,
+        sector: &Ticker,
+        members: &[SectorMember],
+    ) -> Result<(), anyhow::Error>;
+    /// Stocks in a sector.
+    async fn get_sector_members(&self, sector: &Ticker)
+    -> Result<Vec<SectorMember>, anyhow::Error>;
+    /// Sectors of a stock.
+    async fn get_stock_sectors(&self, ticker: &Ticker) -> Result<Vec<SectorMember>, anyhow::Error>;
+    /// Sectors of every stock, keyed by ticker.
+    async fn get_sector_membership(&self) -> Result<HashMap<String, Vec<String>>, anyhow::Error>;
+
+    /// Upsert on (trade_date, ticker).
     async fn create_mf_sector(&self, flows: &[MoneyflowEastmoney]) -> Result<(), anyhow::Error>;
     async fn get_mf_sector(&self) -> Result<Vec<MoneyflowEastmoney>, anyhow::Error>;
     async fn delete_mf_sector(&self) -> Result<(), anyhow::Error>;
+    /// Drops records before a trading date, returns the number deleted.
+    async fn delete_mf_sector_before(&self, trade_date: &str) -> Result<u64, anyhow::Error>;
+
+    /// Upsert on (trade_date, ticker).
+    async fn create_mf_stock(&self, flows: &[MoneyflowStock]) -> Result<(), anyhow::Error>;
+    /// Ordered by trading date, from `start` if given.
+    async fn get_mf_stock(
+        &self,
+        ticker: &Ticker,
+        start: Option<&str>,
+    ) -> Result<Vec<MoneyflowStock>, anyhow::Error>;
+    /// Drops records of all stocks before a trading date, returns the number deleted.
+    async fn delete_mf_stock_before(&self, trade_date: &str) -> Result<u64, anyhow::Error>;
+
+    /// Upsert on (ticker, bar_date).
+    async fn create_strength_signals(
+        &self,
+        signals: &[StrengthSignal],
+    ) -> Result<(), anyhow::Error>;
+    /// Latest relative strength per ticker, highest rating first.
+    async fn get_strength_latest(&self) -> Result<Vec<StrengthSignal>, anyhow::Error>;
+    /// Relative strength of a ticker in chronological order.
+    async fn get_strength_series(
+        &self,
+        ticker: &Ticker,
+    ) -> Result<Vec<StrengthSignal>, anyhow::Error>;
 
-    async fn get_sector_tickers(&self) -> Result<Vec<String>, anyhow::Error>;
-    async fn get_stock_tickers(&self) -> Result<Vec<String>, anyhow::Error>;
+    /// Upsert on (ticker, quoted_at).
+    async fn create_quote_snapshots(&self, quotes: &[Quote]) -> Result<(), anyhow::Error>;
+    /// Snapshots of a ticker in chronological order, from `since` (unix seconds) if given.
+    async fn get_quote_snapshots(
+        &self,
+        ticker: &Ticker,
+        since: Option<i64>,
+    ) -> Result<Vec<Quote>, anyhow::Error>;
+    /// Drops snapshots of all tickers before `quoted_at`, returns the number deleted.
+    async fn delete_quote_snapshots_before(&self, quoted_at: i64) -> Result<u64, anyhow::Error>;
+
+    async fn get_sector_tickers(&self) -> Result<Vec<Ticker>, anyhow::Error>;
+    async fn get_stock_tickers(&self) -> Result<Vec<Ticker>, anyhow::Error>;
+}
+
+/// Repository of financial statements and the ratios derived from them.
+#[async_trait]
+pub trait FinancialRepository: Send + Sync {
+    /// Upsert of statements and ratios on (ticker, period, report_type).
+    async fn create_financials(
+        &self,
+        statements: &FinancialStatements,
+        ratios: &[FinancialRatios],
+    ) -> Result<(), anyhow::Error>;
+    /// Statements of a ticker in chronological order.
+    async fn get_financials(&self, ticker: &Ticker) -> Result<FinancialStatements, anyhow::Error>;
+    /// Ratios of a ticker in chronological order.
+    async fn get_financial_ratios(
+        &self,
+        ticker: &Ticker,
+    ) -> Result<Vec<FinancialRatios>, anyhow::Error>;
+    /// Ratios of the latest period per ticker.
+    async fn get_financial_ratios_latest(&self) -> Result<Vec<FinancialRatios>, anyhow::Error>;
 }
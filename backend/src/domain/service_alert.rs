@@ -0,0 +1,300 @@
+use crate::{
+    domain::{
+        model::{AlertCondition, AlertDirection, AlertField, FinancialRatios, Signal, Stock},
+        service_screener::{ScreenerRow, evaluate, parse_rule},
+        service_strength::StrengthSignal,
+    },
+    infra::data::moneyflow::MoneyflowEastmoney,
+};
+
+/// Prior records needed before a moneyflow spike can be judged.
+const SPIKE_MIN_HISTORY: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Evaluation {
+    /// Condition holds, with a human readable message.
+    Hit(String),
+    Miss,
+    /// Not enough data to judge, the rule state is left as is.
+    Unknown,
+}
+
+/// Data a condition is evaluated against, all in chronological order.
+#[derive(Default)]
+pub struct AlertInputs<'a> {
+    pub signals: &'a [Signal],
+    pub stock: Option<&'a Stock>,
+    pub flows: &'a [MoneyflowEastmoney],
+    /// Sector tickers, strength and latest ratios of the stock, for pattern rules.
+    pub sectors: &'a [String],
+    pub strength: Option<&'a StrengthSignal>,
+    pub ratios: Option<&'a FinancialRatios>,
+}
+
+pub fn evaluate_condition(condition: &AlertCondition, inputs: &AlertInputs) -> Evaluation {
+    match condition {
+        AlertCondition::Threshold {
+            ticker,
+            field,
+            direction,
+            value,
+            ..
+        } => {
+            let Some(last) = inputs.signals.last() else {
+                return Evaluation::Unknown;
+            };
+            let (name, current) = match field {
+                AlertField::KdjK => ("K", last.kdj_k),
+                AlertField::KdjD => ("D", last.kdj_d),
+                AlertField::BollDist => ("BOLL dist", last.boll_dist),
+            };
+            let hit = match direction {
+                AlertDirection::Above => current > *value,
+                AlertDirection::Below => current < *value,
+            };
+            if hit {
+                Evaluation::Hit(format!(
+                    "{ticker} {name} {current:.2} is {} {value} on {}",
+                    direction_str(*direction),
+                    last.bar_date
+                ))
+            } else {
+                Evaluation::Miss
+            }
+        }
+        AlertCondition::Cross {
+            ticker, direction, ..
+        } => {
+            let [.., prev, last] = inputs.signals else {
+                return Evaluation::Unknown;
+            };
+            let crossed = match direction {
+                AlertDirection::Above => prev.kdj_k <= prev.kdj_d && last.kdj_k > last.kdj_d,
+                AlertDirection::Below => prev.kdj_k >= prev.kdj_d && last.kdj_k < last.kdj_d,
+            };
+            if crossed {
+                Evaluation::Hit(format!(
+                    "{ticker} K {:.2} crossed {} D {:.2} on {}",
+                    last.kdj_k,
+                    direction_str(*direction),
+                    last.kdj_d,
+                    last.bar_date
+                ))
+            } else {
+                Evaluation::Miss
+            }
+        }
+        AlertCondition::Pattern {
+            ticker, expression, ..
+        } => {
+            let Some(last) = inputs.signals.last() else {
+                return Evaluation::Unknown;
+            };
+            let Ok(expr) = parse_rule(expression) else {
+                return Evaluation::Unknown;
+            };
+            let row = ScreenerRow::new(last, inputs.stock, inputs.sectors.to_vec())
+                .with_strength(inputs.strength)
+                .with_financials(inputs.ratios);
+            if evaluate(&expr, &row) {
+                Evaluation::Hit(format!(
+                    "{ticker} matches `{expression}` on {}",
+                    last.bar_date
+                ))
+            } else {
+                Evaluation::Miss
+            }
+        }
+        AlertCondition::MoneyflowSpike { ticker, multiple } => {
+            let [prior @ .., last] = inputs.flows else {
+                return Evaluation::Unknown;
+            };
+            if prior.len() < SPIKE_MIN_HISTORY {
+                return Evaluation::Unknown;
+            }
+            let mean = prior.iter().map(|f| f.lead_value.abs()).sum::<f64>() / prior.len() as f64;
+            if mean > 0.0 && last.lead_value.abs() >= multiple * mean {
+                Evaluation::Hit(format!(
+                    "{ticker} main net inflow {:.0} is {:.1}x its average {:.0} on {}",
+                    last.lead_value,
+                    last.lead_value.abs() / mean,
+                    mean,
+                    last.trade_date
+                ))
+            } else {
+                Evaluation::Miss
+            }
+        }
+    }
+}
+
+fn direction_str(direction: AlertDirection) -> &'static str {
+    match direction {
+        AlertDirection::Above => "above",
+        AlertDirection::Below => "below",
+    }
+}
+
+/// Edge trigger: returns the new rule state and whether to fire.
+/// A rule fires only when its condition turns true, and re-arms once it turns false.
+pub fn next_state(triggered: bool, evaluation: &Evaluation) -> (bool, bool) {
+    match evaluation {
+        Evaluation::Hit(_) => (true, !triggered),
+        Evaluation::Miss => (false, false),
+        Evaluation::Unknown => (triggered, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::SignalPeriod;
+
+    fn signal(bar_date: i64, kdj_k: f64, kdj_d: f64) -> Signal {
+        Signal {
+            ticker: "1.600635".to_string(),
+            period: SignalPeriod::Day,
+            bar_date,
+            kdj_k,
+            kdj_d,
+            boll_dist: 1.0,
+        }
+    }
+
+    fn flow(lead_value: f64) -> MoneyflowEastmoney {
+        MoneyflowEastmoney {
+            trade_date: "2025-11-05".to_string(),
+            ticker: "90.BK0475".to_string(),
+            realname: "bank".to_string(),
+            lead_value,
+            lead_share: 0.0,
+            super_value: 0.0,
+            super_share: 0.0,
+            large_value: 0.0,
+            large_share: 0.0,
+            mid_value: 0.0,
+            mid_share: 0.0,
+            small_value: 0.0,
+            small_share: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_threshold_edge_triggered() {
+        let condition = AlertCondition::Threshold {
+            ticker: "1.600635".parse().unwrap(),
+            period: SignalPeriod::Day,
+            field: AlertField::KdjK,
+            direction: AlertDirection::Below,
+            value: 20.0,
+        };
+
+        let mut triggered = false;
+        let mut fired = vec![];
+        for k in [30.0, 15.0, 10.0, 25.0, 12.0] {
+            let signals = [signal(20251105, k, 50.0)];
+            let inputs = AlertInputs {
+                signals: &signals,
+                ..Default::default()
+            };
+            let (state, fire) = next_state(triggered, &evaluate_condition(&condition, &inputs));
+            triggered = state;
+            fired.push(fire);
+        }
+        assert_eq!(fired, vec![false, true, false, false, true]);
+
+        // Missing data keeps the state.
+        let (state, fire) = next_state(
+            true,
+            &evaluate_condition(&condition, &AlertInputs::default()),
+        );
+        assert!(state);
+        assert!(!fire);
+    }
+
+    #[test]
+    fn test_cross_and_pattern() {
+        let condition = AlertCondition::Cross {
+            ticker: "1.600635".parse().unwrap(),
+            period: SignalPeriod::Day,
+            direction: AlertDirection::Above,
+        };
+        let signals = [signal(20251104, 20.0, 25.0), signal(20251105, 30.0, 26.0)];
+        let inputs = AlertInputs {
+            signals: &signals,
+            ..Default::default()
+        };
+        assert!(matches!(
+            evaluate_condition(&condition, &inputs),
+            Evaluation::Hit(_)
+        ));
+        let inputs = AlertInputs {
+            signals: &signals[1..],
+            ..Default::default()
+        };
+        assert_eq!(evaluate_condition(&condition, &inputs), Evaluation::Unknown);
+
+        let condition = AlertCondition::Pattern {
+            ticker: "1.600635".parse().unwrap(),
+            period: SignalPeriod::Day,
+            expression: "kdj_k > kdj_d AND boll_dist < 2".to_string(),
+        };
+        let inputs = AlertInputs {
+            signals: &signals,
+            ..Default::default()
+        };
+        assert!(matches!(
+            evaluate_condition(&condition, &inputs),
+            Evaluation::Hit(_)
+        ));
+
+        let condition = AlertCondition::Pattern {
+            ticker: "1.600635".parse().unwrap(),
+            period: SignalPeriod::Day,
+            expression: "sector = '90.BK1036'".to_string(),
+        };
+        assert_eq!(evaluate_condition(&condition, &inputs), Evaluation::Miss);
+        let sectors = ["90.BK1036".to_string()];
+        let inputs = AlertInputs {
+            signals: &signals,
+            sectors: &sectors,
+            ..Default::default()
+        };
+        assert!(matches!(
+            evaluate_condition(&condition, &inputs),
+            Evaluation::Hit(_)
+        ));
+    }
+
+    #[test]
+    fn test_moneyflow_spike() {
+        let condition = AlertCondition::MoneyflowSpike {
+            ticker: "90.BK0475".parse().unwrap(),
+            multiple: 3.0,
+        };
+
+        let flows = [flow(1e8), flow(-1e8), flow(1e8)];
+        let inputs = AlertInputs {
+            flows: &flows,
+            ..Default::default()
+        };
+        assert_eq!(evaluate_condition(&condition, &inputs), Evaluation::Unknown);
+
+        let flows = [flow(1e8), flow(-1e8), flow(1e8), flow(-3.5e8)];
+        let inputs = AlertInputs {
+            flows: &flows,
+            ..Default::default()
+        };
+        assert!(matches!(
+            evaluate_condition(&condition, &inputs),
+            Evaluation::Hit(_)
+        ));
+
+        let flows = [flow(1e8), flow(-1e8), flow(1e8), flow(2e8)];
+        let inputs = AlertInputs {
+            flows: &flows,
+            ..Default::default()
+        };
+        assert_eq!(evaluate_condition(&condition, &inputs), Evaluation::Miss);
+    }
+}
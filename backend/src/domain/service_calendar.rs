@@ -0,0 +1,430 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{
+        LazyLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::service_market::Ticker;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Exchange {
+    Sse,
+    Szse,
+    Hkex,
+    Nyse,
+    Nasdaq,
+}
+
+impl Exchange {
+    pub const ALL: [Exchange; 5] = [
+        Exchange::Sse,
+        Exchange::Szse,
+        Exchange::Hkex,
+        Exchange::Nyse,
+        Exchange::Nasdaq,
+    ];
+
+    fn data(&self) -> &'static str {
+        match self {
+            Exchange::Sse => include_str!("../../data/calendars/sse.json"),
+            Exchange::Szse => include_str!("../../data/calendars/szse.json"),
+            Exchange::Hkex => include_str!("../../data/calendars/hkex.json"),
+            Exchange::Nyse => include_str!("../../data/calendars/nyse.json"),
+            Exchange::Nasdaq => include_str!("../../data/calendars/nasdaq.json"),
+        }
+    }
+}
+
+/// Bundled calendar file, times in exchange time.
+#[derive(Deserialize)]
+struct CalendarData {
+    timezone: String,
+    /// Last date the holidays are bundled for.
+    covered_until: String,
+    sessions: Vec<(String, String)>,
+    holidays: Vec<String>,
+    /// Early close of a half-day.
+    half_days: BTreeMap<String, String>,
+}
+
+/// A date of an exchange calendar, times in RFC 3339 UTC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CalendarDay {
+    pub exchange: Exchange,
+    /// yyyy-mm-dd
+    pub date: String,
+    pub trading_day: bool,
+    pub half_day: bool,
+    pub open: Option<String>,
+    pub close: Option<String>,
+    pub previous_trading_day: String,
+    pub next_trading_day: String,
+}
+
+static CALENDARS: LazyLock<HashMap<Exchange, TradingCalendar>> = LazyLock::new(|| {
+    Exchange::ALL
+        .iter()
+        .map(|exchange| (*exchange, TradingCalendar::load(*exchange)))
+        .collect()
+});
+
+/// Whether a lookup past the bundled holidays was logged, per [Exchange::ALL].
+static UNCOVERED_WARNED: [AtomicBool; 5] = [const { AtomicBool::new(false) }; 5];
+
+/// Sessions, holidays and half-days of an exchange.
+///
+/// NOTE: past `covered_until` every weekday counts as a trading day, the first such lookup
+/// logs a warning.
+#[derive(Debug, Clone)]
+pub struct TradingCalendar {
+    pub exchange: Exchange,
+    pub tz: Tz,
+    /// Last date the holidays are bundled for.
+    pub covered_until: NaiveDate,
+    /// Continuous trading windows, auctions excluded.
+    sessions: Vec<(NaiveTime, NaiveTime)>,
+    holidays: BTreeSet<NaiveDate>,
+    half_days: BTreeMap<NaiveDate, NaiveTime>,
+}
+
+fn parse_date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap_or_else(|_| panic!("bad calendar date {s}"))
+}
+
+fn parse_time(s: &str) -> NaiveTime {
+    NaiveTime::parse_from_str(s, "%H:%M").unwrap_or_else(|_| panic!("bad calendar time {s}"))
+}
+
+impl TradingCalendar {
+    /// Parses the bundled file, panics on malformed data.
+    fn load(exchange: Exchange) -> Self {
+        let data: CalendarData =
+            serde_json::from_str(exchange.data()).expect("bundled calendar is valid json");
+
+        Self {
+            exchange,
+            tz: data.timezone.parse().expect("bundled calendar timezone"),
+            covered_until: parse_date(&data.covered_until),
+            sessions: data
+                .sessions
+                .iter()
+                .map(|(open, close)| (parse_time(open), parse_time(close)))
+                .collect(),
+            holidays: data.holidays.iter().map(|d| parse_date(d)).collect(),
+            half_days: data
+                .half_days
+                .iter()
+                .map(|(d, close)| (parse_date(d), parse_time(close)))
+                .collect(),
+        }
+    }
+
+    pub fn get(exchange: Exchange) -> &'static TradingCalendar {
+        &CALENDARS[&exchange]
+    }
+
+    /// Calendar of the exchange a ticker trades on, None for the global indexes.
+    pub fn for_ticker(ticker: &Ticker) -> Option<&'static TradingCalendar> {
+        ticker.exchange().map(TradingCalendar::get)
+    }
+
+    /// Exchange date at `now`.
+    pub fn local_date(&self, now: DateTime<Utc>) -> NaiveDate {
+        now.with_timezone(&self.tz).date_naive()
+    }
+
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        if date > self.covered_until {
+            self.warn_uncovered(date);
+        }
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    fn warn_uncovered(&self, date: NaiveDate) {
+        let idx = Exchange::ALL
+            .iter()
+            .position(|e| *e == self.exchange)
+            .expect("exchange in ALL");
+        if !UNCOVERED_WARNED[idx].swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                "{:?} holidays are bundled until {}, {} and later weekdays count as trading days",
+                self.exchange,
+                self.covered_until,
+                date
+            );
+        }
+    }
+
+    pub fn is_half_day(&self, date: NaiveDate) -> bool {
+        self.is_trading_day(date) && self.half_days.contains_key(&date)
+    }
+
+    /// First trading day after `date`.
+    pub fn next_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut next = date.succ_opt().unwrap();
+        while !self.is_trading_day(next) {
+            next = next.succ_opt().unwrap();
+        }
+        next
+    }
+
+    /// Last trading day before `date`.
+    pub fn previous_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut prev = date.pred_opt().unwrap();
+        while !self.is_trading_day(prev) {
+            prev = prev.pred_opt().unwrap();
+        }
+        prev
+    }
+
+    /// Trading days from `start` to `end` inclusive.
+    pub fn trading_days(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        start
+            .iter_days()
+            .take_while(|d| *d <= end)
+            .filter(|d| self.is_trading_day(*d))
+            .collect()
+    }
+
+    /// Trading windows of `date`, cut at the early close of a half-day, empty on other days.
+    pub fn sessions(&self, date: NaiveDate) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        if !self.is_trading_day(date) {
+            return vec![];
+        }
+        let early_close = self.half_days.get(&date);
+        let at = |time: NaiveTime| {
+            self.tz
+                .from_local_datetime(&date.and_time(time))
+                .earliest()
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        self.sessions
+            .iter()
+            .filter_map(|(open, close)| {
+                let close = early_close.map_or(*close, |early| (*close).min(*early));
+                (*open < close).then(|| (at(*open), at(close)))
+            })
+            .collect()
+    }
+
+    pub fn session_open(&self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        self.sessions(date).first().map(|(open, _)| *open)
+    }
+
+    pub fn session_close(&self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        self.sessions(date).last().map(|(_, close)| *close)
+    }
+
+    /// Whether `now` falls in a trading window.
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.sessions(self.local_date(now))
+            .iter()
+            .any(|(open, close)| *open <= now && now <= *close)
+    }
+
+    /// Whether the session of the exchange date at `now` is over.
+    pub fn is_closed_today(&self, now: DateTime<Utc>) -> bool {
+        self.session_close(self.local_date(now))
+            .is_some_and(|close| now >= close)
+    }
+
+    pub fn day(&self, date: NaiveDate) -> CalendarDay {
+        CalendarDay {
+            exchange: self.exchange,
+            date: date.to_string(),
+            trading_day: self.is_trading_day(date),
+            half_day: self.is_half_day(date),
+            open: self.session_open(date).map(|t| t.to_rfc3339()),
+            close: self.session_close(date).map(|t| t.to_rfc3339()),
+            previous_trading_day: self.previous_trading_day(date).to_string(),
+            next_trading_day: self.next_trading_day(date).to_string(),
+        }
+    }
+
+    /// Trading date the data at `now` belongs to.
+    ///
+    /// Before the open the figures are still those of the previous session, and non-trading
+    /// days roll back to the last trading day.
+    pub fn trade_date(&self, now: DateTime<Utc>) -> NaiveDate {
+        let date = self.local_date(now);
+        match self.session_open(date) {
+            Some(open) if now >= open => date,
+            _ => self.previous_trading_day(date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_date(s)
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_bundled_calendars() {
+        for exchange in Exchange::ALL {
+            let calendar = TradingCalendar::get(exchange);
+            assert_eq!(calendar.exchange, exchange);
+            assert!(!calendar.sessions.is_empty());
+            assert!(calendar.holidays.iter().all(|d| d.year() >= 2025));
+            assert!(
+                calendar
+                    .holidays
+                    .iter()
+                    .all(|d| *d <= calendar.covered_until)
+            );
+            assert!(calendar.covered_until >= date("2026-12-31"));
+        }
+        let tsla = "105.TSLA".parse().unwrap();
+        assert_eq!(
+            TradingCalendar::for_ticker(&tsla).unwrap().tz,
+            chrono_tz::America::New_York
+        );
+        let ndx = "100.NDX".parse().unwrap();
+        assert!(TradingCalendar::for_ticker(&ndx).is_none());
+    }
+
+    #[test]
+    fn test_trading_days() {
+        let sse = TradingCalendar::get(Exchange::Sse);
+        // National Day week.
+        assert!(!sse.is_trading_day(date("2025-10-01")));
+        assert!(!sse.is_trading_day(date("2025-10-04")));
+        assert_eq!(sse.next_trading_day(date("2025-09-30")), date("2025-10-09"));
+        assert_eq!(
+            sse.previous_trading_day(date("2025-10-09")),
+            date("2025-09-30")
+        );
+        assert_eq!(
+            sse.trading_days(date("2025-09-29"), date("2025-10-10")),
+            vec![
+                date("2025-09-29"),
+                date("2025-09-30"),
+                date("2025-10-09"),
+                date("2025-10-10")
+            ]
+        );
+
+        // Thanksgiving closes New York but not Shanghai.
+        let nyse = TradingCalendar::get(Exchange::Nyse);
+        assert!(!nyse.is_trading_day(date("2025-11-27")));
+        assert!(sse.is_trading_day(date("2025-11-27")));
+
+        // 2027: Juneteenth observed on Friday, Lunar New Year after a Sunday second day.
+        assert!(!nyse.is_trading_day(date("2027-06-18")));
+        let hkex = TradingCalendar::get(Exchange::Hkex);
+        assert!(!hkex.is_trading_day(date("2027-02-09")));
+        assert!(hkex.is_half_day(date("2027-02-05")));
+    }
+
+    #[test]
+    fn test_sessions() {
+        let hkex = TradingCalendar::get(Exchange::Hkex);
+        let day = hkex.sessions(date("2025-10-15"));
+        assert_eq!(
+            day,
+            vec![
+                (at("2025-10-15T01:30:00Z"), at("2025-10-15T04:00:00Z")),
+                (at("2025-10-15T05:00:00Z"), at("2025-10-15T08:00:00Z"))
+            ]
+        );
+        // Christmas Eve trades the morning only.
+        assert!(hkex.is_half_day(date("2025-12-24")));
+        assert_eq!(
+            hkex.session_close(date("2025-12-24")),
+            Some(at("2025-12-24T04:00:00Z"))
+        );
+
+        // The day after Thanksgiving closes at 13:00 New York.
+        let nyse = TradingCalendar::get(Exchange::Nyse);
+        assert_eq!(
+            nyse.session_close(date("2025-11-28")),
+            Some(at("2025-11-28T18:00:00Z"))
+        );
+        // Daylight saving time moves the close in UTC.
+        assert_eq!(
+            nyse.session_close(date("2025-07-01")),
+            Some(at("2025-07-01T20:00:00Z"))
+        );
+        assert_eq!(nyse.session_close(date("2025-07-04")), None);
+
+        let day = nyse.day(date("2025-11-28"));
+        assert!(day.half_day);
+        assert_eq!(day.close.as_deref(), Some("2025-11-28T18:00:00+00:00"));
+        assert_eq!(day.previous_trading_day, "2025-11-26");
+    }
+
+    #[test]
+    fn test_is_open() {
+        let sse = TradingCalendar::get(Exchange::Sse);
+        let hkex = TradingCalendar::get(Exchange::Hkex);
+        let nasdaq = TradingCalendar::get(Exchange::Nasdaq);
+
+        // 10:00 Shanghai and Hong Kong on a Wednesday.
+        assert!(sse.is_open(at("2025-10-15T02:00:00Z")));
+        assert!(hkex.is_open(at("2025-10-15T02:00:00Z")));
+        assert!(!nasdaq.is_open(at("2025-10-15T02:00:00Z")));
+        // 12:30 is the lunch break of both.
+        assert!(!sse.is_open(at("2025-10-15T04:30:00Z")));
+        assert!(!hkex.is_open(at("2025-10-15T04:30:00Z")));
+        // 15:30, Hong Kong trades until 16:00.
+        assert!(!sse.is_open(at("2025-10-15T07:30:00Z")));
+        assert!(hkex.is_open(at("2025-10-15T07:30:00Z")));
+        // 10:00 New York.
+        assert!(nasdaq.is_open(at("2025-10-15T14:00:00Z")));
+        // Mid-morning of a holiday.
+        assert!(!sse.is_open(at("2025-10-08T02:00:00Z")));
+    }
+
+    #[test]
+    fn test_trade_date() {
+        let sse = TradingCalendar::get(Exchange::Sse);
+        // Before the open of the first day after the National Day week.
+        assert_eq!(
+            sse.trade_date(at("2025-10-09T01:00:00Z")),
+            date("2025-09-30")
+        );
+        assert_eq!(
+            sse.trade_date(at("2025-10-09T01:30:00Z")),
+            date("2025-10-09")
+        );
+        // Evening of a holiday.
+        assert_eq!(
+            sse.trade_date(at("2025-10-03T12:00:00Z")),
+            date("2025-09-30")
+        );
+
+        let nyse = TradingCalendar::get(Exchange::Nyse);
+        // 18:00 Shanghai is early morning in New York, the US session is not over yet.
+        assert!(sse.is_closed_today(at("2025-10-15T10:00:00Z")));
+        assert!(!nyse.is_closed_today(at("2025-10-15T10:00:00Z")));
+        assert!(nyse.is_closed_today(at("2025-10-15T22:00:00Z")));
+
+        // US sessions during the National Day week keep their own dates.
+        let tsla = TradingCalendar::for_ticker(&"105.TSLA".parse().unwrap()).unwrap();
+        assert_eq!(
+            tsla.trade_date(at("2025-10-02T22:00:00Z")),
+            date("2025-10-02")
+        );
+        assert_eq!(
+            tsla.trade_date(at("2025-10-03T22:00:00Z")),
+            date("2025-10-03")
+        );
+    }
+}
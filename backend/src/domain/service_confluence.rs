@@ -0,0 +1,199 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::model::Signal;
+
+/// Component weights of the composite score, they need not sum to one.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfluenceWeights {
+    pub daily_oversold: f64,
+    pub weekly_turn_up: f64,
+    pub weekly_low: f64,
+    pub daily_boll: f64,
+}
+
+impl Default for ConfluenceWeights {
+    fn default() -> Self {
+        Self {
+            daily_oversold: 0.35,
+            weekly_turn_up: 0.35,
+            weekly_low: 0.15,
+            daily_boll: 0.15,
+        }
+    }
+}
+
+/// KDJ K below which a ticker counts as oversold.
+pub const OVERSOLD_K: f64 = 20.0;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConfluenceComponent {
+    pub name: String,
+    pub weight: f64,
+    /// Score in [0, 1].
+    pub score: f64,
+    /// Points added to the composite, out of 100.
+    pub contribution: f64,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Confluence {
+    pub ticker: String,
+    /// Composite score in [0, 100].
+    pub score: f64,
+    /// Weekly KDJ turning up while daily is oversold.
+    pub setup: bool,
+    pub components: Vec<ConfluenceComponent>,
+}
+
+/// Scores a ticker from its recent daily and weekly signals (chronological, last is latest).
+/// Returns None without at least one signal of each period.
+pub fn compute_confluence(
+    daily: &[Signal],
+    weekly: &[Signal],
+    weights: ConfluenceWeights,
+) -> Option<Confluence> {
+    let d = daily.last()?;
+    let w = weekly.last()?;
+    let w_prev = weekly.len().checked_sub(2).map(|i| &weekly[i]);
+
+    let mut components = vec![];
+
+    // Full score at K <= 20, fading out by K = 40.
+    let score = ((2.0 * OVERSOLD_K - d.kdj_k) / OVERSOLD_K).clamp(0.0, 1.0);
+    components.push((
+        "daily_oversold",
+        weights.daily_oversold,
+        score,
+        format!("daily K {:.1} (oversold below {OVERSOLD_K})", d.kdj_k),
+    ));
+
+    let (score, explanation) = match w_prev {
+        Some(prev) if prev.kdj_k <= prev.kdj_d && w.kdj_k > w.kdj_d => (
+            1.0,
+            format!(
+                "weekly K crossed above D ({:.1} -> {:.1} vs D {:.1})",
+                prev.kdj_k, w.kdj_k, w.kdj_d
+            ),
+        ),
+        Some(prev) if w.kdj_k > prev.kdj_k => (
+            0.6,
+            format!("weekly K rising {:.1} -> {:.1}", prev.kdj_k, w.kdj_k),
+        ),
+        Some(prev) => (
+            0.0,
+            format!("weekly K falling {:.1} -> {:.1}", prev.kdj_k, w.kdj_k),
+        ),
+        None => (0.0, "no prior weekly signal".to_string()),
+    };
+    components.push(("weekly_turn_up", weights.weekly_turn_up, score, explanation));
+
+    // Full score at K <= 20, nothing above 50.
+    let score = ((50.0 - w.kdj_k) / 30.0).clamp(0.0, 1.0);
+    components.push((
+        "weekly_low",
+        weights.weekly_low,
+        score,
+        format!("weekly K {:.1}", w.kdj_k),
+    ));
+
+    // boll_dist is in std devs above the lower band, the middle band sits at 2.
+    let score = (1.0 - d.boll_dist / 2.0).clamp(0.0, 1.0);
+    components.push((
+        "daily_boll",
+        weights.daily_boll,
+        score,
+        format!("daily close {:.2} std above lower band", d.boll_dist),
+    ));
+
+    let total_weight: f64 = components.iter().map(|c| c.1).sum();
+    let components: Vec<ConfluenceComponent> = components
+        .into_iter()
+        .map(|(name, weight, score, explanation)| ConfluenceComponent {
+            name: name.to_string(),
+            weight,
+            score,
+            contribution: if total_weight > 0.0 {
+                weight * score / total_weight * 100.0
+            } else {
+                0.0
+            },
+            explanation,
+        })
+        .collect();
+
+    let turning_up = w_prev.is_some_and(|prev| w.kdj_k > prev.kdj_k);
+
+    Some(Confluence {
+        ticker: d.ticker.clone(),
+        score: components.iter().map(|c| c.contribution).sum(),
+        setup: d.kdj_k < OVERSOLD_K && turning_up,
+        components,
+    })
+}
+
+/// Sorts by setup first, then by score, highest first.
+pub fn rank_confluence(scores: &mut [Confluence]) {
+    scores.sort_by(|a, b| b.setup.cmp(&a.setup).then(b.score.total_cmp(&a.score)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::SignalPeriod;
+
+    fn signal(period: SignalPeriod, bar_date: i64, kdj_k: f64, kdj_d: f64) -> Signal {
+        Signal {
+            ticker: "1.600635".to_string(),
+            period,
+            bar_date,
+            kdj_k,
+            kdj_d,
+            boll_dist: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_compute_confluence_setup() {
+        let daily = vec![signal(SignalPeriod::Day, 20251105, 12.0, 18.0)];
+        let weekly = vec![
+            signal(SignalPeriod::Week, 20251031, 18.0, 22.0),
+            signal(SignalPeriod::Week, 20251107, 25.0, 23.0),
+        ];
+
+        let c = compute_confluence(&daily, &weekly, ConfluenceWeights::default()).unwrap();
+        assert!(c.setup);
+        assert_eq!(c.components.len(), 4);
+        assert_eq!(c.components[1].score, 1.0);
+        assert!(c.components[1].explanation.contains("crossed above"));
+        assert!(c.score > 80.0 && c.score <= 100.0);
+        let sum: f64 = c.components.iter().map(|x| x.contribution).sum();
+        assert!((sum - c.score).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compute_confluence_ranking() {
+        let daily = vec![signal(SignalPeriod::Day, 20251105, 12.0, 18.0)];
+        let falling = vec![
+            signal(SignalPeriod::Week, 20251031, 40.0, 35.0),
+            signal(SignalPeriod::Week, 20251107, 30.0, 33.0),
+        ];
+        let rising = vec![
+            signal(SignalPeriod::Week, 20251031, 30.0, 35.0),
+            signal(SignalPeriod::Week, 20251107, 33.0, 34.0),
+        ];
+
+        let w = ConfluenceWeights::default();
+        let a = compute_confluence(&daily, &falling, w).unwrap();
+        let b = compute_confluence(&daily, &rising, w).unwrap();
+        assert!(!a.setup);
+        assert!(b.setup);
+
+        let mut scores = vec![a, b];
+        rank_confluence(&mut scores);
+        assert!(scores[0].setup);
+
+        assert!(compute_confluence(&daily, &[], w).is_none());
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use crate::domain::model::{
+    BalanceSheet, CashflowStatement, FinancialRatios, FinancialStatements, IncomeStatement,
+};
+
+/// `a / b`, None when either is missing or `b` is not positive.
+fn ratio(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    let (a, b) = (a?, b?);
+    (b > 0.0).then(|| a / b)
+}
+
+/// Same period of the previous year, e.g. `2024-06-30` for `2025-06-30`.
+fn prior_year(period: &str) -> Option<String> {
+    let year: i32 = period.get(..4)?.parse().ok()?;
+    Some(format!("{}{}", year - 1, period.get(4..)?))
+}
+
+/// Ratios of every period with an income statement, in chronological order.
+///
+/// Balance sheet ratios use the closing balances of the period.
+pub fn compute_ratios(statements: &FinancialStatements) -> Vec<FinancialRatios> {
+    let income: BTreeMap<&str, &IncomeStatement> = statements
+        .income
+        .iter()
+        .map(|s| (s.period.as_str(), s))
+        .collect();
+    let balance: BTreeMap<&str, &BalanceSheet> = statements
+        .balance
+        .iter()
+        .map(|s| (s.period.as_str(), s))
+        .collect();
+    let cashflow: BTreeMap<&str, &CashflowStatement> = statements
+        .cashflow
+        .iter()
+        .map(|s| (s.period.as_str(), s))
+        .collect();
+
+    income
+        .values()
+        .map(|inc| {
+            let annualise = 12.0 / inc.report_type.months() as f64;
+            let bal = balance.get(inc.period.as_str());
+            let cf = cashflow.get(inc.period.as_str());
+            let prior = prior_year(&inc.period).and_then(|p| income.get(p.as_str()));
+            let growth = |cur: Option<f64>, prev: Option<f64>| ratio(cur, prev).map(|r| r - 1.0);
+
+            FinancialRatios {
+                ticker: inc.ticker.clone(),
+                period: inc.period.clone(),
+                report_type: inc.report_type,
+                roe: ratio(
+                    inc.parent_net_profit.map(|n| n * annualise),
+                    bal.and_then(|b| b.parent_equity),
+                ),
+                roa: ratio(
+                    inc.net_profit.map(|n| n * annualise),
+                    bal.and_then(|b| b.total_assets),
+                ),
+                gross_margin: ratio(
+                    inc.revenue.zip(inc.operating_cost).map(|(r, c)| r - c),
+                    inc.revenue,
+                ),
+                fcf: cf.and_then(|c| Some(c.operating_cf? - c.capex.unwrap_or(0.0))),
+                debt_to_equity: ratio(
+                    bal.and_then(|b| b.total_liabilities),
+                    bal.and_then(|b| b.total_equity),
+                ),
+                revenue_yoy: growth(inc.revenue, prior.and_then(|p| p.revenue)),
+                net_profit_yoy: growth(
+                    inc.parent_net_profit,
+                    prior.and_then(|p| p.parent_net_profit),
+                ),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::ReportType;
+
+    fn income(period: &str, revenue: f64, parent_net_profit: f64) -> IncomeStatement {
+        IncomeStatement {
+            ticker: "1.600635".to_string(),
+            period: period.to_string(),
+            report_type: ReportType::from_period(period).unwrap(),
+            revenue: Some(revenue),
+            operating_cost: Some(revenue * 0.6),
+            operating_profit: None,
+            total_profit: None,
+            net_profit: Some(parent_net_profit),
+            parent_net_profit: Some(parent_net_profit),
+            eps: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_ratios() {
+        let statements = FinancialStatements {
+            income: vec![
+                income("2025-06-30", 600.0, 60.0),
+                income("2024-06-30", 500.0, 40.0),
+            ],
+            balance: vec![BalanceSheet {
+                ticker: "1.600635".to_string(),
+                period: "2025-06-30".to_string(),
+                report_type: ReportType::H1,
+                total_assets: Some(2_000.0),
+                total_liabilities: Some(1_200.0),
+                total_equity: Some(800.0),
+                parent_equity: Some(600.0),
+                cash: None,
+                current_assets: None,
+                current_liabilities: None,
+            }],
+            cashflow: vec![CashflowStatement {
+                ticker: "1.600635".to_string(),
+                period: "2025-06-30".to_string(),
+                report_type: ReportType::H1,
+                operating_cf: Some(90.0),
+                investing_cf: Some(-50.0),
+                financing_cf: None,
+                capex: Some(30.0),
+            }],
+        };
+
+        let ratios = compute_ratios(&statements);
+        assert_eq!(ratios.len(), 2);
+        assert_eq!(ratios[0].period, "2024-06-30");
+        assert_eq!(ratios[0].roe, None);
+        assert_eq!(ratios[0].revenue_yoy, None);
+
+        let h1 = &ratios[1];
+        // Half-year profit annualised.
+        assert!((h1.roe.unwrap() - 0.2).abs() < 1e-12);
+        assert!((h1.roa.unwrap() - 0.06).abs() < 1e-12);
+        assert!((h1.gross_margin.unwrap() - 0.4).abs() < 1e-12);
+        assert_eq!(h1.fcf, Some(60.0));
+        assert!((h1.debt_to_equity.unwrap() - 1.5).abs() < 1e-12);
+        assert!((h1.revenue_yoy.unwrap() - 0.2).abs() < 1e-12);
+        assert!((h1.net_profit_yoy.unwrap() - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_report_type_from_period() {
+        assert_eq!(ReportType::from_period("2025-03-31"), Some(ReportType::Q1));
+        assert_eq!(
+            ReportType::from_period("2024-12-31 00:00:00"),
+            Some(ReportType::Annual)
+        );
+        assert_eq!(ReportType::from_period("2025-05-31"), None);
+        assert_eq!(prior_year("2025-09-30").as_deref(), Some("2024-09-30"));
+    }
+}
@@ -0,0 +1,439 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::domain::model::{Kline, Level, LevelKind, PivotMethod, Pivots};
+
+/// Tuning knobs for [compute_levels].
+#[derive(Debug, Clone, Copy)]
+pub struct LevelParams {
+    /// Bars on each side a swing high/low must dominate.
+    pub swing_window: usize,
+    /// Max distance in percent between a swing point and a cluster centre.
+    pub tolerance_pct: f64,
+    /// Number of price bins of the volume-at-price profile.
+    pub volume_bins: usize,
+}
+
+impl Default for LevelParams {
+    fn default() -> Self {
+        Self {
+            swing_window: 3,
+            tolerance_pct: 1.5,
+            volume_bins: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwingPoint {
+    pub index: usize,
+    pub price: f64,
+    pub is_high: bool,
+}
+
+/// Finds swing highs and lows, i.e. bars whose high (low) is the extreme of the
+/// `window` bars on both sides.
+///
+/// # Arguments
+/// * `klines` - Slice of candles (must be in chronological order).
+/// * `window` - Bars on each side to compare against.
+pub fn compute_swing_points(klines: &[Kline], window: usize) -> Vec<SwingPoint> {
+    let mut points = vec![];
+    if window == 0 || klines.len() < 2 * window + 1 {
+        return points;
+    }
+
+    for i in window..klines.len() - window {
+        let left = &klines[i - window..i];
+        let right = &klines[i + 1..=i + window];
+        let high = klines[i].k_high;
+        let low = klines[i].k_low;
+
+        // NOTE: strict on the left, loose on the right, so a flat top yields one point.
+        if left.iter().all(|k| k.k_high < high) && right.iter().all(|k| k.k_high <= high) {
+            points.push(SwingPoint {
+                index: i,
+                price: high,
+                is_high: true,
+            });
+        }
+        if left.iter().all(|k| k.k_low > low) && right.iter().all(|k| k.k_low >= low) {
+            points.push(SwingPoint {
+                index: i,
+                price: low,
+                is_high: false,
+            });
+        }
+    }
+
+    points
+}
+
+/// Volume-at-price histogram. Each bar's volume is spread evenly over its high-low range.
+///
+/// # Returns
+/// `(lowest price, bin width, volume per bin)`.
+pub fn compute_volume_profile(klines: &[Kline], bins: usize) -> (f64, f64, Vec<f64>) {
+    let lowest = klines.iter().map(|k| k.k_low).fold(f64::INFINITY, f64::min);
+    let highest = klines
+        .iter()
+        .map(|k| k.k_high)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    if bins == 0 || !lowest.is_finite() || !highest.is_finite() || highest <= lowest {
+        return (lowest, 0.0, vec![]);
+    }
+
+    let width = (highest - lowest) / bins as f64;
+    let mut profile = vec![0.0; bins];
+    let bin_of = |price: f64| (((price - lowest) / width) as usize).min(bins - 1);
+
+    for k in klines {
+        let range = k.k_high - k.k_low;
+        if range <= 0.0 {
+            profile[bin_of(k.k_close)] += k.k_volume;
+            continue;
+        }
+        for (b, volume) in profile
+            .iter_mut()
+            .enumerate()
+            .take(bin_of(k.k_high) + 1)
+            .skip(bin_of(k.k_low))
+        {
+            let bin_low = lowest + b as f64 * width;
+            let overlap = k.k_high.min(bin_low + width) - k.k_low.max(bin_low);
+            if overlap > 0.0 {
+                *volume += k.k_volume * overlap / range;
+            }
+        }
+    }
+
+    (lowest, width, profile)
+}
+
+/// Derives horizontal support/resistance zones by clustering swing points and
+/// weighting each cluster by the traded volume at its price.
+///
+/// # Returns
+/// Levels sorted by price ascending. Levels at or below the last close are supports.
+pub fn compute_levels(klines: &[Kline], params: LevelParams) -> Vec<Level> {
+    let Some(last) = klines.last() else {
+        return vec![];
+    };
+
+    let mut prices: Vec<f64> = compute_swing_points(klines, params.swing_window)
+        .iter()
+        .map(|p| p.price)
+        .collect();
+    prices.sort_by(|a, b| a.total_cmp(b));
+
+    let mut clusters: Vec<Vec<f64>> = vec![];
+    for price in prices {
+        match clusters.last_mut() {
+            Some(cluster) if within_pct(mean(cluster), price, params.tolerance_pct) => {
+                cluster.push(price)
+            }
+            _ => clusters.push(vec![price]),
+        }
+    }
+
+    let (lowest, width, profile) = compute_volume_profile(klines, params.volume_bins);
+    let total_volume: f64 = profile.iter().sum();
+    let mean_volume = if profile.is_empty() {
+        0.0
+    } else {
+        total_volume / profile.len() as f64
+    };
+
+    clusters
+        .iter()
+        .map(|cluster| {
+            let price = mean(cluster);
+            let bin_volume = if width > 0.0 {
+                let bin = (((price - lowest) / width) as usize).min(profile.len() - 1);
+                profile[bin]
+            } else {
+                0.0
+            };
+            let volume_share = if total_volume > 0.0 {
+                bin_volume / total_volume
+            } else {
+                0.0
+            };
+            let relative_volume = if mean_volume > 0.0 {
+                bin_volume / mean_volume
+            } else {
+                0.0
+            };
+
+            Level {
+                price,
+                lower: cluster.first().copied().unwrap_or(price),
+                upper: cluster.last().copied().unwrap_or(price),
+                kind: if price <= last.k_close {
+                    LevelKind::Support
+                } else {
+                    LevelKind::Resistance
+                },
+                touches: cluster.len(),
+                volume_share,
+                strength: cluster.len() as f64 * (1.0 + relative_volume),
+            }
+        })
+        .collect()
+}
+
+/// Closest support at or below `price`.
+pub fn nearest_support(levels: &[Level], price: f64) -> Option<&Level> {
+    levels
+        .iter()
+        .filter(|l| l.kind == LevelKind::Support && l.price <= price)
+        .max_by(|a, b| a.price.total_cmp(&b.price))
+}
+
+/// Computes pivot points from the high, low and close of a single bar.
+pub fn compute_pivots(high: f64, low: f64, close: f64, method: PivotMethod) -> Pivots {
+    let pivot = (high + low + close) / 3.0;
+    let range = high - low;
+
+    match method {
+        PivotMethod::Classic => Pivots {
+            method,
+            pivot,
+            r1: 2.0 * pivot - low,
+            r2: pivot + range,
+            r3: high + 2.0 * (pivot - low),
+            r4: None,
+            s1: 2.0 * pivot - high,
+            s2: pivot - range,
+            s3: low - 2.0 * (high - pivot),
+            s4: None,
+        },
+        PivotMethod::Fibonacci => Pivots {
+            method,
+            pivot,
+            r1: pivot + 0.382 * range,
+            r2: pivot + 0.618 * range,
+            r3: pivot + range,
+            r4: None,
+            s1: pivot - 0.382 * range,
+            s2: pivot - 0.618 * range,
+            s3: pivot - range,
+            s4: None,
+        },
+        PivotMethod::Camarilla => Pivots {
+            method,
+            pivot,
+            r1: close + range * 1.1 / 12.0,
+            r2: close + range * 1.1 / 6.0,
+            r3: close + range * 1.1 / 4.0,
+            r4: Some(close + range * 1.1 / 2.0),
+            s1: close - range * 1.1 / 12.0,
+            s2: close - range * 1.1 / 6.0,
+            s3: close - range * 1.1 / 4.0,
+            s4: Some(close - range * 1.1 / 2.0),
+        },
+    }
+}
+
+/// Pivots for the next day, from the last daily bar.
+pub fn compute_pivots_day(klines: &[Kline], method: PivotMethod) -> Option<Pivots> {
+    klines
+        .last()
+        .map(|k| compute_pivots(k.k_high, k.k_low, k.k_close, method))
+}
+
+/// Pivots for the week after the last completed ISO week of daily bars.
+/// The week of `today` counts as completed from its Saturday on.
+pub fn compute_pivots_week(
+    klines: &[Kline],
+    method: PivotMethod,
+    today: NaiveDate,
+) -> Option<Pivots> {
+    let weekend = today.weekday().number_from_monday() > 5;
+    let week = resample_week(klines).into_iter().rev().find(|k| {
+        weekend || date_from_i64(k.k_date).is_some_and(|d| d.iso_week() != today.iso_week())
+    })?;
+    Some(compute_pivots(
+        week.k_high,
+        week.k_low,
+        week.k_close,
+        method,
+    ))
+}
+
+/// Aggregates daily klines into ISO-week klines dated on the week's last bar.
+pub fn resample_week(klines: &[Kline]) -> Vec<Kline> {
+    let mut weeks: Vec<(chrono::IsoWeek, Kline)> = vec![];
+
+    for k in klines {
+        let Some(week) = date_from_i64(k.k_date).map(|d| d.iso_week()) else {
+            continue;
+        };
+        match weeks.last_mut() {
+            Some((w, agg)) if *w == week => {
+                agg.k_date = k.k_date;
+                agg.k_high = agg.k_high.max(k.k_high);
+                agg.k_low = agg.k_low.min(k.k_low);
+                agg.k_close = k.k_close;
+                agg.k_volume += k.k_volume;
+                agg.k_value += k.k_value;
+            }
+            _ => weeks.push((week, k.clone())),
+        }
+    }
+
+    weeks.into_iter().map(|(_, k)| k).collect()
+}
+
+/// Converts `yyyymmdd` as stored in `k_date` into a date.
+pub fn date_from_i64(date: i64) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(
+        (date / 10000) as i32,
+        ((date / 100) % 100) as u32,
+        (date % 100) as u32,
+    )
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn within_pct(base: f64, price: f64, pct: f64) -> bool {
+    base != 0.0 && ((price - base) / base).abs() * 100.0 <= pct
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kline(date: i64, high: f64, low: f64, close: f64, volume: f64) -> Kline {
+        Kline {
+            k_ticker: "1.600635".to_string(),
+            k_date: date,
+            k_open: close,
+            k_high: high,
+            k_low: low,
+            k_close: close,
+            k_volume: volume,
+            k_value: volume * close,
+        }
+    }
+
+    /// Zigzag between ~10 and ~12 with a final close of 11.
+    fn zigzag() -> Vec<Kline> {
+        let closes = [
+            11.0, 10.5, 10.0, 10.5, 11.0, 11.5, 12.0, 11.5, 11.0, 10.5, 10.05, 10.5, 11.0, 11.5,
+            11.95, 11.5, 11.0,
+        ];
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| kline(20250101 + i as i64, c + 0.1, c - 0.1, *c, 1000.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_swing_points() {
+        let points = compute_swing_points(&zigzag(), 2);
+
+        let highs: Vec<usize> = points
+            .iter()
+            .filter(|p| p.is_high)
+            .map(|p| p.index)
+            .collect();
+        let lows: Vec<usize> = points
+            .iter()
+            .filter(|p| !p.is_high)
+            .map(|p| p.index)
+            .collect();
+
+        assert_eq!(highs, vec![6, 14]);
+        assert_eq!(lows, vec![2, 10]);
+    }
+
+    #[test]
+    fn test_levels_cluster_and_classify() {
+        let params = LevelParams {
+            swing_window: 2,
+            tolerance_pct: 1.0,
+            volume_bins: 10,
+        };
+        let levels = compute_levels(&zigzag(), params);
+
+        assert_eq!(levels.len(), 2);
+
+        let support = &levels[0];
+        assert_eq!(support.kind, LevelKind::Support);
+        assert_eq!(support.touches, 2);
+        assert!((support.price - 9.925).abs() < 1e-9);
+
+        let resistance = &levels[1];
+        assert_eq!(resistance.kind, LevelKind::Resistance);
+        assert_eq!(resistance.touches, 2);
+        assert!((resistance.price - 12.075).abs() < 1e-9);
+
+        assert_eq!(nearest_support(&levels, 11.0).unwrap().price, support.price);
+        assert!(nearest_support(&levels, 9.0).is_none());
+    }
+
+    #[test]
+    fn test_volume_profile_conserves_volume() {
+        let klines = zigzag();
+        let (_, _, profile) = compute_volume_profile(&klines, 20);
+
+        let total: f64 = profile.iter().sum();
+        assert!((total - 1000.0 * klines.len() as f64).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pivots() {
+        let classic = compute_pivots(12.0, 10.0, 11.0, PivotMethod::Classic);
+        assert_eq!(classic.pivot, 11.0);
+        assert_eq!(classic.r1, 12.0);
+        assert_eq!(classic.s1, 10.0);
+        assert_eq!(classic.r2, 13.0);
+        assert_eq!(classic.s2, 9.0);
+        assert_eq!(classic.r3, 14.0);
+        assert_eq!(classic.s3, 8.0);
+
+        let fib = compute_pivots(12.0, 10.0, 11.0, PivotMethod::Fibonacci);
+        assert!((fib.r1 - 11.764).abs() < 1e-9);
+        assert!((fib.s2 - 9.764).abs() < 1e-9);
+        assert_eq!(fib.r3, 13.0);
+
+        let cam = compute_pivots(12.0, 10.0, 11.0, PivotMethod::Camarilla);
+        assert!((cam.r3 - 11.55).abs() < 1e-9);
+        assert!((cam.s4.unwrap() - 9.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_resample_week() {
+        // 2025-01-02 (Thu) .. 2025-01-07 (Tue) spans two ISO weeks.
+        let klines = vec![
+            kline(20250102, 11.0, 9.0, 10.0, 100.0),
+            kline(20250103, 12.0, 10.0, 11.0, 100.0),
+            kline(20250106, 13.0, 11.0, 12.0, 100.0),
+            kline(20250107, 12.5, 10.5, 11.5, 100.0),
+        ];
+        let weeks = resample_week(&klines);
+
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].k_date, 20250103);
+        assert_eq!(weeks[0].k_high, 12.0);
+        assert_eq!(weeks[0].k_low, 9.0);
+        assert_eq!(weeks[0].k_close, 11.0);
+        assert_eq!(weeks[1].k_volume, 200.0);
+
+        // Midweek, the week in progress is left out.
+        let today = NaiveDate::from_ymd_opt(2025, 1, 8).unwrap();
+        let pivots = compute_pivots_week(&klines, PivotMethod::Classic, today).unwrap();
+        assert!((pivots.pivot - (12.0 + 9.0 + 11.0) / 3.0).abs() < 1e-9);
+
+        let today = NaiveDate::from_ymd_opt(2025, 1, 11).unwrap();
+        let pivots = compute_pivots_week(&klines, PivotMethod::Classic, today).unwrap();
+        assert!((pivots.pivot - (13.0 + 10.5 + 11.5) / 3.0).abs() < 1e-9);
+
+        let today = NaiveDate::from_ymd_opt(2025, 1, 3).unwrap();
+        assert!(compute_pivots_week(&klines[..2], PivotMethod::Classic, today).is_none());
+    }
+}
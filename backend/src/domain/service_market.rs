@@ -0,0 +1,775 @@
+use std::{borrow::Borrow, fmt, ops::Deref, str::FromStr};
+
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    Decode, Encode, Sqlite, Type,
+    encode::IsNull,
+    error::BoxDynError,
+    sqlite::{SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef},
+};
+use utoipa::ToSchema;
+
+use crate::domain::{model::Currency, service_calendar::Exchange};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetClass {
+    Equity,
+    Index,
+    /// Eastmoney BK boards.
+    Sector,
+}
+
+/// Shape of the code part of a secid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeFormat {
+    /// Fixed number of digits, zero padded.
+    Digits(usize),
+    /// `BK` and 4 digits.
+    Board,
+    /// Letters, digits and `_`, e.g. `BRK_B`.
+    Symbol,
+}
+
+impl CodeFormat {
+    fn is_valid(&self, code: &str) -> bool {
+        match self {
+            CodeFormat::Digits(n) => code.len() == *n && code.bytes().all(|b| b.is_ascii_digit()),
+            CodeFormat::Board => code
+                .strip_prefix("BK")
+                .is_some_and(|n| n.len() == 4 && n.bytes().all(|b| b.is_ascii_digit())),
+            CodeFormat::Symbol => {
+                !code.is_empty()
+                    && code.len() <= 10
+                    && code.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+            }
+        }
+    }
+
+    /// Exchange symbols drop leading zeros and use `.` for share classes, e.g. `700.HK`, `BRK.B.US`.
+    fn normalise(&self, code: &str) -> String {
+        match self {
+            CodeFormat::Digits(n) if code.len() < *n => format!("{code:0>n$}"),
+            CodeFormat::Symbol => code.replace('.', "_"),
+            _ => code.to_string(),
+        }
+    }
+
+    /// Code as in an exchange symbol, the reverse of [CodeFormat::normalise] bar zero padding.
+    fn denormalise(&self, code: &str) -> String {
+        match self {
+            CodeFormat::Symbol => code.replace('_', "."),
+            _ => code.to_string(),
+        }
+    }
+}
+
+/// An eastmoney market, the numeric prefix of a secid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Market {
+    pub code: u16,
+    /// Exchange whose calendar the market follows, None for the global indexes.
+    pub exchange: Option<Exchange>,
+    /// None for the global indexes.
+    pub currency: Option<Currency>,
+    pub timezone: Tz,
+    pub asset_class: AssetClass,
+    /// Exchange symbol suffixes, the first is used to format, e.g. `SH` of `600635.SH`.
+    /// US markets format with their venue (`O`, `N`, `A`) and share the ambiguous `US`.
+    pub suffixes: &'static [&'static str],
+    /// ISIN country prefix of the listings.
+    pub country: Option<&'static str>,
+    format: CodeFormat,
+}
+
+/// Market of the BK sector boards.
+pub const SECTOR_MARKET: u16 = 90;
+
+/// Known eastmoney markets.
+pub static MARKETS: [Market; 9] = [
+    // NOTE: BSE listings share the secid market of SZSE, see [Ticker::symbol].
+    Market {
+        code: 0,
+        exchange: Some(Exchange::Szse),
+        currency: Some(Currency::Cny),
+        timezone: chrono_tz::Asia::Shanghai,
+        asset_class: AssetClass::Equity,
+        suffixes: &["SZ", "BJ"],
+        country: Some("CN"),
+        format: CodeFormat::Digits(6),
+    },
+    Market {
+        code: 1,
+        exchange: Some(Exchange::Sse),
+        currency: Some(Currency::Cny),
+        timezone: chrono_tz::Asia::Shanghai,
+        asset_class: AssetClass::Equity,
+        suffixes: &["SH"],
+        country: Some("CN"),
+        format: CodeFormat::Digits(6),
+    },
+    // BK sectors trade with SSE hours.
+    Market {
+        code: SECTOR_MARKET,
+        exchange: Some(Exchange::Sse),
+        currency: Some(Currency::Cny),
+        timezone: chrono_tz::Asia::Shanghai,
+        asset_class: AssetClass::Sector,
+        suffixes: &[],
+        country: None,
+        format: CodeFormat::Board,
+    },
+    // Global indexes such as `100.NDX`.
+    Market {
+        code: 100,
+        exchange: None,
+        currency: None,
+        timezone: chrono_tz::UTC,
+        asset_class: AssetClass::Index,
+        suffixes: &[],
+        country: None,
+        format: CodeFormat::Symbol,
+    },
+    Market {
+        code: 105,
+        exchange: Some(Exchange::Nasdaq),
+        currency: Some(Currency::Usd),
+        timezone: chrono_tz::America::New_York,
+        asset_class: AssetClass::Equity,
+        suffixes: &["O", "US"],
+        country: Some("US"),
+        format: CodeFormat::Symbol,
+    },
+    Market {
+        code: 106,
+        exchange: Some(Exchange::Nyse),
+        currency: Some(Currency::Usd),
+        timezone: chrono_tz::America::New_York,
+        asset_class: AssetClass::Equity,
+        suffixes: &["N", "US"],
+        country: Some("US"),
+        format: CodeFormat::Symbol,
+    },
+    // NYSE American follows NYSE.
+    Market {
+        code: 107,
+        exchange: Some(Exchange::Nyse),
+        currency: Some(Currency::Usd),
+        timezone: chrono_tz::America::New_York,
+        asset_class: AssetClass::Equity,
+        suffixes: &["A", "US"],
+        country: Some("US"),
+        format: CodeFormat::Symbol,
+    },
+    Market {
+        code: 116,
+        exchange: Some(Exchange::Hkex),
+        currency: Some(Currency::Hkd),
+        timezone: chrono_tz::Asia::Hong_Kong,
+        asset_class: AssetClass::Equity,
+        suffixes: &["HK"],
+        country: Some("HK"),
+        format: CodeFormat::Digits(5),
+    },
+    // Southbound connect quotes of HKEX listings, `.HK` symbols resolve to 116.
+    Market {
+        code: 128,
+        exchange: Some(Exchange::Hkex),
+        currency: Some(Currency::Hkd),
+        timezone: chrono_tz::Asia::Hong_Kong,
+        asset_class: AssetClass::Equity,
+        suffixes: &[],
+        country: Some("HK"),
+        format: CodeFormat::Digits(5),
+    },
+];
+
+impl Market {
+    pub fn get(code: u16) -> Option<&'static Market> {
+        MARKETS.iter().find(|m| m.code == code)
+    }
+
+    /// Markets an exchange symbol suffix may refer to.
+    pub fn for_suffix(suffix: &str) -> Vec<&'static Market> {
+        MARKETS
+            .iter()
+            .filter(|m| m.suffixes.contains(&suffix))
+            .collect()
+    }
+
+    /// Markets listing securities of an ISIN country.
+    pub fn for_country(country: &str) -> Vec<&'static Market> {
+        MARKETS
+            .iter()
+            .filter(|m| m.country == Some(country))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TickerError {
+    #[error(
+        "malformed ticker {0:?}, expected a secid such as 1.600635 or a symbol such as 600635.SH"
+    )]
+    Malformed(String),
+    #[error("unknown market {0}")]
+    UnknownMarket(String),
+    #[error("invalid code {code:?} for market {market}")]
+    InvalidCode { market: u16, code: String },
+    #[error("{symbol} is ambiguous, use one of {}", join(candidates))]
+    Ambiguous {
+        symbol: String,
+        candidates: Vec<Ticker>,
+    },
+    #[error("invalid ISIN {0}")]
+    InvalidIsin(String),
+    #[error("ISIN {0} is not linked to a stored stock, link it or use a secid or exchange symbol")]
+    UnmappedIsin(String),
+    #[error("ISIN {isin} cannot list {ticker}, a {} market", .ticker.market().code)]
+    IsinMarket { isin: String, ticker: Ticker },
+}
+
+fn join(tickers: &[Ticker]) -> String {
+    tickers
+        .iter()
+        .map(Ticker::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Eastmoney secid such as `1.600635`, `105.TSLA` or `90.BK0475`, always normalised.
+///
+/// Parses from a secid or an exchange symbol (`600635.SH`, `700.HK`, `TSLA.O`), formats as a
+/// secid. `TSLA.US` names no venue, see [Ticker::resolve]. Stored as TEXT, decoding validates too.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct Ticker(String);
+
+impl Ticker {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn market(&self) -> &'static Market {
+        let (market, _) = self.0.split_once('.').expect("validated secid");
+        Market::get(market.parse().expect("validated secid")).expect("validated secid")
+    }
+
+    /// Code part, e.g. `600635` of `1.600635`.
+    pub fn code(&self) -> &str {
+        self.0.split_once('.').map_or("", |(_, code)| code)
+    }
+
+    pub fn exchange(&self) -> Option<Exchange> {
+        self.market().exchange
+    }
+
+    pub fn currency(&self) -> Option<Currency> {
+        self.market().currency
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.market().timezone
+    }
+
+    /// Mainland index codes (`1.000300`, `0.399006`) share the markets of the stocks.
+    pub fn asset_class(&self) -> AssetClass {
+        let market = self.market();
+        match market.code {
+            1 if self.code().starts_with("000") => AssetClass::Index,
+            0 if self.code().starts_with("399") => AssetClass::Index,
+            _ => market.asset_class,
+        }
+    }
+
+    pub fn is_sector(&self) -> bool {
+        self.asset_class() == AssetClass::Sector
+    }
+
+    /// Mainland A-share stock, listed on SSE, SZSE or BSE.
+    pub fn is_a_share(&self) -> bool {
+        matches!(self.market().code, 0 | 1) && self.asset_class() == AssetClass::Equity
+    }
+
+    /// Exchange symbol such as `600635.SH` or `TSLA.O`, None for sectors and global indexes.
+    pub fn symbol(&self) -> Option<String> {
+        let market = self.market();
+        let code = self.code();
+        let suffix = match market.code {
+            0 if code.starts_with(['4', '8']) || code.starts_with("92") => "BJ",
+            _ => market.suffixes.first()?,
+        };
+
+        Some(format!("{}.{suffix}", market.format.denormalise(code)))
+    }
+
+    /// Parses `s`, settling an ambiguous symbol such as `TSLA.US` on its only `known` candidate.
+    pub fn resolve(s: &str, known: impl Fn(&Ticker) -> bool) -> Result<Self, TickerError> {
+        match s.parse() {
+            Err(TickerError::Ambiguous { symbol, candidates }) => {
+                let mut found = candidates.iter().filter(|t| known(t));
+                match (found.next(), found.next()) {
+                    (Some(ticker), None) => Ok(ticker.clone()),
+                    _ => Err(TickerError::Ambiguous { symbol, candidates }),
+                }
+            }
+            parsed => parsed,
+        }
+    }
+
+    /// Stored stock an ISIN is `linked` to, among the markets of its country.
+    /// HKEX listings and their connect quotes share an ISIN, so two links are ambiguous.
+    pub fn from_isin(isin: &Isin, linked: &[Ticker]) -> Result<Self, TickerError> {
+        let candidates: Vec<Ticker> = linked.iter().filter(|t| isin.lists(t)).cloned().collect();
+        match candidates.as_slice() {
+            [] => Err(TickerError::UnmappedIsin(isin.to_string())),
+            [ticker] => Ok(ticker.clone()),
+            _ => Err(TickerError::Ambiguous {
+                symbol: isin.to_string(),
+                candidates,
+            }),
+        }
+    }
+
+    fn from_secid(market: u16, code: &str) -> Result<Self, TickerError> {
+        let Some(found) = Market::get(market) else {
+            return Err(TickerError::UnknownMarket(market.to_string()));
+        };
+        if !found.format.is_valid(code) {
+            return Err(TickerError::InvalidCode {
+                market,
+                code: code.to_string(),
+            });
+        }
+
+        Ok(Ticker(format!("{market}.{code}")))
+    }
+
+    fn from_symbol(symbol: &str) -> Result<Self, TickerError> {
+        let Some((code, suffix)) = symbol.rsplit_once('.') else {
+            return Err(TickerError::Malformed(symbol.to_string()));
+        };
+        let candidates = Market::for_suffix(suffix);
+        let Some(first) = candidates.first() else {
+            return Err(match code.bytes().all(|b| b.is_ascii_digit()) {
+                true => TickerError::UnknownMarket(code.to_string()),
+                false => TickerError::Malformed(symbol.to_string()),
+            });
+        };
+        let code = first.format.normalise(code);
+
+        match candidates.as_slice() {
+            [market] => Ticker::from_secid(market.code, &code),
+            markets => Err(TickerError::Ambiguous {
+                symbol: symbol.to_string(),
+                candidates: markets
+                    .iter()
+                    .map(|m| Ticker::from_secid(m.code, &code))
+                    .collect::<Result<_, _>>()?,
+            }),
+        }
+    }
+}
+
+impl FromStr for Ticker {
+    type Err = TickerError;
+
+    /// Tries a secid first, so `106.O` is Realty Income rather than a NASDAQ symbol.
+    /// Zero padded prefixes are codes, `000001.SZ` is not market 1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_uppercase();
+        if let Some((prefix, code)) = s.split_once('.')
+            && let Ok(market) = prefix.parse::<u16>()
+            && market.to_string() == prefix
+            && Market::get(market).is_some()
+        {
+            return Ticker::from_secid(market, code);
+        }
+        if s.contains('.') {
+            return Ticker::from_symbol(&s);
+        }
+
+        match s.parse::<Isin>() {
+            Ok(isin) => Err(TickerError::UnmappedIsin(isin.to_string())),
+            Err(e) if s.len() == 12 => Err(e),
+            Err(_) => Err(TickerError::Malformed(s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Ticker {
+    type Error = TickerError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Ticker> for String {
+    fn from(ticker: Ticker) -> Self {
+        ticker.0
+    }
+}
+
+impl fmt::Display for Ticker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Deref for Ticker {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Ticker {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Ticker {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Ticker {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Ticker {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Ticker {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
+impl Type<Sqlite> for Ticker {
+    fn type_info() -> SqliteTypeInfo {
+        <String as Type<Sqlite>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for Ticker {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
+        <String as Encode<'q, Sqlite>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Ticker {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<String as Decode<'r, Sqlite>>::decode(value)?.parse()?)
+    }
+}
+
+impl PartialEq<Ticker> for String {
+    fn eq(&self, other: &Ticker) -> bool {
+        *self == other.0
+    }
+}
+
+/// Parses comma separated tickers, skipping blanks.
+pub fn parse_tickers(s: &str) -> Result<Vec<Ticker>, TickerError> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// International Securities Identification Number, e.g. `US88160R1014`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Isin(String);
+
+impl Isin {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// ISO 3166 country code, e.g. `CN`.
+    pub fn country(&self) -> &str {
+        &self.0[..2]
+    }
+
+    /// Markets the security may trade on, empty if none is known for the country.
+    pub fn markets(&self) -> Vec<&'static Market> {
+        Market::for_country(self.country())
+    }
+
+    /// Whether `ticker` trades on a market of the ISIN's country.
+    pub fn lists(&self, ticker: &Ticker) -> bool {
+        self.markets()
+            .iter()
+            .any(|m| m.code == ticker.market().code)
+    }
+
+    /// Checks `ticker` may carry this ISIN, see [Isin::lists].
+    pub fn check(&self, ticker: &Ticker) -> Result<(), TickerError> {
+        match self.lists(ticker) {
+            true => Ok(()),
+            false => Err(TickerError::IsinMarket {
+                isin: self.to_string(),
+                ticker: ticker.clone(),
+            }),
+        }
+    }
+}
+
+impl FromStr for Isin {
+    type Err = TickerError;
+
+    /// Checks the layout and the Luhn check digit over the letters expanded to numbers.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_uppercase();
+        let b = s.as_bytes();
+        let layout = b.len() == 12
+            && b[..2].iter().all(|c| c.is_ascii_uppercase())
+            && b[2..11].iter().all(|c| c.is_ascii_alphanumeric())
+            && b[11].is_ascii_digit();
+        if !layout {
+            return Err(TickerError::InvalidIsin(s));
+        }
+
+        let digits: Vec<u32> = s
+            .chars()
+            .flat_map(|c| {
+                let n = c.to_digit(36).unwrap();
+                match n {
+                    0..=9 => vec![n],
+                    _ => vec![n / 10, n % 10],
+                }
+            })
+            .collect();
+        let sum: u32 = digits
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, d)| match i % 2 {
+                0 => *d,
+                _ => (d * 2) / 10 + (d * 2) % 10,
+            })
+            .sum();
+        if !sum.is_multiple_of(10) {
+            return Err(TickerError::InvalidIsin(s));
+        }
+
+        Ok(Isin(s))
+    }
+}
+
+impl fmt::Display for Isin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// What the registry knows of a ticker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TickerInfo {
+    pub ticker: Ticker,
+    pub market: u16,
+    pub symbol: Option<String>,
+    pub exchange: Option<Exchange>,
+    pub currency: Option<Currency>,
+    /// IANA name, e.g. Asia/Shanghai.
+    pub timezone: String,
+    pub asset_class: AssetClass,
+}
+
+impl From<&Ticker> for TickerInfo {
+    fn from(ticker: &Ticker) -> Self {
+        Self {
+            ticker: ticker.clone(),
+            market: ticker.market().code,
+            symbol: ticker.symbol(),
+            exchange: ticker.exchange(),
+            currency: ticker.currency(),
+            timezone: ticker.timezone().name().to_string(),
+            asset_class: ticker.asset_class(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(s: &str) -> Ticker {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_secid() {
+        assert_eq!(ticker("1.600635").as_str(), "1.600635");
+        assert_eq!(ticker(" 105.tsla ").as_str(), "105.TSLA");
+        assert_eq!(ticker("90.bk0475").as_str(), "90.BK0475");
+        assert_eq!(ticker("106.O").exchange(), Some(Exchange::Nyse));
+
+        assert_eq!(
+            "999.X".parse::<Ticker>(),
+            Err(TickerError::UnknownMarket("999".to_string()))
+        );
+        assert!(matches!(
+            "1.60063".parse::<Ticker>(),
+            Err(TickerError::InvalidCode { market: 1, .. })
+        ));
+        assert!(matches!(
+            "90.600635".parse::<Ticker>(),
+            Err(TickerError::InvalidCode { market: 90, .. })
+        ));
+        assert!("".parse::<Ticker>().is_err());
+        assert!("1.600635;DROP".parse::<Ticker>().is_err());
+        assert!("TSLA".parse::<Ticker>().is_err());
+    }
+
+    #[test]
+    fn test_parse_symbol() {
+        assert_eq!(ticker("600635.SH").as_str(), "1.600635");
+        assert_eq!(ticker("000001.sz").as_str(), "0.000001");
+        assert_eq!(ticker("830799.BJ").as_str(), "0.830799");
+        assert_eq!(ticker("700.HK").as_str(), "116.00700");
+        assert_eq!(ticker("TSLA.O").as_str(), "105.TSLA");
+        assert_eq!(ticker("BRK.B.N").as_str(), "106.BRK_B");
+
+        // Listed on one of the three US markets, the symbol does not tell which.
+        let err = "TSLA.US".parse::<Ticker>().unwrap_err();
+        assert_eq!(
+            err,
+            TickerError::Ambiguous {
+                symbol: "TSLA.US".to_string(),
+                candidates: vec![ticker("105.TSLA"), ticker("106.TSLA"), ticker("107.TSLA")],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "TSLA.US is ambiguous, use one of 105.TSLA, 106.TSLA, 107.TSLA"
+        );
+        assert!("600635.XX".parse::<Ticker>().is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let known = [ticker("105.TSLA"), ticker("106.BRK_B")];
+        let resolve = |s: &str| Ticker::resolve(s, |t| known.contains(t));
+        assert_eq!(resolve("TSLA.US"), Ok(ticker("105.TSLA")));
+        assert_eq!(resolve("brk.b.us"), Ok(ticker("106.BRK_B")));
+        assert_eq!(resolve("600635.SH"), Ok(ticker("1.600635")));
+        assert!(matches!(
+            resolve("AAPL.US"),
+            Err(TickerError::Ambiguous { .. })
+        ));
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(ticker("1.600635").symbol().as_deref(), Some("600635.SH"));
+        assert_eq!(ticker("0.000001").symbol().as_deref(), Some("000001.SZ"));
+        assert_eq!(ticker("0.830799").symbol().as_deref(), Some("830799.BJ"));
+        assert_eq!(ticker("116.00700").symbol().as_deref(), Some("00700.HK"));
+        assert_eq!(ticker("105.TSLA").symbol().as_deref(), Some("TSLA.O"));
+        assert_eq!(ticker("106.BRK_B").symbol().as_deref(), Some("BRK.B.N"));
+        assert_eq!(ticker("107.IMO").symbol().as_deref(), Some("IMO.A"));
+        assert_eq!(ticker("90.BK0475").symbol(), None);
+        assert_eq!(ticker("100.NDX").symbol(), None);
+
+        // Every symbol parses back to its secid.
+        for secid in [
+            "1.600635",
+            "0.000001",
+            "0.830799",
+            "116.00700",
+            "105.TSLA",
+            "106.BRK_B",
+            "107.IMO",
+        ] {
+            assert_eq!(ticker(&ticker(secid).symbol().unwrap()), ticker(secid));
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let t = ticker("116.00700");
+        assert_eq!(t.currency(), Some(Currency::Hkd));
+        assert_eq!(t.timezone(), chrono_tz::Asia::Hong_Kong);
+        assert_eq!(t.exchange(), Some(Exchange::Hkex));
+
+        assert_eq!(ticker("1.600635").asset_class(), AssetClass::Equity);
+        assert_eq!(ticker("1.000300").asset_class(), AssetClass::Index);
+        assert_eq!(ticker("0.399006").asset_class(), AssetClass::Index);
+        assert_eq!(ticker("100.NDX").asset_class(), AssetClass::Index);
+        assert!(ticker("90.BK0475").is_sector());
+        assert!(ticker("0.300750").is_a_share());
+        assert!(!ticker("1.000300").is_a_share());
+        assert_eq!(ticker("100.NDX").exchange(), None);
+
+        assert_eq!(Market::for_suffix("US").len(), 3);
+        assert_eq!(Market::for_country("CN").len(), 2);
+    }
+
+    #[test]
+    fn test_isin() {
+        let isin: Isin = "us88160r1014".parse().unwrap();
+        assert_eq!(isin.as_str(), "US88160R1014");
+        assert_eq!(isin.country(), "US");
+        assert_eq!(isin.markets().len(), 3);
+        assert!("US0378331005".parse::<Isin>().is_ok());
+        assert!("CNE000000R36".parse::<Isin>().is_ok());
+
+        // Check digit off by one.
+        assert_eq!(
+            "US0378331006".parse::<Isin>(),
+            Err(TickerError::InvalidIsin("US0378331006".to_string()))
+        );
+        assert_eq!(
+            "US0378331005".parse::<Ticker>(),
+            Err(TickerError::UnmappedIsin("US0378331005".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_from_isin() {
+        let tesla: Isin = "US88160R1014".parse().unwrap();
+        let linked = [ticker("1.600635"), ticker("105.TSLA")];
+        assert_eq!(Ticker::from_isin(&tesla, &linked), Ok(ticker("105.TSLA")));
+        assert!(tesla.check(&ticker("105.TSLA")).is_ok());
+        assert!(matches!(
+            tesla.check(&ticker("1.600635")),
+            Err(TickerError::IsinMarket { .. })
+        ));
+        assert_eq!(
+            Ticker::from_isin(&tesla, &linked[..1]),
+            Err(TickerError::UnmappedIsin("US88160R1014".to_string()))
+        );
+
+        // HKEX, quoted on both HK markets.
+        let hkex: Isin = "HK0388045442".parse().unwrap();
+        assert!(matches!(
+            Ticker::from_isin(&hkex, &[ticker("116.00388"), ticker("128.00388")]),
+            Err(TickerError::Ambiguous { .. })
+        ));
+    }
+
+    #[test]
+    fn test_serde() {
+        let t: Ticker = serde_json::from_str("\"600635.SH\"").unwrap();
+        assert_eq!(serde_json::to_string(&t).unwrap(), "\"1.600635\"");
+        assert!(serde_json::from_str::<Ticker>("\"nope\"").is_err());
+        assert_eq!(
+            parse_tickers("1.600635, ,105.TSLA").unwrap(),
+            vec![ticker("1.600635"), ticker("105.TSLA")]
+        );
+        assert!(parse_tickers("1.600635,bad").is_err());
+    }
+}
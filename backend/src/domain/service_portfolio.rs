@@ -0,0 +1,501 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::{
+    model::{Currency, FxRate, Transaction, TransactionKind},
+    service_market::Ticker,
+};
+
+/// Quantities below this are treated as a closed position.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum PortfolioError {
+    #[error("transaction {0}: {1} requires a ticker")]
+    MissingTicker(i64, &'static str),
+    #[error("transaction {id}: quantity and price must be positive")]
+    InvalidTrade { id: i64 },
+    #[error("transaction {id}: selling {quantity} of {ticker} but only {held} held")]
+    Oversold {
+        id: i64,
+        ticker: String,
+        held: f64,
+        quantity: f64,
+    },
+    #[error("transaction {id}: {ticker} trades in {expected:?}, not {currency:?}")]
+    CurrencyMismatch {
+        id: i64,
+        ticker: String,
+        currency: Currency,
+        expected: Currency,
+    },
+}
+
+/// Trades and dividends settle in the trading currency of their ticker, as the snapshot values
+/// positions at closes in that currency against their cost.
+pub fn check_currency(tx: &Transaction) -> Result<(), PortfolioError> {
+    if !matches!(
+        tx.kind,
+        TransactionKind::Buy | TransactionKind::Sell | TransactionKind::Dividend
+    ) {
+        return Ok(());
+    }
+    let expected = tx
+        .ticker
+        .as_deref()
+        .and_then(|t| t.parse::<Ticker>().ok())
+        .and_then(|t| t.currency());
+    match (expected, &tx.ticker) {
+        (Some(expected), Some(ticker)) if expected != tx.currency => {
+            Err(PortfolioError::CurrencyMismatch {
+                id: tx.id,
+                ticker: ticker.clone(),
+                currency: tx.currency,
+                expected,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// CNY per unit of each currency.
+#[derive(Debug, Clone)]
+pub struct FxRates(BTreeMap<Currency, f64>);
+
+impl FxRates {
+    pub fn new(rates: &[FxRate]) -> Self {
+        let mut map = BTreeMap::from([(Currency::Cny, 1.0)]);
+        map.extend(
+            rates
+                .iter()
+                .filter(|r| r.cny_rate > 0.0)
+                .map(|r| (r.currency, r.cny_rate)),
+        );
+        Self(map)
+    }
+
+    /// NOTE: a currency without a rate converts at par.
+    pub fn convert(&self, amount: f64, from: Currency, to: Currency) -> f64 {
+        if from == to {
+            return amount;
+        }
+        let rate = |c: Currency| self.0.get(&c).copied().unwrap_or(1.0);
+        amount * rate(from) / rate(to)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Holding {
+    currency: Option<Currency>,
+    quantity: f64,
+    /// Total cost of the open quantity, buy fees included.
+    cost: f64,
+    realised: f64,
+    dividends: f64,
+}
+
+/// Positions and cash of one account, built by replaying its transactions.
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    holdings: BTreeMap<String, Holding>,
+    cash: BTreeMap<Currency, f64>,
+    /// Fees not attached to a trade.
+    fees: BTreeMap<Currency, f64>,
+}
+
+impl Ledger {
+    /// Replays transactions in (trade_date, id) order.
+    pub fn replay(transactions: &[Transaction]) -> Result<Self, PortfolioError> {
+        let mut sorted: Vec<&Transaction> = transactions.iter().collect();
+        sorted.sort_by_key(|t| (t.trade_date, t.id));
+        let mut ledger = Self::default();
+        for tx in sorted {
+            ledger.apply(tx)?;
+        }
+        Ok(ledger)
+    }
+
+    /// Average cost method: buys add to cost, sells realise against the average.
+    pub fn apply(&mut self, tx: &Transaction) -> Result<(), PortfolioError> {
+        let cash = self.cash.entry(tx.currency).or_default();
+        match tx.kind {
+            TransactionKind::Buy | TransactionKind::Sell => {
+                let Some(ticker) = &tx.ticker else {
+                    return Err(PortfolioError::MissingTicker(tx.id, "trade"));
+                };
+                if tx.quantity <= 0.0 || tx.price <= 0.0 {
+                    return Err(PortfolioError::InvalidTrade { id: tx.id });
+                }
+                let holding = self.holdings.entry(ticker.clone()).or_default();
+                holding.currency = Some(tx.currency);
+                let notional = tx.quantity * tx.price;
+
+                if tx.kind == TransactionKind::Buy {
+                    holding.quantity += tx.quantity;
+                    holding.cost += notional + tx.fee;
+                    *cash -= notional + tx.fee;
+                } else {
+                    if tx.quantity > holding.quantity + EPSILON {
+                        return Err(PortfolioError::Oversold {
+                            id: tx.id,
+                            ticker: ticker.clone(),
+                            held: holding.quantity,
+                            quantity: tx.quantity,
+                        });
+                    }
+                    let avg_cost = holding.cost / holding.quantity;
+                    let released = avg_cost * tx.quantity;
+                    holding.realised += notional - released - tx.fee;
+                    holding.quantity -= tx.quantity;
+                    holding.cost -= released;
+                    if holding.quantity < EPSILON {
+                        holding.quantity = 0.0;
+                        holding.cost = 0.0;
+                    }
+                    *cash += notional - tx.fee;
+                }
+            }
+            TransactionKind::Dividend => {
+                let Some(ticker) = &tx.ticker else {
+                    return Err(PortfolioError::MissingTicker(tx.id, "dividend"));
+                };
+                *cash += tx.amount - tx.fee;
+                let holding = self.holdings.entry(ticker.clone()).or_default();
+                holding.currency.get_or_insert(tx.currency);
+                holding.dividends += tx.amount - tx.fee;
+            }
+            TransactionKind::Fee => {
+                *cash -= tx.amount + tx.fee;
+                *self.fees.entry(tx.currency).or_default() += tx.amount + tx.fee;
+            }
+            TransactionKind::Deposit => *cash += tx.amount - tx.fee,
+            TransactionKind::Withdrawal => *cash -= tx.amount + tx.fee,
+        }
+        Ok(())
+    }
+
+    /// Tickers ever traded or paying dividends.
+    pub fn tickers(&self) -> Vec<String> {
+        self.holdings.keys().cloned().collect()
+    }
+
+    /// Values the ledger at `closes` (ticker -> (date, close)), totals in `base`.
+    /// Positions without a close are marked at cost.
+    pub fn snapshot(
+        &self,
+        account: &str,
+        date: i64,
+        closes: &HashMap<String, (i64, f64)>,
+        fx: &FxRates,
+        base: Currency,
+    ) -> PortfolioSnapshot {
+        let mut positions = vec![];
+        let mut totals = PortfolioTotals::default();
+
+        for (ticker, holding) in &self.holdings {
+            let currency = holding
+                .currency
+                .unwrap_or_else(|| Currency::for_ticker(ticker));
+            let close = closes.get(ticker).copied();
+            let market_value = match close {
+                Some((_, price)) => holding.quantity * price,
+                None => holding.cost,
+            };
+            let position = Position {
+                ticker: ticker.clone(),
+                currency,
+                quantity: holding.quantity,
+                avg_cost: if holding.quantity > 0.0 {
+                    holding.cost / holding.quantity
+                } else {
+                    0.0
+                },
+                cost_basis: holding.cost,
+                last_close: close.map(|(_, price)| price),
+                last_date: close.map(|(date, _)| date),
+                market_value,
+                unrealised_pnl: market_value - holding.cost,
+                realised_pnl: holding.realised,
+                dividends: holding.dividends,
+            };
+
+            let to_base = |amount: f64| fx.convert(amount, currency, base);
+            totals.market_value += to_base(position.market_value);
+            totals.cost_basis += to_base(position.cost_basis);
+            totals.unrealised_pnl += to_base(position.unrealised_pnl);
+            totals.realised_pnl += to_base(position.realised_pnl);
+            totals.dividends += to_base(position.dividends);
+            positions.push(position);
+        }
+
+        let cash: Vec<CashBalance> = self
+            .cash
+            .iter()
+            .map(|(currency, amount)| CashBalance {
+                currency: *currency,
+                amount: *amount,
+            })
+            .collect();
+        totals.cash = cash
+            .iter()
+            .map(|c| fx.convert(c.amount, c.currency, base))
+            .sum();
+        totals.fees = self
+            .fees
+            .iter()
+            .map(|(currency, amount)| fx.convert(*amount, *currency, base))
+            .sum();
+        totals.equity = totals.cash + totals.market_value;
+
+        PortfolioSnapshot {
+            account: account.to_string(),
+            date,
+            base_currency: base,
+            positions,
+            cash,
+            totals,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Position {
+    pub ticker: String,
+    pub currency: Currency,
+    /// Zero once closed, kept for its realised P&L.
+    pub quantity: f64,
+    pub avg_cost: f64,
+    pub cost_basis: f64,
+    pub last_close: Option<f64>,
+    pub last_date: Option<i64>,
+    pub market_value: f64,
+    pub unrealised_pnl: f64,
+    pub realised_pnl: f64,
+    pub dividends: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CashBalance {
+    pub currency: Currency,
+    pub amount: f64,
+}
+
+/// Account totals in the base currency.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PortfolioTotals {
+    pub equity: f64,
+    pub cash: f64,
+    pub market_value: f64,
+    pub cost_basis: f64,
+    pub unrealised_pnl: f64,
+    pub realised_pnl: f64,
+    pub dividends: f64,
+    /// Fees not attached to a trade, trade fees are in cost and realised P&L.
+    pub fees: f64,
+}
+
+/// Positions in their own currency, totals in `base_currency`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PortfolioSnapshot {
+    pub account: String,
+    pub date: i64,
+    pub base_currency: Currency,
+    pub positions: Vec<Position>,
+    pub cash: Vec<CashBalance>,
+    pub totals: PortfolioTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PortfolioPoint {
+    pub date: i64,
+    #[serde(flatten)]
+    pub totals: PortfolioTotals,
+}
+
+/// Daily totals from the first transaction on, over the union of the close dates
+/// (ticker -> [(date, close)] ascending) and transaction dates.
+/// NOTE: converts at today's rates, no FX history is kept.
+pub fn portfolio_history(
+    transactions: &[Transaction],
+    closes: &HashMap<String, Vec<(i64, f64)>>,
+    fx: &FxRates,
+    base: Currency,
+) -> Result<Vec<PortfolioPoint>, PortfolioError> {
+    let mut sorted: Vec<&Transaction> = transactions.iter().collect();
+    sorted.sort_by_key(|t| (t.trade_date, t.id));
+    let Some(first) = sorted.first().map(|t| t.trade_date) else {
+        return Ok(vec![]);
+    };
+
+    let mut dates: Vec<i64> = closes
+        .values()
+        .flatten()
+        .map(|(date, _)| *date)
+        .chain(sorted.iter().map(|t| t.trade_date))
+        .filter(|date| *date >= first)
+        .collect();
+    dates.sort_unstable();
+    dates.dedup();
+
+    let mut ledger = Ledger::default();
+    let mut next_tx = 0;
+    let mut cursors: HashMap<&str, usize> = HashMap::new();
+    let mut marks: HashMap<String, (i64, f64)> = HashMap::new();
+    let mut points = Vec::with_capacity(dates.len());
+
+    for date in dates {
+        while next_tx < sorted.len() && sorted[next_tx].trade_date <= date {
+            ledger.apply(sorted[next_tx])?;
+            next_tx += 1;
+        }
+        for (ticker, series) in closes {
+            let cursor = cursors.entry(ticker).or_default();
+            while *cursor < series.len() && series[*cursor].0 <= date {
+                marks.insert(ticker.clone(), series[*cursor]);
+                *cursor += 1;
+            }
+        }
+        let snapshot = ledger.snapshot("", date, &marks, fx, base);
+        points.push(PortfolioPoint {
+            date,
+            totals: snapshot.totals,
+        });
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(
+        id: i64,
+        trade_date: i64,
+        kind: TransactionKind,
+        ticker: Option<&str>,
+        quantity: f64,
+        price: f64,
+        amount: f64,
+    ) -> Transaction {
+        let currency = ticker.map(Currency::for_ticker).unwrap_or(Currency::Cny);
+        Transaction {
+            id,
+            account: "main".to_string(),
+            trade_date,
+            kind,
+            ticker: ticker.map(str::to_string),
+            quantity,
+            price,
+            amount,
+            fee: 0.0,
+            currency,
+            notes: None,
+            created_at: String::new(),
+        }
+    }
+
+    fn rates() -> FxRates {
+        FxRates::new(&[FxRate {
+            currency: Currency::Usd,
+            cny_rate: 7.0,
+            updated_at: String::new(),
+        }])
+    }
+
+    #[test]
+    fn test_ledger_average_cost() {
+        use TransactionKind::*;
+        let mut buy = tx(2, 20250102, Buy, Some("1.600635"), 100.0, 10.0, 0.0);
+        buy.fee = 5.0;
+        let txs = vec![
+            tx(1, 20250101, Deposit, None, 0.0, 0.0, 10_000.0),
+            buy,
+            tx(3, 20250103, Buy, Some("1.600635"), 100.0, 12.0, 0.0),
+            tx(4, 20250104, Sell, Some("1.600635"), 50.0, 13.0, 0.0),
+            tx(5, 20250105, Dividend, Some("1.600635"), 0.0, 0.0, 30.0),
+            tx(6, 20250105, Fee, None, 0.0, 0.0, 10.0),
+        ];
+        let ledger = Ledger::replay(&txs).unwrap();
+        let closes = HashMap::from([("1.600635".to_string(), (20250105, 14.0))]);
+        let snap = ledger.snapshot("main", 20250105, &closes, &rates(), Currency::Cny);
+
+        let pos = &snap.positions[0];
+        // (1000 + 5 + 1200) / 200 = 11.025
+        assert!((pos.avg_cost - 11.025).abs() < 1e-9);
+        assert_eq!(pos.quantity, 150.0);
+        assert!((pos.realised_pnl - 50.0 * (13.0 - 11.025)).abs() < 1e-9);
+        assert!((pos.unrealised_pnl - 150.0 * (14.0 - 11.025)).abs() < 1e-9);
+        assert_eq!(pos.dividends, 30.0);
+
+        // 10000 - 1005 - 1200 + 650 + 30 - 10
+        assert!((snap.totals.cash - 8_465.0).abs() < 1e-9);
+        assert!((snap.totals.equity - (8_465.0 + 2_100.0)).abs() < 1e-9);
+        assert_eq!(snap.totals.fees, 10.0);
+    }
+
+    #[test]
+    fn test_ledger_errors_and_fx() {
+        use TransactionKind::*;
+        let oversold = vec![
+            tx(1, 20250101, Buy, Some("105.TSLA"), 10.0, 200.0, 0.0),
+            tx(2, 20250102, Sell, Some("105.TSLA"), 11.0, 210.0, 0.0),
+        ];
+        assert!(matches!(
+            Ledger::replay(&oversold),
+            Err(PortfolioError::Oversold { id: 2, .. })
+        ));
+        // Order is by trade date, not by id.
+        let backdated = vec![
+            tx(2, 20250103, Sell, Some("105.TSLA"), 10.0, 210.0, 0.0),
+            tx(1, 20250101, Buy, Some("105.TSLA"), 10.0, 200.0, 0.0),
+        ];
+        let ledger = Ledger::replay(&backdated).unwrap();
+        let snap = ledger.snapshot("main", 20250103, &HashMap::new(), &rates(), Currency::Cny);
+        assert_eq!(snap.positions[0].currency, Currency::Usd);
+        assert!((snap.positions[0].realised_pnl - 100.0).abs() < 1e-9);
+        assert!((snap.totals.realised_pnl - 700.0).abs() < 1e-9);
+        assert_eq!(snap.cash[0].currency, Currency::Usd);
+    }
+
+    #[test]
+    fn test_check_currency() {
+        use TransactionKind::*;
+        let mut buy = tx(1, 20250101, Buy, Some("105.TSLA"), 10.0, 200.0, 0.0);
+        assert!(check_currency(&buy).is_ok());
+        buy.currency = Currency::Cny;
+        assert!(matches!(
+            check_currency(&buy),
+            Err(PortfolioError::CurrencyMismatch {
+                expected: Currency::Usd,
+                ..
+            })
+        ));
+
+        // Cash moves may be in any currency.
+        let mut deposit = tx(2, 20250101, Deposit, None, 0.0, 0.0, 1_000.0);
+        deposit.currency = Currency::Hkd;
+        assert!(check_currency(&deposit).is_ok());
+    }
+
+    #[test]
+    fn test_portfolio_history() {
+        use TransactionKind::*;
+        let txs = vec![
+            tx(1, 20250102, Deposit, None, 0.0, 0.0, 1_000.0),
+            tx(2, 20250102, Buy, Some("1.600635"), 100.0, 10.0, 0.0),
+        ];
+        let closes = HashMap::from([(
+            "1.600635".to_string(),
+            vec![(20250101, 9.0), (20250102, 10.0), (20250103, 11.0)],
+        )]);
+        let history = portfolio_history(&txs, &closes, &rates(), Currency::Cny).unwrap();
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].totals.equity, 1_000.0);
+        assert!((history[1].totals.equity - 1_100.0).abs() < 1e-9);
+        assert!((history[1].totals.unrealised_pnl - 100.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,489 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::service_market::Ticker;
+
+const PERIODS_PER_YEAR: f64 = 252.0;
+
+/// Sector of tickers without a known one.
+pub const UNCLASSIFIED: &str = "unclassified";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct RiskParams {
+    /// Confidence of VaR and CVaR, e.g. 0.95.
+    pub confidence: f64,
+    /// Most recent common trading days used.
+    pub lookback: usize,
+}
+
+impl RiskParams {
+    /// Confidence strictly between 0 and 1, at least two returns.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.confidence > 0.0 && self.confidence < 1.0) {
+            return Err(format!(
+                "confidence = {}, expected between 0 and 1",
+                self.confidence
+            ));
+        }
+        if self.lookback < 2 {
+            return Err(format!("lookback = {}, expected at least 2", self.lookback));
+        }
+        Ok(())
+    }
+}
+
+impl Default for RiskParams {
+    fn default() -> Self {
+        Self {
+            confidence: 0.95,
+            lookback: 250,
+        }
+    }
+}
+
+/// Open position valued in the base currency.
+#[derive(Debug, Clone)]
+pub struct RiskPosition {
+    pub ticker: String,
+    pub market_value: f64,
+    /// Daily closes, ascending by date.
+    pub closes: Vec<(i64, f64)>,
+    pub sectors: Vec<String>,
+}
+
+/// One-day loss measures as positive fractions of market value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ValueAtRisk {
+    pub historical: f64,
+    pub historical_cvar: f64,
+    pub parametric: f64,
+    pub parametric_cvar: f64,
+}
+
+impl ValueAtRisk {
+    fn is_finite(&self) -> bool {
+        [
+            self.historical,
+            self.historical_cvar,
+            self.parametric,
+            self.parametric_cvar,
+        ]
+        .iter()
+        .all(|v| v.is_finite())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PositionRisk {
+    pub ticker: String,
+    pub market_value: f64,
+    pub weight: f64,
+    /// Annualised.
+    pub volatility: f64,
+    /// `None` without benchmark data.
+    pub beta: Option<f64>,
+    pub var: ValueAtRisk,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CorrelationMatrix {
+    pub tickers: Vec<String>,
+    /// Row-major, `values[i][j]` pairs `tickers[i]` with `tickers[j]`.
+    pub values: Vec<Vec<f64>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SectorExposure {
+    pub sector: String,
+    pub market_value: f64,
+    /// Tickers in several sectors count fully in each, so weights may sum above 1.
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RiskReport {
+    pub params: RiskParams,
+    pub benchmark: Option<String>,
+    /// Returns used, after aligning all series on common dates.
+    pub observations: usize,
+    pub market_value: f64,
+    /// Annualised.
+    pub volatility: f64,
+    pub beta: Option<f64>,
+    pub var: ValueAtRisk,
+    /// `var` in the base currency.
+    pub var_amount: ValueAtRisk,
+    pub positions: Vec<PositionRisk>,
+    pub correlation: CorrelationMatrix,
+    pub sectors: Vec<SectorExposure>,
+}
+
+impl RiskReport {
+    /// NaN and infinities serialise as JSON null and would not read back.
+    pub fn is_finite(&self) -> bool {
+        let finite = |v: f64| v.is_finite();
+        finite(self.market_value)
+            && finite(self.volatility)
+            && self.beta.is_none_or(finite)
+            && self.var.is_finite()
+            && self.var_amount.is_finite()
+            && self.positions.iter().all(|p| {
+                finite(p.market_value)
+                    && finite(p.weight)
+                    && finite(p.volatility)
+                    && p.beta.is_none_or(finite)
+                    && p.var.is_finite()
+            })
+            && self.correlation.values.iter().flatten().all(|v| finite(*v))
+            && self
+                .sectors
+                .iter()
+                .all(|s| finite(s.market_value) && finite(s.weight))
+    }
+}
+
+/// Risk of a long-only portfolio from daily close-to-close returns, aligned on the
+/// dates every position (and the benchmark, if any) traded.
+pub fn compute_risk(
+    positions: &[RiskPosition],
+    benchmark: Option<(&str, &[(i64, f64)])>,
+    params: &RiskParams,
+) -> RiskReport {
+    let positions: Vec<&RiskPosition> = positions.iter().filter(|p| p.market_value > 0.0).collect();
+    let market_value: f64 = positions.iter().map(|p| p.market_value).sum();
+
+    let series: Vec<BTreeMap<i64, f64>> = positions.iter().map(|p| returns(&p.closes)).collect();
+    let bench = benchmark
+        .map(|(_, closes)| returns(closes))
+        .filter(|r| !r.is_empty());
+    let dates = common_dates(series.iter().chain(bench.iter()), params.lookback);
+    let aligned: Vec<Vec<f64>> = series
+        .iter()
+        .map(|s| dates.iter().map(|d| s[d]).collect())
+        .collect();
+    let bench: Option<Vec<f64>> = bench.map(|b| dates.iter().map(|d| b[d]).collect());
+
+    let weights: Vec<f64> = positions
+        .iter()
+        .map(|p| p.market_value / market_value)
+        .collect();
+    let portfolio: Vec<f64> = (0..dates.len())
+        .map(|i| aligned.iter().zip(&weights).map(|(r, w)| r[i] * w).sum())
+        .collect();
+    let beta_of = |r: &[f64]| bench.as_deref().and_then(|b| beta(r, b));
+
+    let position_risks = positions
+        .iter()
+        .zip(&aligned)
+        .zip(&weights)
+        .map(|((p, r), w)| PositionRisk {
+            ticker: p.ticker.clone(),
+            market_value: p.market_value,
+            weight: *w,
+            volatility: std(r) * PERIODS_PER_YEAR.sqrt(),
+            beta: beta_of(r),
+            var: value_at_risk(r, params.confidence),
+        })
+        .collect();
+
+    let n = aligned.len();
+    let mut values = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        values[i][i] = 1.0;
+        for j in i + 1..n {
+            let c = correlation(&aligned[i], &aligned[j]);
+            values[i][j] = c;
+            values[j][i] = c;
+        }
+    }
+
+    let mut sectors: BTreeMap<&str, f64> = BTreeMap::new();
+    for p in &positions {
+        if p.sectors.is_empty() {
+            *sectors.entry(UNCLASSIFIED).or_default() += p.market_value;
+        }
+        for sector in &p.sectors {
+            *sectors.entry(sector).or_default() += p.market_value;
+        }
+    }
+    let mut sectors: Vec<SectorExposure> = sectors
+        .into_iter()
+        .map(|(sector, value)| SectorExposure {
+            sector: sector.to_string(),
+            market_value: value,
+            weight: value / market_value,
+        })
+        .collect();
+    sectors.sort_by(|a, b| b.market_value.total_cmp(&a.market_value));
+
+    let var = value_at_risk(&portfolio, params.confidence);
+    let var_amount = ValueAtRisk {
+        historical: var.historical * market_value,
+        historical_cvar: var.historical_cvar * market_value,
+        parametric: var.parametric * market_value,
+        parametric_cvar: var.parametric_cvar * market_value,
+    };
+
+    RiskReport {
+        params: *params,
+        benchmark: benchmark.map(|(ticker, _)| ticker.to_string()),
+        observations: dates.len(),
+        market_value,
+        volatility: std(&portfolio) * PERIODS_PER_YEAR.sqrt(),
+        beta: beta_of(&portfolio),
+        var,
+        var_amount,
+        positions: position_risks,
+        correlation: CorrelationMatrix {
+            tickers: positions.iter().map(|p| p.ticker.clone()).collect(),
+            values,
+        },
+        sectors,
+    }
+}
+
+/// Close-to-close returns keyed by the later date.
+fn returns(closes: &[(i64, f64)]) -> BTreeMap<i64, f64> {
+    closes
+        .windows(2)
+        .filter(|w| w[0].1 > 0.0)
+        .map(|w| (w[1].0, w[1].1 / w[0].1 - 1.0))
+        .collect()
+}
+
+/// Last `lookback` dates present in every series.
+fn common_dates<'a>(
+    mut series: impl Iterator<Item = &'a BTreeMap<i64, f64>>,
+    lookback: usize,
+) -> Vec<i64> {
+    let Some(first) = series.next() else {
+        return vec![];
+    };
+    let rest: Vec<_> = series.collect();
+    let dates: Vec<i64> = first
+        .keys()
+        .filter(|d| rest.iter().all(|s| s.contains_key(d)))
+        .copied()
+        .collect();
+    dates[dates.len().saturating_sub(lookback)..].to_vec()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Sample standard deviation.
+fn std(values: &[f64]) -> f64 {
+    covariance(values, values).sqrt()
+}
+
+fn covariance(a: &[f64], b: &[f64]) -> f64 {
+    if a.len() < 2 {
+        return 0.0;
+    }
+    let (ma, mb) = (mean(a), mean(b));
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - ma) * (y - mb))
+        .sum::<f64>()
+        / (a.len() - 1) as f64
+}
+
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let denom = std(a) * std(b);
+    if denom > 0.0 {
+        covariance(a, b) / denom
+    } else {
+        0.0
+    }
+}
+
+fn beta(returns: &[f64], benchmark: &[f64]) -> Option<f64> {
+    let var = covariance(benchmark, benchmark);
+    (var > 0.0).then(|| covariance(returns, benchmark) / var)
+}
+
+fn value_at_risk(returns: &[f64], confidence: f64) -> ValueAtRisk {
+    if returns.is_empty() {
+        return ValueAtRisk::default();
+    }
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    // Worst (1 - confidence) share of days, at least one.
+    // NOTE: the epsilon keeps e.g. (1 - 0.95) * 100 from rounding up to 6.
+    let tail = (((1.0 - confidence) * sorted.len() as f64 - 1e-9).ceil() as usize).max(1);
+    let historical = -sorted[tail - 1];
+    let historical_cvar = -mean(&sorted[..tail]);
+
+    let (mu, sigma) = (mean(returns), std(returns));
+    let z = normal_quantile(confidence);
+    let parametric = sigma * z - mu;
+    let parametric_cvar = sigma * normal_pdf(z) / (1.0 - confidence) - mu;
+
+    ValueAtRisk {
+        historical,
+        historical_cvar,
+        parametric,
+        parametric_cvar,
+    }
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Inverse standard normal CDF, Acklam's rational approximation (|error| < 1.2e-9).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Sectors of a ticker, BK sector tickers are their own sector.
+pub fn sectors_of(ticker: &str, members: &HashMap<String, Vec<String>>) -> Vec<String> {
+    if ticker.parse::<Ticker>().is_ok_and(|t| t.is_sector()) {
+        return vec![ticker.to_string()];
+    }
+    members.get(ticker).cloned().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(returns: &[f64]) -> Vec<(i64, f64)> {
+        let mut close = 10.0;
+        let mut out = vec![(20250101, close)];
+        for (i, r) in returns.iter().enumerate() {
+            close *= 1.0 + r;
+            out.push((20250102 + i as i64, close));
+        }
+        out
+    }
+
+    #[test]
+    fn test_normal_quantile() {
+        assert!(normal_quantile(0.5).abs() < 1e-9);
+        assert!((normal_quantile(0.95) - 1.644853627).abs() < 1e-6);
+        assert!((normal_quantile(0.01) + 2.326347874).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_value_at_risk() {
+        let returns: Vec<f64> = (1..=100).map(|i| (i as f64 - 50.5) / 1000.0).collect();
+        let var = value_at_risk(&returns, 0.95);
+        // Worst 5 days: -0.0495 .. -0.0455.
+        assert!((var.historical - 0.0455).abs() < 1e-12);
+        assert!((var.historical_cvar - 0.0475).abs() < 1e-12);
+        assert!(var.parametric > 0.0 && var.parametric_cvar > var.parametric);
+    }
+
+    #[test]
+    fn test_compute_risk() {
+        let base = [0.01, -0.02, 0.015, -0.005, 0.02, -0.01, 0.005, -0.015];
+        let doubled: Vec<f64> = base.iter().map(|r| r * 2.0).collect();
+        let inverse: Vec<f64> = base.iter().map(|r| -r).collect();
+        let positions = vec![
+            RiskPosition {
+                ticker: "1.600036".to_string(),
+                market_value: 3_000.0,
+                closes: series(&doubled),
+                sectors: vec!["90.BK0475".to_string()],
+            },
+            RiskPosition {
+                ticker: "1.600519".to_string(),
+                market_value: 1_000.0,
+                closes: series(&inverse),
+                sectors: vec![],
+            },
+        ];
+        let bench = series(&base);
+        let report = compute_risk(
+            &positions,
+            Some(("1.000300", &bench)),
+            &RiskParams::default(),
+        );
+
+        assert_eq!(report.observations, base.len());
+        assert!((report.positions[0].beta.unwrap() - 2.0).abs() < 1e-9);
+        assert!((report.positions[1].beta.unwrap() + 1.0).abs() < 1e-9);
+        // 0.75 * 2 - 0.25 * 1
+        assert!((report.beta.unwrap() - 1.25).abs() < 1e-9);
+        assert!((report.correlation.values[0][1] + 1.0).abs() < 1e-9);
+        assert_eq!(report.sectors[0].sector, "90.BK0475");
+        assert_eq!(report.sectors[0].weight, 0.75);
+        assert_eq!(report.sectors[1].sector, UNCLASSIFIED);
+        assert!((report.var_amount.historical - report.var.historical * 4_000.0).abs() < 1e-9);
+
+        let no_bench = compute_risk(&positions, None, &RiskParams::default());
+        assert!(no_bench.beta.is_none());
+        assert!(no_bench.is_finite());
+
+        let certain = RiskParams {
+            confidence: 1.0,
+            lookback: 250,
+        };
+        assert!(certain.validate().is_err());
+        assert!(!compute_risk(&positions, None, &certain).is_finite());
+    }
+
+    #[test]
+    fn test_validate_params() {
+        assert!(RiskParams::default().validate().is_ok());
+        for (confidence, lookback) in [(0.0, 250), (1.5, 250), (f64::NAN, 250), (0.95, 1)] {
+            let params = RiskParams {
+                confidence,
+                lookback,
+            };
+            assert!(params.validate().is_err());
+        }
+    }
+}
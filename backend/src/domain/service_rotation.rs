@@ -0,0 +1,251 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::infra::data::moneyflow::MoneyflowEastmoney;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RotationParams {
+    /// Days summed into the cumulative net inflow.
+    pub window: usize,
+    /// Trading dates of rank history returned per sector.
+    pub history: usize,
+    /// Size of the leading group, entering it is flagged.
+    pub top: usize,
+}
+
+impl Default for RotationParams {
+    fn default() -> Self {
+        Self {
+            window: 5,
+            history: 20,
+            top: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RankPoint {
+    pub trade_date: String,
+    /// 1 is the largest cumulative inflow.
+    pub rank: usize,
+    pub cumulative: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SectorRotation {
+    pub ticker: String,
+    pub realname: String,
+    /// Main force net inflow over the last `window` records.
+    pub cumulative: f64,
+    /// Of the latest cumulative against the sector's own cumulative history.
+    pub zscore: Option<f64>,
+    pub rank: usize,
+    pub previous_rank: Option<usize>,
+    /// Positive when the sector moved up.
+    pub rank_change: Option<i64>,
+    /// In the top group on the latest date but not on the previous one.
+    pub new_top: bool,
+    /// Chronological, the last point is the latest date.
+    pub ranks: Vec<RankPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RotationReport {
+    pub params: RotationParams,
+    /// Latest trading date.
+    pub trade_date: Option<String>,
+    /// Ordered by rank.
+    pub sectors: Vec<SectorRotation>,
+}
+
+/// Ranks sectors by rolling cumulative main force net inflow.
+///
+/// A sector gets a cumulative on a date once it has `window` records up to that date.
+pub fn compute_rotation(flows: &[MoneyflowEastmoney], params: &RotationParams) -> RotationReport {
+    let window = params.window.max(1);
+
+    let mut by_ticker: HashMap<&str, Vec<&MoneyflowEastmoney>> = HashMap::new();
+    for flow in flows {
+        by_ticker.entry(&flow.ticker).or_default().push(flow);
+    }
+
+    // Cumulative inflow of each sector keyed by date.
+    let mut cumulatives: HashMap<&str, Vec<(&str, f64)>> = HashMap::new();
+    for (ticker, series) in by_ticker.iter_mut() {
+        series.sort_by(|a, b| a.trade_date.cmp(&b.trade_date));
+        let rolling = series
+            .windows(window)
+            .map(|w| {
+                let last = w.last().unwrap();
+                (
+                    last.trade_date.as_str(),
+                    w.iter().map(|f| f.lead_value).sum(),
+                )
+            })
+            .collect();
+        cumulatives.insert(ticker, rolling);
+    }
+
+    let dates: BTreeSet<&str> = cumulatives
+        .values()
+        .flat_map(|series| series.iter().map(|(date, _)| *date))
+        .collect();
+    let Some(latest) = dates.last().copied() else {
+        return RotationReport {
+            params: *params,
+            trade_date: None,
+            sectors: vec![],
+        };
+    };
+    let previous = dates.range(..latest).next_back().copied();
+
+    // Ranks on each of the recent dates.
+    let recent: Vec<&str> = dates
+        .iter()
+        .rev()
+        .take(params.history.max(2))
+        .rev()
+        .copied()
+        .collect();
+    let mut ranks: HashMap<&str, Vec<RankPoint>> = HashMap::new();
+    for date in &recent {
+        let mut day: Vec<(&str, f64)> = cumulatives
+            .iter()
+            .filter_map(|(ticker, series)| {
+                series
+                    .iter()
+                    .find(|(d, _)| d == date)
+                    .map(|(_, cum)| (*ticker, *cum))
+            })
+            .collect();
+        day.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        for (i, (ticker, cumulative)) in day.into_iter().enumerate() {
+            ranks.entry(ticker).or_default().push(RankPoint {
+                trade_date: date.to_string(),
+                rank: i + 1,
+                cumulative,
+            });
+        }
+    }
+
+    let rank_on = |points: &[RankPoint], date: Option<&str>| {
+        date.and_then(|date| points.iter().find(|p| p.trade_date == date))
+            .map(|p| p.rank)
+    };
+
+    let mut sectors: Vec<SectorRotation> = ranks
+        .into_iter()
+        .filter_map(|(ticker, points)| {
+            let rank = rank_on(&points, Some(latest))?;
+            let previous_rank = rank_on(&points, previous);
+            let series = &cumulatives[ticker];
+            let cumulative = series.last().map(|(_, cum)| *cum).unwrap_or_default();
+            let realname = by_ticker[ticker].last().unwrap().realname.clone();
+
+            Some(SectorRotation {
+                ticker: ticker.to_string(),
+                realname,
+                cumulative,
+                zscore: zscore(series.iter().map(|(_, cum)| *cum), cumulative),
+                rank,
+                previous_rank,
+                rank_change: previous_rank.map(|prev| prev as i64 - rank as i64),
+                new_top: rank <= params.top && previous_rank.is_some_and(|p| p > params.top),
+                ranks: points,
+            })
+        })
+        .collect();
+    sectors.sort_by_key(|s| s.rank);
+
+    RotationReport {
+        params: *params,
+        trade_date: Some(latest.to_string()),
+        sectors,
+    }
+}
+
+/// None with fewer than two values or no dispersion.
+fn zscore(values: impl Iterator<Item = f64>, x: f64) -> Option<f64> {
+    let values: Vec<f64> = values.collect();
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+
+    (std > 0.0).then(|| (x - mean) / std)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(ticker: &str, day: u32, lead_value: f64) -> MoneyflowEastmoney {
+        MoneyflowEastmoney {
+            trade_date: format!("2025-11-{day:02}"),
+            ticker: ticker.to_string(),
+            realname: ticker.to_string(),
+            lead_value,
+            lead_share: 0.0,
+            super_value: 0.0,
+            super_share: 0.0,
+            large_value: 0.0,
+            large_share: 0.0,
+            mid_value: 0.0,
+            mid_share: 0.0,
+            small_value: 0.0,
+            small_share: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_compute_rotation() {
+        // A leads steadily, B bursts on the last day, C bleeds.
+        let mut flows = vec![];
+        for day in 1..=6 {
+            flows.push(flow("A", day, 10.0));
+            flows.push(flow("B", day, if day == 6 { 100.0 } else { 1.0 }));
+            flows.push(flow("C", day, -5.0));
+        }
+        let params = RotationParams {
+            window: 3,
+            history: 10,
+            top: 1,
+        };
+        let report = compute_rotation(&flows, &params);
+
+        assert_eq!(report.trade_date.as_deref(), Some("2025-11-06"));
+        let tickers: Vec<&str> = report.sectors.iter().map(|s| s.ticker.as_str()).collect();
+        assert_eq!(tickers, vec!["B", "A", "C"]);
+
+        let b = &report.sectors[0];
+        assert_eq!(b.cumulative, 102.0);
+        assert_eq!(b.rank, 1);
+        assert_eq!(b.previous_rank, Some(2));
+        assert_eq!(b.rank_change, Some(1));
+        assert!(b.new_top);
+        // Rolling sums from day 3 to day 6.
+        assert_eq!(b.ranks.len(), 4);
+        assert!(b.zscore.unwrap() > 1.0);
+
+        let a = &report.sectors[1];
+        assert_eq!(a.rank_change, Some(-1));
+        assert!(!a.new_top);
+        // A flat history has no dispersion.
+        assert_eq!(a.zscore, None);
+
+        assert_eq!(report.sectors[2].rank_change, Some(0));
+    }
+
+    #[test]
+    fn test_compute_rotation_short_history() {
+        let flows = vec![flow("A", 1, 10.0), flow("A", 2, 10.0)];
+        let report = compute_rotation(&flows, &RotationParams::default());
+
+        assert_eq!(report.trade_date, None);
+        assert!(report.sectors.is_empty());
+    }
+}
@@ -0,0 +1,627 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::{
+    model::{FinancialRatios, Signal, Stock},
+    service_strength::StrengthSignal,
+};
+
+/// Fields a screener rule may refer to.
+pub const SCREENER_FIELDS: &[&str] = &[
+    "ticker",
+    "realname",
+    "market",
+    "sector",
+    "bar_date",
+    "kdj_k",
+    "kdj_d",
+    "boll_dist",
+    "total_cap",
+    "pe",
+    "pb",
+    "revenue",
+    "net",
+    "margin",
+    "debt",
+    "rs_rating",
+    "rs_1w",
+    "rs_1m",
+    "rs_3m",
+    "rs_6m",
+    "sector_rs_1w",
+    "sector_rs_1m",
+    "sector_rs_3m",
+    "sector_rs_6m",
+    "roe",
+    "roa",
+    "gross_margin",
+    "fcf",
+    "debt_to_equity",
+    "revenue_yoy",
+    "net_profit_yoy",
+];
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ScreenerError {
+    #[error("unexpected character '{0}' at {1}")]
+    UnexpectedChar(char, usize),
+    #[error("unterminated string at {0}")]
+    UnterminatedString(usize),
+    #[error("unexpected token '{0}' at {1}")]
+    UnexpectedToken(String, usize),
+    #[error("unexpected end of rule")]
+    UnexpectedEnd,
+    #[error("unknown field '{0}'")]
+    UnknownField(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum Value {
+    Num(f64),
+    Str(String),
+    List(Vec<String>),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Field(String),
+    Num(f64),
+    Str(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp(Operand, CmpOp, Operand),
+}
+
+/// One ticker's fields as seen by a screener rule.
+#[derive(Debug, Clone)]
+pub struct ScreenerRow {
+    pub ticker: String,
+    pub fields: HashMap<String, Value>,
+}
+
+impl ScreenerRow {
+    /// Joins the latest signal of a ticker with its fundamentals and sectors.
+    pub fn new(signal: &Signal, stock: Option<&Stock>, sectors: Vec<String>) -> Self {
+        let num = |v: Option<f64>| v.map(Value::Num).unwrap_or(Value::Null);
+
+        let mut fields = HashMap::new();
+        fields.insert("ticker".to_string(), Value::Str(signal.ticker.clone()));
+        fields.insert("sector".to_string(), Value::List(sectors));
+        fields.insert("bar_date".to_string(), Value::Num(signal.bar_date as f64));
+        fields.insert("kdj_k".to_string(), Value::Num(signal.kdj_k));
+        fields.insert("kdj_d".to_string(), Value::Num(signal.kdj_d));
+        fields.insert("boll_dist".to_string(), Value::Num(signal.boll_dist));
+        fields.insert(
+            "realname".to_string(),
+            stock
+                .map(|s| Value::Str(s.realname.clone()))
+                .unwrap_or(Value::Null),
+        );
+        fields.insert("market".to_string(), num(stock.map(|s| s.market as f64)));
+        fields.insert(
+            "total_cap".to_string(),
+            num(stock.and_then(|s| s.total_cap)),
+        );
+        fields.insert("pe".to_string(), num(stock.and_then(|s| s.pe)));
+        fields.insert("pb".to_string(), num(stock.and_then(|s| s.pb)));
+        fields.insert("revenue".to_string(), num(stock.and_then(|s| s.revenue)));
+        fields.insert("net".to_string(), num(stock.and_then(|s| s.net)));
+        fields.insert("margin".to_string(), num(stock.and_then(|s| s.margin)));
+        fields.insert("debt".to_string(), num(stock.and_then(|s| s.debt)));
+
+        Self {
+            ticker: signal.ticker.clone(),
+            fields,
+        }
+    }
+
+    /// Adds the relative strength fields, left null without a strength signal.
+    pub fn with_strength(mut self, strength: Option<&StrengthSignal>) -> Self {
+        let s = strength;
+        let fields = [
+            ("rs_rating", s.and_then(|s| s.rating.map(|r| r as f64))),
+            ("rs_1w", s.and_then(|s| s.rs_1w)),
+            ("rs_1m", s.and_then(|s| s.rs_1m)),
+            ("rs_3m", s.and_then(|s| s.rs_3m)),
+            ("rs_6m", s.and_then(|s| s.rs_6m)),
+            ("sector_rs_1w", s.and_then(|s| s.sector_rs_1w)),
+            ("sector_rs_1m", s.and_then(|s| s.sector_rs_1m)),
+            ("sector_rs_3m", s.and_then(|s| s.sector_rs_3m)),
+            ("sector_rs_6m", s.and_then(|s| s.sector_rs_6m)),
+        ];
+        for (name, value) in fields {
+            self.fields.insert(
+                name.to_string(),
+                value.map(Value::Num).unwrap_or(Value::Null),
+            );
+        }
+
+        self
+    }
+
+    /// Adds the ratios of the latest financial period, left null without statements.
+    pub fn with_financials(mut self, ratios: Option<&FinancialRatios>) -> Self {
+        let r = ratios;
+        let fields = [
+            ("roe", r.and_then(|r| r.roe)),
+            ("roa", r.and_then(|r| r.roa)),
+            ("gross_margin", r.and_then(|r| r.gross_margin)),
+            ("fcf", r.and_then(|r| r.fcf)),
+            ("debt_to_equity", r.and_then(|r| r.debt_to_equity)),
+            ("revenue_yoy", r.and_then(|r| r.revenue_yoy)),
+            ("net_profit_yoy", r.and_then(|r| r.net_profit_yoy)),
+        ];
+        for (name, value) in fields {
+            self.fields.insert(
+                name.to_string(),
+                value.map(Value::Num).unwrap_or(Value::Null),
+            );
+        }
+
+        self
+    }
+
+    fn get(&self, field: &str) -> &Value {
+        self.fields.get(field).unwrap_or(&Value::Null)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScreenerMatch {
+    pub rank: usize,
+    pub ticker: String,
+    #[schema(value_type = Object)]
+    pub fields: HashMap<String, Value>,
+}
+
+// ---------------------------------------------------------------
+// Grammar
+//   or   := and ("OR" and)*
+//   and  := not ("AND" not)*
+//   not  := "NOT" not | atom
+//   atom := "(" or ")" | operand op operand
+//   op   := < | <= | > | >= | = | == | != | <>
+// ---------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Num(f64),
+    Str(String),
+    Op(CmpOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ScreenerError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push((Token::LParen, start));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((Token::RParen, start));
+                i += 1;
+            }
+            '<' | '>' | '=' | '!' => {
+                let next = chars.get(i + 1).copied();
+                let (op, len) = match (c, next) {
+                    ('<', Some('=')) => (CmpOp::Le, 2),
+                    ('<', Some('>')) => (CmpOp::Ne, 2),
+                    ('<', _) => (CmpOp::Lt, 1),
+                    ('>', Some('=')) => (CmpOp::Ge, 2),
+                    ('>', _) => (CmpOp::Gt, 1),
+                    ('=', Some('=')) => (CmpOp::Eq, 2),
+                    ('=', _) => (CmpOp::Eq, 1),
+                    ('!', Some('=')) => (CmpOp::Ne, 2),
+                    _ => return Err(ScreenerError::UnexpectedChar(c, start)),
+                };
+                tokens.push((Token::Op(op), start));
+                i += len;
+            }
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&x| x == c)
+                    .ok_or(ScreenerError::UnterminatedString(start))?;
+                let s: String = chars[i + 1..i + 1 + end].iter().collect();
+                tokens.push((Token::Str(s), start));
+                i += end + 2;
+            }
+            c if c.is_ascii_digit() || c == '.' || c == '-' => {
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || chars[i] == '.'
+                        || chars[i] == 'e'
+                        || chars[i] == 'E'
+                        || ((chars[i] == '-' || chars[i] == '+')
+                            && matches!(chars[i - 1], 'e' | 'E')))
+                {
+                    i += 1;
+                }
+                let s: String = chars[start..i].iter().collect();
+                let n = s
+                    .parse::<f64>()
+                    .map_err(|_| ScreenerError::UnexpectedToken(s.clone(), start))?;
+                tokens.push((Token::Num(n), start));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let token = match word.to_ascii_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Ident(word.to_ascii_lowercase()),
+                };
+                tokens.push((token, start));
+            }
+            _ => return Err(ScreenerError::UnexpectedChar(c, start)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) -> Result<(Token, usize), ScreenerError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or(ScreenerError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn or(&mut self) -> Result<Expr, ScreenerError> {
+        let mut lhs = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, ScreenerError> {
+        let mut lhs = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            lhs = Expr::And(Box::new(lhs), Box::new(self.not()?));
+        }
+        Ok(lhs)
+    }
+
+    fn not(&mut self) -> Result<Expr, ScreenerError> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, ScreenerError> {
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let expr = self.or()?;
+            return match self.next()? {
+                (Token::RParen, _) => Ok(expr),
+                (t, at) => Err(ScreenerError::UnexpectedToken(format!("{t:?}"), at)),
+            };
+        }
+
+        let lhs = self.operand()?;
+        let op = match self.next()? {
+            (Token::Op(op), _) => op,
+            (t, at) => return Err(ScreenerError::UnexpectedToken(format!("{t:?}"), at)),
+        };
+        let rhs = self.operand()?;
+        Ok(Expr::Cmp(lhs, op, rhs))
+    }
+
+    fn operand(&mut self) -> Result<Operand, ScreenerError> {
+        match self.next()? {
+            (Token::Ident(name), _) => {
+                if !SCREENER_FIELDS.contains(&name.as_str()) {
+                    return Err(ScreenerError::UnknownField(name));
+                }
+                Ok(Operand::Field(name))
+            }
+            (Token::Num(n), _) => Ok(Operand::Num(n)),
+            (Token::Str(s), _) => Ok(Operand::Str(s)),
+            (t, at) => Err(ScreenerError::UnexpectedToken(format!("{t:?}"), at)),
+        }
+    }
+}
+
+/// Parses a rule such as `kdj_k < 20 AND (pe < 30 OR sector = '90.BK1036')`.
+/// Keywords are case-insensitive, fields are checked against [SCREENER_FIELDS].
+pub fn parse_rule(input: &str) -> Result<Expr, ScreenerError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+    };
+    let expr = parser.or()?;
+    if let Some((t, at)) = parser.tokens.get(parser.pos) {
+        return Err(ScreenerError::UnexpectedToken(format!("{t:?}"), *at));
+    }
+    Ok(expr)
+}
+
+/// Evaluates a rule against a row. Comparisons with missing values are false.
+pub fn evaluate(expr: &Expr, row: &ScreenerRow) -> bool {
+    match expr {
+        Expr::And(a, b) => evaluate(a, row) && evaluate(b, row),
+        Expr::Or(a, b) => evaluate(a, row) || evaluate(b, row),
+        Expr::Not(a) => !evaluate(a, row),
+        Expr::Cmp(lhs, op, rhs) => compare(&resolve(lhs, row), *op, &resolve(rhs, row)),
+    }
+}
+
+fn resolve(operand: &Operand, row: &ScreenerRow) -> Value {
+    match operand {
+        Operand::Field(name) => row.get(name).clone(),
+        Operand::Num(n) => Value::Num(*n),
+        Operand::Str(s) => Value::Str(s.clone()),
+    }
+}
+
+fn compare(lhs: &Value, op: CmpOp, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::Num(a), Value::Num(b)) => match op {
+            CmpOp::Lt => a < b,
+            CmpOp::Le => a <= b,
+            CmpOp::Gt => a > b,
+            CmpOp::Ge => a >= b,
+            CmpOp::Eq => a == b,
+            CmpOp::Ne => a != b,
+        },
+        (Value::Str(a), Value::Str(b)) => match op {
+            CmpOp::Eq => a.eq_ignore_ascii_case(b),
+            CmpOp::Ne => !a.eq_ignore_ascii_case(b),
+            _ => false,
+        },
+        // NOTE: `sector = 'x'` reads as membership.
+        (Value::List(list), Value::Str(s)) | (Value::Str(s), Value::List(list)) => match op {
+            CmpOp::Eq => list.iter().any(|x| x.eq_ignore_ascii_case(s)),
+            CmpOp::Ne => !list.iter().any(|x| x.eq_ignore_ascii_case(s)),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Filters rows by the rule and ranks matches by `rank_by`, missing values last.
+/// Without `rank_by`, matches are ranked by ticker.
+pub fn run_screener(
+    expr: &Expr,
+    rows: Vec<ScreenerRow>,
+    rank_by: Option<&str>,
+    descending: bool,
+) -> Vec<ScreenerMatch> {
+    let mut matched: Vec<ScreenerRow> = rows.into_iter().filter(|r| evaluate(expr, r)).collect();
+
+    matched.sort_by(|a, b| {
+        let ordering = match rank_by {
+            Some(field) => match (a.get(field), b.get(field)) {
+                (Value::Num(x), Value::Num(y)) => x.total_cmp(y),
+                (Value::Str(x), Value::Str(y)) => x.cmp(y),
+                (Value::Null, Value::Null) => Ordering::Equal,
+                // Missing values always sink to the bottom.
+                (Value::Null, _) => return Ordering::Greater,
+                (_, Value::Null) => return Ordering::Less,
+                _ => Ordering::Equal,
+            },
+            None => a.ticker.cmp(&b.ticker),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    matched
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| ScreenerMatch {
+            rank: i + 1,
+            ticker: row.ticker,
+            fields: row.fields,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::SignalPeriod;
+
+    fn row(ticker: &str, kdj_k: f64, pe: Option<f64>, sectors: &[&str]) -> ScreenerRow {
+        let signal = Signal {
+            ticker: ticker.to_string(),
+            period: SignalPeriod::Day,
+            bar_date: 20251105,
+            kdj_k,
+            kdj_d: 50.0,
+            boll_dist: 0.3,
+        };
+        let stock = Stock {
+            ticker: ticker.to_string(),
+            realname: "demo".to_string(),
+            market: 1,
+            total_cap: None,
+            pe,
+            pb: None,
+            revenue: None,
+            net: None,
+            margin: None,
+            debt: None,
+        };
+        ScreenerRow::new(
+            &signal,
+            Some(&stock),
+            sectors.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let expr = parse_rule("kdj_k < 20 and boll_dist < 0.5 AND pe < 30").unwrap();
+        assert!(matches!(expr, Expr::And(_, _)));
+
+        let expr = parse_rule("NOT (pe >= 30 OR kdj_k > -1.5e1)").unwrap();
+        assert!(matches!(expr, Expr::Not(_)));
+
+        assert_eq!(
+            parse_rule("foo < 1"),
+            Err(ScreenerError::UnknownField("foo".to_string()))
+        );
+        assert_eq!(parse_rule("kdj_k <"), Err(ScreenerError::UnexpectedEnd));
+        assert_eq!(
+            parse_rule("sector = '90.BK1036"),
+            Err(ScreenerError::UnterminatedString(9))
+        );
+        assert!(parse_rule("kdj_k < 20 pe").is_err());
+        assert!(parse_rule("kdj_k ! 20").is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let r = row("1.600635", 15.0, Some(25.0), &["90.BK1036"]);
+
+        let hit = parse_rule("kdj_k < 20 AND boll_dist < 0.5 AND pe < 30 AND sector = '90.BK1036'")
+            .unwrap();
+        assert!(evaluate(&hit, &r));
+
+        let miss = parse_rule("kdj_k < 20 AND sector = '90.BK0475'").unwrap();
+        assert!(!evaluate(&miss, &r));
+
+        let not = parse_rule("NOT sector = '90.BK0475' AND ticker = '1.600635'").unwrap();
+        assert!(evaluate(&not, &r));
+
+        // Missing fundamentals never match.
+        let r = row("1.688981", 15.0, None, &[]);
+        assert!(!evaluate(&parse_rule("pe < 30").unwrap(), &r));
+        assert!(!evaluate(&parse_rule("pe >= 30").unwrap(), &r));
+    }
+
+    #[test]
+    fn test_evaluate_strength() {
+        let strength = StrengthSignal {
+            ticker: "1.600635".to_string(),
+            bar_date: 20251105,
+            benchmark: "1.000300".to_string(),
+            sector: None,
+            ret_1w: Some(0.02),
+            ret_1m: Some(0.1),
+            ret_3m: None,
+            ret_6m: None,
+            rs_1w: Some(1.01),
+            rs_1m: Some(1.08),
+            rs_3m: None,
+            rs_6m: None,
+            sector_rs_1w: None,
+            sector_rs_1m: None,
+            sector_rs_3m: None,
+            sector_rs_6m: None,
+            score: Some(0.3),
+            rating: Some(85),
+        };
+        let expr = parse_rule("rs_rating >= 80 AND rs_1m > 1").unwrap();
+
+        let r = row("1.600635", 15.0, None, &[]).with_strength(Some(&strength));
+        assert!(evaluate(&expr, &r));
+        assert!(!evaluate(&parse_rule("rs_3m > 1").unwrap(), &r));
+
+        let r = row("1.600635", 15.0, None, &[]).with_strength(None);
+        assert!(!evaluate(&expr, &r));
+    }
+
+    #[test]
+    fn test_evaluate_financials() {
+        let ratios = FinancialRatios {
+            ticker: "1.600635".to_string(),
+            period: "2025-06-30".to_string(),
+            report_type: crate::domain::model::ReportType::H1,
+            roe: Some(0.18),
+            roa: Some(0.07),
+            gross_margin: Some(0.35),
+            fcf: Some(-1e8),
+            debt_to_equity: Some(0.8),
+            revenue_yoy: Some(0.12),
+            net_profit_yoy: None,
+        };
+        let expr = parse_rule("roe > 0.15 AND debt_to_equity < 1").unwrap();
+
+        let r = row("1.600635", 15.0, None, &[]).with_financials(Some(&ratios));
+        assert!(evaluate(&expr, &r));
+        assert!(!evaluate(&parse_rule("fcf > 0").unwrap(), &r));
+        assert!(!evaluate(&parse_rule("net_profit_yoy > 0").unwrap(), &r));
+
+        let r = row("1.600635", 15.0, None, &[]).with_financials(None);
+        assert!(!evaluate(&expr, &r));
+    }
+
+    #[test]
+    fn test_run_screener_ranks() {
+        let rows = vec![
+            row("a", 10.0, Some(40.0), &[]),
+            row("b", 5.0, None, &[]),
+            row("c", 30.0, Some(10.0), &[]),
+            row("d", 15.0, Some(20.0), &[]),
+        ];
+        let expr = parse_rule("kdj_k < 20").unwrap();
+
+        let ranked = run_screener(&expr, rows.clone(), Some("kdj_k"), false);
+        let tickers: Vec<&str> = ranked.iter().map(|m| m.ticker.as_str()).collect();
+        assert_eq!(tickers, vec!["b", "a", "d"]);
+        assert_eq!(ranked[0].rank, 1);
+
+        let ranked = run_screener(&expr, rows, Some("pe"), true);
+        let tickers: Vec<&str> = ranked.iter().map(|m| m.ticker.as_str()).collect();
+        assert_eq!(tickers, vec!["a", "d", "b"]);
+    }
+}
@@ -1,6 +1,6 @@
 use core::f64;
 
-use crate::domain::model::{KDJ, Kline};
+use crate::domain::model::{BOLL, KDJ, Kline};
 
 /// Computes the KDJ indicator for a series of klines.
 ///
@@ -148,6 +148,65 @@ pub fn compute_boll_dist(klines: &[Kline]) -> f64 {
     (closes.last().unwrap() - lower_band.last().unwrap()) / final_std_dev
 }
 
+/// Computes Bollinger Bands of `period` bars and `k` standard deviations.
+///
+/// # Returns
+/// A vector of `BOLL` values, one for each input bar. Bars before a full
+/// window collapse all bands onto the close.
+pub fn compute_boll(klines: &[Kline], period: usize, k: f64) -> Vec<BOLL> {
+    let (closes, _, _) = destuct_klines(klines);
+    let period = period.max(1);
+
+    closes
+        .iter()
+        .enumerate()
+        .map(|(i, &close)| {
+            if i + 1 < period {
+                return BOLL {
+                    upper: close,
+                    middle: close,
+                    lower: close,
+                };
+            }
+            let window = &closes[i + 1 - period..=i];
+            let middle = window.iter().sum::<f64>() / period as f64;
+            let std_dev =
+                (window.iter().map(|x| (x - middle).powi(2)).sum::<f64>() / period as f64).sqrt();
+            BOLL {
+                upper: middle + k * std_dev,
+                middle,
+                lower: middle - k * std_dev,
+            }
+        })
+        .collect()
+}
+
+/// Computes the Average True Range of the last bar, Wilder smoothed over `period` bars.
+///
+/// # Returns
+/// `None` with fewer than `period + 1` bars.
+pub fn compute_atr(klines: &[Kline], period: usize) -> Option<f64> {
+    let period = period.max(1);
+    if klines.len() < period + 1 {
+        return None;
+    }
+
+    let true_ranges: Vec<f64> = klines
+        .windows(2)
+        .map(|w| {
+            let prev_close = w[0].k_close;
+            (w[1].k_high - w[1].k_low)
+                .max((w[1].k_high - prev_close).abs())
+                .max((w[1].k_low - prev_close).abs())
+        })
+        .collect();
+
+    let seed = true_ranges[..period].iter().sum::<f64>() / period as f64;
+    Some(true_ranges[period..].iter().fold(seed, |atr, tr| {
+        (atr * (period - 1) as f64 + tr) / period as f64
+    }))
+}
+
 /// Extract `Vec<Kline>` into a tuple of 'closes', 'highs', 'lows'.
 fn destuct_klines(klines: &[Kline]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
     let mut closes = vec![0.0; klines.len()];
@@ -263,4 +322,30 @@ mod tests {
     //
     //     assert_eq!(boll_dist, 1.0);
     // }
+
+    #[test]
+    fn test_compute_atr() {
+        let klines: Vec<Kline> = (0..20)
+            .map(|i| Kline {
+                k_ticker: "AAPL".to_string(),
+                k_date: 20201111 + i,
+                k_open: 10.0,
+                k_high: 10.5,
+                k_low: 9.5,
+                k_close: 10.0,
+                k_volume: 111.0,
+                k_value: 111.0,
+            })
+            .collect();
+
+        assert!(compute_atr(&klines[..14], 14).is_none());
+        assert!(approx_equal(1.0, compute_atr(&klines, 14).unwrap(), 1e-12));
+
+        // A gap up widens the true range past the bar's own range.
+        let mut gapped = klines.clone();
+        gapped[19].k_high = 13.0;
+        gapped[19].k_low = 12.0;
+        let atr = compute_atr(&gapped, 14).unwrap();
+        assert!(approx_equal((13.0 * 1.0 + 3.0) / 14.0, atr, 1e-12));
+    }
 }
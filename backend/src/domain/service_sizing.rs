@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum SizingError {
+    #[error("equity must be positive")]
+    NoEquity,
+    #[error("risk percent must be in (0, 100]")]
+    InvalidRisk,
+    #[error("entry and stop must be finite prices")]
+    InvalidPrice,
+    #[error("stop must be below the entry price of {0}")]
+    InvalidStop(f64),
+    #[error("max concentration must be in (0, 1]")]
+    InvalidConcentration,
+}
+
+/// Long entry sized so that hitting the stop loses `risk_pct` of equity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizingInput {
+    /// In the ticker's currency.
+    pub equity: f64,
+    /// Percent of equity, e.g. 1.0 for 1%.
+    pub risk_pct: f64,
+    pub entry: f64,
+    pub stop: f64,
+    pub lot_size: f64,
+    /// Max share of equity in one ticker, e.g. 0.2.
+    pub max_concentration: f64,
+    /// Market value already held in the ticker.
+    pub existing_value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PositionSize {
+    /// Whole lots.
+    pub quantity: f64,
+    pub notional: f64,
+    pub entry: f64,
+    pub stop: f64,
+    /// Budgeted loss at the stop.
+    pub risk_budget: f64,
+    /// Loss at the stop after rounding down to lots.
+    pub risk_amount: f64,
+    /// Share of equity in the ticker after the trade, existing holdings included.
+    pub concentration: f64,
+    /// Largest quantity within the concentration cap.
+    pub max_quantity: f64,
+    pub warnings: Vec<String>,
+}
+
+pub fn size_position(input: &SizingInput) -> Result<PositionSize, SizingError> {
+    if !(input.equity.is_finite() && input.equity > 0.0) {
+        return Err(SizingError::NoEquity);
+    }
+    if !(input.risk_pct > 0.0 && input.risk_pct <= 100.0) {
+        return Err(SizingError::InvalidRisk);
+    }
+    if !(input.max_concentration > 0.0 && input.max_concentration <= 1.0) {
+        return Err(SizingError::InvalidConcentration);
+    }
+    if !(input.entry.is_finite() && input.stop.is_finite()) {
+        return Err(SizingError::InvalidPrice);
+    }
+    let per_share = input.entry - input.stop;
+    if per_share <= 0.0 || input.stop < 0.0 {
+        return Err(SizingError::InvalidStop(input.entry));
+    }
+
+    let lot = input.lot_size.max(1.0);
+    let round_lot = |quantity: f64| (quantity / lot).floor() * lot;
+    let risk_budget = input.equity * input.risk_pct / 100.0;
+    let quantity = round_lot(risk_budget / per_share);
+    let notional = quantity * input.entry;
+    let concentration = (input.existing_value + notional) / input.equity;
+    let max_quantity = round_lot(
+        ((input.equity * input.max_concentration - input.existing_value) / input.entry).max(0.0),
+    );
+
+    let mut warnings = vec![];
+    if quantity == 0.0 {
+        warnings.push(format!(
+            "risk budget {risk_budget:.2} does not cover one lot of {lot} at a stop distance of {per_share:.2}"
+        ));
+    }
+    if concentration > input.max_concentration {
+        warnings.push(format!(
+            "position would be {:.1}% of equity, above the {:.1}% cap; at most {max_quantity} shares",
+            concentration * 100.0,
+            input.max_concentration * 100.0,
+        ));
+    }
+    if notional > input.equity {
+        warnings
+            .push("notional exceeds equity, the stop is too tight for the risk budget".to_string());
+    }
+
+    Ok(PositionSize {
+        quantity,
+        notional,
+        entry: input.entry,
+        stop: input.stop,
+        risk_budget,
+        risk_amount: quantity * per_share,
+        concentration,
+        max_quantity,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> SizingInput {
+        SizingInput {
+            equity: 100_000.0,
+            risk_pct: 1.0,
+            entry: 10.0,
+            stop: 9.3,
+            lot_size: 100.0,
+            max_concentration: 0.2,
+            existing_value: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_size_position() {
+        // 1000 / 0.7 = 1428.6 -> 1400 shares.
+        let size = size_position(&input()).unwrap();
+        assert_eq!(size.quantity, 1_400.0);
+        assert_eq!(size.notional, 14_000.0);
+        assert!((size.risk_amount - 980.0).abs() < 1e-9);
+        assert_eq!(size.max_quantity, 2_000.0);
+        assert!(size.warnings.is_empty());
+
+        // Tight stop: 1000 / 0.1 = 10000 shares, 100% of equity.
+        let tight = size_position(&SizingInput {
+            stop: 9.9,
+            ..input()
+        })
+        .unwrap();
+        assert_eq!(tight.quantity, 10_000.0);
+        assert_eq!(tight.warnings.len(), 1);
+
+        // Existing holdings count towards the cap.
+        let held = size_position(&SizingInput {
+            existing_value: 15_000.0,
+            ..input()
+        })
+        .unwrap();
+        assert_eq!(held.max_quantity, 500.0);
+        assert_eq!(held.warnings.len(), 1);
+
+        assert_eq!(
+            size_position(&SizingInput {
+                stop: 10.5,
+                ..input()
+            }),
+            Err(SizingError::InvalidStop(10.0))
+        );
+        assert_eq!(
+            size_position(&SizingInput {
+                equity: 0.0,
+                ..input()
+            }),
+            Err(SizingError::NoEquity)
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        for max_concentration in [0.0, -0.2, 1.5, f64::NAN] {
+            assert_eq!(
+                size_position(&SizingInput {
+                    max_concentration,
+                    ..input()
+                }),
+                Err(SizingError::InvalidConcentration)
+            );
+        }
+        assert!(
+            size_position(&SizingInput {
+                max_concentration: 1.0,
+                ..input()
+            })
+            .is_ok()
+        );
+        for (entry, stop) in [(f64::NAN, 9.3), (10.0, f64::NAN), (f64::INFINITY, 9.3)] {
+            assert_eq!(
+                size_position(&SizingInput {
+                    entry,
+                    stop,
+                    ..input()
+                }),
+                Err(SizingError::InvalidPrice)
+            );
+        }
+        assert_eq!(
+            size_position(&SizingInput {
+                equity: f64::NAN,
+                ..input()
+            }),
+            Err(SizingError::NoEquity)
+        );
+    }
+}
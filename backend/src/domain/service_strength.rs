@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use crate::domain::model::Currency;
+
+/// Lookbacks in trading days: 1w, 1m, 3m and 6m.
+pub const LOOKBACKS: [usize; 4] = [5, 21, 63, 126];
+
+/// Benchmark of each market, keyed by its trading currency: CSI 300, Hang Seng and S&P 500.
+pub const MARKET_BENCHMARKS: [(Currency, &str); 3] = [
+    (Currency::Cny, "1.000300"),
+    (Currency::Hkd, "100.HSI"),
+    (Currency::Usd, "100.SPX"),
+];
+
+/// Benchmark stocks trading in `currency` are compared against.
+pub fn market_benchmark(currency: Currency) -> &'static str {
+    MARKET_BENCHMARKS
+        .iter()
+        .find(|(c, _)| *c == currency)
+        .map(|(_, b)| *b)
+        .expect("benchmark of every currency")
+}
+
+/// Relative strength of a ticker as of the close of `bar_date` (yyyymmdd).
+///
+/// Ratios are `(1 + ticker return) / (1 + reference return)` over the same dates, above 1 is
+/// outperformance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct StrengthSignal {
+    pub ticker: String,
+    pub bar_date: i64,
+    pub benchmark: String,
+    /// BK sector compared against, if any has klines.
+    pub sector: Option<String>,
+    pub ret_1w: Option<f64>,
+    pub ret_1m: Option<f64>,
+    pub ret_3m: Option<f64>,
+    pub ret_6m: Option<f64>,
+    pub rs_1w: Option<f64>,
+    pub rs_1m: Option<f64>,
+    pub rs_3m: Option<f64>,
+    pub rs_6m: Option<f64>,
+    pub sector_rs_1w: Option<f64>,
+    pub sector_rs_1m: Option<f64>,
+    pub sector_rs_3m: Option<f64>,
+    pub sector_rs_6m: Option<f64>,
+    /// Weighted performance behind the rating.
+    pub score: Option<f64>,
+    /// Percentile of `score` among rated tickers of the same market, 1 to 99.
+    pub rating: Option<i64>,
+}
+
+/// Close on `date`, or the last close before it.
+fn close_at(closes: &[(i64, f64)], date: i64) -> Option<f64> {
+    let idx = closes.partition_point(|(d, _)| *d <= date);
+    idx.checked_sub(1).map(|i| closes[i].1)
+}
+
+/// Returns of the ticker and a reference over each lookback, dated by the ticker's bars.
+fn returns(closes: &[(i64, f64)], reference: &[(i64, f64)], days: usize) -> Option<(f64, f64)> {
+    let (end_date, end) = *closes.last()?;
+    let (start_date, start) = closes[closes.len().checked_sub(days + 1)?];
+    let ref_end = close_at(reference, end_date)?;
+    let ref_start = close_at(reference, start_date)?;
+    if start <= 0.0 || ref_start <= 0.0 {
+        return None;
+    }
+
+    Some((end / start - 1.0, ref_end / ref_start - 1.0))
+}
+
+/// IBD-style weighted performance over the last two quarters, the recent one counting double.
+///
+/// NOTE: IBD weighs four quarters, the 6m lookback caps this at two.
+fn weighted_score(closes: &[(i64, f64)]) -> Option<f64> {
+    let n = closes.len();
+    let end = closes.last()?.1;
+    let q1 = closes[n.checked_sub(LOOKBACKS[2] + 1)?].1;
+    let q2 = closes[n.checked_sub(LOOKBACKS[3] + 1)?].1;
+    if q1 <= 0.0 || q2 <= 0.0 {
+        return None;
+    }
+
+    Some(2.0 * (end / q1 - 1.0) + (q1 / q2 - 1.0))
+}
+
+/// Relative strength of a ticker from chronological daily closes, `rating` is left empty.
+pub fn compute_strength(
+    ticker: &str,
+    closes: &[(i64, f64)],
+    benchmark: (&str, &[(i64, f64)]),
+    sector: Option<(&str, &[(i64, f64)])>,
+) -> Option<StrengthSignal> {
+    let (bar_date, _) = *closes.last()?;
+
+    let vs_bench: Vec<Option<(f64, f64)>> = LOOKBACKS
+        .iter()
+        .map(|days| returns(closes, benchmark.1, *days))
+        .collect();
+    let vs_sector: Vec<Option<(f64, f64)>> = LOOKBACKS
+        .iter()
+        .map(|days| sector.and_then(|(_, s)| returns(closes, s, *days)))
+        .collect();
+    let ret = |i: usize| {
+        let days = LOOKBACKS[i];
+        let start = closes.len().checked_sub(days + 1).map(|j| closes[j].1)?;
+        (start > 0.0).then(|| closes.last().unwrap().1 / start - 1.0)
+    };
+    let ratio =
+        |r: Option<(f64, f64)>| r.and_then(|(a, b)| (b > -1.0).then(|| (1.0 + a) / (1.0 + b)));
+
+    Some(StrengthSignal {
+        ticker: ticker.to_string(),
+        bar_date,
+        benchmark: benchmark.0.to_string(),
+        sector: sector.map(|(s, _)| s.to_string()),
+        ret_1w: ret(0),
+        ret_1m: ret(1),
+        ret_3m: ret(2),
+        ret_6m: ret(3),
+        rs_1w: ratio(vs_bench[0]),
+        rs_1m: ratio(vs_bench[1]),
+        rs_3m: ratio(vs_bench[2]),
+        rs_6m: ratio(vs_bench[3]),
+        sector_rs_1w: ratio(vs_sector[0]),
+        sector_rs_1m: ratio(vs_sector[1]),
+        sector_rs_3m: ratio(vs_sector[2]),
+        sector_rs_6m: ratio(vs_sector[3]),
+        score: weighted_score(closes),
+        rating: None,
+    })
+}
+
+/// Fills `rating` with the percentile of `score`, 99 for the strongest.
+/// Pass the signals of one market, ratings do not compare across markets.
+pub fn rate_strength(signals: &mut [StrengthSignal]) {
+    let mut scores: Vec<f64> = signals.iter().filter_map(|s| s.score).collect();
+    scores.sort_by(|a, b| a.total_cmp(b));
+    let n = scores.len();
+
+    for signal in signals.iter_mut() {
+        signal.rating = signal.score.map(|score| {
+            if n == 1 {
+                return 99;
+            }
+            let below = scores.partition_point(|s| *s < score);
+            (1.0 + 98.0 * below as f64 / (n - 1) as f64).round() as i64
+        });
+    }
+}
+
+/// First sector of a ticker that has closes.
+pub fn pick_sector<'a>(
+    sectors: &'a [String],
+    closes: &HashMap<String, Vec<(i64, f64)>>,
+) -> Option<&'a str> {
+    sectors
+        .iter()
+        .find(|s| closes.get(*s).is_some_and(|c| !c.is_empty()))
+        .map(|s| s.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Closes growing by `daily` per bar from 20250101 on.
+    fn series(count: usize, daily: f64) -> Vec<(i64, f64)> {
+        let mut close = 10.0;
+        (0..count)
+            .map(|i| {
+                close *= 1.0 + daily;
+                (20250101 + i as i64, close)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compute_strength() {
+        let stock = series(200, 0.002);
+        let bench = series(200, 0.001);
+        let sector = series(200, 0.003);
+
+        let signal = compute_strength(
+            "1.600635",
+            &stock,
+            ("1.000300", &bench),
+            Some(("90.BK1036", &sector)),
+        )
+        .unwrap();
+        assert_eq!(signal.bar_date, stock.last().unwrap().0);
+        assert!((signal.ret_1w.unwrap() - (1.002f64.powi(5) - 1.0)).abs() < 1e-12);
+        let expected = 1.002f64.powi(21) / 1.001f64.powi(21);
+        assert!((signal.rs_1m.unwrap() - expected).abs() < 1e-12);
+        assert!(signal.rs_6m.unwrap() > 1.0);
+        assert!(signal.sector_rs_3m.unwrap() < 1.0);
+        assert!(signal.score.is_some());
+
+        // Too short for the longer lookbacks.
+        let short = compute_strength("1.600635", &stock[..30], ("1.000300", &bench), None).unwrap();
+        assert!(short.rs_1m.is_some());
+        assert!(short.rs_3m.is_none());
+        assert!(short.sector_rs_1w.is_none());
+        assert!(short.score.is_none());
+    }
+
+    #[test]
+    fn test_close_at_gaps() {
+        let closes = vec![(20250102, 1.0), (20250106, 2.0)];
+        assert_eq!(close_at(&closes, 20250101), None);
+        assert_eq!(close_at(&closes, 20250103), Some(1.0));
+        assert_eq!(close_at(&closes, 20250106), Some(2.0));
+    }
+
+    #[test]
+    fn test_rate_strength() {
+        let bench = series(200, 0.0);
+        let mut signals: Vec<StrengthSignal> = [0.001, 0.003, -0.001, 0.002]
+            .iter()
+            .enumerate()
+            .map(|(i, daily)| {
+                compute_strength(&i.to_string(), &series(200, *daily), ("b", &bench), None).unwrap()
+            })
+            .collect();
+        signals.push(compute_strength("short", &series(10, 0.01), ("b", &bench), None).unwrap());
+
+        rate_strength(&mut signals);
+        let ratings: Vec<Option<i64>> = signals.iter().map(|s| s.rating).collect();
+        assert_eq!(ratings, vec![Some(34), Some(99), Some(1), Some(66), None]);
+    }
+
+    #[test]
+    fn test_market_benchmark() {
+        use crate::domain::service_market::Ticker;
+
+        assert_eq!(market_benchmark(Currency::Cny), "1.000300");
+        assert_eq!(market_benchmark(Currency::Usd), "100.SPX");
+        for (_, benchmark) in MARKET_BENCHMARKS {
+            assert!(benchmark.parse::<Ticker>().is_ok());
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    domain::{
+        model::{
+            BalanceSheet, CashflowStatement, FinancialStatements, IncomeStatement, ReportType,
+        },
+        service_market::Ticker,
+    },
+    infra::data::service::{parse_raw_eastmoney, url2text},
+};
+
+/// Periods per statement, 10 years of quarterly reports.
+const PAGE_SIZE: usize = 40;
+
+const INCOME_COLUMNS: &str = "REPORT_DATE,TOTAL_OPERATE_INCOME,OPERATE_COST,OPERATE_PROFIT,TOTAL_PROFIT,NETPROFIT,PARENT_NETPROFIT,BASIC_EPS";
+const BALANCE_COLUMNS: &str = "REPORT_DATE,TOTAL_ASSETS,TOTAL_LIABILITIES,TOTAL_EQUITY,TOTAL_PARENT_EQUITY,MONETARYFUNDS,TOTAL_CURRENT_ASSETS,TOTAL_CURRENT_LIAB";
+const CASHFLOW_COLUMNS: &str =
+    "REPORT_DATE,NETCASH_OPERATE,NETCASH_INVEST,NETCASH_FINANCE,CONSTRUCT_LONG_ASSET";
+
+/// Security code of an A-share such as `600635.SH`, None for other markets.
+pub fn secucode(ticker: &Ticker) -> Option<String> {
+    ticker.is_a_share().then(|| ticker.symbol()).flatten()
+}
+
+pub struct UrlFinancialEastmoney(String);
+
+// NOTE: the F10 general layout, banks and insurers report under other names and come back empty.
+impl UrlFinancialEastmoney {
+    fn new(report: &str, columns: &str, secucode: &str) -> Self {
+        let url = format!(
+            "https://datacenter.eastmoney.com/securities/api/data/v1/get?callback=jQuery112302947934802286927_1761565635682&reportName={}&columns={}&filter=(SECUCODE%3D%22{}%22)&pageNumber=1&pageSize={}&sortTypes=-1&sortColumns=REPORT_DATE&source=HSF10&client=PC",
+            report,
+            columns.replace(',', "%2C"),
+            secucode,
+            PAGE_SIZE
+        );
+        UrlFinancialEastmoney(url)
+    }
+
+    pub fn income(secucode: &str) -> Self {
+        Self::new("RPT_F10_FINANCE_GINCOME", INCOME_COLUMNS, secucode)
+    }
+
+    pub fn balance(secucode: &str) -> Self {
+        Self::new("RPT_F10_FINANCE_GBALANCE", BALANCE_COLUMNS, secucode)
+    }
+
+    pub fn cashflow(secucode: &str) -> Self {
+        Self::new("RPT_F10_FINANCE_GCASHFLOW", CASHFLOW_COLUMNS, secucode)
+    }
+}
+
+/// Crawl income, balance sheet and cash flow statements of an A-share from `eastmoney api`.
+pub async fn crawl_financials_eastmoney(
+    ticker: &Ticker,
+) -> Result<FinancialStatements, anyhow::Error> {
+    let Some(code) = secucode(ticker) else {
+        anyhow::bail!("financial statements are crawled for A-shares only: {ticker}");
+    };
+
+    let income: Vec<RawIncomeItem> = crawl_rows(UrlFinancialEastmoney::income(&code)).await?;
+    let balance: Vec<RawBalanceItem> = crawl_rows(UrlFinancialEastmoney::balance(&code)).await?;
+    let cashflow: Vec<RawCashflowItem> = crawl_rows(UrlFinancialEastmoney::cashflow(&code)).await?;
+
+    Ok(create_financials(ticker, income, balance, cashflow))
+}
+
+async fn crawl_rows<T: DeserializeOwned>(
+    url: UrlFinancialEastmoney,
+) -> Result<Vec<T>, anyhow::Error> {
+    let raw = url2text(&url.0).await?;
+    let raw_financial: RawFinancialEastmoney<T> = parse_raw_eastmoney(&raw)?;
+
+    Ok(raw_financial.result.map(|r| r.data).unwrap_or_default())
+}
+
+/// Chronological statements, periods off the quarter ends are dropped.
+fn create_financials(
+    ticker: &str,
+    income: Vec<RawIncomeItem>,
+    balance: Vec<RawBalanceItem>,
+    cashflow: Vec<RawCashflowItem>,
+) -> FinancialStatements {
+    let period = |report_date: &str| {
+        let period = report_date.get(..10)?.to_string();
+        ReportType::from_period(&period).map(|report_type| (period, report_type))
+    };
+
+    let mut statements = FinancialStatements {
+        income: income
+            .into_iter()
+            .filter_map(|r| {
+                let (period, report_type) = period(&r.report_date)?;
+                Some(IncomeStatement {
+                    ticker: ticker.to_string(),
+                    period,
+                    report_type,
+                    revenue: r.revenue,
+                    operating_cost: r.operating_cost,
+                    operating_profit: r.operating_profit,
+                    total_profit: r.total_profit,
+                    net_profit: r.net_profit,
+                    parent_net_profit: r.parent_net_profit,
+                    eps: r.eps,
+                })
+            })
+            .collect(),
+        balance: balance
+            .into_iter()
+            .filter_map(|r| {
+                let (period, report_type) = period(&r.report_date)?;
+                Some(BalanceSheet {
+                    ticker: ticker.to_string(),
+                    period,
+                    report_type,
+                    total_assets: r.total_assets,
+                    total_liabilities: r.total_liabilities,
+                    total_equity: r.total_equity,
+                    parent_equity: r.parent_equity,
+                    cash: r.cash,
+                    current_assets: r.current_assets,
+                    current_liabilities: r.current_liabilities,
+                })
+            })
+            .collect(),
+        cashflow: cashflow
+            .into_iter()
+            .filter_map(|r| {
+                let (period, report_type) = period(&r.report_date)?;
+                Some(CashflowStatement {
+                    ticker: ticker.to_string(),
+                    period,
+                    report_type,
+                    operating_cf: r.operating_cf,
+                    investing_cf: r.investing_cf,
+                    financing_cf: r.financing_cf,
+                    capex: r.capex,
+                })
+            })
+            .collect(),
+    };
+    statements.income.sort_by(|a, b| a.period.cmp(&b.period));
+    statements.balance.sort_by(|a, b| a.period.cmp(&b.period));
+    statements.cashflow.sort_by(|a, b| a.period.cmp(&b.period));
+
+    statements
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawFinancialEastmoney<T> {
+    /// Null when the ticker has no statements of the layout.
+    #[serde(rename = "result")]
+    result: Option<RawFinancialEastmoneyResult<T>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawFinancialEastmoneyResult<T> {
+    #[serde(rename = "data")]
+    data: Vec<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawIncomeItem {
+    #[serde(rename = "REPORT_DATE")]
+    report_date: String,
+    #[serde(rename = "TOTAL_OPERATE_INCOME")]
+    revenue: Option<f64>,
+    #[serde(rename = "OPERATE_COST")]
+    operating_cost: Option<f64>,
+    #[serde(rename = "OPERATE_PROFIT")]
+    operating_profit: Option<f64>,
+    #[serde(rename = "TOTAL_PROFIT")]
+    total_profit: Option<f64>,
+    #[serde(rename = "NETPROFIT")]
+    net_profit: Option<f64>,
+    #[serde(rename = "PARENT_NETPROFIT")]
+    parent_net_profit: Option<f64>,
+    #[serde(rename = "BASIC_EPS")]
+    eps: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawBalanceItem {
+    #[serde(rename = "REPORT_DATE")]
+    report_date: String,
+    #[serde(rename = "TOTAL_ASSETS")]
+    total_assets: Option<f64>,
+    #[serde(rename = "TOTAL_LIABILITIES")]
+    total_liabilities: Option<f64>,
+    #[serde(rename = "TOTAL_EQUITY")]
+    total_equity: Option<f64>,
+    #[serde(rename = "TOTAL_PARENT_EQUITY")]
+    parent_equity: Option<f64>,
+    #[serde(rename = "MONETARYFUNDS")]
+    cash: Option<f64>,
+    #[serde(rename = "TOTAL_CURRENT_ASSETS")]
+    current_assets: Option<f64>,
+    #[serde(rename = "TOTAL_CURRENT_LIAB")]
+    current_liabilities: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawCashflowItem {
+    #[serde(rename = "REPORT_DATE")]
+    report_date: String,
+    #[serde(rename = "NETCASH_OPERATE")]
+    operating_cf: Option<f64>,
+    #[serde(rename = "NETCASH_INVEST")]
+    investing_cf: Option<f64>,
+    #[serde(rename = "NETCASH_FINANCE")]
+    financing_cf: Option<f64>,
+    #[serde(rename = "CONSTRUCT_LONG_ASSET")]
+    capex: Option<f64>,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::model::ReportType,
+        infra::data::{
+            financial::{
+                RawBalanceItem, RawCashflowItem, RawFinancialEastmoney, RawIncomeItem,
+                crawl_financials_eastmoney, create_financials, secucode,
+            },
+            service::parse_raw_eastmoney,
+        },
+    };
+
+    const RAW_INCOME_EASTMONEY: &str = r#"jQuery112302947934802286927_1761565635682({"version":"b0c1d5a1e0a5","result":{"pages":1,"data":[{"REPORT_DATE":"2025-06-30 00:00:00","TOTAL_OPERATE_INCOME":3120504033.12,"OPERATE_COST":2512043877.4,"OPERATE_PROFIT":412309846.21,"TOTAL_PROFIT":410228519.93,"NETPROFIT":352410937.55,"PARENT_NETPROFIT":318804412.06,"BASIC_EPS":0.108},{"REPORT_DATE":"2025-03-31 00:00:00","TOTAL_OPERATE_INCOME":1587724118.49,"OPERATE_COST":1301560982.33,"OPERATE_PROFIT":190355211.87,"TOTAL_PROFIT":189976542.1,"NETPROFIT":161230011.72,"PARENT_NETPROFIT":146420375.8,"BASIC_EPS":0.0496},{"REPORT_DATE":"2024-12-31 00:00:00","TOTAL_OPERATE_INCOME":6410022875.5,"OPERATE_COST":5288120453.91,"OPERATE_PROFIT":702331904.65,"TOTAL_PROFIT":698743100.2,"NETPROFIT":590122874.31,"PARENT_NETPROFIT":533872001.44,"BASIC_EPS":null}],"count":3},"success":true,"message":"ok","code":0});"#;
+
+    const RAW_BALANCE_EASTMONEY: &str = r#"jQuery112302947934802286927_1761565635682({"version":"b0c1d5a1e0a5","result":{"pages":1,"data":[{"REPORT_DATE":"2025-06-30 00:00:00","TOTAL_ASSETS":31288410022.5,"TOTAL_LIABILITIES":17201338409.9,"TOTAL_EQUITY":14087071612.6,"TOTAL_PARENT_EQUITY":12108842233.7,"MONETARYFUNDS":4102287710.35,"TOTAL_CURRENT_ASSETS":9932004418.2,"TOTAL_CURRENT_LIAB":8122380911.4}],"count":1},"success":true,"message":"ok","code":0});"#;
+
+    const RAW_CASHFLOW_EASTMONEY: &str = r#"jQuery112302947934802286927_1761565635682({"version":"b0c1d5a1e0a5","result":{"pages":1,"data":[{"REPORT_DATE":"2025-06-30 00:00:00","NETCASH_OPERATE":502331977.1,"NETCASH_INVEST":-820114532.8,"NETCASH_FINANCE":210877403.55,"CONSTRUCT_LONG_ASSET":611207345.2}],"count":1},"success":true,"message":"ok","code":0});"#;
+
+    #[test]
+    fn test_parse_raw_financials_eastmoney() {
+        let income: RawFinancialEastmoney<RawIncomeItem> =
+            parse_raw_eastmoney(RAW_INCOME_EASTMONEY).unwrap();
+        let balance: RawFinancialEastmoney<RawBalanceItem> =
+            parse_raw_eastmoney(RAW_BALANCE_EASTMONEY).unwrap();
+        let cashflow: RawFinancialEastmoney<RawCashflowItem> =
+            parse_raw_eastmoney(RAW_CASHFLOW_EASTMONEY).unwrap();
+
+        let statements = create_financials(
+            "1.600635",
+            income.result.unwrap().data,
+            balance.result.unwrap().data,
+            cashflow.result.unwrap().data,
+        );
+
+        assert_eq!(statements.income.len(), 3);
+        let first = &statements.income[0];
+        assert_eq!(first.period, "2024-12-31");
+        assert_eq!(first.report_type, ReportType::Annual);
+        assert_eq!(first.eps, None);
+        assert_eq!(statements.income[2].report_type, ReportType::H1);
+        assert_eq!(statements.income[2].revenue, Some(3120504033.12));
+
+        assert_eq!(statements.balance[0].parent_equity, Some(12108842233.7));
+        assert_eq!(statements.cashflow[0].capex, Some(611207345.2));
+
+        let empty: RawFinancialEastmoney<RawIncomeItem> = parse_raw_eastmoney(
+            r#"jQuery1({"version":null,"result":null,"success":false,"message":"none","code":9201});"#,
+        )
+        .unwrap();
+        assert!(empty.result.is_none());
+    }
+
+    #[test]
+    fn test_secucode() {
+        let secucode = |ticker: &str| secucode(&ticker.parse().unwrap());
+        assert_eq!(secucode("1.600635").as_deref(), Some("600635.SH"));
+        assert_eq!(secucode("0.000001").as_deref(), Some("000001.SZ"));
+        assert_eq!(secucode("0.830799").as_deref(), Some("830799.BJ"));
+        assert_eq!(secucode("105.TSLA"), None);
+        assert_eq!(secucode("116.00700"), None);
+        assert_eq!(secucode("1.000300"), None);
+    }
+
+    #[tokio::test]
+    #[ignore = "network call to eastmoney"]
+    async fn test_crawl_financials_eastmoney() {
+        let statements = crawl_financials_eastmoney(&"1.600635".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert!(!statements.income.is_empty());
+        assert!(!statements.balance.is_empty());
+        assert!(!statements.cashflow.is_empty());
+    }
+}
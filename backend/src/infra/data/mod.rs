@@ -1,4 +1,7 @@
+pub mod financial;
 pub mod kline;
 pub mod moneyflow;
+pub mod quote;
+pub mod sector;
 pub mod service;
 pub mod stock;
@@ -2,11 +2,15 @@ use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use utoipa::ToSchema;
 
-use crate::infra::data::service::{parse_raw_eastmoney, url2text};
+use crate::{
+    domain::service_calendar::{Exchange, TradingCalendar},
+    infra::data::service::{parse_raw_eastmoney, url2text},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct MoneyflowEastmoney {
-    pub date_time: String,
+    /// Shanghai trading date, YYYY-MM-DD.
+    pub trade_date: String,
     pub ticker: String,
     pub realname: String,
     pub lead_value: f64,
@@ -75,18 +79,14 @@ pub struct RawMoneyflowEastmoneyItem {
     pub small_share: f64,
 }
 
-pub fn create_moneyflow(raw: RawMoneyflowEastmoney) -> Vec<MoneyflowEastmoney> {
-    let now = chrono::Utc::now()
-        .to_string()
-        .split_whitespace()
-        .next()
-        .unwrap()
-        .to_string();
+/// Keeps the `tracked` sectors only.
+pub fn create_moneyflow(raw: RawMoneyflowEastmoney, tracked: &[String]) -> Vec<MoneyflowEastmoney> {
+    let trade_date = trade_date_shanghai(chrono::Utc::now()).to_string();
     raw.data
         .diff
         .iter()
         .map(|d| MoneyflowEastmoney {
-            date_time: now.to_string(),
+            trade_date: trade_date.clone(),
             ticker: format!("{}.{}", d.market, d.ticker),
             realname: d.name.clone(),
             lead_value: d.lead_value,
@@ -100,10 +100,18 @@ pub fn create_moneyflow(raw: RawMoneyflowEastmoney) -> Vec<MoneyflowEastmoney> {
             small_value: d.small_value,
             small_share: d.small_share,
         })
-        .filter(|mf| BK.iter().any(|x| **x == mf.ticker))
+        .filter(|mf| tracked.contains(&mf.ticker))
         .collect()
 }
 
+/// Trading date the moneyflow at `now` belongs to.
+///
+/// Before the 09:30 open the figures are still those of the previous session, and weekends
+/// and holidays roll back to the last trading day.
+pub fn trade_date_shanghai(now: chrono::DateTime<chrono::Utc>) -> chrono::NaiveDate {
+    TradingCalendar::get(Exchange::Sse).trade_date(now)
+}
+
 pub struct UrlMoneyflowSectorEastmoney(String);
 
 // NOTE: this url is for weekly kline.
@@ -124,83 +132,186 @@ impl Default for UrlMoneyflowSectorEastmoney {
 
 pub async fn crawl_moneyflow_sector_eastmoney(
     url: UrlMoneyflowSectorEastmoney,
+    tracked: &[String],
 ) -> Result<Vec<MoneyflowEastmoney>, anyhow::Error> {
     let raw = url2text(&url.0).await?;
     let raw_moneyflow: Result<RawMoneyflowEastmoney, _> = parse_raw_eastmoney(&raw);
 
     match raw_moneyflow {
-        Ok(res) => Ok(create_moneyflow(res)),
+        Ok(res) => Ok(create_moneyflow(res, tracked)),
         Err(e) => anyhow::bail!(e.to_string()),
     }
 }
 
-const BK: [&str; 55] = [
-    "90.BK0475",
-    "90.BK1036",
-    "90.BK0464",
-    "90.BK0473",
-    "90.BK1037",
-    "90.BK0428",
-    "90.BK0736",
-    "90.BK0474",
-    "90.BK0459",
-    "90.BK1033",
-    "90.BK0448",
-    "90.BK0737",
-    "90.BK0481",
-    "90.BK0478",
-    "90.BK1029",
-    "90.BK0465",
-    "90.BK0910",
-    "90.BK0437",
-    "90.BK0447",
-    "90.BK1031",
-    "90.BK0438",
-    "90.BK1044",
-    "90.BK0545",
-    "90.BK0538",
-    "90.BK0457",
-    "90.BK0425",
-    "90.BK1041",
-    "90.BK0480",
-    "90.BK1027",
-    "90.BK1038",
-    "90.BK0735",
-    "90.BK0486",
-    "90.BK0433",
-    "90.BK0450",
-    "90.BK0479",
-    "90.BK1019",
-    "90.BK0422",
-    "90.BK0727",
-    "90.BK0739",
-    "90.BK0429",
-    "90.BK1034",
-    "90.BK0454",
-    "90.BK1046",
-    "90.BK1015",
-    "90.BK0471",
-    "90.BK0732",
-    "90.BK0731",
-    "90.BK1020",
-    "90.BK0729",
-    "90.BK0546",
-    "90.BK0458",
-    "90.BK0424",
-    "90.BK1039",
-    "90.BK1017",
-    "90.BK1030",
-];
+/// Daily order-size breakdown of a stock's net inflow.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema, PartialEq)]
+pub struct MoneyflowStock {
+    /// Trading date, YYYY-MM-DD.
+    pub trade_date: String,
+    pub ticker: String,
+    /// Main force net inflow, super plus large orders.
+    pub lead_value: f64,
+    pub lead_share: f64,
+    pub super_value: f64,
+    pub super_share: f64,
+    pub large_value: f64,
+    pub large_share: f64,
+    pub mid_value: f64,
+    pub mid_share: f64,
+    pub small_value: f64,
+    pub small_share: f64,
+    pub close: f64,
+    pub change_pct: f64,
+}
+
+/// Cumulative net inflow of a stock up to a minute of the current session.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct MoneyflowMinute {
+    /// YYYY-MM-DD HH:MM.
+    pub time: String,
+    pub lead_value: f64,
+    pub super_value: f64,
+    pub large_value: f64,
+    pub mid_value: f64,
+    pub small_value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawMoneyflowStockEastmoney {
+    /// Null for an unknown ticker.
+    #[serde(rename = "data")]
+    data: Option<RawMoneyflowStockEastmoneyData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawMoneyflowStockEastmoneyData {
+    #[serde(rename = "code")]
+    code: String,
+    #[serde(rename = "market")]
+    market: i64,
+    #[serde(rename = "klines")]
+    klines: Vec<String>,
+}
+
+pub struct UrlMoneyflowStockEastmoney(String);
+
+impl UrlMoneyflowStockEastmoney {
+    /// Daily history, about the last 120 trading days.
+    pub fn daily(ticker: &str) -> Self {
+        let url = format!(
+            "https://push2his.eastmoney.com/api/qt/stock/fflow/daykline/get?cb=jQuery112306575010884934862_1761565635682&lmt=0&klt=101&secid={}&fields1=f1%2Cf2%2Cf3%2Cf7&fields2=f51%2Cf52%2Cf53%2Cf54%2Cf55%2Cf56%2Cf57%2Cf58%2Cf59%2Cf60%2Cf61%2Cf62%2Cf63%2Cf64%2Cf65&ut=b2884a393a59ad64002292a3e90d46a5",
+            ticker
+        );
+        UrlMoneyflowStockEastmoney(url)
+    }
+
+    /// Minute series of the current, or last, session.
+    pub fn intraday(ticker: &str) -> Self {
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/stock/fflow/kline/get?cb=jQuery112306575010884934862_1761565635682&lmt=0&klt=1&secid={}&fields1=f1%2Cf2%2Cf3%2Cf7&fields2=f51%2Cf52%2Cf53%2Cf54%2Cf55%2Cf56%2Cf57%2Cf58%2Cf59%2Cf60%2Cf61%2Cf62%2Cf63%2Cf64%2Cf65&ut=b2884a393a59ad64002292a3e90d46a5",
+            ticker
+        );
+        UrlMoneyflowStockEastmoney(url)
+    }
+}
+
+pub async fn crawl_moneyflow_stock_eastmoney(
+    url: UrlMoneyflowStockEastmoney,
+) -> Result<Vec<MoneyflowStock>, anyhow::Error> {
+    let raw = url2text(&url.0).await?;
+    let raw_moneyflow: RawMoneyflowStockEastmoney = parse_raw_eastmoney(&raw)?;
+
+    create_moneyflow_stock(raw_moneyflow)
+}
+
+pub async fn crawl_moneyflow_intraday_eastmoney(
+    url: UrlMoneyflowStockEastmoney,
+) -> Result<Vec<MoneyflowMinute>, anyhow::Error> {
+    let raw = url2text(&url.0).await?;
+    let raw_moneyflow: RawMoneyflowStockEastmoney = parse_raw_eastmoney(&raw)?;
+
+    create_moneyflow_intraday(raw_moneyflow)
+}
+
+// Daily fields: date, lead, small, mid, large, super values, then the same shares, close, change %.
+pub fn create_moneyflow_stock(
+    raw: RawMoneyflowStockEastmoney,
+) -> Result<Vec<MoneyflowStock>, anyhow::Error> {
+    let Some(data) = raw.data else {
+        return Ok(vec![]);
+    };
+    let ticker = format!("{}.{}", data.market, data.code);
+
+    data.klines
+        .iter()
+        .map(|line| {
+            let parts: Vec<&str> = line.split(',').collect();
+            if parts.len() < 13 {
+                anyhow::bail!("Expected 13 fields, got {}", parts.len());
+            }
+            let num = |i: usize| parts[i].parse::<f64>();
+            Ok(MoneyflowStock {
+                trade_date: parts[0].to_string(),
+                ticker: ticker.clone(),
+                lead_value: num(1)?,
+                small_value: num(2)?,
+                mid_value: num(3)?,
+                large_value: num(4)?,
+                super_value: num(5)?,
+                lead_share: num(6)?,
+                small_share: num(7)?,
+                mid_share: num(8)?,
+                large_share: num(9)?,
+                super_share: num(10)?,
+                close: num(11)?,
+                change_pct: num(12)?,
+            })
+        })
+        .collect()
+}
+
+// Minute fields: time, lead, small, mid, large, super values.
+pub fn create_moneyflow_intraday(
+    raw: RawMoneyflowStockEastmoney,
+) -> Result<Vec<MoneyflowMinute>, anyhow::Error> {
+    let Some(data) = raw.data else {
+        return Ok(vec![]);
+    };
+
+    data.klines
+        .iter()
+        .map(|line| {
+            let parts: Vec<&str> = line.split(',').collect();
+            if parts.len() < 6 {
+                anyhow::bail!("Expected 6 fields, got {}", parts.len());
+            }
+            let num = |i: usize| parts[i].parse::<f64>();
+            Ok(MoneyflowMinute {
+                time: parts[0].to_string(),
+                lead_value: num(1)?,
+                small_value: num(2)?,
+                mid_value: num(3)?,
+                large_value: num(4)?,
+                super_value: num(5)?,
+            })
+        })
+        .collect()
+}
 
 #[cfg(test)]
 mod test {
     use crate::infra::data::{
         moneyflow::{
-            RawMoneyflowEastmoney, UrlMoneyflowSectorEastmoney, crawl_moneyflow_sector_eastmoney,
+            RawMoneyflowEastmoney, RawMoneyflowStockEastmoney, UrlMoneyflowSectorEastmoney,
+            UrlMoneyflowStockEastmoney, crawl_moneyflow_intraday_eastmoney,
+            crawl_moneyflow_sector_eastmoney, crawl_moneyflow_stock_eastmoney,
+            create_moneyflow_intraday, create_moneyflow_stock,
         },
         service::parse_raw_eastmoney,
     };
 
+    use super::trade_date_shanghai;
+
     const RAW_MONEYFLOW_SECTOR_EASTMONEY: &str = r#"jQuery112301083078708820121_1761565635682({"rc":0,"rt":6,"svr":181669432,"lt":1,"full":1,"dlmkts":"","data":{"total":86,"diff":[{"f1":2,"f2":35063.87,"f3":1.9,"f12":"BK0459","f13":90,"f14":"电子元件","f62":2672467968.0,"f66":2872657664.0,"f69":2.02,"f72":-200189696.0,"f75":-0.14,"f78":-1933110528.0,"f81":-1.36,"f84":-768382720.0,"f87":-0.54,"f124":1761550782,"f184":1.88,"f204":"胜宏科技","f205":"300476","f206":0},{"f1":2,"f2":18444.16,"f3":1.56,"f12":"BK0448","f13":90,"f14":"通信设备","f62":2602910976.0,"f66":3761489408.0,"f69":2.88,"f72":-1158578432.0,"f75":-0.89,"f78":-1900750336.0,"f81":-1.45,"f84":-711574272.0,"f87":-0.54,"f124":1761550782,"f184":1.99,"f204":"恒宝股份","f205":"002104","f206":0},{"f1":2,"f2":1987.6,"f3":1.54,"f12":"BK1037","f13":90,"f14":"消费电子","f62":2465783296.0,"f66":1903972096.0,"f69":1.94,"f72":561811200.0,"f75":0.57,"f78":-1500482304.0,"f81":-1.53,"f84":-998753792.0,"f87":-1.02,"f124":1761550782,"f184":2.52,"f204":"工业富联","f205":"601138","f206":1},{"f1":2,"f2":3109.46,"f3":2.39,"f12":"BK1027","f13":90,"f14":"小金属","f62":2056189696.0,"f66":2037394432.0,"f69":4.12,"f72":18795264.0,"f75":0.04,"f78":-770170368.0,"f81":-1.56,"f84":-1281168128.0,"f87":-2.59,"f124":1761550782,"f184":4.16,"f204":"厦门钨业","f205":"600549","f206":1},{"f1":2,"f2":2027.18,"f3":2.13,"f12":"BK1036","f13":90,"f14":"半导体","f62":1888555776.0,"f66":2031833856.0,"f69":0.83,"f72":-143278080.0,"f75":-0.06,"f78":-980680704.0,"f81":-0.4,"f84":-914381824.0,"f87":-0.38,"f124":1761550782,"f184":0.78,"f204":"兆易创新","f205":"603986","f206":1},{"f1":2,"f2":38790.99,"f3":0.83,"f12":"BK0465","f13":90,"f14":"化学制药","f62":1096540608.0,"f66":999148992.0,"f69":2.37,"f72":97391616.0,"f75":0.23,"f78":-981284352.0,"f81":-2.33,"f84":-143647744.0,"f87":-0.34,"f124":1761550782,"f184":2.6,"f204":"向日葵","f205":"300111","f206":0},{"f1":2,"f2":18347.21,"f3":1.36,"f12":"BK0428","f13":90,"f14":"电力行业","f62":1026856192.0,"f66":849184512.0,"f69":2.29,"f72":177671680.0,"f75":0.48,"f78":-1001579264.0,"f81":-2.7,"f84":-25276928.0,"f87":-0.07,"f124":1761550782,"f184":2.77,"f204":"中国核电","f205":"601985","f206":1},{"f1":2,"f2":9024.85,"f3":1.92,"f12":"BK0479","f13":90,"f14":"钢铁行业","f62":997690944.0,"f66":1004895040.0,"f69":5.74,"f72":-7204096.0,"f75":-0.04,"f78":-414445312.0,"f81":-2.37,"f84":-578170880.0,"f87":-3.3,"f124":1761550782,"f184":5.7,"f204":"新兴铸管","f205":"000778","f206":0},{"f1":2,"f2":33663.93,"f3":0.89,"f12":"BK0457","f13":90,"f14":"电网设备","f62":697530368.0,"f66":628551680.0,"f69":1.33,"f72":68978688.0,"f75":0.15,"f78":-144529664.0,"f81":-0.3,"f84":-603417088.0,"f87":-1.27,"f124":1761550782,"f184":1.47,"f204":"中国西电","f205":"601179","f206":1},{"f1":2,"f2":3500.02,"f3":0.93,"f12":"BK0910","f13":90,"f14":"专用设备","f62":645576192.0,"f66":684069888.0,"f69":0.86,"f72":-38493696.0,"f75":-0.05,"f78":-413467904.0,"f81":-0.52,"f84":-281076992.0,"f87":-0.35,"f124":1761550782,"f184":0.81,"f204":"英维克","f205":"002837","f206":0},{"f1":2,"f2":28302.66,"f3":0.58,"f12":"BK0425","f13":90,"f14":"工程建设","f62":616678496.0,"f66":506754144.0,"f69":1.8,"f72":109924352.0,"f75":0.39,"f78":-155837440.0,"f81":-0.55,"f84":-463444992.0,"f87":-1.65,"f124":1761550782,"f184":2.19,"f204":"中国电建","f205":"601669","f206":1},{"f1":2,"f2":1506.72,"f3":0.82,"f12":"BK0474","f13":90,"f14":"保险","f62":502335744.0,"f66":388679376.0,"f69":4.07,"f72":113656368.0,"f75":1.19,"f78":-406719424.0,"f81":-4.26,"f84":-95616336.0,"f87":-1.0,"f124":1761550782,"f184":5.26,"f204":"中国平安","f205":"601318","f206":1},{"f1":2,"f2":11688.06,"f3":1.27,"f12":"BK0484","f13":90,"f14":"贸易行业","f62":371387936.0,"f66":285093952.0,"f69":2.37,"f72":86293984.0,"f75":0.72,"f78":-119965136.0,"f81":-1.0,"f84":-258886784.0,"f87":-2.15,"f124":1761550782,"f184":3.09,"f204":"中电港","f205":"001287","f206":0},{"f1":2,"f2":47942.24,"f3":1.41,"f12":"BK0546","f13":90,"f14":"玻璃玻纤","f62":293145184.0,"f66":168121440.0,"f69":2.3,"f72":125023744.0,"f75":1.71,"f78":-94673024.0,"f81":-1.3,"f84":-179209440.0,"f87":-2.45,"f124":1761550782,"f184":4.01,"f204":"中材科技","f205":"002080","f206":0},{"f1":2,"f2":12567.57,"f3":0.51,"f12":"BK0539","f13":90,"f14":"综合行业","f62":284617232.0,"f66":321809104.0,"f69":4.11,"f72":-37191872.0,"f75":-0.47,"f78":-37467264.0,"f81":-0.48,"f84":-247149968.0,"f87":-3.15,"f124":1761550782,"f184":3.63,"f204":"东阳光","f205":"600673","f206":1},{"f1":2,"f2":12772.6,"f3":0.9,"f12":"BK0437","f13":90,"f14":"煤炭行业","f62":272668576.0,"f66":288082608.0,"f69":1.53,"f72":-15414032.0,"f75":-0.08,"f78":-38485504.0,"f81":-0.2,"f84":-234182912.0,"f87":-1.24,"f124":1761550782,"f184":1.45,"f204":"郑州煤电","f205":"600121","f206":1},{"f1":2,"f2":1087.13,"f3":2.34,"f12":"BK0729","f13":90,"f14":"船舶制造","f62":257691648.0,"f66":266805024.0,"f69":3.05,"f72":-9113376.0,"f75":-0.1,"f78":-195964128.0,"f81":-2.24,"f84":-61727520.0,"f87":-0.71,"f124":1761550782,"f184":2.94,"f204":"中国船舶","f205":"600150","f206":1},{"f1":2,"f2":2196.43,"f3":3.08,"f12":"BK1039","f13":90,"f14":"电子化学品","f62":255217344.0,"f66":585644992.0,"f69":2.33,"f72":-330427648.0,"f75":-1.31,"f78":-314919168.0,"f81":-1.25,"f84":59701760.0,"f87":0.24,"f124":1761550782,"f184":1.01,"f204":"晶瑞电材","f205":"300655","f206":0},{"f1":2,"f2":1350.48,"f3":0.97,"f12":"BK0730","f13":90,"f14":"农药兽药","f62":206520080.0,"f66":77003872.0,"f69":0.83,"f72":129516208.0,"f75":1.4,"f78":39617120.0,"f81":0.43,"f84":-255658768.0,"f87":-2.76,"f124":1761550782,"f184":2.23,"f204":"联化科技","f205":"002250","f206":0},{"f1":2,"f2":23845.93,"f3":1.06,"f12":"BK0478","f13":90,"f14":"有色金属","f62":189264384.0,"f66":111172096.0,"f69":0.18,"f72":78092288.0,"f75":0.12,"f78":443991040.0,"f81":0.71,"f84":-640158976.0,"f87":-1.02,"f124":1761550782,"f184":0.3,"f204":"紫金矿业","f205":"601899","f206":1},{"f1":2,"f2":15733.54,"f3":0.79,"f12":"BK0433","f13":90,"f14":"农牧饲渔","f62":184318400.0,"f66":299851392.0,"f69":1.79,"f72":-115532992.0,"f75":-0.69,"f78":-91829760.0,"f81":-0.55,"f84":-106748672.0,"f87":-0.64,"f124":1761550782,"f184":1.1,"f204":"牧原股份","f205":"002714","f206":0},{"f1":2,"f2":30942.83,"f3":1.0,"f12":"BK0538","f13":90,"f14":"化学制品","f62":141105904.0,"f66":212118000.0,"f69":0.49,"f72":-71012096.0,"f75":-0.16,"f78":-174632704.0,"f81":-0.4,"f84":47441152.0,"f87":0.11,"f124":1761550782,"f184":0.33,"f204":"天赐材料","f205":"002709","f206":0},{"f1":2,"f2":7908.95,"f3":0.17,"f12":"BK0422","f13":90,"f14":"物流行业","f62":134002704.0,"f66":189793424.0,"f69":1.76,"f72":-55790720.0,"f75":-0.52,"f78":-103790080.0,"f81":-0.96,"f84":-33989888.0,"f87":-0.32,"f124":1761550782,"f184":1.25,"f204":"胜通能源","f205":"001331","f206":0},{"f1":2,"f2":30530.19,"f3":0.97,"f12":"BK0424","f13":90,"f14":"水泥建材","f62":106185504.0,"f66":49538272.0,"f69":0.8,"f72":56647232.0,"f75":0.92,"f78":-48269104.0,"f81":-0.78,"f84":-57916384.0,"f87":-0.94,"f124":1761550782,"f184":1.72,"f204":"海螺水泥","f205":"600585","f206":1},{"f1":2,"f2":1285.58,"f3":1.16,"f12":"BK0727","f13":90,"f14":"医疗服务","f62":93195760.0,"f66":327629040.0,"f69":1.67,"f72":-234433280.0,"f75":-1.19,"f78":-302119168.0,"f81":-1.54,"f84":208923648.0,"f87":1.06,"f124":1761550782,"f184":0.47,"f204":"药明康德","f205":"603259","f206":1},{"f1":2,"f2":1024.65,"f3":0.29,"f12":"BK1041","f13":90,"f14":"医疗器械","f62":88950640.0,"f66":-221685904.0,"f69":-0.99,"f72":310636544.0,"f75":1.38,"f78":17168640.0,"f81":0.08,"f84":-114670592.0,"f87":-0.51,"f124":1761550782,"f184":0.4,"f204":"福瑞股份","f205":"300049","f206":0},{"f1":2,"f2":1128.72,"f3":1.07,"f12":"BK1016","f13":90,"f14":"汽车服务","f62":70827612.0,"f66":125612796.0,"f69":7.37,"f72":-54785184.0,"f75":-3.21,"f78":-76294032.0,"f81":-4.48,"f84":521312.0,"f87":0.03,"f124":1761550782,"f184":4.16,"f204":"漳州发展","f205":"000753","f206":0},{"f1":2,"f2":15298.11,"f3":-0.17,"f12":"BK0451","f13":90,"f14":"房地产开发","f62":58891120.0,"f66":-36992144.0,"f69":-0.16,"f72":95883264.0,"f75":0.41,"f78":-26480384.0,"f81":-0.11,"f84":-32410624.0,"f87":-0.14,"f124":1761550782,"f184":0.25,"f204":"首开股份","f205":"600376","f206":1},{"f1":2,"f2":1635.22,"f3":1.39,"f12":"BK0731","f13":90,"f14":"化肥行业","f62":46755136.0,"f66":212334640.0,"f69":2.25,"f72":-165579504.0,"f75":-1.76,"f78":-253192816.0,"f81":-2.68,"f84":206437680.0,"f87":2.19,"f124":1761550782,"f184":0.5,"f204":"盐湖股份","f205":"000792","f206":0},{"f1":2,"f2":483.59,"f3":0.49,"f12":"BK0734","f13":90,"f14":"珠宝首饰","f62":45087792.0,"f66":-30447968.0,"f69":-1.42,"f72":75535760.0,"f75":3.51,"f78":-2759760.0,"f81":-0.13,"f84":-42328016.0,"f87":-1.97,"f124":1761550782,"f184":2.1,"f204":"潮宏基","f205":"002345","f206":0},{"f1":2,"f2":693.44,"f3":0.33,"f12":"BK0738","f13":90,"f14":"多元金融","f62":41639344.0,"f66":86104544.0,"f69":1.26,"f72":-44465200.0,"f75":-0.65,"f78":-74005520.0,"f81":-1.08,"f84":32366160.0,"f87":0.47,"f124":1761550782,"f184":0.61,"f204":"中油资本","f205":"000617","f206":0},{"f1":2,"f2":715.47,"f3":2.17,"f12":"BK1015","f13":90,"f14":"能源金属","f62":36233600.0,"f66":38813056.0,"f69":0.16,"f72":-2579456.0,"f75":-0.01,"f78":-186657280.0,"f81":-0.78,"f84":150423552.0,"f87":0.63,"f124":1761550782,"f184":0.15,"f204":"华友钴业","f205":"603799","f206":1},{"f1":2,"f2":1759.77,"f3":0.83,"f12":"BK1020","f13":90,"f14":"非金属材料","f62":36116352.0,"f66":161883776.0,"f69":0.8,"f72":-125767424.0,"f75":-0.62,"f78":-47178752.0,"f81":-0.23,"f84":-10470656.0,"f87":-0.05,"f124":1761550782,"f184":0.18,"f204":"菲利华","f205":"300395","f206":0},{"f1":2,"f2":2632.39,"f3":0.34,"f12":"BK1030","f13":90,"f14":"电机","f62":27743792.0,"f66":182289472.0,"f69":1.05,"f72":-154545680.0,"f75":-0.89,"f78":-322093056.0,"f81":-1.85,"f84":232761344.0,"f87":1.34,"f124":1761550782,"f184":0.16,"f204":"卧龙电驱","f205":"600580","f206":1},{"f1":2,"f2":692.49,"f3":0.59,"f12":"BK0740","f13":90,"f14":"教育","f62":20278388.0,"f66":7033636.0,"f69":0.31,"f72":13244752.0,"f75":0.59,"f78":-39129168.0,"f81":-1.73,"f84":18850768.0,"f87":0.83,"f124":1761550782,"f184":0.9,"f204":"中公教育","f205":"002607","f206":0},{"f1":2,"f2":19150.0,"f3":0.8,"f12":"BK0454","f13":90,"f14":"塑料制品","f62":16523088.0,"f66":-34563472.0,"f69":-0.21,"f72":51086560.0,"f75":0.31,"f78":165054208.0,"f81":1.01,"f84":-211354368.0,"f87":-1.29,"f124":1761550782,"f184":0.1,"f204":"佛塑科技","f205":"000973","f206":0},{"f1":2,"f2":1235.4,"f3":0.4,"f12":"BK0733","f13":90,"f14":"包装材料","f62":12034057.0,"f66":471449.0,"f69":0.01,"f72":11562608.0,"f75":0.31,"f78":-27117552.0,"f81":-0.73,"f84":12477664.0,"f87":0.34,"f124":1761550782,"f184":0.33,"f204":"王子新材","f205":"002735","f206":0},{"f1":2,"f2":18673.43,"f3":0.44,"f12":"BK0482","f13":90,"f14":"商业百货","f62":-32728768.0,"f66":73189520.0,"f69":0.65,"f72":-105918288.0,"f75":-0.94,"f78":9347488.0,"f81":0.08,"f84":23381264.0,"f87":0.21,"f124":1761550782,"f184":-0.29,"f204":"国光连锁","f205":"605188","f206":1},{"f1":2,"f2":1128.47,"f3":0.61,"f12":"BK1042","f13":90,"f14":"医药商业","f62":-43339544.0,"f66":-4437864.0,"f69":-0.11,"f72":-38901680.0,"f75":-0.94,"f78":-34779520.0,"f81":-0.84,"f84":78119072.0,"f87":1.89,"f124":1761550782,"f184":-1.05,"f204":"九州通","f205":"600998","f206":1},{"f1":2,"f2":10602.94,"f3":0.23,"f12":"BK0470","f13":90,"f14":"造纸印刷","f62":-46049120.0,"f66":-80690000.0,"f69":-1.38,"f72":34640880.0,"f75":0.59,"f78":79723008.0,"f81":1.37,"f84":-42126336.0,"f87":-0.72,"f124":1761550782,"f184":-0.79,"f204":"中顺洁柔","f205":"002511","f206":0},{"f1":2,"f2":533.72,"f3":0.2,"f12":"BK0728","f13":90,"f14":"环保行业","f62":-67351584.0,"f66":-20234016.0,"f69":-0.1,"f72":-47117568.0,"f75":-0.24,"f78":142688768.0,"f81":0.72,"f84":-81471232.0,"f87":-0.41,"f124":1761550782,"f184":-0.34,"f204":"高能环境","f205":"603588","f206":1},{"f1":2,"f2":6204.4,"f3":0.2,"f12":"BK0464","f13":90,"f14":"石油行业","f62":-99684464.0,"f66":242880368.0,"f69":3.3,"f72":-342564832.0,"f75":-4.66,"f78":70292512.0,"f81":0.96,"f84":29391968.0,"f87":0.4,"f124":1761550782,"f184":-1.36,"f204":"中国石化","f205":"600028","f206":1},{"f1":2,"f2":1715.29,"f3":0.88,"f12":"BK1018","f13":90,"f14":"橡胶制品","f62":-117049728.0,"f66":-45177648.0,"f69":-0.6,"f72":-71872080.0,"f75":-0.95,"f78":-7609728.0,"f81":-0.1,"f84":86729680.0,"f87":1.15,"f124":1761550782,"f184":-1.55,"f204":"科创新源","f205":"300731","f206":0},{"f1":2,"f2":1223.82,"f3":-0.47,"f12":"BK1045","f13":90,"f14":"房地产服务","f62":-122941878.0,"f66":-69009094.0,"f69":-2.69,"f72":-53932784.0,"f75":-2.11,"f78":-15535600.0,"f81":-0.61,"f84":138477472.0,"f87":5.41,"f124":1761550782,"f184":-4.8,"f204":"我爱我家","f205":"000560","f206":0},{"f1":2,"f2":9115.6,"f3":0.01,"f12":"BK0427","f13":90,"f14":"公用事业","f62":-132585264.0,"f66":-82856112.0,"f69":-2.36,"f72":-49729152.0,"f75":-1.42,"f78":29656448.0,"f81":0.84,"f84":102928816.0,"f87":2.93,"f124":1761550782,"f184":-3.77,"f204":"海天股份","f205":"603759","f206":1},{"f1":2,"f2":16999.7,"f3":0.11,"f12":"BK0471","f13":90,"f14":"化纤行业","f62":-132674803.0,"f66":-112412947.0,"f69":-2.54,"f72":-20261856.0,"f75":-0.46,"f78":34904880.0,"f81":0.79,"f84":92093280.0,"f87":2.08,"f124":1761550782,"f184":-3.0,"f204":"恒力石化","f205":"600346","f206":1},{"f1":2,"f2":754.04,"f3":0.58,"f12":"BK0725","f13":90,"f14":"装修装饰","f62":-159712048.0,"f66":-180949088.0,"f69":-2.99,"f72":21237040.0,"f75":0.35,"f78":144701184.0,"f81":2.39,"f84":55498560.0,"f87":0.92,"f124":1761550782,"f184":-2.64,"f204":"中铁装配","f205":"300374","f206":0},{"f1":2,"f2":1880.31,"f3":0.25,"f12":"BK1028","f13":90,"f14":"燃气","f62":-159932336.0,"f66":-177324928.0,"f69":-2.51,"f72":17392592.0,"f75":0.25,"f78":-31213168.0,"f81":-0.44,"f84":182760304.0,"f87":2.59,"f124":1761550782,"f184":-2.27,"f204":"首华燃气","f205":"300483","f206":0},{"f1":2,"f2":11887.93,"f3":1.16,"f12":"BK0429","f13":90,"f14":"交运设备","f62":-173683152.0,"f66":-154603040.0,"f69":-1.47,"f72":-19080112.0,"f75":-0.18,"f78":75509008.0,"f81":0.72,"f84":94802976.0,"f87":0.9,"f124":1761550782,"f184":-1.65,"f204":"宗申动力","f205":"001696","f206":0},{"f1":2,"f2":5272.9,"f3":0.05,"f12":"BK0420","f13":90,"f14":"航空机场","f62":-186943328.0,"f66":-205286688.0,"f69":-4.64,"f72":18343360.0,"f75":0.41,"f78":56613232.0,"f81":1.28,"f84":130330080.0,"f87":2.94,"f124":1761550782,"f184":-4.22,"f204":"南方航空","f205":"600029","f206":1}]}});"#;
 
     #[test]
@@ -232,11 +343,52 @@ mod test {
         assert_eq!(last.small_share, 2.94);
     }
 
+    #[test]
+    fn test_trade_date_shanghai() {
+        let at = |s: &str| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        };
+        let date = |s: &str| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+
+        // 23:59 Wednesday in Shanghai is still Wednesday 15:59 UTC.
+        assert_eq!(
+            trade_date_shanghai(at("2025-11-05T15:59:00Z")),
+            date("2025-11-05")
+        );
+        // 00:30 Thursday in Shanghai is Wednesday in UTC, before the open it stays Wednesday.
+        assert_eq!(
+            trade_date_shanghai(at("2025-11-05T16:30:00Z")),
+            date("2025-11-05")
+        );
+        // After the Thursday open.
+        assert_eq!(
+            trade_date_shanghai(at("2025-11-06T02:00:00Z")),
+            date("2025-11-06")
+        );
+        // Saturday and Monday morning roll back to Friday.
+        assert_eq!(
+            trade_date_shanghai(at("2025-11-08T04:00:00Z")),
+            date("2025-11-07")
+        );
+        assert_eq!(
+            trade_date_shanghai(at("2025-11-10T00:00:00Z")),
+            date("2025-11-07")
+        );
+        // National Day holidays roll back to the last session before them.
+        assert_eq!(
+            trade_date_shanghai(at("2025-10-08T04:00:00Z")),
+            date("2025-09-30")
+        );
+    }
+
     #[tokio::test]
     #[ignore = "network call to eastmoney"]
     async fn test_crawl_moneyflow_sector_eastmoney() {
         let url = UrlMoneyflowSectorEastmoney::default();
-        let result = crawl_moneyflow_sector_eastmoney(url).await;
+        let tracked = vec!["90.BK0475".to_string(), "90.BK1036".to_string()];
+        let result = crawl_moneyflow_sector_eastmoney(url, &tracked).await;
 
         assert!(result.is_ok());
 
@@ -247,4 +399,52 @@ mod test {
         assert!(first.ticker.contains("BK"));
         assert!(first.lead_value.is_normal());
     }
+
+    const RAW_MONEYFLOW_STOCK_EASTMONEY: &str = r#"jQuery112306575010884934862_1761565635682({"rc":0,"rt":22,"svr":181669432,"lt":1,"full":0,"dlmkts":"","data":{"code":"600635","market":1,"name":"大众公用","klines":["2025-11-04,12345678.0,-8000000.0,-4345678.0,2345678.0,10000000.0,5.12,-3.32,-1.80,0.97,4.15,5.61,2.19,0.00,0.00","2025-11-05,-2345678.0,1000000.0,1345678.0,-1345678.0,-1000000.0,-1.02,0.44,0.58,-0.58,-0.44,5.55,-1.07,0.00,0.00"]}});"#;
+    const RAW_MONEYFLOW_INTRADAY_EASTMONEY: &str = r#"jQuery112306575010884934862_1761565635682({"rc":0,"rt":21,"svr":181669432,"lt":1,"full":0,"dlmkts":"","data":{"code":"600635","market":1,"name":"大众公用","tradePeriods":{},"klines":["2025-11-05 09:31,120000.0,-50000.0,-70000.0,20000.0,100000.0","2025-11-05 09:32,180000.0,-90000.0,-90000.0,30000.0,150000.0"]}});"#;
+
+    #[test]
+    fn test_parse_moneyflow_stock_eastmoney() {
+        let raw: RawMoneyflowStockEastmoney =
+            parse_raw_eastmoney(RAW_MONEYFLOW_STOCK_EASTMONEY).unwrap();
+        let daily = create_moneyflow_stock(raw).unwrap();
+
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].ticker, "1.600635");
+        assert_eq!(daily[0].trade_date, "2025-11-04");
+        assert_eq!(daily[0].lead_value, 12345678.0);
+        assert_eq!(daily[0].super_value, 10000000.0);
+        assert_eq!(daily[0].large_value, 2345678.0);
+        assert_eq!(daily[0].small_share, -3.32);
+        assert_eq!(daily[1].close, 5.55);
+        assert_eq!(daily[1].change_pct, -1.07);
+
+        let raw: RawMoneyflowStockEastmoney =
+            parse_raw_eastmoney(RAW_MONEYFLOW_INTRADAY_EASTMONEY).unwrap();
+        let minutes = create_moneyflow_intraday(raw).unwrap();
+
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(minutes[1].time, "2025-11-05 09:32");
+        assert_eq!(minutes[1].lead_value, 180000.0);
+        assert_eq!(minutes[1].super_value, 150000.0);
+
+        let unknown: RawMoneyflowStockEastmoney =
+            parse_raw_eastmoney(r#"jQuery1({"rc":0,"rt":22,"data":null});"#).unwrap();
+        assert!(create_moneyflow_stock(unknown).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "network call to eastmoney"]
+    async fn test_crawl_moneyflow_stock_eastmoney() {
+        let daily = crawl_moneyflow_stock_eastmoney(UrlMoneyflowStockEastmoney::daily("1.600635"))
+            .await
+            .unwrap();
+        assert!(!daily.is_empty());
+
+        let minutes =
+            crawl_moneyflow_intraday_eastmoney(UrlMoneyflowStockEastmoney::intraday("1.600635"))
+                .await
+                .unwrap();
+        assert!(!minutes.is_empty());
+    }
 }
@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::quotes::QuoteSource,
+    domain::{model::Quote, service_market::Ticker},
+    infra::data::{
+        service::{agent2text, parse_raw_eastmoney, proxy_agent, url2text},
+        stock::deserialize_optional_float,
+    },
+};
+
+/// Secids per request, eastmoney truncates longer lists.
+const BATCH_SIZE: usize = 50;
+/// Bounds each request of a poll.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Bounds a whole poll, all batches included.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+pub struct UrlQuoteEastmoney(String);
+
+impl UrlQuoteEastmoney {
+    /// Quotes of several secids in one request.
+    pub fn new(tickers: &[Ticker]) -> Self {
+        let tickers: Vec<&str> = tickers.iter().map(Ticker::as_str).collect();
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/ulist.np/get?cb=jQuery112303046526133459395_1761565635682&fltt=2&invt=2&ut=bd1d9ddb04089700cf9c27f6f7426281&fields=f2%2Cf3%2Cf5%2Cf12%2Cf13%2Cf14%2Cf31%2Cf32%2Cf124&secids={}",
+            tickers.join("%2C")
+        );
+        UrlQuoteEastmoney(url)
+    }
+}
+
+/// Crawl quotes of one batch from `eastmoney api`, unknown secids are left out.
+pub async fn crawl_quotes_eastmoney(url: UrlQuoteEastmoney) -> Result<Vec<Quote>, anyhow::Error> {
+    let raw = url2text(&url.0).await?;
+    let raw_quotes: Result<RawQuoteEastmoney, _> = parse_raw_eastmoney(&raw);
+
+    match raw_quotes {
+        Ok(res) => Ok(create_quotes(res)),
+        Err(e) => anyhow::bail!(e.to_string()),
+    }
+}
+
+/// Raw responses of all batches through one proxy, blocking.
+fn fetch_raw_eastmoney(urls: &[UrlQuoteEastmoney]) -> Result<Vec<String>, anyhow::Error> {
+    let agent = proxy_agent(Some(REQUEST_TIMEOUT))?;
+    urls.iter().map(|url| agent2text(&agent, &url.0)).collect()
+}
+
+fn create_quotes(raw: RawQuoteEastmoney) -> Vec<Quote> {
+    let Some(data) = raw.data else {
+        return vec![];
+    };
+    data.diff
+        .into_iter()
+        .map(|d| Quote {
+            ticker: format!("{}.{}", d.market, d.ticker),
+            realname: d.name,
+            last: d.last,
+            bid: d.bid,
+            ask: d.ask,
+            volume: d.volume,
+            change_pct: d.change_pct,
+            quoted_at: d
+                .quoted_at
+                .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+        })
+        .collect()
+}
+
+/// Quote source polling eastmoney in batches of [BATCH_SIZE].
+/// A poll shares one proxy and runs off the async runtime, bounded by [FETCH_TIMEOUT].
+pub struct EastmoneyQuoteSource;
+
+#[async_trait]
+impl QuoteSource for EastmoneyQuoteSource {
+    async fn fetch(&self, tickers: &[Ticker]) -> Result<Vec<Quote>, anyhow::Error> {
+        let urls: Vec<UrlQuoteEastmoney> = tickers
+            .chunks(BATCH_SIZE)
+            .map(UrlQuoteEastmoney::new)
+            .collect();
+        let fetch = tokio::task::spawn_blocking(move || fetch_raw_eastmoney(&urls));
+        let raws = match tokio::time::timeout(FETCH_TIMEOUT, fetch).await {
+            Ok(raws) => raws??,
+            Err(_) => anyhow::bail!("quote fetch timed out after {FETCH_TIMEOUT:?}"),
+        };
+
+        let mut quotes = vec![];
+        for raw in raws {
+            let raw: RawQuoteEastmoney = parse_raw_eastmoney(&raw)?;
+            quotes.extend(create_quotes(raw));
+        }
+
+        Ok(quotes)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawQuoteEastmoney {
+    /// Null when none of the secids is known.
+    #[serde(rename = "data")]
+    data: Option<RawQuoteEastmoneyData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawQuoteEastmoneyData {
+    #[serde(rename = "diff")]
+    diff: Vec<RawQuoteEastmoneyItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawQuoteEastmoneyItem {
+    #[serde(rename = "f12")]
+    ticker: String,
+    #[serde(rename = "f13")]
+    market: u64,
+    #[serde(rename = "f14")]
+    name: String,
+    #[serde(
+        rename = "f2",
+        deserialize_with = "deserialize_optional_float",
+        default
+    )]
+    last: Option<f64>,
+    #[serde(
+        rename = "f3",
+        deserialize_with = "deserialize_optional_float",
+        default
+    )]
+    change_pct: Option<f64>,
+    #[serde(
+        rename = "f5",
+        deserialize_with = "deserialize_optional_float",
+        default
+    )]
+    volume: Option<f64>,
+    #[serde(
+        rename = "f31",
+        deserialize_with = "deserialize_optional_float",
+        default
+    )]
+    bid: Option<f64>,
+    #[serde(
+        rename = "f32",
+        deserialize_with = "deserialize_optional_float",
+        default
+    )]
+    ask: Option<f64>,
+    #[serde(rename = "f124", default)]
+    quoted_at: Option<i64>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const RAW_QUOTES: &str = r#"jQuery112303046526133459395_1761565635682({"rc":0,"rt":11,"svr":177617938,"lt":1,"full":1,"dlmkts":"","data":{"total":2,"diff":[{"f2":5.68,"f3":1.25,"f5":412873,"f12":"600635","f13":1,"f14":"大众公用","f31":5.67,"f32":5.68,"f124":1761545100},{"f2":"-","f3":"-","f5":"-","f12":"000001","f13":0,"f14":"平安银行","f31":"-","f32":"-","f124":1761545100}]}});"#;
+    const RAW_QUOTES_EMPTY: &str =
+        r#"jQuery112303046526133459395_1761565635682({"rc":0,"rt":11,"data":null});"#;
+
+    #[test]
+    fn test_create_quotes() {
+        let raw: RawQuoteEastmoney = parse_raw_eastmoney(RAW_QUOTES).unwrap();
+        let quotes = create_quotes(raw);
+
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(
+            quotes[0],
+            Quote {
+                ticker: "1.600635".to_string(),
+                realname: "大众公用".to_string(),
+                last: Some(5.68),
+                bid: Some(5.67),
+                ask: Some(5.68),
+                volume: Some(412873.0),
+                change_pct: Some(1.25),
+                quoted_at: 1761545100,
+            }
+        );
+        // Suspended.
+        assert_eq!(quotes[1].ticker, "0.000001");
+        assert_eq!(quotes[1].last, None);
+
+        let raw: RawQuoteEastmoney = parse_raw_eastmoney(RAW_QUOTES_EMPTY).unwrap();
+        assert!(create_quotes(raw).is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "network call to eastmoney"]
+    async fn test_crawl_quotes_eastmoney() {
+        let tickers = vec!["1.600635".parse().unwrap(), "105.TSLA".parse().unwrap()];
+        let quotes = EastmoneyQuoteSource.fetch(&tickers).await.unwrap();
+
+        assert_eq!(quotes.len(), 2);
+        assert!(quotes.iter().any(|q| q.ticker == "105.TSLA"));
+    }
+}
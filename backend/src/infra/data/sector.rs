@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{model::SectorMember, service_market::Ticker},
+    infra::data::service::{parse_raw_eastmoney, url2text},
+};
+
+/// Page size of the board constituents list, eastmoney caps it at 100.
+const PAGE_SIZE: usize = 100;
+
+pub struct UrlSectorMembersEastmoney(String);
+
+impl UrlSectorMembersEastmoney {
+    /// `sector` is a BK ticker such as `90.BK1036`, `page` starts from 1.
+    pub fn new(sector: &Ticker, page: usize) -> Self {
+        let board = sector.code();
+        let url = format!(
+            "https://push2.eastmoney.com/api/qt/clist/get?cb=jQuery112305166225047316698_1761565635682&fid=f12&po=0&pz={}&pn={}&np=1&fltt=2&invt=2&ut=bd1d9ddb04089700cf9c27f6f7426281&fs=b%3A{}+f%3A!50&fields=f12%2Cf13%2Cf14",
+            PAGE_SIZE, page, board
+        );
+        UrlSectorMembersEastmoney(url)
+    }
+}
+
+/// Crawl the constituents of a BK sector board from `eastmoney api`.
+pub async fn crawl_sector_members_eastmoney(
+    sector: &Ticker,
+) -> Result<Vec<SectorMember>, anyhow::Error> {
+    let mut members = vec![];
+
+    for page in 1.. {
+        let raw = url2text(&UrlSectorMembersEastmoney::new(sector, page).0).await?;
+        let raw_members: RawSectorMembersEastmoney = parse_raw_eastmoney(&raw)?;
+        let Some(data) = raw_members.data else {
+            break;
+        };
+
+        let count = data.diff.len();
+        members.extend(create_sector_members(sector, data.diff));
+        if count < PAGE_SIZE || members.len() >= data.total {
+            break;
+        }
+    }
+
+    Ok(members)
+}
+
+fn create_sector_members(sector: &str, items: Vec<RawSectorMemberItem>) -> Vec<SectorMember> {
+    items
+        .into_iter()
+        .map(|item| SectorMember {
+            sector: sector.to_string(),
+            ticker: format!("{}.{}", item.market, item.ticker),
+            realname: item.name,
+            updated_at: String::new(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawSectorMembersEastmoney {
+    /// Null for an unknown board.
+    #[serde(rename = "data")]
+    data: Option<RawSectorMembersEastmoneyData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawSectorMembersEastmoneyData {
+    #[serde(rename = "total")]
+    total: usize,
+    #[serde(rename = "diff")]
+    diff: Vec<RawSectorMemberItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RawSectorMemberItem {
+    #[serde(rename = "f12")]
+    ticker: String,
+    #[serde(rename = "f13")]
+    market: u64,
+    #[serde(rename = "f14")]
+    name: String,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::infra::data::{
+        sector::{
+            RawSectorMembersEastmoney, crawl_sector_members_eastmoney, create_sector_members,
+        },
+        service::parse_raw_eastmoney,
+    };
+
+    const RAW_SECTOR_MEMBERS_EASTMONEY: &str = r#"jQuery112305166225047316698_1761565635682({"rc":0,"rt":6,"svr":181669432,"lt":1,"full":1,"dlmkts":"","data":{"total":3,"diff":[{"f12":"002371","f13":0,"f14":"北方华创"},{"f12":"600584","f13":1,"f14":"长电科技"},{"f12":"688981","f13":1,"f14":"中芯国际"}]}});"#;
+
+    #[test]
+    fn test_parse_raw_sector_members_eastmoney() {
+        let raw: RawSectorMembersEastmoney =
+            parse_raw_eastmoney(RAW_SECTOR_MEMBERS_EASTMONEY).unwrap();
+        let data = raw.data.unwrap();
+        assert_eq!(data.total, 3);
+
+        let members = create_sector_members("90.BK1036", data.diff);
+        assert_eq!(members.len(), 3);
+        assert_eq!(members[0].sector, "90.BK1036");
+        assert_eq!(members[0].ticker, "0.002371");
+        assert_eq!(members[0].realname, "北方华创");
+        assert_eq!(members[2].ticker, "1.688981");
+
+        let unknown: RawSectorMembersEastmoney =
+            parse_raw_eastmoney(r#"jQuery1({"rc":0,"rt":6,"data":null});"#).unwrap();
+        assert!(unknown.data.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "network call to eastmoney"]
+    async fn test_crawl_sector_members_eastmoney() {
+        let members = crawl_sector_members_eastmoney(&"90.BK1036".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert!(members.len() > 100);
+        assert!(members.iter().all(|m| m.sector == "90.BK1036"));
+    }
+}
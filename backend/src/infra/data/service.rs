@@ -1,5 +1,5 @@
 use serde::Deserialize;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use ureq::{Agent, Proxy};
 
@@ -36,8 +36,18 @@ struct ProxyData {
 }
 
 // url2text GET url and returns text results.
+// NOTE: blocks on ureq, see [proxy_agent] and [agent2text] to share a proxy off the runtime.
 pub async fn url2text(url: &str) -> Result<String, anyhow::Error> {
+    let agent = proxy_agent(None)?;
+    agent2text(&agent, url)
+}
+
+/// Agent through a freshly fetched proxy IP, `timeout` bounds each whole request.
+pub fn proxy_agent(timeout: Option<Duration>) -> Result<Agent, anyhow::Error> {
     let proxy_body = ureq::get(PROXY_URL)
+        .config()
+        .timeout_global(timeout)
+        .build()
         .call()?
         .body_mut()
         .read_json::<ProxyBody>()?;
@@ -60,7 +70,15 @@ pub async fn url2text(url: &str) -> Result<String, anyhow::Error> {
     tracing::debug!("{}", proxy_server);
 
     let proxy = Proxy::new(&proxy_server)?;
-    let agent: Agent = Agent::config_builder().proxy(Some(proxy)).build().into();
+    Ok(Agent::config_builder()
+        .proxy(Some(proxy))
+        .timeout_global(timeout)
+        .build()
+        .into())
+}
+
+/// GET url through `agent`, blocking.
+pub fn agent2text(agent: &Agent, url: &str) -> Result<String, anyhow::Error> {
     let user_agent = choose_random(USER_AGENTS).unwrap();
 
     match agent.get(url).header("User-Agent", *user_agent).call() {
@@ -76,7 +76,7 @@ pub struct RawStockEastmoneyData {
 }
 
 // Custom deserializer that handles null, "-", and valid numbers for f64.
-fn deserialize_optional_float<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+pub(crate) fn deserialize_optional_float<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
 where
     D: Deserializer<'de>,
 {
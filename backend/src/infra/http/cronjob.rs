@@ -1,12 +1,29 @@
+use chrono::Utc;
 use serde_json::json;
 use tokio_cron_scheduler::{JobBuilder, JobScheduler};
 
 use crate::{
     application::{
-        handlers::{create_mf_sector::CreateMfSectorPayload, create_signals::CreateSignalPayload},
+        handlers::{
+            compute_risk::{ComputeRiskPayload, DEFAULT_BENCHMARK},
+            compute_strength::ComputeStrengthPayload,
+            create_financials::CreateFinancialsPayload,
+            create_klines::CreateKlinePayload,
+            create_mf_sector::CreateMfSectorPayload,
+            create_mf_stock::CreateMfStockPayload,
+            create_sector_members::CreateSectorMembersPayload,
+            create_signals::CreateSignalPayload,
+            create_stock::CreateStockPayload,
+        },
         model::{Job, JobType},
     },
+    domain::{
+        service_calendar::{Exchange, TradingCalendar},
+        service_market::Ticker,
+        service_strength::MARKET_BENCHMARKS,
+    },
     infra::{
+        data::financial::secucode,
         http::AppState,
         logging::{LogEntry, LogLevel, logit},
     },
@@ -21,6 +38,14 @@ pub async fn setup_cron_jobs(app_state: AppState) -> anyhow::Result<()> {
     // and its reference count is moved into job1_state and job2_state.
     let job1_state = app_state.clone();
     let job2_state = app_state.clone();
+    let job3_state = app_state.clone();
+    let job4_state = app_state.clone();
+    let job5_state = app_state.clone();
+    let job6_state = app_state.clone();
+    let job7_state = app_state.clone();
+    let job8_state = app_state.clone();
+    let job9_state = app_state.clone();
+    let job10_state = app_state.clone();
 
     // ----------------------
     // Job 1: Runs every weekday at 23:59 (11:59 PM)
@@ -70,12 +95,232 @@ pub async fn setup_cron_jobs(app_state: AppState) -> anyhow::Result<()> {
         )
         .await?;
 
+    // ----------------------
+    // Job 10: Runs every weekday at 16:40, daily klines for jobs 3 and 6
+    // ----------------------
+    scheduler
+        .add(
+            JobBuilder::new()
+                .with_timezone(chrono_tz::Tz::Asia__Shanghai)
+                .with_cron_job_type()
+                .with_schedule("0 40 16 * * 1-5")
+                .unwrap()
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let app_state_for_run = job10_state.clone();
+                    Box::pin(async move {
+                        if let Err(e) = cron_create_klines(app_state_for_run).await {
+                            tracing::error!("Cron job 10 (16:40) failed: {}", e);
+                        }
+                    })
+                }))
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    // ----------------------
+    // Job 3: Runs every weekday at 17:30, after the daily klines of job 10 are in
+    // ----------------------
+    scheduler
+        .add(
+            JobBuilder::new()
+                .with_timezone(chrono_tz::Tz::Asia__Shanghai)
+                .with_cron_job_type()
+                .with_schedule("0 30 17 * * 1-5")
+                .unwrap()
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let app_state_for_run = job3_state.clone();
+                    Box::pin(async move {
+                        if let Err(e) = cron_compute_risk(app_state_for_run).await {
+                            tracing::error!("Cron job 3 (17:30) failed: {}", e);
+                        }
+                    })
+                }))
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    // ----------------------
+    // Job 4: Runs every Saturday at 10:00, sector constituents change slowly
+    // ----------------------
+    scheduler
+        .add(
+            JobBuilder::new()
+                .with_timezone(chrono_tz::Tz::Asia__Shanghai)
+                .with_cron_job_type()
+                .with_schedule("0 0 10 * * 6")
+                .unwrap()
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let app_state_for_run = job4_state.clone();
+                    Box::pin(async move {
+                        if let Err(e) = cron_create_sector_members(app_state_for_run).await {
+                            tracing::error!("Cron job 4 (Sat 10:00) failed: {}", e);
+                        }
+                    })
+                }))
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    // ----------------------
+    // Job 5: Runs every weekday at 16:30, moneyflow of watched and held stocks
+    // ----------------------
+    scheduler
+        .add(
+            JobBuilder::new()
+                .with_timezone(chrono_tz::Tz::Asia__Shanghai)
+                .with_cron_job_type()
+                .with_schedule("0 30 16 * * 1-5")
+                .unwrap()
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let app_state_for_run = job5_state.clone();
+                    Box::pin(async move {
+                        if let Err(e) = cron_create_mf_stock(app_state_for_run).await {
+                            tracing::error!("Cron job 5 (16:30) failed: {}", e);
+                        }
+                    })
+                }))
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    // ----------------------
+    // Job 6: Runs every weekday at 17:45, relative strength after the daily klines of job 10 are in
+    // ----------------------
+    scheduler
+        .add(
+            JobBuilder::new()
+                .with_timezone(chrono_tz::Tz::Asia__Shanghai)
+                .with_cron_job_type()
+                .with_schedule("0 45 17 * * 1-5")
+                .unwrap()
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let app_state_for_run = job6_state.clone();
+                    Box::pin(async move {
+                        if let Err(e) = cron_compute_strength(app_state_for_run).await {
+                            tracing::error!("Cron job 6 (17:45) failed: {}", e);
+                        }
+                    })
+                }))
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    // ----------------------
+    // Job 7: Runs every weekday at 18:00, refreshes fundamentals of the stocks closed by now
+    // ----------------------
+    scheduler
+        .add(
+            JobBuilder::new()
+                .with_timezone(chrono_tz::Tz::Asia__Shanghai)
+                .with_cron_job_type()
+                .with_schedule("0 0 18 * * 1-5")
+                .unwrap()
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let app_state_for_run = job7_state.clone();
+                    Box::pin(async move {
+                        if let Err(e) = cron_refresh_stocks(app_state_for_run).await {
+                            tracing::error!("Cron job 7 (18:00) failed: {}", e);
+                        }
+                    })
+                }))
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    // ----------------------
+    // Job 8: Runs every Saturday at 11:00, financial statements are quarterly
+    // ----------------------
+    scheduler
+        .add(
+            JobBuilder::new()
+                .with_timezone(chrono_tz::Tz::Asia__Shanghai)
+                .with_cron_job_type()
+                .with_schedule("0 0 11 * * 6")
+                .unwrap()
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let app_state_for_run = job8_state.clone();
+                    Box::pin(async move {
+                        if let Err(e) = cron_create_financials(app_state_for_run).await {
+                            tracing::error!("Cron job 8 (Sat 11:00) failed: {}", e);
+                        }
+                    })
+                }))
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    // ----------------------
+    // Job 9: Runs every weekday at 18:00 New York, refreshes fundamentals of US stocks
+    // ----------------------
+    scheduler
+        .add(
+            JobBuilder::new()
+                .with_timezone(chrono_tz::Tz::America__New_York)
+                .with_cron_job_type()
+                .with_schedule("0 0 18 * * 1-5")
+                .unwrap()
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let app_state_for_run = job9_state.clone();
+                    Box::pin(async move {
+                        if let Err(e) = cron_refresh_stocks(app_state_for_run).await {
+                            tracing::error!("Cron job 9 (18:00 New York) failed: {}", e);
+                        }
+                    })
+                }))
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
     scheduler.start().await?;
 
     Ok(())
 }
 
+/// Whether the A-share market trades today, its weekday jobs skip holidays.
+fn sse_trading_day() -> bool {
+    let sse = TradingCalendar::get(Exchange::Sse);
+    sse.is_trading_day(sse.local_date(Utc::now()))
+}
+
+/// Parses stored tickers, logging each one that no longer parses instead of queueing it.
+async fn parse_stored(
+    state: &AppState,
+    job: &str,
+    tickers: impl IntoIterator<Item = String>,
+) -> Vec<Ticker> {
+    let mut parsed = vec![];
+    for ticker in tickers {
+        match ticker.parse() {
+            Ok(t) => parsed.push(t),
+            Err(e) => {
+                logit(
+                    state,
+                    LogEntry::new(
+                        LogLevel::Error,
+                        format!("{job} skipped stored ticker {ticker}: {e}"),
+                        "http/cronjob.rs",
+                        309,
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+    parsed
+}
+
 async fn cron_create_mf_sector(state: AppState) -> anyhow::Result<()> {
+    if !sse_trading_day() {
+        return Ok(());
+    }
     let job = Job::new(JobType::CreateMfSector, json!(CreateMfSectorPayload {}));
 
     if let Err(e) = state.runner.repo_job.create_jobs(vec![job]).await {
@@ -111,8 +356,18 @@ async fn cron_create_mf_sector(state: AppState) -> anyhow::Result<()> {
 }
 
 async fn cron_create_signals_sector(state: AppState) -> anyhow::Result<()> {
-    let tickers: Vec<String> = match state.runner.repo_domain.get_sector_tickers().await {
-        Ok(stocks) => stocks,
+    if !sse_trading_day() {
+        return Ok(());
+    }
+    let tickers = match state.runner.repo_domain.get_tracked_sectors().await {
+        Ok(sectors) => {
+            parse_stored(
+                &state,
+                "cron_create_signals_sector",
+                sectors.into_iter().map(|s| s.ticker),
+            )
+            .await
+        }
         Err(e) => {
             return Err(anyhow::anyhow!("{e}"));
         }
@@ -123,7 +378,7 @@ async fn cron_create_signals_sector(state: AppState) -> anyhow::Result<()> {
         jobs.push(Job::new(
             JobType::CreateSignal,
             json!(CreateSignalPayload {
-                ticker: ticker.to_string(),
+                ticker: ticker.clone(),
                 week: false
             }),
         ));
@@ -160,3 +415,418 @@ async fn cron_create_signals_sector(state: AppState) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Refetches the daily klines that risk and strength are computed from: stored stocks, their
+/// sectors, held tickers, the default benchmark and the benchmark of each market.
+async fn cron_create_klines(state: AppState) -> anyhow::Result<()> {
+    if !sse_trading_day() {
+        return Ok(());
+    }
+    let repo = &state.runner.repo_domain;
+
+    let mut tickers = repo.get_stock_tickers().await?;
+    let members = repo.get_sector_membership().await?;
+    tickers.extend(
+        parse_stored(
+            &state,
+            "cron_create_klines",
+            members.into_values().flatten(),
+        )
+        .await,
+    );
+    for account in repo.get_accounts().await? {
+        let txs = repo.get_transactions(&account).await?;
+        tickers.extend(
+            parse_stored(
+                &state,
+                "cron_create_klines",
+                txs.into_iter().filter_map(|t| t.ticker),
+            )
+            .await,
+        );
+    }
+    tickers.push(DEFAULT_BENCHMARK.parse().expect("valid benchmark"));
+    tickers.extend(
+        MARKET_BENCHMARKS
+            .iter()
+            .map(|(_, b)| b.parse::<Ticker>().expect("valid benchmark")),
+    );
+    tickers.sort_unstable();
+    tickers.dedup();
+
+    // NOTE: klines are replaced whole, so the full history is fetched.
+    let jobs: Vec<Job> = tickers
+        .into_iter()
+        .map(|ticker| {
+            Job::new(
+                JobType::CreateKline,
+                json!(CreateKlinePayload {
+                    ticker,
+                    start: "0".to_string(),
+                    end: "20500101".to_string(),
+                }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in cron_create_klines",
+                "http/cronjob.rs",
+                380,
+            ),
+        )
+        .await;
+        return Err(anyhow::anyhow!("{e}"));
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/cronjob.rs",
+                    395,
+                ),
+            )
+            .await;
+        }
+    });
+
+    Ok(())
+}
+
+async fn cron_compute_risk(state: AppState) -> anyhow::Result<()> {
+    if !sse_trading_day() {
+        return Ok(());
+    }
+    let accounts = state.runner.repo_domain.get_accounts().await?;
+    if accounts.is_empty() {
+        return Ok(());
+    }
+
+    let jobs: Vec<Job> = accounts
+        .into_iter()
+        .map(|account| {
+            Job::new(
+                JobType::ComputeRisk,
+                json!(ComputeRiskPayload {
+                    account,
+                    benchmark: None,
+                    base: None,
+                    params: Default::default(),
+                }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in cron_compute_risk",
+                "http/cronjob.rs",
+                200,
+            ),
+        )
+        .await;
+        return Err(anyhow::anyhow!("{e}"));
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/cronjob.rs",
+                    215,
+                ),
+            )
+            .await;
+        }
+    });
+
+    Ok(())
+}
+
+async fn cron_create_sector_members(state: AppState) -> anyhow::Result<()> {
+    let sectors = state.runner.repo_domain.get_tracked_sectors().await?;
+    if sectors.is_empty() {
+        return Ok(());
+    }
+
+    let sectors = parse_stored(
+        &state,
+        "cron_create_sector_members",
+        sectors.into_iter().map(|s| s.ticker),
+    )
+    .await;
+    let jobs: Vec<Job> = sectors
+        .into_iter()
+        .map(|sector| {
+            Job::new(
+                JobType::CreateSectorMembers,
+                json!(CreateSectorMembersPayload { sector }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in cron_create_sector_members",
+                "http/cronjob.rs",
+                264,
+            ),
+        )
+        .await;
+        return Err(anyhow::anyhow!("{e}"));
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/cronjob.rs",
+                    279,
+                ),
+            )
+            .await;
+        }
+    });
+
+    Ok(())
+}
+
+async fn cron_create_mf_stock(state: AppState) -> anyhow::Result<()> {
+    if !sse_trading_day() {
+        return Ok(());
+    }
+    let repo = &state.runner.repo_domain;
+
+    let mut tickers = vec![];
+    for watchlist in repo.get_watchlists().await? {
+        tickers.extend(repo.get_watchlist_tickers(watchlist.id).await?);
+    }
+    for account in repo.get_accounts().await? {
+        let txs = repo.get_transactions(&account).await?;
+        tickers.extend(
+            parse_stored(
+                &state,
+                "cron_create_mf_stock",
+                txs.into_iter().filter_map(|t| t.ticker),
+            )
+            .await,
+        );
+    }
+    // Moneyflow is reported for A-shares only.
+    tickers.retain(Ticker::is_a_share);
+    tickers.sort_unstable();
+    tickers.dedup();
+    if tickers.is_empty() {
+        return Ok(());
+    }
+
+    let jobs: Vec<Job> = tickers
+        .into_iter()
+        .map(|ticker| {
+            Job::new(
+                JobType::CreateMfStock,
+                json!(CreateMfStockPayload { ticker }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in cron_create_mf_stock",
+                "http/cronjob.rs",
+                359,
+            ),
+        )
+        .await;
+        return Err(anyhow::anyhow!("{e}"));
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/cronjob.rs",
+                    374,
+                ),
+            )
+            .await;
+        }
+    });
+
+    Ok(())
+}
+
+async fn cron_compute_strength(state: AppState) -> anyhow::Result<()> {
+    if !sse_trading_day() {
+        return Ok(());
+    }
+    let job = Job::new(
+        JobType::ComputeStrength,
+        json!(ComputeStrengthPayload { benchmark: None }),
+    );
+
+    if let Err(e) = state.runner.repo_job.create_jobs(vec![job]).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in cron_compute_strength",
+                "http/cronjob.rs",
+                420,
+            ),
+        )
+        .await;
+        return Err(anyhow::anyhow!("{e}"));
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/cronjob.rs",
+                    435,
+                ),
+            )
+            .await;
+        }
+    });
+
+    Ok(())
+}
+
+/// Refreshes the stocks whose exchange closed a session today, so each market is picked up by
+/// the run after its own close. Stocks of no known exchange follow SSE.
+async fn cron_refresh_stocks(state: AppState) -> anyhow::Result<()> {
+    let now = Utc::now();
+    let mut tickers = state.runner.repo_domain.get_stock_tickers().await?;
+    tickers.retain(|t| {
+        TradingCalendar::for_ticker(t)
+            .unwrap_or(TradingCalendar::get(Exchange::Sse))
+            .is_closed_today(now)
+    });
+    if tickers.is_empty() {
+        return Ok(());
+    }
+
+    let jobs: Vec<Job> = tickers
+        .into_iter()
+        .map(|ticker| {
+            Job::new(
+                JobType::CreateStock,
+                json!(CreateStockPayload {
+                    ticker,
+                    refresh: true,
+                }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in cron_refresh_stocks",
+                "http/cronjob.rs",
+                490,
+            ),
+        )
+        .await;
+        return Err(anyhow::anyhow!("{e}"));
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/cronjob.rs",
+                    505,
+                ),
+            )
+            .await;
+        }
+    });
+
+    Ok(())
+}
+
+async fn cron_create_financials(state: AppState) -> anyhow::Result<()> {
+    let mut tickers = state.runner.repo_domain.get_stock_tickers().await?;
+    tickers.retain(|t| secucode(t).is_some());
+    if tickers.is_empty() {
+        return Ok(());
+    }
+
+    let jobs: Vec<Job> = tickers
+        .into_iter()
+        .map(|ticker| {
+            Job::new(
+                JobType::CreateFinancials,
+                json!(CreateFinancialsPayload { ticker }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in cron_create_financials",
+                "http/cronjob.rs",
+                560,
+            ),
+        )
+        .await;
+        return Err(anyhow::anyhow!("{e}"));
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/cronjob.rs",
+                    575,
+                ),
+            )
+            .await;
+        }
+    });
+
+    Ok(())
+}
@@ -1,11 +1,18 @@
+use std::{collections::HashMap, convert::Infallible};
+
 use axum::{
     Json,
-    extract::{Query, State},
-    response::IntoResponse,
+    extract::{FromRequest, FromRequestParts, Query, Request, State},
+    http::request::Parts,
+    response::{
+        IntoResponse, Response,
+        sse::{Event as SseEvent, KeepAlive, Sse},
+    },
 };
 use hyper::StatusCode;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de::DeserializeOwned};
 use serde_json::{Value, json};
+use tokio_stream::{StreamExt, wrappers::BroadcastStream};
 
 use utoipa::{IntoParams, ToSchema};
 
@@ -13,12 +20,62 @@ use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
     application::{
-        handlers::{create_mf_sector::CreateMfSectorPayload, create_stock::CreateStockPayload},
+        events::{Event, EventFilter, Topic},
+        handlers::{
+            compute_risk::{ComputeRiskPayload, load_portfolio},
+            compute_strength::ComputeStrengthPayload,
+            create_financials::CreateFinancialsPayload,
+            create_klines::CreateKlinePayload,
+            create_mf_sector::CreateMfSectorPayload,
+            create_mf_stock::CreateMfStockPayload,
+            create_sector_members::CreateSectorMembersPayload,
+            create_signals::CreateSignalPayload,
+            create_stock::CreateStockPayload,
+            run_backtest::RunBacktestPayload,
+            walk_forward::WalkForwardPayload,
+        },
         model::{Job, JobType},
     },
-    domain::model::{Signal, Stock, User},
+    domain::{
+        backtest::{
+            BacktestConfig, BacktestReport,
+            montecarlo::{MonteCarloConfig, MonteCarloReport, ResampleMethod, monte_carlo},
+            optimize::{
+                Heatmap, Objective, ParamSpace, SearchMethod, WalkForwardReport, heatmap,
+                search_space,
+            },
+            rules::MarketRules,
+            strategy::StrategyConfig,
+        },
+        model::{
+            AlertCondition, AlertEvent, AlertRule, BacktestRun, Currency, FinancialRatios,
+            FinancialStatements, FundamentalsSnapshot, FxRate, Kline, Level, LevelKind,
+            Optimisation, OptimisationMode, PivotMethod, Pivots, Quote, RiskRun, ScreenerRule,
+            SectorMember, Signal, SignalPeriod, Stock, TrackedSector, Transaction, TransactionKind,
+            User, Watchlist,
+        },
+        service_calendar::{CalendarDay, Exchange, TradingCalendar},
+        service_confluence::{Confluence, ConfluenceWeights, compute_confluence, rank_confluence},
+        service_level::{
+            LevelParams, compute_levels, compute_pivots_day, compute_pivots_week, date_from_i64,
+            nearest_support,
+        },
+        service_market::{Isin, Ticker, TickerError, TickerInfo, parse_tickers},
+        service_portfolio::{
+            Ledger, PortfolioPoint, PortfolioSnapshot, check_currency, portfolio_history,
+        },
+        service_risk::RiskParams,
+        service_rotation::{RotationParams, RotationReport, compute_rotation},
+        service_screener::{self, ScreenerMatch, ScreenerRow, parse_rule},
+        service_signal::compute_atr,
+        service_sizing::{PositionSize, SizingInput, size_position},
+        service_strength::StrengthSignal,
+    },
     infra::{
-        data::moneyflow::MoneyflowEastmoney,
+        data::moneyflow::{
+            MoneyflowEastmoney, MoneyflowMinute, MoneyflowStock, UrlMoneyflowStockEastmoney,
+            crawl_moneyflow_intraday_eastmoney,
+        },
         http::AppState,
         logging::{LogEntry, LogLevel, logit},
     },
@@ -32,28 +89,143 @@ enum ApiError {
     NotFound(String),
     #[schema(example = "require query param x")]
     MissingInput(String),
+    #[schema(example = "unknown field 'foo'")]
+    InvalidInput(String),
     #[schema(example = "missing api key")]
     Unauthorized(String),
     #[schema(example = "job runner error")]
     RunnerError(String),
 }
 
+/// [Query] whose rejection is an [ApiError] body, so a malformed ticker
+/// reads like every other invalid input.
+pub struct ApiQuery<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Query::<T>::from_request_parts(parts, state).await {
+            Ok(Query(value)) => Ok(ApiQuery(value)),
+            Err(e) => Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::InvalidInput(e.body_text())),
+            )
+                .into_response()),
+        }
+    }
+}
+
+/// [Json] whose rejection is an [ApiError] body, see [ApiQuery].
+pub struct ApiJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(ApiJson(value)),
+            Err(e) => {
+                Err((e.status(), Json(ApiError::InvalidInput(e.body_text()))).into_response())
+            }
+        }
+    }
+}
+
 pub fn create_routes_api(app_state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
         // /logs
         .routes(routes!(list_logs))
         // /jobs
         .routes(routes!(list_jobs, delete_jobs))
-        // /signals
-        .routes(routes!(list_signals))
+        // /signals GET, POST
+        .routes(routes!(list_signals, create_signals))
+        .routes(routes!(list_signal_history))
         // /stocks GET, POST, DELETE
         .routes(routes!(create_stocks, list_stocks, delete_stock))
+        .routes(routes!(list_fundamentals))
+        .routes(routes!(create_financials, get_financials))
         // /klines?ticker=a
-        // .routes(routes!(list_klines))
+        .routes(routes!(create_klines, list_klines))
+        // /levels?ticker=a
+        .routes(routes!(list_levels))
+        .routes(routes!(screen_near_support))
+        // /screeners GET, POST, DELETE
+        .routes(routes!(create_screener, list_screeners, delete_screener))
+        .routes(routes!(run_screener))
+        // /confluence
+        .routes(routes!(list_confluence))
+        // /alerts GET, POST, DELETE
+        .routes(routes!(create_alert, list_alerts, delete_alert))
+        .routes(routes!(list_alert_events))
+        // /backtests GET, POST
+        .routes(routes!(create_backtests, list_backtests))
+        .routes(routes!(get_backtest_report))
+        .routes(routes!(get_backtest_montecarlo))
+        // /optimisations GET, POST
+        .routes(routes!(create_optimisation, list_optimisations))
+        .routes(routes!(get_optimisation_heatmap))
+        .routes(routes!(create_walk_forward, get_walk_forward))
+        // /watchlists GET, POST, PATCH, DELETE
+        .routes(routes!(
+            create_watchlist,
+            list_watchlists,
+            update_watchlist,
+            delete_watchlist
+        ))
+        .routes(routes!(
+            add_watchlist_tickers,
+            update_watchlist_ticker,
+            remove_watchlist_ticker
+        ))
+        .routes(routes!(reorder_watchlist))
+        // /portfolio
+        .routes(routes!(get_portfolio))
+        .routes(routes!(get_portfolio_history))
+        .routes(routes!(list_accounts))
+        .routes(routes!(
+            create_transaction,
+            list_transactions,
+            delete_transaction
+        ))
+        .routes(routes!(list_fx_rates, set_fx_rate))
+        // /risk GET, POST
+        .routes(routes!(create_risk_report, get_risk_report))
+        // /sizing
+        .routes(routes!(get_position_size))
+        // /strength GET, POST
+        .routes(routes!(create_strength, list_strength))
+        // /sectors GET, POST, DELETE
+        .routes(routes!(list_tracked_sectors, track_sector, untrack_sector))
+        // /sectors/members GET, POST
+        .routes(routes!(create_sector_members, list_sector_members))
+        .routes(routes!(list_stock_sectors))
         // /update/stocks
         // .routes(routes!(update_stocks))
         // /mf/sector
         .routes(routes!(create_mf_sector, list_mf_sector))
+        .routes(routes!(get_sector_rotation))
+        // /mf/stock
+        .routes(routes!(create_mf_stock, list_mf_stock))
+        .routes(routes!(get_mf_intraday))
+        // /quotes
+        .routes(routes!(list_quotes))
+        .routes(routes!(list_quote_history))
+        // /events
+        .routes(routes!(stream_events))
+        // /calendar
+        .routes(routes!(get_calendar_day))
+        // /tickers
+        .routes(routes!(resolve_ticker))
+        .routes(routes!(link_isin))
         // /sector-signals
         // .routes(routes!(create_sector_signals, list_sector_signals))
         .with_state(app_state)
@@ -129,12 +301,13 @@ pub async fn list_jobs(State(state): State<AppState>) -> impl IntoResponse {
     ),
     responses(
         (status = 200, description = "Delete jobs successful"),
+        (status = 400, description = "Malformed query", body = ApiError),
         (status = 500, description = "Database error", body = ApiError)
     )
 )]
 pub async fn delete_jobs(
     State(state): State<AppState>,
-    Query(param): Query<DeleteJobsQuery>,
+    ApiQuery(param): ApiQuery<DeleteJobsQuery>,
 ) -> impl IntoResponse {
     match state.runner.repo_job.delete_jobs(param.days).await {
         Ok(()) => (StatusCode::OK).into_response(),
@@ -201,9 +374,9 @@ pub async fn list_logs(State(state): State<AppState>) -> impl IntoResponse {
     }
 }
 
-/// List all signals.
+/// List latest signals.
 ///
-/// Returns all signals.
+/// Returns the latest signal of each ticker.
 #[utoipa::path(
     get,
     path = "/signals",
@@ -212,100 +385,48 @@ pub async fn list_logs(State(state): State<AppState>) -> impl IntoResponse {
         SignalQuery
     ),
     responses(
-        (status = 200, description = "List all signals from signals table.", body = [Signal]),
+        (status = 200, description = "List latest signals from signals table.", body = [Signal]),
         (status = 400, description = "Missing query params", body = ApiError),
         (status = 500, description = "Database error", body = ApiError)
     )
 )]
 pub async fn list_signals(
     State(state): State<AppState>,
-    Query(params): Query<SignalQuery>,
+    ApiQuery(params): ApiQuery<SignalQuery>,
 ) -> impl IntoResponse {
     // NOTE: logic is required.
-    // - `week` -> get signals from `signals_w` else `signals_d`
+    // - `week` -> get weekly signals else daily
     // - `sector` -> query on tickers for sectors only else stocks
+    let period = if params.week {
+        SignalPeriod::Week
+    } else {
+        SignalPeriod::Day
+    };
 
-    match (params.sector, params.week) {
-        (true, true) => match state.runner.repo_domain.get_signals_sector_w().await {
-            Ok(signals) => (StatusCode::OK, Json(signals)).into_response(),
-            Err(e) => {
-                logit(
-                    &state,
-                    LogEntry::new(
-                        LogLevel::Error,
-                        format!("db error: get_signals_sector_w: {}", e),
-                        "http/handlers.rs",
-                        203,
-                    ),
-                )
-                .await;
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiError::DatabaseError(e.to_string())),
-                )
-                    .into_response()
-            }
-        },
-        (true, false) => match state.runner.repo_domain.get_signals_sector_d().await {
-            Ok(signals) => (StatusCode::OK, Json(signals)).into_response(),
-            Err(e) => {
-                logit(
-                    &state,
-                    LogEntry::new(
-                        LogLevel::Error,
-                        format!("db error: get_signals_sector_d: {}", e),
-                        "http/handlers.rs",
-                        223,
-                    ),
-                )
-                .await;
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiError::DatabaseError(e.to_string())),
-                )
-                    .into_response()
-            }
-        },
-        (false, true) => match state.runner.repo_domain.get_signals_stock_w().await {
-            Ok(signals) => (StatusCode::OK, Json(signals)).into_response(),
-            Err(e) => {
-                logit(
-                    &state,
-                    LogEntry::new(
-                        LogLevel::Error,
-                        format!("db error: get_signals_stock_w: {}", e),
-                        "http/handlers.rs",
-                        243,
-                    ),
-                )
-                .await;
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiError::DatabaseError(e.to_string())),
-                )
-                    .into_response()
-            }
-        },
-        (false, false) => match state.runner.repo_domain.get_signals_stock_d().await {
-            Ok(signals) => (StatusCode::OK, Json(signals)).into_response(),
-            Err(e) => {
-                logit(
-                    &state,
-                    LogEntry::new(
-                        LogLevel::Error,
-                        format!("db error: get_signals_stock_d: {}", e),
-                        "http/handlers.rs",
-                        263,
-                    ),
-                )
-                .await;
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiError::DatabaseError(e.to_string())),
-                )
-                    .into_response()
-            }
-        },
+    match state
+        .runner
+        .repo_domain
+        .get_signals_latest(period, params.sector)
+        .await
+    {
+        Ok(signals) => (StatusCode::OK, Json(signals)).into_response(),
+        Err(e) => {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("db error: get_signals_latest: {}", e),
+                    "http/handlers.rs",
+                    252,
+                ),
+            )
+            .await;
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response()
+        }
     }
 }
 
@@ -315,6 +436,162 @@ pub struct SignalQuery {
     pub week: bool,
 }
 
+/// Create signals.
+///
+/// Returns a 200 if the job is submitted.
+#[utoipa::path(
+    post,
+    path = "/signals",
+    tag = "candlescyther",
+    request_body = CreateSignalRequest,
+    responses(
+        (status = 200, description = "Job submitted"),
+        (status = 400, description = "Tickers is required", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_signals(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateSignalRequest>,
+) -> impl IntoResponse {
+    let tickers = match target_tickers(&state, &req_body.tickers, req_body.watchlist).await {
+        Ok(tickers) => tickers,
+        Err(resp) => return resp,
+    };
+
+    if tickers.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput(
+                "`tickers` or a non-empty `watchlist` required in body".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    let jobs: Vec<Job> = tickers
+        .iter()
+        .map(|ticker| {
+            Job::new(
+                JobType::CreateSignal,
+                json!(CreateSignalPayload {
+                    ticker: ticker.clone(),
+                    week: req_body.week,
+                }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_signals",
+                "http/handlers.rs",
+                360,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_signals: {}", e),
+                    "http/handlers.rs",
+                    378,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateSignalRequest {
+    #[serde(default)]
+    pub tickers: String,
+    /// Targets the tickers of a watchlist instead.
+    pub watchlist: Option<i64>,
+    #[serde(default)]
+    pub week: bool,
+}
+
+/// List signal history of a ticker.
+///
+/// Returns signals in chronological order.
+#[utoipa::path(
+    get,
+    path = "/signals/history",
+    tag = "candlescyther",
+    params(
+        SignalHistoryQuery
+    ),
+    responses(
+        (status = 200, description = "Signal time series of the ticker.", body = [Signal]),
+        (status = 400, description = "Missing or malformed ticker", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_signal_history(
+    State(state): State<AppState>,
+    ApiQuery(params): ApiQuery<SignalHistoryQuery>,
+) -> impl IntoResponse {
+    let period = if params.week {
+        SignalPeriod::Week
+    } else {
+        SignalPeriod::Day
+    };
+
+    match state
+        .runner
+        .repo_domain
+        .get_signal_series(&params.ticker, period, params.start, params.end)
+        .await
+    {
+        Ok(signals) => (StatusCode::OK, Json(signals)).into_response(),
+        Err(e) => {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("db error: get_signal_series: {}", e),
+                    "http/handlers.rs",
+                    321,
+                ),
+            )
+            .await;
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct SignalHistoryQuery {
+    pub ticker: Ticker,
+    pub week: bool,
+    /// yyyymmdd, inclusive.
+    pub start: Option<i64>,
+    /// yyyymmdd, inclusive.
+    pub end: Option<i64>,
+}
+
 /// List all stocks.
 ///
 /// Returns all stocks.
@@ -360,18 +637,18 @@ pub async fn list_stocks(State(state): State<AppState>) -> impl IntoResponse {
     responses(
         (status = 200, description = "Job submitted"),
         (status = 400, description = "Tickers is required", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
         (status = 500, description = "Job runner error", body = ApiError),
     )
 )]
 pub async fn create_stocks(
     State(state): State<AppState>,
-    Json(req_body): Json<CreateStockRequest>,
+    ApiJson(req_body): ApiJson<CreateStockRequest>,
 ) -> impl IntoResponse {
-    let tickers: Vec<String> = req_body
-        .tickers
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .collect();
+    let tickers = match target_tickers(&state, &req_body.tickers, req_body.watchlist).await {
+        Ok(tickers) => tickers,
+        Err(resp) => return resp,
+    };
 
     if tickers.is_empty() {
         return (
@@ -388,7 +665,8 @@ pub async fn create_stocks(
         jobs.push(Job::new(
             JobType::CreateStock,
             json!(CreateStockPayload {
-                ticker: ticker.to_string(),
+                ticker: ticker.clone(),
+                refresh: req_body.refresh,
             }),
         ));
     }
@@ -431,7 +709,52 @@ pub async fn create_stocks(
 
 #[derive(Deserialize, ToSchema)]
 pub struct CreateStockRequest {
+    #[serde(default)]
     pub tickers: String,
+    /// Targets the tickers of a watchlist instead.
+    pub watchlist: Option<i64>,
+    /// Re-crawls stored tickers to update their fundamentals.
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Comma separated `tickers`, or those of `watchlist` when given, 400 on a malformed ticker,
+/// stored ones included.
+async fn target_tickers(
+    state: &AppState,
+    tickers: &str,
+    watchlist: Option<i64>,
+) -> Result<Vec<Ticker>, Response> {
+    let Some(id) = watchlist else {
+        return parse_tickers(tickers).map_err(invalid_ticker);
+    };
+
+    match state.runner.repo_domain.get_watchlist(id).await {
+        Ok(Some(watchlist)) => watchlist
+            .items
+            .into_iter()
+            .map(|i| i.ticker.parse())
+            .collect::<Result<_, _>>()
+            .map_err(invalid_ticker),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("watchlist = {id}"))),
+        )
+            .into_response()),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response()),
+    }
+}
+
+fn invalid_ticker(e: TickerError) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiError::InvalidInput(e.to_string())),
+    )
+        .into_response()
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -458,15 +781,18 @@ where
     tag = "candlescyther",
     responses(
         (status = 200, description = "Delete stock and its records"),
-        (status = 400, description = "Ticker is required", body = ApiError),
+        (status = 400, description = "Missing or malformed ticker", body = ApiError),
         (status = 500, description = "Database error", body = ApiError)
     )
 )]
 pub async fn delete_stock(
     State(state): State<AppState>,
-    Query(param): Query<DeleteStockQuery>,
+    ApiQuery(param): ApiQuery<DeleteStockQuery>,
 ) -> impl IntoResponse {
-    let tickers: Vec<&str> = param.ticker.split(',').map(|s| s.trim()).collect();
+    let tickers = match parse_tickers(&param.ticker) {
+        Ok(tickers) => tickers,
+        Err(e) => return invalid_ticker(e),
+    };
 
     // WARN: This is not async due to expecting small ops.
     match state.runner.repo_domain.delete_stocks(&tickers).await {
@@ -496,110 +822,97 @@ pub struct DeleteStockQuery {
     ticker: String,
 }
 
-// #[utoipa::path(
-//     get,
-//     path = "/klines",
-//     tag = "candlescyther",
-//     params(
-//         KlineQuery,
-//     ),
-//     responses(
-//         (status = 200, description = "List all klines for the ticker", body = [Kline]),
-//         (status = 400, description = "Ticker is required", body = ApiError),
-//         (status = 500, description = "Database error", body = ApiError)
-//     )
-// )]
-// pub async fn list_klines(
-//     State(state): State<AppState>,
-//     query: Query<KlineQuery>,
-// ) -> impl IntoResponse {
-//     if query.ticker.is_empty() {
-//         return (
-//             StatusCode::BAD_REQUEST,
-//             Json(ApiError::MissingInput("missing param".to_string())),
-//         )
-//             .into_response();
-//     }
-//
-//     match sqlx::query_as::<sqlite::Sqlite, Kline>(
-//         "SELECT * FROM klines WHERE k_ticker = ? ORDER BY k_date ASC",
-//     )
-//     .bind(&query.ticker)
-//     .fetch_all(&state.db)
-//     .await
-//     {
-//         Ok(signals) => (StatusCode::OK, Json(signals)).into_response(),
-//         Err(e) => {
-//             logit(
-//                 &state,
-//                 LogEntry::new(
-//                     LogLevel::Error,
-//                     format!("failed to query database {}", e),
-//                     "http/handlers.rs",
-//                     141,
-//                 ),
-//             )
-//             .await;
-//             (
-//                 StatusCode::INTERNAL_SERVER_ERROR,
-//                 Json(ApiError::DatabaseError(e.to_string())),
-//             )
-//                 .into_response()
-//         }
-//     }
-// }
-
-// #[derive(Deserialize, IntoParams)]
-// pub struct KlineQuery {
-//     pub ticker: String,
-// }
-
-/// Update all stocks.
+/// List klines of a ticker.
 ///
-/// Returns ok.
+/// Returns daily klines in chronological order.
 #[utoipa::path(
     get,
-    path = "/update/stocks",
+    path = "/klines",
     tag = "candlescyther",
     params(
-        TriggerQuery,
+        KlineQuery,
     ),
     responses(
-        (status = 200, description = "Update job is submitted."),
-        (status = 400, description = "Missing code", body = ApiError),
+        (status = 200, description = "List all klines for the ticker", body = [Kline]),
+        (status = 400, description = "Missing or malformed ticker", body = ApiError),
         (status = 500, description = "Database error", body = ApiError)
     )
 )]
-pub async fn update_stocks(
+pub async fn list_klines(
     State(state): State<AppState>,
-    query: Query<TriggerQuery>,
+    ApiQuery(query): ApiQuery<KlineQuery>,
 ) -> impl IntoResponse {
-    // FIX: refine security
-    if query.code.is_empty() || query.code != "1l0veu" {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiError::MissingInput("missing code".to_string())),
-        )
-            .into_response();
-    }
-
-    let tickers: Vec<String> = match state.runner.repo_domain.get_stock_all().await {
-        Ok(tickers) => tickers.iter().map(|t| t.ticker.clone()).collect(),
+    match state.runner.repo_domain.get_klines(&query.ticker).await {
+        Ok(klines) => (StatusCode::OK, Json(klines)).into_response(),
         Err(e) => {
-            return (
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("failed to query database {}", e),
+                    "http/handlers.rs",
+                    531,
+                ),
+            )
+            .await;
+            (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiError::DatabaseError(e.to_string())),
             )
-                .into_response();
+                .into_response()
         }
-    };
+    }
+}
 
-    let mut jobs = vec![];
-    for ticker in &tickers {
+#[derive(Deserialize, IntoParams)]
+pub struct KlineQuery {
+    pub ticker: Ticker,
+}
+
+/// Create daily klines of tickers.
+///
+/// Returns a 200 if the job is submitted.
+#[utoipa::path(
+    post,
+    path = "/klines",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "Job submitted"),
+        (status = 400, description = "Tickers is required", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_klines(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateKlineRequest>,
+) -> impl IntoResponse {
+    let tickers = match target_tickers(&state, &req_body.tickers, req_body.watchlist).await {
+        Ok(tickers) => tickers,
+        Err(resp) => return resp,
+    };
+
+    if tickers.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput(
+                "`tickers` field required in body".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    let start = req_body.start.unwrap_or_else(|| "0".to_string());
+    let end = req_body.end.unwrap_or_else(|| "20500101".to_string());
+
+    let mut jobs = vec![];
+    for ticker in &tickers {
         jobs.push(Job::new(
-            JobType::CreateStock,
-            json!(CreateStockPayload {
-                ticker: ticker.to_string(),
+            JobType::CreateKline,
+            json!(CreateKlinePayload {
+                ticker: ticker.clone(),
+                start: start.clone(),
+                end: end.clone(),
             }),
         ));
     }
@@ -609,9 +922,9 @@ pub async fn update_stocks(
             &state,
             LogEntry::new(
                 LogLevel::Error,
-                "failed to create_jobs in update_all",
+                "failed to create_jobs in create_klines",
                 "http/handlers.rs",
-                491,
+                606,
             ),
         )
         .await;
@@ -628,9 +941,9 @@ pub async fn update_stocks(
                 &state,
                 LogEntry::new(
                     LogLevel::Error,
-                    format!("runner error: {}", e),
+                    format!("runner error: create_klines: {}", e),
                     "http/handlers.rs",
-                    510,
+                    623,
                 ),
             )
             .await;
@@ -640,85 +953,271 @@ pub async fn update_stocks(
     (StatusCode::OK).into_response()
 }
 
-#[derive(Deserialize, IntoParams)]
-pub struct TriggerQuery {
-    pub code: String,
+#[derive(Deserialize, ToSchema)]
+pub struct CreateKlineRequest {
+    #[serde(default)]
+    pub tickers: String,
+    /// Targets the tickers of a watchlist instead.
+    pub watchlist: Option<i64>,
+    /// yyyymmdd, defaults to the first available bar.
+    pub start: Option<String>,
+    /// yyyymmdd, defaults to the latest bar.
+    pub end: Option<String>,
 }
 
-/// Create moneyflow sector data.
+/// List support/resistance levels and pivots of a ticker.
 ///
-/// Returns a 200 if the job is submitted.
+/// Levels are derived from stored daily klines.
 #[utoipa::path(
-    post,
-    path = "/mf/sector",
+    get,
+    path = "/levels",
     tag = "candlescyther",
+    params(
+        LevelQuery,
+    ),
     responses(
-        (status = 200, description = "Job submitted"),
-        (status = 500, description = "Job runner error", body = ApiError),
+        (status = 200, description = "Levels and pivots of the ticker", body = LevelsResponse),
+        (status = 400, description = "Missing or malformed ticker", body = ApiError),
+        (status = 404, description = "No klines stored for the ticker", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
     )
 )]
-pub async fn create_mf_sector(State(state): State<AppState>) -> impl IntoResponse {
-    let job = Job::new(JobType::CreateMfSector, json!(CreateMfSectorPayload {}));
+pub async fn list_levels(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<LevelQuery>,
+) -> impl IntoResponse {
+    let klines = match state.runner.repo_domain.get_klines(&query.ticker).await {
+        Ok(klines) => klines,
+        Err(e) => {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("db error: list_levels: {}", e),
+                    "http/handlers.rs",
+                    683,
+                ),
+            )
+            .await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
 
-    if let Err(e) = state.runner.repo_job.create_jobs(vec![job]).await {
-        logit(
-            &state,
-            LogEntry::new(
-                LogLevel::Error,
-                "failed to create_jobs in create_mf_sector",
-                "http/handlers.rs",
-                670,
-            ),
-        )
-        .await;
+    let Some(last) = klines.last() else {
         return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiError::RunnerError(e.to_string())),
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("klines of {}", query.ticker))),
         )
             .into_response();
-    }
+    };
 
-    tokio::spawn(async move {
-        if let Err(e) = state.runner.run().await {
+    let method = query.method.unwrap_or_default();
+    let levels = compute_levels(&klines, LevelParams::default());
+    let (supports, resistances) = levels
+        .into_iter()
+        .partition(|l| l.kind == LevelKind::Support);
+
+    let resp = LevelsResponse {
+        ticker: query.ticker.to_string(),
+        date: last.k_date,
+        close: last.k_close,
+        supports,
+        resistances,
+        pivots_day: compute_pivots_day(&klines, method),
+        pivots_week: compute_pivots_week(
+            &klines,
+            method,
+            chrono::Utc::now()
+                .with_timezone(&query.ticker.timezone())
+                .date_naive(),
+        ),
+    };
+
+    (StatusCode::OK, Json(resp)).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct LevelQuery {
+    pub ticker: Ticker,
+    /// Pivot method, defaults to classic.
+    pub method: Option<PivotMethod>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LevelsResponse {
+    pub ticker: String,
+    pub date: i64,
+    pub close: f64,
+    pub supports: Vec<Level>,
+    pub resistances: Vec<Level>,
+    pub pivots_day: Option<Pivots>,
+    /// From the last completed week, in the ticker's timezone.
+    pub pivots_week: Option<Pivots>,
+}
+
+/// Screen stocks trading near support.
+///
+/// Returns tickers whose last close is within `pct` percent above their nearest support.
+#[utoipa::path(
+    get,
+    path = "/levels/screen",
+    tag = "candlescyther",
+    params(
+        NearSupportQuery,
+    ),
+    responses(
+        (status = 200, description = "Tickers near support, closest first", body = [NearSupport]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn screen_near_support(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<NearSupportQuery>,
+) -> impl IntoResponse {
+    let stocks = match state.runner.repo_domain.get_stock_all().await {
+        Ok(stocks) => stocks,
+        Err(e) => {
             logit(
                 &state,
                 LogEntry::new(
                     LogLevel::Error,
-                    format!("runner error: {}", e),
+                    format!("db error: screen_near_support: {}", e),
                     "http/handlers.rs",
-                    615,
+                    773,
                 ),
             )
             .await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
         }
-    });
+    };
 
-    (StatusCode::OK).into_response()
+    let mut hits = vec![];
+    for stock in stocks {
+        let Ok(ticker) = stock.ticker.parse::<Ticker>() else {
+            continue;
+        };
+        let klines = match state.runner.repo_domain.get_klines(&ticker).await {
+            Ok(klines) => klines,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::DatabaseError(e.to_string())),
+                )
+                    .into_response();
+            }
+        };
+        let Some(last) = klines.last() else {
+            continue;
+        };
+
+        let levels = compute_levels(&klines, LevelParams::default());
+        if let Some(support) = nearest_support(&levels, last.k_close) {
+            let distance_pct = (last.k_close - support.price) / support.price * 100.0;
+            if distance_pct <= query.pct {
+                hits.push(NearSupport {
+                    ticker: stock.ticker.clone(),
+                    realname: stock.realname.clone(),
+                    close: last.k_close,
+                    support: support.price,
+                    strength: support.strength,
+                    distance_pct,
+                });
+            }
+        }
+    }
+
+    hits.sort_by(|a, b| a.distance_pct.total_cmp(&b.distance_pct));
+
+    (StatusCode::OK, Json(hits)).into_response()
 }
 
-/// List moneyflow sector data.
+#[derive(Deserialize, IntoParams)]
+pub struct NearSupportQuery {
+    /// Max distance above support in percent.
+    pub pct: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct NearSupport {
+    pub ticker: String,
+    pub realname: String,
+    pub close: f64,
+    pub support: f64,
+    pub strength: f64,
+    pub distance_pct: f64,
+}
+
+/// Save a screener rule.
 ///
-/// Returns a 200 if the job is submitted.
+/// Returns ok, rules with the same name are replaced.
 #[utoipa::path(
-    get,
-    path = "/mf/sector",
+    post,
+    path = "/screeners",
     tag = "candlescyther",
+    request_body = CreateScreenerRequest,
     responses(
-        (status = 200, description = "List moneyflow sector records", body = [MoneyflowEastmoney]),
-        (status = 500, description = "Job runner error", body = ApiError),
+        (status = 200, description = "Rule is saved"),
+        (status = 400, description = "Invalid expression", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
     )
 )]
-pub async fn list_mf_sector(State(state): State<AppState>) -> impl IntoResponse {
-    match state.runner.repo_domain.get_mf_sector().await {
-        Ok(mf) => (StatusCode::OK, Json(mf)).into_response(),
+pub async fn create_screener(
+    State(state): State<AppState>,
+    ApiJson(body): ApiJson<CreateScreenerRequest>,
+) -> impl IntoResponse {
+    if body.name.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput("missing name".to_string())),
+        )
+            .into_response();
+    }
+    if let Err(e) = parse_rule(&body.expression) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(e.to_string())),
+        )
+            .into_response();
+    }
+    if let Some(field) = &body.rank_by
+        && !service_screener::SCREENER_FIELDS.contains(&field.as_str())
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(format!("unknown field '{field}'"))),
+        )
+            .into_response();
+    }
+
+    match state
+        .runner
+        .repo_domain
+        .create_screener_rule(
+            body.name.trim(),
+            &body.expression,
+            body.rank_by.as_deref(),
+            body.descending,
+        )
+        .await
+    {
+        Ok(_) => (StatusCode::OK).into_response(),
         Err(e) => {
             logit(
                 &state,
                 LogEntry::new(
                     LogLevel::Error,
-                    format!("failed to query database {}", e),
+                    format!("db error: create_screener: {}", e),
                     "http/handlers.rs",
-                    211,
+                    890,
                 ),
             )
             .await;
@@ -731,6 +1230,3609 @@ pub async fn list_mf_sector(State(state): State<AppState>) -> impl IntoResponse
     }
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct CreateScreenerRequest {
+    #[schema(example = "oversold-cheap")]
+    pub name: String,
+    #[schema(example = "kdj_k < 20 AND boll_dist < 0.5 AND pe < 30")]
+    pub expression: String,
+    /// Field to rank matches by.
+    pub rank_by: Option<String>,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// List screener rules.
+///
+/// Returns all saved rules by name.
+#[utoipa::path(
+    get,
+    path = "/screeners",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "List all saved rules", body = [ScreenerRule]),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_screeners(State(state): State<AppState>) -> impl IntoResponse {
+    match state.runner.repo_domain.get_screener_rules().await {
+        Ok(rules) => (StatusCode::OK, Json(rules)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Delete a screener rule.
+#[utoipa::path(
+    delete,
+    path = "/screeners",
+    tag = "candlescyther",
+    params(
+        ScreenerQuery,
+    ),
+    responses(
+        (status = 200, description = "Rule is deleted"),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn delete_screener(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<ScreenerQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .delete_screener_rule(&query.name)
+        .await
+    {
+        Ok(_) => (StatusCode::OK).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ScreenerQuery {
+    pub name: String,
+}
+
+/// Run a screener rule.
+///
+/// Returns ranked matches of a saved rule, or of an ad-hoc `expression`, against the latest signals.
+#[utoipa::path(
+    get,
+    path = "/screeners/run",
+    tag = "candlescyther",
+    params(
+        RunScreenerQuery,
+    ),
+    responses(
+        (status = 200, description = "Ranked matches", body = [ScreenerMatch]),
+        (status = 400, description = "Missing or invalid rule", body = ApiError),
+        (status = 404, description = "Rule not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn run_screener(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<RunScreenerQuery>,
+) -> impl IntoResponse {
+    let (expression, rank_by, descending) = match (&query.name, &query.expression) {
+        (Some(name), _) => match state.runner.repo_domain.get_screener_rule(name).await {
+            Ok(Some(rule)) => (rule.expression, rule.rank_by, rule.descending),
+            Ok(None) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(ApiError::NotFound(format!("name = {name}"))),
+                )
+                    .into_response();
+            }
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::DatabaseError(e.to_string())),
+                )
+                    .into_response();
+            }
+        },
+        (None, Some(expression)) => (
+            expression.clone(),
+            query.rank_by.clone(),
+            query.descending.unwrap_or(false),
+        ),
+        (None, None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::MissingInput(
+                    "require query param name or expression".to_string(),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    if let Some(field) = &rank_by
+        && !service_screener::SCREENER_FIELDS.contains(&field.as_str())
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(format!("unknown field '{field}'"))),
+        )
+            .into_response();
+    }
+
+    let expr = match parse_rule(&expression) {
+        Ok(expr) => expr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::InvalidInput(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    let period = if query.week {
+        SignalPeriod::Week
+    } else {
+        SignalPeriod::Day
+    };
+    let (signals, stocks, membership, strength, ratios) = match tokio::try_join!(
+        state.runner.repo_domain.get_signals_latest(period, false),
+        state.runner.repo_domain.get_stock_all(),
+        state.runner.repo_domain.get_sector_membership(),
+        state.runner.repo_domain.get_strength_latest(),
+        state.runner.repo_financial.get_financial_ratios_latest(),
+    ) {
+        Ok(res) => res,
+        Err(e) => {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("db error: run_screener: {}", e),
+                    "http/handlers.rs",
+                    1040,
+                ),
+            )
+            .await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    let stocks: HashMap<&str, &Stock> = stocks.iter().map(|s| (s.ticker.as_str(), s)).collect();
+    let strength: HashMap<&str, &StrengthSignal> =
+        strength.iter().map(|s| (s.ticker.as_str(), s)).collect();
+    let ratios: HashMap<&str, &FinancialRatios> =
+        ratios.iter().map(|r| (r.ticker.as_str(), r)).collect();
+    let rows: Vec<ScreenerRow> = signals
+        .iter()
+        .map(|signal| {
+            let ticker = signal.ticker.as_str();
+            let stock = stocks.get(ticker).copied();
+            let sectors = membership.get(ticker).cloned().unwrap_or_default();
+            let rs = strength.get(ticker).copied();
+            let fin = ratios.get(ticker).copied();
+            ScreenerRow::new(signal, stock, sectors)
+                .with_strength(rs)
+                .with_financials(fin)
+        })
+        .collect();
+
+    let mut matches = service_screener::run_screener(&expr, rows, rank_by.as_deref(), descending);
+    if let Some(limit) = query.limit {
+        matches.truncate(limit);
+    }
+
+    (StatusCode::OK, Json(matches)).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct RunScreenerQuery {
+    /// Name of a saved rule, takes precedence over `expression`.
+    pub name: Option<String>,
+    /// Ad-hoc rule, e.g. `kdj_k < 20 AND pe < 30`.
+    pub expression: Option<String>,
+    /// Ranking field of the ad-hoc rule.
+    pub rank_by: Option<String>,
+    pub descending: Option<bool>,
+    /// Screen weekly signals instead of daily.
+    #[serde(default)]
+    pub week: bool,
+    pub limit: Option<usize>,
+}
+
+/// Rank tickers by multi-timeframe confluence.
+///
+/// Returns composite scores of daily and weekly signals with per-component explanations,
+/// tickers with weekly KDJ turning up while daily is oversold first.
+#[utoipa::path(
+    get,
+    path = "/confluence",
+    tag = "candlescyther",
+    params(
+        ConfluenceQuery,
+    ),
+    responses(
+        (status = 200, description = "Ranked confluence scores", body = [Confluence]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_confluence(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<ConfluenceQuery>,
+) -> impl IntoResponse {
+    let repo = &state.runner.repo_domain;
+    let (daily, weekly) = match tokio::try_join!(
+        repo.get_signals_recent(SignalPeriod::Day, query.sector, 2),
+        repo.get_signals_recent(SignalPeriod::Week, query.sector, 2),
+    ) {
+        Ok(res) => res,
+        Err(e) => {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("db error: list_confluence: {}", e),
+                    "http/handlers.rs",
+                    1130,
+                ),
+            )
+            .await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    // Both are ordered by ticker, so each ticker's bars are one chunk.
+    let weekly: HashMap<&str, &[Signal]> = weekly
+        .chunk_by(|a, b| a.ticker == b.ticker)
+        .map(|w| (w[0].ticker.as_str(), w))
+        .collect();
+    let weights = ConfluenceWeights::default();
+    let mut scores: Vec<Confluence> = daily
+        .chunk_by(|a, b| a.ticker == b.ticker)
+        .filter_map(|d| {
+            let w = weekly
+                .get(d[0].ticker.as_str())
+                .copied()
+                .unwrap_or_default();
+            compute_confluence(d, w, weights)
+        })
+        .filter(|c| !query.setup_only || c.setup)
+        .collect();
+
+    rank_confluence(&mut scores);
+    if let Some(limit) = query.limit {
+        scores.truncate(limit);
+    }
+
+    (StatusCode::OK, Json(scores)).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ConfluenceQuery {
+    /// Score sectors instead of stocks.
+    #[serde(default)]
+    pub sector: bool,
+    /// Only tickers in the entry setup.
+    #[serde(default)]
+    pub setup_only: bool,
+    pub limit: Option<usize>,
+}
+
+/// Save an alert rule.
+///
+/// Returns ok, rules with the same name are replaced and re-armed.
+#[utoipa::path(
+    post,
+    path = "/alerts",
+    tag = "candlescyther",
+    request_body = CreateAlertRequest,
+    responses(
+        (status = 200, description = "Rule is saved"),
+        (status = 400, description = "Invalid rule or malformed ticker", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn create_alert(
+    State(state): State<AppState>,
+    ApiJson(body): ApiJson<CreateAlertRequest>,
+) -> impl IntoResponse {
+    if body.name.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput("missing name".to_string())),
+        )
+            .into_response();
+    }
+    if let AlertCondition::Pattern { expression, .. } = &body.condition
+        && let Err(e) = parse_rule(expression)
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(e.to_string())),
+        )
+            .into_response();
+    }
+
+    match state
+        .runner
+        .repo_domain
+        .create_alert_rule(body.name.trim(), &body.condition)
+        .await
+    {
+        Ok(_) => (StatusCode::OK).into_response(),
+        Err(e) => {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("db error: create_alert: {}", e),
+                    "http/handlers.rs",
+                    1200,
+                ),
+            )
+            .await;
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateAlertRequest {
+    #[schema(example = "600635-oversold")]
+    pub name: String,
+    pub condition: AlertCondition,
+}
+
+/// List alert rules.
+///
+/// Returns all rules with their current state.
+#[utoipa::path(
+    get,
+    path = "/alerts",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "List all alert rules", body = [AlertRule]),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_alerts(State(state): State<AppState>) -> impl IntoResponse {
+    match state.runner.repo_domain.get_alert_rules().await {
+        Ok(rules) => (StatusCode::OK, Json(rules)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Delete an alert rule.
+#[utoipa::path(
+    delete,
+    path = "/alerts",
+    tag = "candlescyther",
+    params(
+        AlertQuery,
+    ),
+    responses(
+        (status = 200, description = "Rule is deleted"),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn delete_alert(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<AlertQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .delete_alert_rule(&query.name)
+        .await
+    {
+        Ok(_) => (StatusCode::OK).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct AlertQuery {
+    pub name: String,
+}
+
+/// List fired alerts.
+///
+/// Returns the most recent alert events first.
+#[utoipa::path(
+    get,
+    path = "/alerts/events",
+    tag = "candlescyther",
+    params(
+        AlertEventQuery,
+    ),
+    responses(
+        (status = 200, description = "Fired alerts", body = [AlertEvent]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_alert_events(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<AlertEventQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_alert_events(query.limit.unwrap_or(100))
+        .await
+    {
+        Ok(events) => (StatusCode::OK, Json(events)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct AlertEventQuery {
+    /// Defaults to 100.
+    pub limit: Option<i64>,
+}
+
+/// Backtest a strategy over stored daily klines.
+///
+/// Returns a 200 if the jobs are submitted, one per ticker.
+#[utoipa::path(
+    post,
+    path = "/backtests",
+    tag = "candlescyther",
+    request_body = CreateBacktestRequest,
+    responses(
+        (status = 200, description = "Job submitted"),
+        (status = 400, description = "Missing tickers or invalid config", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_backtests(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateBacktestRequest>,
+) -> impl IntoResponse {
+    let tickers = match parse_tickers(&req_body.tickers) {
+        Ok(tickers) => tickers,
+        Err(e) => return invalid_ticker(e),
+    };
+
+    if tickers.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput(
+                "`tickers` field required in body".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    if let Err(e) = req_body.config.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ApiError::InvalidInput(e))).into_response();
+    }
+
+    let jobs: Vec<Job> = tickers
+        .iter()
+        .map(|ticker| {
+            Job::new(
+                JobType::RunBacktest,
+                json!(RunBacktestPayload {
+                    ticker: ticker.clone(),
+                    strategy: req_body.strategy.clone(),
+                    config: req_body.config,
+                    optimisation_id: None,
+                }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_backtests",
+                "http/handlers.rs",
+                1480,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_backtests: {}", e),
+                    "http/handlers.rs",
+                    1497,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateBacktestRequest {
+    #[schema(example = "1.600635,1.688981")]
+    pub tickers: String,
+    #[serde(default)]
+    pub strategy: StrategyConfig,
+    /// Cash, commission and slippage, defaults apply to missing fields.
+    #[serde(default)]
+    pub config: BacktestConfig,
+}
+
+/// List backtest runs.
+///
+/// Returns run summaries with metrics, most recent first.
+#[utoipa::path(
+    get,
+    path = "/backtests",
+    tag = "candlescyther",
+    params(
+        BacktestQuery,
+    ),
+    responses(
+        (status = 200, description = "Backtest runs", body = [BacktestRun]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_backtests(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<BacktestQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_backtest_runs(query.ticker.as_ref())
+        .await
+    {
+        Ok(runs) => (StatusCode::OK, Json(runs)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct BacktestQuery {
+    pub ticker: Option<Ticker>,
+}
+
+/// Get the report of a backtest run.
+///
+/// Returns metrics, trades, fills and the equity curve.
+#[utoipa::path(
+    get,
+    path = "/backtests/report",
+    tag = "candlescyther",
+    params(
+        BacktestReportQuery,
+    ),
+    responses(
+        (status = 200, description = "Backtest report", body = BacktestReport),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "Run not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_backtest_report(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<BacktestReportQuery>,
+) -> impl IntoResponse {
+    match state.runner.repo_domain.get_backtest_report(query.id).await {
+        Ok(Some(report)) => (StatusCode::OK, Json(report)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("id = {}", query.id))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct BacktestReportQuery {
+    pub id: i64,
+}
+
+/// Max resamples of one Monte Carlo analysis.
+const MAX_ITERATIONS: usize = 20_000;
+
+/// Monte Carlo analysis of a backtest.
+///
+/// Returns return and drawdown distributions of resampled trades or daily returns.
+#[utoipa::path(
+    get,
+    path = "/backtests/montecarlo",
+    tag = "candlescyther",
+    params(
+        MonteCarloQuery,
+    ),
+    responses(
+        (status = 200, description = "Monte Carlo report", body = MonteCarloReport),
+        (status = 400, description = "Invalid config or nothing to resample", body = ApiError),
+        (status = 404, description = "Run not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_backtest_montecarlo(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<MonteCarloQuery>,
+) -> impl IntoResponse {
+    let defaults = MonteCarloConfig::default();
+    let config = MonteCarloConfig {
+        method: query.method.unwrap_or(defaults.method),
+        iterations: query.iterations.unwrap_or(defaults.iterations),
+        seed: query.seed.unwrap_or(defaults.seed),
+        confidence: query.confidence.unwrap_or(defaults.confidence),
+        block: query.block.unwrap_or(defaults.block),
+    };
+    if config.iterations > MAX_ITERATIONS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(format!(
+                "iterations above {MAX_ITERATIONS}"
+            ))),
+        )
+            .into_response();
+    }
+
+    let report = match state.runner.repo_domain.get_backtest_report(query.id).await {
+        Ok(Some(report)) => report,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::NotFound(format!("id = {}", query.id))),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    match tokio::task::spawn_blocking(move || monte_carlo(&report, &config)).await {
+        Ok(Ok(mc)) => (StatusCode::OK, Json(mc)).into_response(),
+        Ok(Err(e)) => (StatusCode::BAD_REQUEST, Json(ApiError::InvalidInput(e))).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct MonteCarloQuery {
+    /// Backtest run id.
+    pub id: i64,
+    pub method: Option<ResampleMethod>,
+    /// Defaults to 1000.
+    pub iterations: Option<usize>,
+    /// Same seed, same result.
+    pub seed: Option<u64>,
+    /// Defaults to 0.95.
+    pub confidence: Option<f64>,
+    /// Bootstrap block length in bars, defaults to 5.
+    pub block: Option<usize>,
+}
+
+/// Search strategy parameters.
+///
+/// Returns the optimisation id, every trial runs as its own backtest job.
+#[utoipa::path(
+    post,
+    path = "/optimisations",
+    tag = "candlescyther",
+    request_body = CreateOptimisationRequest,
+    responses(
+        (status = 200, description = "Jobs submitted", body = OptimisationCreated),
+        (status = 400, description = "Invalid parameter space or config", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_optimisation(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateOptimisationRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = req_body.config.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ApiError::InvalidInput(e))).into_response();
+    }
+    let trials = match search_space(&req_body.strategy, &req_body.params, req_body.method) {
+        Ok(trials) => trials,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiError::InvalidInput(e))).into_response();
+        }
+    };
+
+    let id = match state
+        .runner
+        .repo_domain
+        .create_optimisation(
+            &req_body.ticker,
+            OptimisationMode::Search,
+            &req_body.strategy,
+            &req_body.params,
+            &req_body.method,
+            req_body.objective,
+        )
+        .await
+    {
+        Ok(id) => id,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    let jobs: Vec<Job> = trials
+        .iter()
+        .map(|strategy| {
+            Job::new(
+                JobType::RunBacktest,
+                json!(RunBacktestPayload {
+                    ticker: req_body.ticker.clone(),
+                    strategy: strategy.clone(),
+                    config: req_body.config,
+                    optimisation_id: Some(id),
+                }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_optimisation",
+                "http/handlers.rs",
+                1640,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    let created = OptimisationCreated {
+        id,
+        trials: trials.len(),
+    };
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_optimisation: {}", e),
+                    "http/handlers.rs",
+                    1662,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK, Json(created)).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateOptimisationRequest {
+    #[schema(example = "1.600635")]
+    pub ticker: Ticker,
+    /// Base strategy, parameters in `params` are overridden.
+    #[serde(default)]
+    pub strategy: StrategyConfig,
+    #[schema(value_type = Object, example = json!({"buy_below": {"start": 10, "end": 30, "step": 5}}))]
+    pub params: ParamSpace,
+    pub method: SearchMethod,
+    pub objective: Objective,
+    #[serde(default)]
+    pub config: BacktestConfig,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OptimisationCreated {
+    pub id: i64,
+    pub trials: usize,
+}
+
+/// List optimisations.
+///
+/// Returns parameter searches and walk-forward analyses, most recent first.
+#[utoipa::path(
+    get,
+    path = "/optimisations",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "Optimisations", body = [Optimisation]),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_optimisations(State(state): State<AppState>) -> impl IntoResponse {
+    match state.runner.repo_domain.get_optimisations().await {
+        Ok(optimisations) => (StatusCode::OK, Json(optimisations)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Heatmap of a parameter search.
+///
+/// Returns the objective over two parameters from the trials finished so far.
+#[utoipa::path(
+    get,
+    path = "/optimisations/heatmap",
+    tag = "candlescyther",
+    params(
+        HeatmapQuery,
+    ),
+    responses(
+        (status = 200, description = "Heatmap", body = Heatmap),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "Optimisation not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_optimisation_heatmap(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<HeatmapQuery>,
+) -> impl IntoResponse {
+    let repo = &state.runner.repo_domain;
+    let (optimisation, runs) = match tokio::try_join!(
+        repo.get_optimisation(query.id),
+        repo.get_optimisation_runs(query.id),
+    ) {
+        Ok((Some(optimisation), runs)) => (optimisation, runs),
+        Ok((None, _)) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::NotFound(format!("id = {}", query.id))),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    // Defaults to the first two searched parameters.
+    let mut names = optimisation.space.keys().cloned();
+    let x = query.x.clone().or_else(|| names.next()).unwrap_or_default();
+    let y = query
+        .y
+        .clone()
+        .or_else(|| names.find(|n| *n != x))
+        .unwrap_or_else(|| x.clone());
+
+    let trials: Vec<(StrategyConfig, _)> =
+        runs.into_iter().map(|r| (r.strategy, r.metrics)).collect();
+    let map = heatmap(
+        &trials,
+        &x,
+        &y,
+        query.objective.unwrap_or(optimisation.objective),
+    );
+
+    (StatusCode::OK, Json(map)).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct HeatmapQuery {
+    pub id: i64,
+    /// Column parameter.
+    pub x: Option<String>,
+    /// Row parameter.
+    pub y: Option<String>,
+    /// Defaults to the optimisation's objective.
+    pub objective: Option<Objective>,
+}
+
+/// Walk-forward analysis of strategy parameters.
+///
+/// Returns the optimisation id, the analysis runs as a single job.
+#[utoipa::path(
+    post,
+    path = "/optimisations/walkforward",
+    tag = "candlescyther",
+    request_body = CreateWalkForwardRequest,
+    responses(
+        (status = 200, description = "Job submitted", body = OptimisationCreated),
+        (status = 400, description = "Invalid parameter space or config", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_walk_forward(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateWalkForwardRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = req_body.config.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ApiError::InvalidInput(e))).into_response();
+    }
+    let trials = match search_space(&req_body.strategy, &req_body.params, req_body.method) {
+        Ok(trials) => trials.len(),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiError::InvalidInput(e))).into_response();
+        }
+    };
+    if req_body.in_sample == 0 || req_body.out_sample == 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(
+                "in_sample and out_sample must be positive".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    let id = match state
+        .runner
+        .repo_domain
+        .create_optimisation(
+            &req_body.ticker,
+            OptimisationMode::WalkForward,
+            &req_body.strategy,
+            &req_body.params,
+            &req_body.method,
+            req_body.objective,
+        )
+        .await
+    {
+        Ok(id) => id,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    let job = Job::new(
+        JobType::WalkForward,
+        json!(WalkForwardPayload {
+            optimisation_id: id,
+            in_sample: req_body.in_sample,
+            out_sample: req_body.out_sample,
+            config: req_body.config,
+        }),
+    );
+
+    if let Err(e) = state.runner.repo_job.create_jobs(vec![job]).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_walk_forward",
+                "http/handlers.rs",
+                1850,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_walk_forward: {}", e),
+                    "http/handlers.rs",
+                    1867,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK, Json(OptimisationCreated { id, trials })).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateWalkForwardRequest {
+    #[schema(example = "1.600635")]
+    pub ticker: Ticker,
+    #[serde(default)]
+    pub strategy: StrategyConfig,
+    #[schema(value_type = Object, example = json!({"buy_below": {"start": 10, "end": 30, "step": 5}}))]
+    pub params: ParamSpace,
+    pub method: SearchMethod,
+    pub objective: Objective,
+    /// Bars per in-sample window.
+    #[schema(example = 500)]
+    pub in_sample: usize,
+    /// Bars per out-of-sample window.
+    #[schema(example = 120)]
+    pub out_sample: usize,
+    #[serde(default)]
+    pub config: BacktestConfig,
+}
+
+/// Get a walk-forward report.
+///
+/// Returns per-window winners with in-sample and out-of-sample scores.
+#[utoipa::path(
+    get,
+    path = "/optimisations/walkforward",
+    tag = "candlescyther",
+    params(
+        BacktestReportQuery,
+    ),
+    responses(
+        (status = 200, description = "Walk-forward report", body = WalkForwardReport),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "Not found or not finished", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_walk_forward(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<BacktestReportQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_walk_forward_report(query.id)
+        .await
+    {
+        Ok(Some(report)) => (StatusCode::OK, Json(report)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("id = {}", query.id))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Create a watchlist.
+///
+/// Returns the new watchlist.
+#[utoipa::path(
+    post,
+    path = "/watchlists",
+    tag = "candlescyther",
+    request_body = CreateWatchlistRequest,
+    responses(
+        (status = 200, description = "Watchlist created", body = Watchlist),
+        (status = 400, description = "Missing or duplicate name", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn create_watchlist(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateWatchlistRequest>,
+) -> impl IntoResponse {
+    let name = req_body.name.trim();
+    if name.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput("`name` field required".to_string())),
+        )
+            .into_response();
+    }
+
+    let repo = &state.runner.repo_domain;
+    let id = match repo.create_watchlist(name, req_body.notes.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::InvalidInput(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    let result = match repo.add_watchlist_tickers(id, &req_body.tickers).await {
+        Ok(()) => repo.get_watchlist(id).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(watchlist) => (StatusCode::OK, Json(watchlist)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateWatchlistRequest {
+    #[schema(example = "banks")]
+    pub name: String,
+    pub notes: Option<String>,
+    /// Initial tickers, in order.
+    #[serde(default)]
+    pub tickers: Vec<Ticker>,
+}
+
+/// List watchlists.
+///
+/// Returns all watchlists with their tickers in order.
+#[utoipa::path(
+    get,
+    path = "/watchlists",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "Watchlists", body = [Watchlist]),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_watchlists(State(state): State<AppState>) -> impl IntoResponse {
+    match state.runner.repo_domain.get_watchlists().await {
+        Ok(watchlists) => (StatusCode::OK, Json(watchlists)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Rename a watchlist or change its notes.
+///
+/// Returns a 200 if updated.
+#[utoipa::path(
+    patch,
+    path = "/watchlists",
+    tag = "candlescyther",
+    params(
+        WatchlistQuery,
+    ),
+    request_body = UpdateWatchlistRequest,
+    responses(
+        (status = 200, description = "Watchlist updated"),
+        (status = 400, description = "Duplicate name", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+    )
+)]
+pub async fn update_watchlist(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<WatchlistQuery>,
+    ApiJson(req_body): ApiJson<UpdateWatchlistRequest>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .update_watchlist(
+            query.id,
+            req_body
+                .name
+                .as_deref()
+                .map(str::trim)
+                .filter(|n| !n.is_empty()),
+            req_body.notes.as_deref(),
+        )
+        .await
+    {
+        Ok(true) => (StatusCode::OK).into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("id = {}", query.id))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct WatchlistQuery {
+    pub id: i64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateWatchlistRequest {
+    pub name: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Delete a watchlist.
+///
+/// Returns a 200 if deleted.
+#[utoipa::path(
+    delete,
+    path = "/watchlists",
+    tag = "candlescyther",
+    params(
+        WatchlistQuery,
+    ),
+    responses(
+        (status = 200, description = "Watchlist deleted"),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn delete_watchlist(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<WatchlistQuery>,
+) -> impl IntoResponse {
+    match state.runner.repo_domain.delete_watchlist(query.id).await {
+        Ok(true) => (StatusCode::OK).into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("id = {}", query.id))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Add tickers to a watchlist.
+///
+/// Returns the watchlist, new tickers are appended and existing ones keep their place.
+#[utoipa::path(
+    post,
+    path = "/watchlists/tickers",
+    tag = "candlescyther",
+    params(
+        WatchlistQuery,
+    ),
+    request_body = WatchlistTickersRequest,
+    responses(
+        (status = 200, description = "Tickers added", body = Watchlist),
+        (status = 400, description = "Malformed query or body", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn add_watchlist_tickers(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<WatchlistQuery>,
+    ApiJson(req_body): ApiJson<WatchlistTickersRequest>,
+) -> impl IntoResponse {
+    let repo = &state.runner.repo_domain;
+    match repo.get_watchlist(query.id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::NotFound(format!("id = {}", query.id))),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    }
+
+    let result = match repo
+        .add_watchlist_tickers(query.id, &req_body.tickers)
+        .await
+    {
+        Ok(()) => repo.get_watchlist(query.id).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(watchlist) => (StatusCode::OK, Json(watchlist)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WatchlistTickersRequest {
+    #[schema(example = json!(["1.600036", "0.000001"]))]
+    pub tickers: Vec<Ticker>,
+}
+
+/// Set the notes of a ticker in a watchlist.
+///
+/// Returns a 200 if updated.
+#[utoipa::path(
+    patch,
+    path = "/watchlists/tickers",
+    tag = "candlescyther",
+    params(
+        WatchlistTickerQuery,
+    ),
+    request_body = WatchlistItemRequest,
+    responses(
+        (status = 200, description = "Notes updated"),
+        (status = 400, description = "Malformed query or body", body = ApiError),
+        (status = 404, description = "Ticker not in watchlist", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn update_watchlist_ticker(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<WatchlistTickerQuery>,
+    ApiJson(req_body): ApiJson<WatchlistItemRequest>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .set_watchlist_item_notes(query.id, &query.ticker, req_body.notes.as_deref())
+        .await
+    {
+        Ok(true) => (StatusCode::OK).into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!(
+                "id = {}, ticker = {}",
+                query.id, query.ticker
+            ))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct WatchlistTickerQuery {
+    pub id: i64,
+    pub ticker: Ticker,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WatchlistItemRequest {
+    /// `null` clears the notes.
+    pub notes: Option<String>,
+}
+
+/// Remove a ticker from a watchlist.
+///
+/// Returns a 200 if removed.
+#[utoipa::path(
+    delete,
+    path = "/watchlists/tickers",
+    tag = "candlescyther",
+    params(
+        WatchlistTickerQuery,
+    ),
+    responses(
+        (status = 200, description = "Ticker removed"),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "Ticker not in watchlist", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn remove_watchlist_ticker(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<WatchlistTickerQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .remove_watchlist_ticker(query.id, &query.ticker)
+        .await
+    {
+        Ok(true) => (StatusCode::OK).into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!(
+                "id = {}, ticker = {}",
+                query.id, query.ticker
+            ))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Reorder a watchlist.
+///
+/// Returns the watchlist, the given tickers move to the front in order.
+#[utoipa::path(
+    put,
+    path = "/watchlists/order",
+    tag = "candlescyther",
+    params(
+        WatchlistQuery,
+    ),
+    request_body = WatchlistTickersRequest,
+    responses(
+        (status = 200, description = "Watchlist reordered", body = Watchlist),
+        (status = 400, description = "Malformed query or body", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn reorder_watchlist(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<WatchlistQuery>,
+    ApiJson(req_body): ApiJson<WatchlistTickersRequest>,
+) -> impl IntoResponse {
+    let repo = &state.runner.repo_domain;
+    let result = match repo.reorder_watchlist(query.id, &req_body.tickers).await {
+        Ok(()) => repo.get_watchlist(query.id).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(Some(watchlist)) => (StatusCode::OK, Json(watchlist)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("id = {}", query.id))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Record a portfolio transaction.
+///
+/// Returns the transaction id, rejected if it would sell more than held.
+#[utoipa::path(
+    post,
+    path = "/portfolio/transactions",
+    tag = "candlescyther",
+    request_body = CreateTransactionRequest,
+    responses(
+        (status = 200, description = "Transaction recorded", body = i64),
+        (status = 400, description = "Invalid transaction", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn create_transaction(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateTransactionRequest>,
+) -> impl IntoResponse {
+    if req_body.account.trim().is_empty() || date_from_i64(req_body.trade_date).is_none() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(
+                "`account` and a yyyymmdd `trade_date` are required".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    let ticker = req_body.ticker;
+    let tx = Transaction {
+        // Sorts after existing transactions of the same day.
+        id: i64::MAX,
+        account: req_body.account.trim().to_string(),
+        trade_date: req_body.trade_date,
+        kind: req_body.kind,
+        currency: req_body
+            .currency
+            .or_else(|| ticker.as_ref().and_then(Ticker::currency))
+            .unwrap_or(Currency::Cny),
+        ticker: ticker.map(String::from),
+        quantity: req_body.quantity,
+        price: req_body.price,
+        amount: req_body.amount,
+        fee: req_body.fee,
+        notes: req_body.notes,
+        created_at: String::new(),
+    };
+
+    if let Err(e) = check_currency(&tx) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(e.to_string())),
+        )
+            .into_response();
+    }
+
+    let repo = &state.runner.repo_domain;
+    let mut txs = match repo.get_transactions(&tx.account).await {
+        Ok(txs) => txs,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+    txs.push(tx.clone());
+    if let Err(e) = Ledger::replay(&txs) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(e.to_string())),
+        )
+            .into_response();
+    }
+
+    match repo.create_transaction(&tx).await {
+        Ok(id) => (StatusCode::OK, Json(id)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateTransactionRequest {
+    #[schema(example = "main")]
+    pub account: String,
+    /// yyyymmdd
+    #[schema(example = 20251017)]
+    pub trade_date: i64,
+    pub kind: TransactionKind,
+    /// Required for buys, sells and dividends.
+    #[schema(example = "1.600635")]
+    pub ticker: Option<Ticker>,
+    #[serde(default)]
+    pub quantity: f64,
+    #[serde(default)]
+    pub price: f64,
+    /// Cash amount of dividends, fees, deposits and withdrawals.
+    #[serde(default)]
+    pub amount: f64,
+    #[serde(default)]
+    pub fee: f64,
+    /// Defaults to the trading currency of the ticker, else CNY. Trades and dividends must
+    /// use the trading currency.
+    pub currency: Option<Currency>,
+    pub notes: Option<String>,
+}
+
+/// List transactions of an account.
+///
+/// Returns transactions in trade date order.
+#[utoipa::path(
+    get,
+    path = "/portfolio/transactions",
+    tag = "candlescyther",
+    params(
+        AccountQuery,
+    ),
+    responses(
+        (status = 200, description = "Transactions", body = [Transaction]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_transactions(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<AccountQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_transactions(&query.account)
+        .await
+    {
+        Ok(txs) => (StatusCode::OK, Json(txs)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct AccountQuery {
+    pub account: String,
+}
+
+/// Delete a transaction.
+///
+/// Returns a 200 if deleted, rejected if later sells would exceed holdings.
+#[utoipa::path(
+    delete,
+    path = "/portfolio/transactions",
+    tag = "candlescyther",
+    params(
+        TransactionQuery,
+    ),
+    responses(
+        (status = 200, description = "Transaction deleted"),
+        (status = 400, description = "Ledger would turn invalid", body = ApiError),
+        (status = 404, description = "Transaction not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn delete_transaction(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<TransactionQuery>,
+) -> impl IntoResponse {
+    let repo = &state.runner.repo_domain;
+    let txs = match repo.get_transaction(query.id).await {
+        Ok(Some(tx)) => repo.get_transactions(&tx.account).await,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::NotFound(format!("id = {}", query.id))),
+            )
+                .into_response();
+        }
+        Err(e) => Err(e),
+    };
+    let mut txs = match txs {
+        Ok(txs) => txs,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    txs.retain(|t| t.id != query.id);
+    if let Err(e) = Ledger::replay(&txs) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(e.to_string())),
+        )
+            .into_response();
+    }
+
+    match repo.delete_transaction(query.id).await {
+        Ok(_) => (StatusCode::OK).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct TransactionQuery {
+    pub id: i64,
+}
+
+/// List accounts.
+///
+/// Returns the accounts with at least one transaction.
+#[utoipa::path(
+    get,
+    path = "/portfolio/accounts",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "Accounts", body = [String]),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_accounts(State(state): State<AppState>) -> impl IntoResponse {
+    match state.runner.repo_domain.get_accounts().await {
+        Ok(accounts) => (StatusCode::OK, Json(accounts)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Get the portfolio of an account.
+///
+/// Returns positions, cash and P&L marked at the latest stored closes.
+#[utoipa::path(
+    get,
+    path = "/portfolio",
+    tag = "candlescyther",
+    params(
+        PortfolioQuery,
+    ),
+    responses(
+        (status = 200, description = "Portfolio snapshot", body = PortfolioSnapshot),
+        (status = 400, description = "Invalid ledger", body = ApiError),
+        (status = 404, description = "Account has no transactions", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_portfolio(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<PortfolioQuery>,
+) -> impl IntoResponse {
+    let (txs, closes, fx) =
+        match load_portfolio(state.runner.repo_domain.as_ref(), &query.account).await {
+            Ok((txs, _, _)) if txs.is_empty() => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(ApiError::NotFound(format!("account = {}", query.account))),
+                )
+                    .into_response();
+            }
+            Ok(loaded) => loaded,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::DatabaseError(e.to_string())),
+                )
+                    .into_response();
+            }
+        };
+
+    let ledger = match Ledger::replay(&txs) {
+        Ok(ledger) => ledger,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::InvalidInput(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+    let latest: HashMap<String, (i64, f64)> = closes
+        .into_iter()
+        .filter_map(|(ticker, series)| series.last().map(|last| (ticker, *last)))
+        .collect();
+    let date = latest
+        .values()
+        .map(|(date, _)| *date)
+        .chain(txs.iter().map(|t| t.trade_date))
+        .max()
+        .unwrap_or_default();
+
+    let snapshot = ledger.snapshot(
+        &query.account,
+        date,
+        &latest,
+        &fx,
+        query.base.unwrap_or(Currency::Cny),
+    );
+    (StatusCode::OK, Json(snapshot)).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct PortfolioQuery {
+    pub account: String,
+    /// Currency of the totals, defaults to CNY.
+    pub base: Option<Currency>,
+}
+
+/// Get the portfolio history of an account.
+///
+/// Returns daily totals since the first transaction.
+#[utoipa::path(
+    get,
+    path = "/portfolio/history",
+    tag = "candlescyther",
+    params(
+        PortfolioQuery,
+    ),
+    responses(
+        (status = 200, description = "Portfolio history", body = [PortfolioPoint]),
+        (status = 400, description = "Invalid ledger", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_portfolio_history(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<PortfolioQuery>,
+) -> impl IntoResponse {
+    let (txs, closes, fx) =
+        match load_portfolio(state.runner.repo_domain.as_ref(), &query.account).await {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::DatabaseError(e.to_string())),
+                )
+                    .into_response();
+            }
+        };
+
+    match portfolio_history(&txs, &closes, &fx, query.base.unwrap_or(Currency::Cny)) {
+        Ok(history) => (StatusCode::OK, Json(history)).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// List FX rates.
+///
+/// Returns CNY per unit of each currency.
+#[utoipa::path(
+    get,
+    path = "/portfolio/fx",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "FX rates", body = [FxRate]),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_fx_rates(State(state): State<AppState>) -> impl IntoResponse {
+    match state.runner.repo_domain.get_fx_rates().await {
+        Ok(rates) => (StatusCode::OK, Json(rates)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Set an FX rate.
+///
+/// Returns a 200 if stored.
+#[utoipa::path(
+    put,
+    path = "/portfolio/fx",
+    tag = "candlescyther",
+    request_body = SetFxRateRequest,
+    responses(
+        (status = 200, description = "Rate stored"),
+        (status = 400, description = "Invalid rate", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn set_fx_rate(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<SetFxRateRequest>,
+) -> impl IntoResponse {
+    if req_body.currency == Currency::Cny || req_body.cny_rate <= 0.0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(
+                "rate must be positive and CNY is fixed at 1".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    match state
+        .runner
+        .repo_domain
+        .set_fx_rate(req_body.currency, req_body.cny_rate)
+        .await
+    {
+        Ok(()) => (StatusCode::OK).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetFxRateRequest {
+    pub currency: Currency,
+    /// CNY per unit.
+    #[schema(example = 7.12)]
+    pub cny_rate: f64,
+}
+
+/// Compute the risk report of an account.
+///
+/// Returns a 200 if the job is submitted.
+#[utoipa::path(
+    post,
+    path = "/risk",
+    tag = "candlescyther",
+    request_body = CreateRiskRequest,
+    responses(
+        (status = 200, description = "Job submitted"),
+        (status = 400, description = "Invalid risk params", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_risk_report(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateRiskRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = req_body.params.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ApiError::InvalidInput(e))).into_response();
+    }
+
+    let job = Job::new(
+        JobType::ComputeRisk,
+        json!(ComputeRiskPayload {
+            account: req_body.account,
+            benchmark: req_body.benchmark,
+            base: req_body.base,
+            params: req_body.params,
+        }),
+    );
+
+    if let Err(e) = state.runner.repo_job.create_jobs(vec![job]).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_risk_report",
+                "http/handlers.rs",
+                2990,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_risk_report: {}", e),
+                    "http/handlers.rs",
+                    3008,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateRiskRequest {
+    #[schema(example = "main")]
+    pub account: String,
+    /// Defaults to CSI 300 (1.000300).
+    pub benchmark: Option<Ticker>,
+    /// Defaults to CNY.
+    pub base: Option<Currency>,
+    #[serde(default)]
+    pub params: RiskParams,
+}
+
+/// Get the risk report of an account.
+///
+/// Returns the latest report, recomputed nightly.
+#[utoipa::path(
+    get,
+    path = "/risk",
+    tag = "candlescyther",
+    params(
+        AccountQuery,
+    ),
+    responses(
+        (status = 200, description = "Risk report", body = RiskRun),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "No report yet", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_risk_report(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<AccountQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_risk_report(&query.account)
+        .await
+    {
+        Ok(Some(run)) => (StatusCode::OK, Json(run)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("account = {}", query.account))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Compute relative strength of all stocks, rated within each market.
+///
+/// Returns a 200 if the job is submitted.
+#[utoipa::path(
+    post,
+    path = "/strength",
+    tag = "candlescyther",
+    request_body = CreateStrengthRequest,
+    responses(
+        (status = 200, description = "Job submitted"),
+        (status = 400, description = "Malformed body", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_strength(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateStrengthRequest>,
+) -> impl IntoResponse {
+    let job = Job::new(
+        JobType::ComputeStrength,
+        json!(ComputeStrengthPayload {
+            benchmark: req_body.benchmark,
+        }),
+    );
+
+    if let Err(e) = state.runner.repo_job.create_jobs(vec![job]).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_strength",
+                "http/handlers.rs",
+                3160,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_strength: {}", e),
+                    "http/handlers.rs",
+                    3178,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateStrengthRequest {
+    /// Benchmark of every market, any ticker with stored klines such as `100.NDX`.
+    /// Defaults to each market's own: CSI 300 (1.000300), Hang Seng (100.HSI) or S&P 500 (100.SPX).
+    pub benchmark: Option<Ticker>,
+}
+
+/// List relative strength.
+///
+/// Returns the latest strength of all stocks by rating, or the history of `ticker`.
+#[utoipa::path(
+    get,
+    path = "/strength",
+    tag = "candlescyther",
+    params(
+        StrengthQuery,
+    ),
+    responses(
+        (status = 200, description = "Strength signals", body = [StrengthSignal]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_strength(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<StrengthQuery>,
+) -> impl IntoResponse {
+    let result = match &query.ticker {
+        Some(ticker) => state.runner.repo_domain.get_strength_series(ticker).await,
+        None => state.runner.repo_domain.get_strength_latest().await,
+    };
+
+    match result {
+        Ok(mut signals) => {
+            if let (None, Some(limit)) = (&query.ticker, query.limit) {
+                signals.truncate(limit);
+            }
+            (StatusCode::OK, Json(signals)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct StrengthQuery {
+    pub ticker: Option<Ticker>,
+    /// Top of the ranking, ignored with `ticker`.
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SizingQuery {
+    pub ticker: Ticker,
+    /// Percent of equity risked on the trade, e.g. 1 for 1%.
+    pub risk_pct: f64,
+    /// Ledger account, its equity takes precedence over `equity`.
+    pub account: Option<String>,
+    /// Equity in the ticker's currency, used when the account has no transactions.
+    pub equity: Option<f64>,
+    /// Entry price, defaults to the latest close.
+    pub entry: Option<f64>,
+    /// Stop price, defaults to entry - atr_multiple * ATR.
+    pub stop: Option<f64>,
+    /// Defaults to 14.
+    pub atr_period: Option<usize>,
+    /// Defaults to 2.
+    pub atr_multiple: Option<f64>,
+    /// Max share of equity in the ticker, in (0, 1], defaults to 0.2.
+    pub max_concentration: Option<f64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SizingResponse {
+    pub ticker: String,
+    pub currency: Currency,
+    pub equity: f64,
+    /// "ledger" or "input".
+    pub equity_source: String,
+    pub atr: Option<f64>,
+    pub lot_size: f64,
+    #[serde(flatten)]
+    pub size: PositionSize,
+}
+
+/// Size a long position.
+///
+/// Returns the share quantity, rounded down to whole lots, that loses the risk budget at the stop.
+#[utoipa::path(
+    get,
+    path = "/sizing",
+    tag = "candlescyther",
+    params(
+        SizingQuery,
+    ),
+    responses(
+        (status = 200, description = "Position size", body = SizingResponse),
+        (status = 400, description = "Invalid input", body = ApiError),
+        (status = 404, description = "No klines", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_position_size(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<SizingQuery>,
+) -> impl IntoResponse {
+    let repo = state.runner.repo_domain.as_ref();
+    let klines = match repo.get_klines(&query.ticker).await {
+        Ok(klines) if klines.is_empty() => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::NotFound(format!("ticker = {}", query.ticker))),
+            )
+                .into_response();
+        }
+        Ok(klines) => klines,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+    let currency = Currency::for_ticker(&query.ticker);
+
+    // Ledger equity and what is already held in the ticker, else the given equity.
+    let mut held = (None, 0.0);
+    if let Some(account) = &query.account {
+        let (txs, closes, fx) = match load_portfolio(repo, account).await {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::DatabaseError(e.to_string())),
+                )
+                    .into_response();
+            }
+        };
+        if !txs.is_empty() {
+            let ledger = match Ledger::replay(&txs) {
+                Ok(ledger) => ledger,
+                Err(e) => {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiError::InvalidInput(e.to_string())),
+                    )
+                        .into_response();
+                }
+            };
+            let latest: HashMap<String, (i64, f64)> = closes
+                .into_iter()
+                .filter_map(|(ticker, series)| series.last().map(|last| (ticker, *last)))
+                .collect();
+            let date = latest
+                .values()
+                .map(|(date, _)| *date)
+                .max()
+                .unwrap_or_default();
+            let snapshot = ledger.snapshot(account, date, &latest, &fx, currency);
+            let existing = snapshot
+                .positions
+                .iter()
+                .filter(|p| p.ticker == query.ticker)
+                .map(|p| fx.convert(p.market_value, p.currency, currency))
+                .sum();
+            held = (Some(snapshot.totals.equity), existing);
+        }
+    }
+    let (equity, equity_source) = match (held.0, query.equity) {
+        (Some(equity), _) => (equity, "ledger"),
+        (None, Some(equity)) => (equity, "input"),
+        (None, None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::MissingInput(
+                    "require an account with transactions or query param equity".to_string(),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let atr = compute_atr(&klines, query.atr_period.unwrap_or(14));
+    let entry = query
+        .entry
+        .unwrap_or_else(|| klines.last().map(|k| k.k_close).unwrap_or_default());
+    let stop = match (query.stop, atr) {
+        (Some(stop), _) => stop,
+        (None, Some(atr)) => entry - query.atr_multiple.unwrap_or(2.0) * atr,
+        (None, None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::MissingInput(
+                    "too few klines for ATR, require query param stop".to_string(),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let realname = repo.get_stock(&query.ticker).await.ok().map(|s| s.realname);
+    let lot_size = MarketRules::for_ticker(&query.ticker, realname.as_deref()).lot_size;
+    let input = SizingInput {
+        equity,
+        risk_pct: query.risk_pct,
+        entry,
+        stop,
+        lot_size,
+        max_concentration: query.max_concentration.unwrap_or(0.2),
+        existing_value: held.1,
+    };
+    match size_position(&input) {
+        Ok(size) => (
+            StatusCode::OK,
+            Json(SizingResponse {
+                ticker: query.ticker.into(),
+                currency,
+                equity,
+                equity_source: equity_source.to_string(),
+                atr,
+                lot_size,
+                size,
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// List the tracked sectors.
+///
+/// Returns the sectors read by the moneyflow and sector signal jobs.
+#[utoipa::path(
+    get,
+    path = "/sectors",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "Tracked sectors", body = [TrackedSector]),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_tracked_sectors(State(state): State<AppState>) -> impl IntoResponse {
+    match state.runner.repo_domain.get_tracked_sectors().await {
+        Ok(sectors) => (StatusCode::OK, Json(sectors)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Track a sector.
+///
+/// Returns a 201 if added, 200 if already tracked.
+#[utoipa::path(
+    post,
+    path = "/sectors",
+    tag = "candlescyther",
+    request_body = TrackSectorRequest,
+    responses(
+        (status = 201, description = "Sector tracked"),
+        (status = 200, description = "Sector already tracked"),
+        (status = 400, description = "Invalid sector ticker", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn track_sector(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<TrackSectorRequest>,
+) -> impl IntoResponse {
+    if !req_body.ticker.is_sector() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::InvalidInput(format!(
+                "ticker = {}, expected a BK sector such as 90.BK1036",
+                req_body.ticker
+            ))),
+        )
+            .into_response();
+    }
+
+    match state
+        .runner
+        .repo_domain
+        .add_tracked_sector(&req_body.ticker, &req_body.realname)
+        .await
+    {
+        Ok(true) => (StatusCode::CREATED).into_response(),
+        Ok(false) => (StatusCode::OK).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TrackSectorRequest {
+    #[schema(example = "90.BK0420")]
+    pub ticker: Ticker,
+    #[schema(example = "航空机场")]
+    pub realname: String,
+}
+
+/// Untrack a sector.
+///
+/// Returns a 200 if removed, stored moneyflow and signals are kept.
+#[utoipa::path(
+    delete,
+    path = "/sectors",
+    tag = "candlescyther",
+    params(
+        SectorQuery,
+    ),
+    responses(
+        (status = 200, description = "Sector untracked"),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "Sector not tracked", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn untrack_sector(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<SectorQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .delete_tracked_sector(&query.sector)
+        .await
+    {
+        Ok(true) => (StatusCode::OK).into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("sector = {}", query.sector))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Refresh sector constituents.
+///
+/// Returns a 200 if the jobs are submitted, one per sector.
+#[utoipa::path(
+    post,
+    path = "/sectors/members",
+    tag = "candlescyther",
+    request_body = CreateSectorMembersRequest,
+    responses(
+        (status = 200, description = "Jobs submitted"),
+        (status = 400, description = "Malformed body or stored sector ticker", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_sector_members(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateSectorMembersRequest>,
+) -> impl IntoResponse {
+    let sectors = match req_body.sectors {
+        Some(sectors) => sectors,
+        None => match state.runner.repo_domain.get_tracked_sectors().await {
+            Ok(sectors) => match sectors
+                .into_iter()
+                .map(|s| s.ticker.parse())
+                .collect::<Result<_, _>>()
+            {
+                Ok(sectors) => sectors,
+                Err(e) => return invalid_ticker(e),
+            },
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::DatabaseError(e.to_string())),
+                )
+                    .into_response();
+            }
+        },
+    };
+    let jobs: Vec<Job> = sectors
+        .into_iter()
+        .map(|sector| {
+            Job::new(
+                JobType::CreateSectorMembers,
+                json!(CreateSectorMembersPayload { sector }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_sector_members",
+                "http/handlers.rs",
+                3420,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_sector_members: {}", e),
+                    "http/handlers.rs",
+                    3438,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateSectorMembersRequest {
+    /// BK sector tickers, defaults to the tracked sectors.
+    #[schema(example = json!(["90.BK1036"]))]
+    pub sectors: Option<Vec<Ticker>>,
+}
+
+/// List the stocks in a sector.
+///
+/// Returns the constituents from the last refresh.
+#[utoipa::path(
+    get,
+    path = "/sectors/members",
+    tag = "candlescyther",
+    params(
+        SectorQuery,
+    ),
+    responses(
+        (status = 200, description = "Sector constituents", body = [SectorMember]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_sector_members(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<SectorQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_sector_members(&query.sector)
+        .await
+    {
+        Ok(members) => (StatusCode::OK, Json(members)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SectorQuery {
+    /// BK sector ticker, e.g. 90.BK1036.
+    pub sector: Ticker,
+}
+
+/// List the sectors of a stock.
+///
+/// Returns one record per sector the stock is a constituent of.
+#[utoipa::path(
+    get,
+    path = "/stocks/sectors",
+    tag = "candlescyther",
+    params(
+        StockSectorsQuery,
+    ),
+    responses(
+        (status = 200, description = "Sectors of the stock", body = [SectorMember]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_stock_sectors(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<StockSectorsQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_stock_sectors(&query.ticker)
+        .await
+    {
+        Ok(sectors) => (StatusCode::OK, Json(sectors)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct StockSectorsQuery {
+    pub ticker: Ticker,
+}
+
+/// List the fundamentals history of a stock.
+///
+/// Returns one snapshot per refresh date in chronological order.
+#[utoipa::path(
+    get,
+    path = "/stocks/fundamentals",
+    tag = "candlescyther",
+    params(
+        FundamentalsQuery,
+    ),
+    responses(
+        (status = 200, description = "Fundamentals snapshots", body = [FundamentalsSnapshot]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn list_fundamentals(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<FundamentalsQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_fundamentals(&query.ticker, query.start.as_deref())
+        .await
+    {
+        Ok(snapshots) => (StatusCode::OK, Json(snapshots)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct FundamentalsQuery {
+    pub ticker: Ticker,
+    /// yyyy-mm-dd, inclusive.
+    pub start: Option<String>,
+}
+
+/// Crawl financial statements of A-shares.
+///
+/// Returns a 200 if the jobs are submitted.
+#[utoipa::path(
+    post,
+    path = "/stocks/financials",
+    tag = "candlescyther",
+    request_body = CreateFinancialsRequest,
+    responses(
+        (status = 200, description = "Jobs submitted"),
+        (status = 400, description = "Tickers is required", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_financials(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateFinancialsRequest>,
+) -> impl IntoResponse {
+    let tickers = match target_tickers(&state, &req_body.tickers, req_body.watchlist).await {
+        Ok(tickers) => tickers,
+        Err(resp) => return resp,
+    };
+
+    if tickers.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput(
+                "`tickers` field required in body".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    let jobs: Vec<Job> = tickers
+        .into_iter()
+        .map(|ticker| {
+            Job::new(
+                JobType::CreateFinancials,
+                json!(CreateFinancialsPayload { ticker }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_financials",
+                "http/handlers.rs",
+                3890,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_financials: {}", e),
+                    "http/handlers.rs",
+                    3908,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateFinancialsRequest {
+    #[serde(default)]
+    pub tickers: String,
+    /// Targets the tickers of a watchlist instead.
+    pub watchlist: Option<i64>,
+}
+
+/// Get the financial statements of a stock.
+///
+/// Returns income, balance sheet and cash flow statements with the derived ratios, by period.
+#[utoipa::path(
+    get,
+    path = "/stocks/financials",
+    tag = "candlescyther",
+    params(
+        FinancialsQuery,
+    ),
+    responses(
+        (status = 200, description = "Financial statements", body = FinancialsResponse),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn get_financials(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<FinancialsQuery>,
+) -> impl IntoResponse {
+    let repo = &state.runner.repo_financial;
+    match tokio::try_join!(
+        repo.get_financials(&query.ticker),
+        repo.get_financial_ratios(&query.ticker),
+    ) {
+        Ok((statements, ratios)) => (
+            StatusCode::OK,
+            Json(FinancialsResponse {
+                ticker: query.ticker.into(),
+                statements,
+                ratios,
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FinancialsResponse {
+    pub ticker: String,
+    #[serde(flatten)]
+    pub statements: FinancialStatements,
+    pub ratios: Vec<FinancialRatios>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct FinancialsQuery {
+    pub ticker: Ticker,
+}
+
+/// Update all stocks, or those of a watchlist.
+///
+/// Returns ok.
+#[utoipa::path(
+    get,
+    path = "/update/stocks",
+    tag = "candlescyther",
+    params(
+        TriggerQuery,
+    ),
+    responses(
+        (status = 200, description = "Update job is submitted."),
+        (status = 400, description = "Missing code", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn update_stocks(
+    State(state): State<AppState>,
+    query: Query<TriggerQuery>,
+) -> impl IntoResponse {
+    // FIX: refine security
+    if query.code.is_empty() || query.code != "1l0veu" {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput("missing code".to_string())),
+        )
+            .into_response();
+    }
+
+    let tickers = match query.watchlist {
+        Some(id) => state.runner.repo_domain.get_watchlist_tickers(id).await,
+        None => state
+            .runner
+            .repo_domain
+            .get_stock_all()
+            .await
+            .map(|stocks| {
+                stocks
+                    .into_iter()
+                    .filter_map(|s| s.ticker.parse().ok())
+                    .collect()
+            }),
+    };
+    let tickers = match tickers {
+        Ok(tickers) => tickers,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    let mut jobs = vec![];
+    for ticker in &tickers {
+        jobs.push(Job::new(
+            JobType::CreateStock,
+            json!(CreateStockPayload {
+                ticker: ticker.clone(),
+                refresh: true,
+            }),
+        ));
+    }
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in update_all",
+                "http/handlers.rs",
+                491,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/handlers.rs",
+                    510,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct TriggerQuery {
+    pub code: String,
+    /// Updates only the tickers of this watchlist.
+    pub watchlist: Option<i64>,
+}
+
+/// Create moneyflow sector data.
+///
+/// Returns a 200 if the job is submitted.
+#[utoipa::path(
+    post,
+    path = "/mf/sector",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "Job submitted"),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_mf_sector(State(state): State<AppState>) -> impl IntoResponse {
+    let job = Job::new(JobType::CreateMfSector, json!(CreateMfSectorPayload {}));
+
+    if let Err(e) = state.runner.repo_job.create_jobs(vec![job]).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_mf_sector",
+                "http/handlers.rs",
+                670,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: {}", e),
+                    "http/handlers.rs",
+                    615,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+/// List moneyflow sector data.
+///
+/// Returns a 200 if the job is submitted.
+#[utoipa::path(
+    get,
+    path = "/mf/sector",
+    tag = "candlescyther",
+    responses(
+        (status = 200, description = "List moneyflow sector records", body = [MoneyflowEastmoney]),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn list_mf_sector(State(state): State<AppState>) -> impl IntoResponse {
+    match state.runner.repo_domain.get_mf_sector().await {
+        Ok(mf) => (StatusCode::OK, Json(mf)).into_response(),
+        Err(e) => {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("failed to query database {}", e),
+                    "http/handlers.rs",
+                    211,
+                ),
+            )
+            .await;
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get sector rotation.
+///
+/// Returns sectors ranked by rolling cumulative main force net inflow, with rank history.
+#[utoipa::path(
+    get,
+    path = "/mf/sector/rotation",
+    tag = "candlescyther",
+    params(
+        RotationQuery,
+    ),
+    responses(
+        (status = 200, description = "Sector rotation", body = RotationReport),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn get_sector_rotation(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<RotationQuery>,
+) -> impl IntoResponse {
+    let flows = match state.runner.repo_domain.get_mf_sector().await {
+        Ok(flows) => flows,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    let defaults = RotationParams::default();
+    let params = RotationParams {
+        window: query.window.unwrap_or(defaults.window),
+        history: query.history.unwrap_or(defaults.history),
+        top: query.top.unwrap_or(defaults.top),
+    };
+    (StatusCode::OK, Json(compute_rotation(&flows, &params))).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct RotationQuery {
+    /// Days summed into the cumulative inflow, defaults to 5.
+    pub window: Option<usize>,
+    /// Dates of rank history per sector, defaults to 20.
+    pub history: Option<usize>,
+    /// Size of the leading group, defaults to 5.
+    pub top: Option<usize>,
+}
+
+/// Create moneyflow stock data.
+///
+/// Returns a 200 if the jobs are submitted, one per ticker.
+#[utoipa::path(
+    post,
+    path = "/mf/stock",
+    tag = "candlescyther",
+    request_body = CreateMfStockRequest,
+    responses(
+        (status = 200, description = "Job submitted"),
+        (status = 400, description = "Tickers is required", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Job runner error", body = ApiError),
+    )
+)]
+pub async fn create_mf_stock(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<CreateMfStockRequest>,
+) -> impl IntoResponse {
+    let tickers = match target_tickers(&state, &req_body.tickers, req_body.watchlist).await {
+        Ok(tickers) => tickers,
+        Err(resp) => return resp,
+    };
+
+    if tickers.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput(
+                "`tickers` or a non-empty `watchlist` required in body".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    let jobs: Vec<Job> = tickers
+        .into_iter()
+        .map(|ticker| {
+            Job::new(
+                JobType::CreateMfStock,
+                json!(CreateMfStockPayload { ticker }),
+            )
+        })
+        .collect();
+
+    if let Err(e) = state.runner.repo_job.create_jobs(jobs).await {
+        logit(
+            &state,
+            LogEntry::new(
+                LogLevel::Error,
+                "failed to create_jobs in create_mf_stock",
+                "http/handlers.rs",
+                3900,
+            ),
+        )
+        .await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response();
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = state.runner.run().await {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("runner error: create_mf_stock: {}", e),
+                    "http/handlers.rs",
+                    3918,
+                ),
+            )
+            .await;
+        }
+    });
+
+    (StatusCode::OK).into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateMfStockRequest {
+    #[serde(default)]
+    #[schema(example = "1.600635,0.000001")]
+    pub tickers: String,
+    /// Targets the tickers of a watchlist instead.
+    pub watchlist: Option<i64>,
+}
+
+/// List moneyflow stock data.
+///
+/// Returns the daily records of a ticker in chronological order.
+#[utoipa::path(
+    get,
+    path = "/mf/stock",
+    tag = "candlescyther",
+    params(
+        MfStockQuery,
+    ),
+    responses(
+        (status = 200, description = "List moneyflow stock records", body = [MoneyflowStock]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn list_mf_stock(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<MfStockQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_mf_stock(&query.ticker, query.start.as_deref())
+        .await
+    {
+        Ok(mf) => (StatusCode::OK, Json(mf)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct MfStockQuery {
+    pub ticker: Ticker,
+    /// First trading date, YYYY-MM-DD.
+    pub start: Option<String>,
+}
+
+/// Get the intraday moneyflow of a ticker.
+///
+/// Returns the cumulative minute series of the current, or last, session, crawled on request.
+#[utoipa::path(
+    get,
+    path = "/mf/stock/intraday",
+    tag = "candlescyther",
+    params(
+        MfIntradayQuery,
+    ),
+    responses(
+        (status = 200, description = "Minute moneyflow series", body = [MoneyflowMinute]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "No moneyflow for the ticker", body = ApiError),
+        (status = 500, description = "Crawl error", body = ApiError),
+    )
+)]
+pub async fn get_mf_intraday(ApiQuery(query): ApiQuery<MfIntradayQuery>) -> impl IntoResponse {
+    let url = UrlMoneyflowStockEastmoney::intraday(&query.ticker);
+    match crawl_moneyflow_intraday_eastmoney(url).await {
+        Ok(minutes) if minutes.is_empty() => (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::NotFound(format!("ticker = {}", query.ticker))),
+        )
+            .into_response(),
+        Ok(minutes) => (StatusCode::OK, Json(minutes)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::RunnerError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct MfIntradayQuery {
+    pub ticker: Ticker,
+}
+
+/// List latest quotes.
+///
+/// Returns the cached quotes of the tickers, those not polled yet are left out.
+#[utoipa::path(
+    get,
+    path = "/quotes",
+    tag = "candlescyther",
+    params(
+        QuotesQuery,
+    ),
+    responses(
+        (status = 200, description = "Latest quotes", body = [Quote]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn list_quotes(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<QuotesQuery>,
+) -> impl IntoResponse {
+    let tickers = match target_tickers(&state, &query.tickers, query.watchlist).await {
+        Ok(tickers) => tickers,
+        Err(response) => return response,
+    };
+
+    (StatusCode::OK, Json(state.quotes.latest(&tickers))).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct QuotesQuery {
+    #[serde(default)]
+    pub tickers: String,
+    /// Quotes of a watchlist's tickers instead.
+    pub watchlist: Option<i64>,
+}
+
+/// List quote snapshots.
+///
+/// Returns the persisted quotes of a ticker in chronological order.
+#[utoipa::path(
+    get,
+    path = "/quotes/history",
+    tag = "candlescyther",
+    params(
+        QuoteHistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "Quote snapshots", body = [Quote]),
+        (status = 400, description = "Malformed query", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn list_quote_history(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<QuoteHistoryQuery>,
+) -> impl IntoResponse {
+    match state
+        .runner
+        .repo_domain
+        .get_quote_snapshots(&query.ticker, query.since)
+        .await
+    {
+        Ok(quotes) => (StatusCode::OK, Json(quotes)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct QuoteHistoryQuery {
+    pub ticker: Ticker,
+    /// Unix seconds of the first snapshot.
+    pub since: Option<i64>,
+}
+
+/// Stream events.
+///
+/// Returns a Server-Sent Events stream of job transitions, quotes and fired alerts, each named
+/// by its topic. Events a slow client misses are skipped.
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "candlescyther",
+    params(
+        EventsQuery,
+    ),
+    responses(
+        (status = 200, description = "Event stream", content_type = "text/event-stream", body = Event),
+        (status = 400, description = "Unknown topic, malformed ticker or empty watchlist", body = ApiError),
+        (status = 404, description = "Watchlist not found", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn stream_events(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<EventsQuery>,
+) -> impl IntoResponse {
+    let topics: Result<Vec<Topic>, _> = query
+        .topics
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| serde_json::from_value(Value::String(s.to_string())))
+        .collect();
+    let topics = match topics {
+        Ok(topics) => topics,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::InvalidInput(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+    let tickers = match target_tickers(&state, &query.tickers, query.watchlist).await {
+        Ok(tickers) => tickers,
+        Err(response) => return response,
+    };
+    // An empty ticker filter lets every ticker through.
+    if query.watchlist.is_some() && tickers.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::MissingInput(
+                "`watchlist` has no tickers to filter on".to_string(),
+            )),
+        )
+            .into_response();
+    }
+
+    let filter = EventFilter { topics, tickers };
+    let stream = BroadcastStream::new(state.runner.events.subscribe()).filter_map(move |event| {
+        let event = event.ok().filter(|e| filter.matches(e))?;
+        let sse = SseEvent::default()
+            .event(event.topic().as_str())
+            .json_data(&event)
+            .ok()?;
+        Some(Ok::<_, Infallible>(sse))
+    });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct EventsQuery {
+    /// Comma separated among job, quote and alert, all if empty.
+    #[serde(default)]
+    pub topics: String,
+    /// Comma separated, events of other tickers are left out.
+    #[serde(default)]
+    pub tickers: String,
+    /// Filters on a watchlist's tickers instead.
+    pub watchlist: Option<i64>,
+}
+
+/// Get a day of an exchange calendar.
+///
+/// Returns whether the date trades, its session times and the neighbouring trading days.
+#[utoipa::path(
+    get,
+    path = "/calendar",
+    tag = "candlescyther",
+    params(
+        CalendarQuery,
+    ),
+    responses(
+        (status = 200, description = "Calendar day", body = CalendarDay),
+        (status = 400, description = "Invalid date", body = ApiError),
+    )
+)]
+pub async fn get_calendar_day(ApiQuery(query): ApiQuery<CalendarQuery>) -> impl IntoResponse {
+    let calendar = TradingCalendar::get(query.exchange);
+    let date = match query.date.as_deref() {
+        None => calendar.local_date(chrono::Utc::now()),
+        Some(date) => match chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiError::InvalidInput(e.to_string())),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    (StatusCode::OK, Json(calendar.day(date))).into_response()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct CalendarQuery {
+    pub exchange: Exchange,
+    /// yyyy-mm-dd, today of the exchange if empty.
+    pub date: Option<String>,
+}
+
+/// Resolve a ticker.
+///
+/// Normalises a secid or exchange symbol and returns its market from the registry. A `.US`
+/// symbol resolves to the one US market it is stored under, an ISIN to the stored stock it is
+/// linked to.
+#[utoipa::path(
+    get,
+    path = "/tickers",
+    tag = "candlescyther",
+    params(
+        TickerQuery,
+    ),
+    responses(
+        (status = 200, description = "Normalised ticker and its market", body = TickerInfo),
+        (status = 400, description = "Malformed, ambiguous or unlinked ticker", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn resolve_ticker(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<TickerQuery>,
+) -> impl IntoResponse {
+    let repo = &state.runner.repo_domain;
+    let resolved = match query.ticker.parse::<Isin>() {
+        Ok(isin) => repo
+            .get_isin_tickers(&isin)
+            .await
+            .map(|linked| Ticker::from_isin(&isin, &linked)),
+        Err(_) => repo
+            .get_stock_tickers()
+            .await
+            .map(|known| Ticker::resolve(&query.ticker, |t| known.contains(t))),
+    };
+
+    match resolved {
+        Ok(Ok(ticker)) => (StatusCode::OK, Json(TickerInfo::from(&ticker))).into_response(),
+        Ok(Err(e)) => invalid_ticker(e),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::DatabaseError(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Link an ISIN to a stored stock.
+///
+/// Returns a 200 once the ISIN resolves to the stock, replacing its previous ISIN.
+#[utoipa::path(
+    put,
+    path = "/tickers/isin",
+    tag = "candlescyther",
+    request_body = LinkIsinRequest,
+    responses(
+        (status = 200, description = "ISIN is linked"),
+        (status = 400, description = "Invalid ISIN, or not of the stock's market", body = ApiError),
+        (status = 404, description = "Stock not stored", body = ApiError),
+        (status = 500, description = "Database error", body = ApiError)
+    )
+)]
+pub async fn link_isin(
+    State(state): State<AppState>,
+    ApiJson(req_body): ApiJson<LinkIsinRequest>,
+) -> impl IntoResponse {
+    let isin = match req_body.isin.parse::<Isin>() {
+        Ok(isin) => isin,
+        Err(e) => return invalid_ticker(e),
+    };
+    if let Err(e) = isin.check(&req_body.ticker) {
+        return invalid_ticker(e);
+    }
+
+    let repo = &state.runner.repo_domain;
+    match repo.get_stock_tickers().await {
+        Ok(known) if known.contains(&req_body.ticker) => {}
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::NotFound(format!("stock {}", req_body.ticker))),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response();
+        }
+    }
+
+    match repo.create_stock_isin(&req_body.ticker, &isin).await {
+        Ok(_) => (StatusCode::OK).into_response(),
+        Err(e) => {
+            logit(
+                &state,
+                LogEntry::new(
+                    LogLevel::Error,
+                    format!("db error: link_isin: {}", e),
+                    "http/handlers.rs",
+                    3700,
+                ),
+            )
+            .await;
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::DatabaseError(e.to_string())),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct LinkIsinRequest {
+    #[schema(example = "105.TSLA")]
+    pub ticker: Ticker,
+    #[schema(example = "US88160R1014")]
+    pub isin: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct TickerQuery {
+    /// Secid such as 1.600635, exchange symbol such as 600635.SH, TSLA.O or TSLA.US, or the
+    /// ISIN of a linked stock such as US88160R1014.
+    pub ticker: String,
+}
+
 // #[utoipa::path(
 //     post,
 //     path = "/sector-signals",
@@ -2,13 +2,13 @@ pub mod cronjob;
 pub mod handler;
 
 use sqlx::SqlitePool;
-use std::fs;
+use std::{fs, sync::Arc};
 use tracing::info;
 use utoipa::OpenApi;
 use utoipa_axum::router::OpenApiRouter;
 
 use crate::{
-    application::runner::JobRunner,
+    application::{quotes::QuoteService, runner::JobRunner},
     infra::{http::handler::create_routes_api, storage::Database},
 };
 
@@ -16,6 +16,7 @@ use crate::{
 pub struct AppState {
     pub db: SqlitePool,
     pub runner: JobRunner,
+    pub quotes: Arc<QuoteService>,
 }
 
 #[derive(OpenApi)]
@@ -26,7 +27,11 @@ pub struct AppState {
     )]
 struct ApiDoc;
 
-pub async fn init_server(db: Database, runner: JobRunner) -> anyhow::Result<()> {
+pub async fn init_server(
+    db: Database,
+    runner: JobRunner,
+    quotes: Arc<QuoteService>,
+) -> anyhow::Result<()> {
     // let trace_layer = tower_http::trace::TraceLayer::new_for_http().make_span_with(
     //     |request: &axum::extract::Request<_>| {
     //         let uri = request.uri().to_string();
@@ -37,6 +42,7 @@ pub async fn init_server(db: Database, runner: JobRunner) -> anyhow::Result<()>
     let app_state = AppState {
         db: db.pool,
         runner,
+        quotes: quotes.clone(),
     };
 
     let routes_api = create_routes_api(app_state.clone());
@@ -48,6 +54,8 @@ pub async fn init_server(db: Database, runner: JobRunner) -> anyhow::Result<()>
 
     // setup_cron_jobs(app_state.clone()).await?;
 
+    tokio::spawn(quotes.run());
+
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
     info!("server up and listening on {}", listener.local_addr()?);
     axum::serve(listener, router.into_make_service()).await?;
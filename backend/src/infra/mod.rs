@@ -1,4 +1,5 @@
 pub mod data;
 pub mod http;
 pub mod logging;
+pub mod notify;
 pub mod storage;
@@ -0,0 +1,339 @@
+use std::{path::PathBuf, time::Duration};
+
+use async_trait::async_trait;
+use tokio::{
+    fs::OpenOptions,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use ureq::Agent;
+
+use crate::{application::alerts::NotificationSink, domain::model::AlertEvent};
+
+/// Connect timeout of the network sinks.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Whole delivery timeout of the network sinks.
+const SEND_TIMEOUT: Duration = Duration::from_secs(20);
+
+// ---------------------------------------------------------------
+// Webhook
+// POST the event as JSON.
+// ---------------------------------------------------------------
+pub struct WebhookSink {
+    pub url: String,
+}
+
+#[async_trait]
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+        let url = self.url.clone();
+        let body = serde_json::to_value(event)?;
+
+        // NOTE: ureq blocks, and hooks are usually internal so env proxies are skipped.
+        tokio::task::spawn_blocking(move || {
+            let agent: Agent = Agent::config_builder()
+                .proxy(None)
+                .timeout_connect(Some(CONNECT_TIMEOUT))
+                .timeout_global(Some(SEND_TIMEOUT))
+                .build()
+                .into();
+            match agent.post(&url).send_json(body) {
+                Ok(_) => Ok(()),
+                Err(ureq::Error::StatusCode(code)) => anyhow::bail!("HTTP error: {code}"),
+                Err(e) => anyhow::bail!("Non-HTTP error: {e}"),
+            }
+        })
+        .await?
+    }
+}
+
+// ---------------------------------------------------------------
+// SMTP
+// Plain SMTP without auth or TLS, meant for a local relay.
+// ---------------------------------------------------------------
+pub struct SmtpSink {
+    /// host:port of the relay.
+    pub addr: String,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+impl SmtpSink {
+    fn message(&self, event: &AlertEvent) -> String {
+        let body = event
+            .message
+            .lines()
+            // Dot-stuffing, a lone "." ends DATA.
+            .map(|l| {
+                if l.starts_with('.') {
+                    format!(".{l}")
+                } else {
+                    l.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n");
+
+        format!(
+            "From: {}\r\nTo: {}\r\nSubject: [candlescyther] {} {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n\r\nfired at {}\r\n.\r\n",
+            header(&self.from),
+            header(&self.to.join(", ")),
+            header(&event.rule_name),
+            header(&event.ticker),
+            body,
+            event.fired_at,
+        )
+    }
+
+    async fn session(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+        let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&self.addr))
+            .await
+            .map_err(|_| anyhow::anyhow!("SMTP connect timed out after {CONNECT_TIMEOUT:?}"))??;
+        let (read, mut write) = stream.into_split();
+        let mut reader = BufReader::new(read);
+
+        smtp_reply(&mut reader, "220").await?;
+        write.write_all(b"EHLO candlescyther\r\n").await?;
+        smtp_reply(&mut reader, "250").await?;
+        write
+            .write_all(format!("MAIL FROM:<{}>\r\n", self.from).as_bytes())
+            .await?;
+        smtp_reply(&mut reader, "250").await?;
+        for to in self.to.iter() {
+            write
+                .write_all(format!("RCPT TO:<{}>\r\n", to).as_bytes())
+                .await?;
+            smtp_reply(&mut reader, "25").await?;
+        }
+        write.write_all(b"DATA\r\n").await?;
+        smtp_reply(&mut reader, "354").await?;
+        write.write_all(self.message(event).as_bytes()).await?;
+        smtp_reply(&mut reader, "250").await?;
+        write.write_all(b"QUIT\r\n").await?;
+        smtp_reply(&mut reader, "221").await?;
+
+        Ok(())
+    }
+}
+
+/// Header value on a single line, CR and LF would start a new header.
+fn header(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c == '\r' || c == '\n' { ' ' } else { c })
+        .collect()
+}
+
+async fn smtp_reply<R>(reader: &mut R, expected: &str) -> Result<(), anyhow::Error>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            anyhow::bail!("SMTP connection closed");
+        }
+        if !line.starts_with(expected) {
+            anyhow::bail!("SMTP unexpected reply: {}", line.trim_end());
+        }
+        // "250-" continues a multi-line reply, "250 " ends it.
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for SmtpSink {
+    fn name(&self) -> &str {
+        "smtp"
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+        match tokio::time::timeout(SEND_TIMEOUT, self.session(event)).await {
+            Ok(result) => result,
+            Err(_) => anyhow::bail!("SMTP timed out after {SEND_TIMEOUT:?}"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------
+// File
+// Append the event as a JSON line.
+// ---------------------------------------------------------------
+pub struct FileSink {
+    pub path: PathBuf,
+}
+
+#[async_trait]
+impl NotificationSink for FileSink {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+        file.write_all(line.as_bytes()).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    use super::*;
+
+    fn event() -> AlertEvent {
+        AlertEvent {
+            id: 1,
+            rule_id: 1,
+            rule_name: "oversold".to_string(),
+            ticker: "1.600635".to_string(),
+            message: "1.600635 K 15.00 is below 20 on 20251105".to_string(),
+            fired_at: "2025-11-05T08:00:00+00:00".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 8192];
+            let mut request = String::new();
+            // Read until the JSON body is complete.
+            while !request.ends_with('}') {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.push_str(&String::from_utf8_lossy(&buf[..n]));
+            }
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            tx.send(request).unwrap();
+        });
+
+        let sink = WebhookSink {
+            url: format!("http://{addr}/hook"),
+        };
+        sink.send(&event()).await.unwrap();
+
+        let request = rx.await.unwrap();
+        assert!(request.starts_with("POST /hook"));
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let parsed: AlertEvent = serde_json::from_str(body).unwrap();
+        assert_eq!(parsed.rule_name, "oversold");
+    }
+
+    #[tokio::test]
+    async fn test_smtp_sink() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (read, mut write) = socket.into_split();
+            let mut reader = BufReader::new(read);
+            let mut transcript = vec![];
+
+            write.write_all(b"220 stub ESMTP\r\n").await.unwrap();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                transcript.push(line.trim_end().to_string());
+                let reply: &[u8] = if in_data {
+                    if line != ".\r\n" {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if line.starts_with("EHLO") {
+                    b"250-stub\r\n250 8BITMIME\r\n"
+                } else if line.starts_with("DATA") {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line.starts_with("QUIT") {
+                    write.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                write.write_all(reply).await.unwrap();
+            }
+            tx.send(transcript).unwrap();
+        });
+
+        let sink = SmtpSink {
+            addr: addr.to_string(),
+            from: "alerts@localhost".to_string(),
+            to: vec!["me@localhost".to_string()],
+        };
+        sink.send(&event()).await.unwrap();
+
+        let transcript = rx.await.unwrap();
+        assert!(transcript.contains(&"MAIL FROM:<alerts@localhost>".to_string()));
+        assert!(transcript.contains(&"RCPT TO:<me@localhost>".to_string()));
+        assert!(transcript.contains(&"Subject: [candlescyther] oversold 1.600635".to_string()));
+        assert_eq!(transcript.last().unwrap(), "QUIT");
+    }
+
+    #[test]
+    fn test_smtp_message_headers() {
+        let sink = SmtpSink {
+            addr: String::new(),
+            from: "alerts@localhost".to_string(),
+            to: vec!["me@localhost".to_string()],
+        };
+        let mut event = event();
+        event.rule_name = "oversold\r\nBcc: evil@example.com".to_string();
+
+        let message = sink.message(&event);
+        assert!(
+            message
+                .contains("Subject: [candlescyther] oversold  Bcc: evil@example.com 1.600635\r\n")
+        );
+        assert!(!message.contains("\r\nBcc:"));
+    }
+
+    #[tokio::test]
+    async fn test_file_sink() {
+        let path = std::env::temp_dir().join(format!("alerts-{}.jsonl", std::process::id()));
+        let sink = FileSink { path: path.clone() };
+
+        sink.send(&event()).await.unwrap();
+        sink.send(&event()).await.unwrap();
+
+        let text = tokio::fs::read_to_string(&path).await.unwrap();
+        tokio::fs::remove_file(&path).await.unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let parsed: AlertEvent = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(parsed.ticker, "1.600635");
+    }
+}
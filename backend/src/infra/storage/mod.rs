@@ -5,6 +5,7 @@ use tracing::info;
 use sqlx::SqlitePool;
 
 pub mod repo_domain_sqlite;
+pub mod repo_financial_sqlite;
 pub mod repo_job_sqlite;
 
 #[derive(Debug, Clone)]
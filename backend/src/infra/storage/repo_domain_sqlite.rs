@@ -1,12 +1,29 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Weekday};
 use sqlx::SqlitePool;
 
 use crate::{
     domain::{
-        model::{Signal, Stock},
+        backtest::{
+            BacktestConfig, BacktestReport,
+            optimize::{Objective, ParamSpace, SearchMethod, WalkForwardReport},
+            strategy::StrategyConfig,
+        },
+        model::{
+            AlertCondition, AlertEvent, AlertRule, BacktestRun, Currency, FundamentalsSnapshot,
+            FxRate, Kline, Optimisation, OptimisationMode, Quote, RiskRun, ScreenerRule,
+            SectorMember, Signal, SignalPeriod, Stock, TrackedSector, Transaction, Watchlist,
+            WatchlistItem,
+        },
         repository::DomainRepository,
+        service_level::date_from_i64,
+        service_market::{Isin, SECTOR_MARKET, Ticker},
+        service_risk::RiskReport,
+        service_strength::StrengthSignal,
     },
-    infra::data::moneyflow::MoneyflowEastmoney,
+    infra::data::moneyflow::{MoneyflowEastmoney, MoneyflowStock},
 };
 
 #[derive(Clone)]
@@ -18,32 +35,59 @@ impl SqliteDomainRepository {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
+
+    async fn get_watchlist_items(&self, id: i64) -> Result<Vec<WatchlistItem>, anyhow::Error> {
+        let items = sqlx::query_as::<_, WatchlistItem>(
+            r#"
+            SELECT ticker, position, notes, added_at FROM watchlist_items
+            WHERE watchlist_id = ?
+            ORDER BY position
+        "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items)
+    }
 }
 
 #[async_trait]
 impl DomainRepository for SqliteDomainRepository {
     async fn create_stock(&self, stock: Stock) -> Result<(), anyhow::Error> {
-        sqlx::query!(
-            "INSERT INTO stocks (ticker, realname, market, total_cap, pe, pb, revenue, net, margin, debt)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            stock.ticker,
-            stock.realname,
-            stock.market,
-            stock.total_cap,
-            stock.pe,
-            stock.pb,
-            stock.revenue,
-            stock.net,
-            stock.margin,
-            stock.debt,
+        sqlx::query(
+            r#"
+            INSERT INTO stocks (ticker, realname, market, total_cap, pe, pb, revenue, net, margin, debt)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (ticker) DO UPDATE SET
+                realname = excluded.realname,
+                market = excluded.market,
+                total_cap = excluded.total_cap,
+                pe = excluded.pe,
+                pb = excluded.pb,
+                revenue = excluded.revenue,
+                net = excluded.net,
+                margin = excluded.margin,
+                debt = excluded.debt
+        "#,
         )
-            .execute(&self.pool)
-            .await?;
+        .bind(&stock.ticker)
+        .bind(&stock.realname)
+        .bind(stock.market)
+        .bind(stock.total_cap)
+        .bind(stock.pe)
+        .bind(stock.pb)
+        .bind(stock.revenue)
+        .bind(stock.net)
+        .bind(stock.margin)
+        .bind(stock.debt)
+        .execute(&self.pool)
+        .await?;
 
         Ok(())
     }
 
-    async fn get_stock(&self, ticker: &str) -> Result<Stock, anyhow::Error> {
+    async fn get_stock(&self, ticker: &Ticker) -> Result<Stock, anyhow::Error> {
         let stock = sqlx::query_as!(
             Stock,
             r#"
@@ -74,18 +118,23 @@ impl DomainRepository for SqliteDomainRepository {
         Ok(stocks)
     }
 
-    async fn delete_stocks(&self, tickers: &[&str]) -> Result<(), anyhow::Error> {
-        let tx = self.pool.begin().await?;
+    async fn delete_stocks(&self, tickers: &[Ticker]) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
 
         for ticker in tickers.iter() {
             sqlx::query!("DELETE FROM stocks WHERE ticker = ?", ticker)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
-            sqlx::query!("DELETE FROM signals_d WHERE ticker = ?", ticker)
-                .execute(&self.pool)
+            sqlx::query!("DELETE FROM signals WHERE ticker = ?", ticker)
+                .execute(&mut *tx)
                 .await?;
-            sqlx::query!("DELETE FROM signals_w WHERE ticker = ?", ticker)
-                .execute(&self.pool)
+            sqlx::query("DELETE FROM fundamentals_snapshots WHERE ticker = ?")
+                .bind(ticker)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM stock_isins WHERE ticker = ?")
+                .bind(ticker)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -94,438 +143,1592 @@ impl DomainRepository for SqliteDomainRepository {
         Ok(())
     }
 
-    // NOTE: Restricted to a single ticker.
-    //
-    // async fn create_klines(&self, ticker: &str, klines: &[Kline]) -> Result<(), anyhow::Error> {
-    //     let is_us = is_us(ticker);
-    //
-    //     // NOTE: Clear all records per tickers.
-    //     if is_us {
-    //         sqlx::query!("DELETE FROM klines_us WHERE k_ticker = ?", ticker)
-    //             .execute(&self.pool)
-    //             .await?;
-    //     } else {
-    //         sqlx::query!("DELETE FROM klines WHERE k_ticker = ?", ticker)
-    //             .execute(&self.pool)
-    //             .await?;
-    //     }
-    //
-    //     let batch_size = 5000;
-    //     let chunks: Vec<Vec<Kline>> = klines
-    //         .chunks(batch_size)
-    //         .map(|chunk| chunk.to_vec())
-    //         .collect();
-    //
-    //     for chunk in chunks {
-    //         let tx = self.pool.begin().await?;
-    //
-    //         // Batch commit.
-    //         if is_us {
-    //             for kline in chunk {
-    //                 sqlx::query!(
-    //             "INSERT INTO klines_us (k_ticker, k_date, k_open, k_high, k_low, k_close, k_volume, k_value)
-    //              VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-    //             kline.k_ticker,
-    //             kline.k_date,
-    //             kline.k_open,
-    //             kline.k_high,
-    //             kline.k_low,
-    //             kline.k_close,
-    //             kline.k_volume,
-    //             kline.k_value,
-    //         )
-    //         .execute(&self.pool)
-    //         .await?;
-    //             }
-    //         } else {
-    //             for kline in chunk {
-    //                 sqlx::query!(
-    //                     "INSERT INTO klines (k_ticker, k_date, k_open, k_high, k_low, k_close, k_volume, k_value) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-    //                     kline.k_ticker,
-    //                     kline.k_date,
-    //                     kline.k_open,
-    //                     kline.k_high,
-    //                     kline.k_low,
-    //                     kline.k_close,
-    //                     kline.k_volume,
-    //                     kline.k_value,
-    //                 )
-    //                     .execute(&self.pool)
-    //                 .await?;
-    //             }
-    //         }
-    //
-    //         tx.commit().await?;
-    //     }
-    //
-    //     Ok(())
-    // }
-
-    // async fn get_klines(&self, ticker: &str) -> Result<Vec<Kline>, anyhow::Error> {
-    //     let is_us = is_us(ticker);
-    //
-    //     if is_us {
-    //         let klines = sqlx::query_as!(
-    //             Kline,
-    //             r#"
-    //         SELECT *
-    //         FROM klines_us
-    //         WHERE k_ticker = ?
-    //     "#,
-    //             ticker
-    //         )
-    //         .fetch_all(&self.pool)
-    //         .await?;
-    //
-    //         Ok(klines)
-    //     } else {
-    //         let klines = sqlx::query_as!(
-    //             Kline,
-    //             r#"
-    //         SELECT *
-    //         FROM klines
-    //         WHERE k_ticker = ?
-    //     "#,
-    //             ticker
-    //         )
-    //         .fetch_all(&self.pool)
-    //         .await?;
-    //
-    //         Ok(klines)
-    //     }
-    // }
-
-    async fn create_signals_d(&self, signal: Signal) -> Result<(), anyhow::Error> {
-        sqlx::query_as!(
-            Signal,
-            "INSERT INTO signals_d (ticker, kdj_k, kdj_d, boll_dist) VALUES (?, ?, ?, ?)",
-            signal.ticker,
-            signal.kdj_k,
-            signal.kdj_d,
-            signal.boll_dist,
+    async fn create_stock_isin(&self, ticker: &Ticker, isin: &Isin) -> Result<(), anyhow::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO stock_isins (ticker, isin)
+            VALUES (?, ?)
+            ON CONFLICT (ticker) DO UPDATE SET
+                isin = excluded.isin
+        "#,
         )
+        .bind(ticker)
+        .bind(isin.as_str())
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    async fn create_signals_w(&self, signal: Signal) -> Result<(), anyhow::Error> {
-        sqlx::query_as!(
-            Signal,
-            "INSERT INTO signals_w (ticker, kdj_k, kdj_d, boll_dist) VALUES (?, ?, ?, ?)",
-            signal.ticker,
-            signal.kdj_k,
-            signal.kdj_d,
-            signal.boll_dist,
+    async fn get_isin_tickers(&self, isin: &Isin) -> Result<Vec<Ticker>, anyhow::Error> {
+        let tickers = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT i.ticker
+            FROM stock_isins i
+            JOIN stocks s ON s.ticker = i.ticker
+            WHERE i.isin = ?
+            ORDER BY i.ticker
+        "#,
+        )
+        .bind(isin.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(parse_stored(tickers))
+    }
+
+    async fn create_fundamentals_snapshot(
+        &self,
+        snapshot: &FundamentalsSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO fundamentals_snapshots (ticker, snapshot_date, total_cap, pe, pb, revenue, net, margin, debt)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (ticker, snapshot_date) DO UPDATE SET
+                total_cap = excluded.total_cap,
+                pe = excluded.pe,
+                pb = excluded.pb,
+                revenue = excluded.revenue,
+                net = excluded.net,
+                margin = excluded.margin,
+                debt = excluded.debt
+        "#,
         )
+        .bind(&snapshot.ticker)
+        .bind(&snapshot.snapshot_date)
+        .bind(snapshot.total_cap)
+        .bind(snapshot.pe)
+        .bind(snapshot.pb)
+        .bind(snapshot.revenue)
+        .bind(snapshot.net)
+        .bind(snapshot.margin)
+        .bind(snapshot.debt)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    async fn get_signals_stock_d(&self) -> Result<Vec<Signal>, anyhow::Error> {
-        let signals = sqlx::query_as!(
-            Signal,
+    async fn get_fundamentals(
+        &self,
+        ticker: &Ticker,
+        start: Option<&str>,
+    ) -> Result<Vec<FundamentalsSnapshot>, anyhow::Error> {
+        let snapshots = sqlx::query_as::<_, FundamentalsSnapshot>(
             r#"
-            SELECT *
-            FROM signals_d
-            WHERE ticker NOT LIKE '90.%'
-        "#
+            SELECT * FROM fundamentals_snapshots
+            WHERE ticker = ? AND (? IS NULL OR snapshot_date >= ?)
+            ORDER BY snapshot_date
+        "#,
         )
+        .bind(ticker)
+        .bind(start)
+        .bind(start)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(signals)
+        Ok(snapshots)
     }
 
-    async fn get_signals_stock_w(&self) -> Result<Vec<Signal>, anyhow::Error> {
-        let signals = sqlx::query_as!(
-            Signal,
-            r#"
+    // NOTE: Restricted to a single ticker.
+    //
+    async fn create_klines(&self, ticker: &Ticker, klines: &[Kline]) -> Result<(), anyhow::Error> {
+        let is_us = is_us(ticker);
+
+        // NOTE: Records of the ticker are replaced whole, in one transaction so readers never
+        // see a partial history.
+        let mut tx = self.pool.begin().await?;
+
+        if is_us {
+            sqlx::query!("DELETE FROM klines_us WHERE k_ticker = ?", ticker)
+                .execute(&mut *tx)
+                .await?;
+            for kline in klines {
+                sqlx::query!(
+                    "INSERT INTO klines_us (k_ticker, k_date, k_open, k_high, k_low, k_close, k_volume, k_value) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                    kline.k_ticker,
+                    kline.k_date,
+                    kline.k_open,
+                    kline.k_high,
+                    kline.k_low,
+                    kline.k_close,
+                    kline.k_volume,
+                    kline.k_value,
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        } else {
+            sqlx::query!("DELETE FROM klines WHERE k_ticker = ?", ticker)
+                .execute(&mut *tx)
+                .await?;
+            for kline in klines {
+                sqlx::query!(
+                    "INSERT INTO klines (k_ticker, k_date, k_open, k_high, k_low, k_close, k_volume, k_value) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                    kline.k_ticker,
+                    kline.k_date,
+                    kline.k_open,
+                    kline.k_high,
+                    kline.k_low,
+                    kline.k_close,
+                    kline.k_volume,
+                    kline.k_value,
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_klines(&self, ticker: &Ticker) -> Result<Vec<Kline>, anyhow::Error> {
+        let is_us = is_us(ticker);
+
+        if is_us {
+            let klines = sqlx::query_as!(
+                Kline,
+                r#"
             SELECT *
-            FROM signals_w
-            WHERE ticker NOT LIKE '90.%'
-        "#
+            FROM klines_us
+            WHERE k_ticker = ?
+            ORDER BY k_date ASC
+        "#,
+                ticker
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(klines)
+        } else {
+            let klines = sqlx::query_as!(
+                Kline,
+                r#"
+            SELECT *
+            FROM klines
+            WHERE k_ticker = ?
+            ORDER BY k_date ASC
+        "#,
+                ticker
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(klines)
+        }
+    }
+
+    async fn create_signal(&self, signal: Signal) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // NOTE: The bar of a week in progress is dated on its latest session, so earlier rows of
+        // the same week are replaced rather than kept as weeks of their own.
+        if signal.period == SignalPeriod::Week
+            && let Some((monday, sunday)) = week_of(signal.bar_date)
+        {
+            sqlx::query(
+                r#"
+                DELETE FROM signals
+                WHERE ticker = ? AND period = ? AND bar_date >= ? AND bar_date <= ? AND bar_date != ?
+            "#,
+            )
+            .bind(&signal.ticker)
+            .bind(signal.period)
+            .bind(monday)
+            .bind(sunday)
+            .bind(signal.bar_date)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO signals (ticker, period, bar_date, kdj_k, kdj_d, boll_dist)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (ticker, period, bar_date) DO UPDATE SET
+                kdj_k = excluded.kdj_k,
+                kdj_d = excluded.kdj_d,
+                boll_dist = excluded.boll_dist
+        "#,
         )
-        .fetch_all(&self.pool)
+        .bind(&signal.ticker)
+        .bind(signal.period)
+        .bind(signal.bar_date)
+        .bind(signal.kdj_k)
+        .bind(signal.kdj_d)
+        .bind(signal.boll_dist)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_signals_latest(
+        &self,
+        period: SignalPeriod,
+        sector: bool,
+    ) -> Result<Vec<Signal>, anyhow::Error> {
+        let filter = if sector {
+            "s.ticker LIKE $2"
+        } else {
+            "s.ticker NOT LIKE $2"
+        };
+        let query = format!(
+            r#"
+            SELECT s.*
+            FROM signals s
+            JOIN (
+                SELECT ticker, MAX(bar_date) AS bar_date
+                FROM signals
+                WHERE period = $1
+                GROUP BY ticker
+            ) latest ON s.ticker = latest.ticker AND s.bar_date = latest.bar_date
+            WHERE s.period = $1 AND {filter}
+        "#
+        );
+
+        let signals = sqlx::query_as::<_, Signal>(&query)
+            .bind(period)
+            .bind(sector_pattern())
+            .fetch_all(&self.pool)
+            .await?;
+
         Ok(signals)
     }
 
-    async fn get_signals_sector_d(&self) -> Result<Vec<Signal>, anyhow::Error> {
-        let signals = sqlx::query_as!(
-            Signal,
+    async fn get_signals_recent(
+        &self,
+        period: SignalPeriod,
+        sector: bool,
+        n: i64,
+    ) -> Result<Vec<Signal>, anyhow::Error> {
+        let filter = if sector {
+            "ticker LIKE $3"
+        } else {
+            "ticker NOT LIKE $3"
+        };
+        let query = format!(
             r#"
-            SELECT *
-            FROM signals_d
-            WHERE ticker LIKE '90.%'
+            SELECT ticker, period, bar_date, kdj_k, kdj_d, boll_dist
+            FROM (
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY ticker ORDER BY bar_date DESC) AS rn
+                FROM signals
+                WHERE period = $1 AND {filter}
+            )
+            WHERE rn <= $2
+            ORDER BY ticker ASC, bar_date ASC
         "#
+        );
+
+        let signals = sqlx::query_as::<_, Signal>(&query)
+            .bind(period)
+            .bind(n)
+            .bind(sector_pattern())
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(signals)
+    }
+
+    async fn get_signal_series(
+        &self,
+        ticker: &Ticker,
+        period: SignalPeriod,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> Result<Vec<Signal>, anyhow::Error> {
+        let signals = sqlx::query_as::<_, Signal>(
+            r#"
+            SELECT *
+            FROM signals
+            WHERE ticker = $1 AND period = $2 AND bar_date >= $3 AND bar_date <= $4
+            ORDER BY bar_date ASC
+        "#,
         )
+        .bind(ticker)
+        .bind(period)
+        .bind(start.unwrap_or(0))
+        .bind(end.unwrap_or(i64::MAX))
         .fetch_all(&self.pool)
         .await?;
 
         Ok(signals)
     }
 
-    async fn get_signals_sector_w(&self) -> Result<Vec<Signal>, anyhow::Error> {
-        let signals = sqlx::query_as!(
-            Signal,
+    async fn get_signals_tail(
+        &self,
+        ticker: &Ticker,
+        period: SignalPeriod,
+        n: i64,
+    ) -> Result<Vec<Signal>, anyhow::Error> {
+        let signals = sqlx::query_as::<_, Signal>(
             r#"
             SELECT *
-            FROM signals_w
-            WHERE ticker LIKE '90.%'
-        "#
+            FROM (
+                SELECT *
+                FROM signals
+                WHERE ticker = $1 AND period = $2
+                ORDER BY bar_date DESC
+                LIMIT $3
+            )
+            ORDER BY bar_date ASC
+        "#,
         )
+        .bind(ticker)
+        .bind(period)
+        .bind(n)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(signals)
     }
 
-    async fn delete_signals_d(&self) -> Result<(), anyhow::Error> {
-        sqlx::query!("DELETE FROM signals_d")
+    async fn delete_signals_before(
+        &self,
+        ticker: &Ticker,
+        period: SignalPeriod,
+        bar_date: i64,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query("DELETE FROM signals WHERE ticker = ? AND period = ? AND bar_date < ?")
+            .bind(ticker)
+            .bind(period)
+            .bind(bar_date)
             .execute(&self.pool)
             .await?;
 
         Ok(())
     }
 
-    async fn delete_signals_w(&self) -> Result<(), anyhow::Error> {
-        sqlx::query!("DELETE FROM signals_w")
-            .execute(&self.pool)
-            .await?;
+    async fn create_screener_rule(
+        &self,
+        name: &str,
+        expression: &str,
+        rank_by: Option<&str>,
+        descending: bool,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO screener_rules (name, expression, rank_by, descending)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (name) DO UPDATE SET
+                expression = excluded.expression,
+                rank_by = excluded.rank_by,
+                descending = excluded.descending
+        "#,
+        )
+        .bind(name)
+        .bind(expression)
+        .bind(rank_by)
+        .bind(descending)
+        .execute(&self.pool)
+        .await?;
 
         Ok(())
     }
 
-    async fn create_mf_sector(&self, flows: &[MoneyflowEastmoney]) -> Result<(), anyhow::Error> {
-        let tx = self.pool.begin().await?;
+    async fn get_screener_rule(&self, name: &str) -> Result<Option<ScreenerRule>, anyhow::Error> {
+        let rule = sqlx::query_as::<_, ScreenerRule>("SELECT * FROM screener_rules WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
 
-        for flow in flows.iter() {
-            sqlx::query!(
-                "INSERT INTO moneyflow_sector (date_time, ticker, realname, lead_value, lead_share, super_value, super_share, large_value, large_share, mid_value, mid_share, small_value, small_share) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                flow.date_time,
-                flow.ticker,
-                flow.realname,
-                flow.lead_value,
-                flow.lead_share,
-                flow.super_value,
-                flow.super_share,
-                flow.large_value,
-                flow.large_share,
-                flow.mid_value,
-                flow.mid_share,
-                flow.small_value,
-                flow.small_share,
-            ).execute(&self.pool)
-                .await?;
-        }
+        Ok(rule)
+    }
 
-        tx.commit().await?;
+    async fn get_screener_rules(&self) -> Result<Vec<ScreenerRule>, anyhow::Error> {
+        let rules = sqlx::query_as::<_, ScreenerRule>("SELECT * FROM screener_rules ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rules)
+    }
+
+    async fn delete_screener_rule(&self, name: &str) -> Result<(), anyhow::Error> {
+        sqlx::query("DELETE FROM screener_rules WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
 
         Ok(())
     }
 
-    async fn get_mf_sector(&self) -> Result<Vec<MoneyflowEastmoney>, anyhow::Error> {
-        let flows = sqlx::query_as!(MoneyflowEastmoney, "SELECT * FROM moneyflow_sector")
+    async fn create_alert_rule(
+        &self,
+        name: &str,
+        condition: &AlertCondition,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO alert_rules (name, condition)
+            VALUES (?, ?)
+            ON CONFLICT (name) DO UPDATE SET
+                condition = excluded.condition,
+                triggered = FALSE,
+                last_fired_at = NULL
+        "#,
+        )
+        .bind(name)
+        .bind(serde_json::to_string(condition)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_alert_rules(&self) -> Result<Vec<AlertRule>, anyhow::Error> {
+        let rules = sqlx::query_as::<_, AlertRule>("SELECT * FROM alert_rules ORDER BY name")
             .fetch_all(&self.pool)
             .await?;
 
-        Ok(flows)
+        Ok(rules)
     }
 
-    async fn delete_mf_sector(&self) -> Result<(), anyhow::Error> {
-        sqlx::query!("DELETE FROM moneyflow_sector")
+    async fn delete_alert_rule(&self, name: &str) -> Result<(), anyhow::Error> {
+        sqlx::query("DELETE FROM alert_rules WHERE name = ?")
+            .bind(name)
             .execute(&self.pool)
             .await?;
 
         Ok(())
     }
 
-    async fn get_sector_tickers(&self) -> Result<Vec<String>, anyhow::Error> {
-        let stock = sqlx::query_as!(
-            Stock,
-            r#"
-            SELECT *
-            FROM stocks
-            WHERE ticker LIKE '90.%'
-        "#,
+    async fn update_alert_state(
+        &self,
+        id: i64,
+        triggered: bool,
+        last_fired_at: Option<&str>,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query(
+            "UPDATE alert_rules SET triggered = ?, last_fired_at = COALESCE(?, last_fired_at) WHERE id = ?",
+        )
+        .bind(triggered)
+        .bind(last_fired_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_alert_event(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+        sqlx::query(
+            "INSERT INTO alert_events (rule_id, rule_name, ticker, message, fired_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(event.rule_id)
+        .bind(&event.rule_name)
+        .bind(&event.ticker)
+        .bind(&event.message)
+        .bind(&event.fired_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_alert_events(&self, limit: i64) -> Result<Vec<AlertEvent>, anyhow::Error> {
+        let events = sqlx::query_as::<_, AlertEvent>(
+            "SELECT * FROM alert_events ORDER BY fired_at DESC, id DESC LIMIT ?",
         )
+        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        let tickers = stock.iter().map(|x| x.ticker.clone()).collect();
+        Ok(events)
+    }
+
+    async fn create_backtest_run(
+        &self,
+        ticker: &Ticker,
+        strategy: &StrategyConfig,
+        config: &BacktestConfig,
+        report: &BacktestReport,
+        optimisation_id: Option<i64>,
+    ) -> Result<i64, anyhow::Error> {
+        let result = sqlx::query(
+            "INSERT INTO backtest_runs (ticker, strategy, config, metrics, report, optimisation_id) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(ticker)
+        .bind(serde_json::to_string(strategy)?)
+        .bind(serde_json::to_string(config)?)
+        .bind(serde_json::to_string(&report.metrics)?)
+        .bind(serde_json::to_string(report)?)
+        .bind(optimisation_id)
+        .execute(&self.pool)
+        .await?;
 
-        Ok(tickers)
+        Ok(result.last_insert_rowid())
     }
 
-    async fn get_stock_tickers(&self) -> Result<Vec<String>, anyhow::Error> {
-        let stock = sqlx::query_as!(
-            Stock,
+    async fn get_backtest_runs(
+        &self,
+        ticker: Option<&Ticker>,
+    ) -> Result<Vec<BacktestRun>, anyhow::Error> {
+        let runs = sqlx::query_as::<_, BacktestRun>(
             r#"
-            SELECT *
-            FROM stocks
-            WHERE ticker NOT LIKE '90.%'
+            SELECT id, ticker, strategy, config, metrics, optimisation_id, created_at
+            FROM backtest_runs
+            WHERE $1 IS NULL OR ticker = $1
+            ORDER BY id DESC
         "#,
         )
+        .bind(ticker)
         .fetch_all(&self.pool)
         .await?;
 
-        let tickers = stock.iter().map(|x| x.ticker.clone()).collect();
+        Ok(runs)
+    }
+
+    async fn get_backtest_report(&self, id: i64) -> Result<Option<BacktestReport>, anyhow::Error> {
+        let report: Option<String> =
+            sqlx::query_scalar("SELECT report FROM backtest_runs WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
 
-        Ok(tickers)
+        match report {
+            Some(report) => Ok(Some(serde_json::from_str(&report)?)),
+            None => Ok(None),
+        }
     }
-}
 
-// fn is_us(ticker: &str) -> bool {
-//     let idx = ticker.find('.').unwrap();
-//     let num = &ticker[..idx];
-//     let n = num.parse::<u32>().unwrap();
-//     (100..=110).contains(&n)
-// }
+    async fn create_optimisation(
+        &self,
+        ticker: &Ticker,
+        mode: OptimisationMode,
+        strategy: &StrategyConfig,
+        space: &ParamSpace,
+        method: &SearchMethod,
+        objective: Objective,
+    ) -> Result<i64, anyhow::Error> {
+        let result = sqlx::query(
+            "INSERT INTO optimisations (ticker, mode, strategy, space, method, objective) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(ticker)
+        .bind(mode)
+        .bind(serde_json::to_string(strategy)?)
+        .bind(serde_json::to_string(space)?)
+        .bind(serde_json::to_string(method)?)
+        .bind(serde_json::to_string(&objective)?)
+        .execute(&self.pool)
+        .await?;
 
-// FIX: more test coverage no need network call.
-#[cfg(test)]
-mod tests {
-    use chrono::{Duration, NaiveDate};
-    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+        Ok(result.last_insert_rowid())
+    }
 
-    use crate::{
-        domain::repository::DomainRepository,
-        infra::{
-            data::{
-                moneyflow::{MoneyflowEastmoney, RawMoneyflowEastmoney, create_moneyflow},
-                service::parse_raw_eastmoney,
-            },
-            storage::repo_domain_sqlite::SqliteDomainRepository,
-        },
-    };
+    async fn get_optimisations(&self) -> Result<Vec<Optimisation>, anyhow::Error> {
+        let optimisations = sqlx::query_as::<_, Optimisation>(
+            r#"
+            SELECT id, ticker, mode, strategy, space, method, objective, created_at
+            FROM optimisations
+            ORDER BY id DESC
+        "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
-    async fn setup_test_db() -> Result<SqlitePool, sqlx::Error> {
-        // Using a unique database URL for each test run enhances isolation.
-        // In a real scenario, you might generate a unique name or use an in-memory database.
-        let database_url = "sqlite::memory:";
-        let pool = SqlitePoolOptions::new().connect(database_url).await?;
-        sqlx::migrate!("./migrations").run(&pool).await?;
-        Ok(pool)
+        Ok(optimisations)
     }
 
-    // pub fn generate_sequential_klines(count: usize, ticker: &str, start_date: i64) -> Vec<Kline> {
-    //     let mut klines = Vec::with_capacity(count);
-    //
-    //     for i in 0..count {
-    //         let base_price = 100.0 + (i as f64 * 0.1); // Slowly increasing base price
-    //         let open = base_price;
-    //         let close = open - (i as f64 * 0.2);
-    //
-    //         let high = open.max(close);
-    //         let low = open.min(close);
-    //
-    //         let volume = 100000.0 + (i as f64 * 0.1);
-    //         let value = volume * (open + close) / 2.0;
-    //
-    //         klines.push(Kline {
-    //             k_ticker: ticker.to_string(),
-    //             k_date: start_date + i as i64,
-    //             k_open: open,
-    //             k_high: high,
-    //             k_low: low,
-    //             k_close: close,
-    //             k_volume: volume,
-    //             k_value: value,
-    //         });
-    //     }
-    //
-    //     klines
-    // }
+    async fn get_optimisation(&self, id: i64) -> Result<Option<Optimisation>, anyhow::Error> {
+        let optimisation = sqlx::query_as::<_, Optimisation>(
+            r#"
+            SELECT id, ticker, mode, strategy, space, method, objective, created_at
+            FROM optimisations
+            WHERE id = ?
+        "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
 
-    // #[tokio::test]
-    // async fn test_create_klines() {
-    //     let pool = setup_test_db().await.unwrap();
-    //
-    //     let tickers = vec!["105.AAPL", "105.GOOGL", "105.MSFT"];
-    //     let mut klines: Vec<Vec<Kline>> = vec![];
-    //     for ticker in &tickers {
-    //         let kline = generate_sequential_klines(8000, ticker, 20200101);
-    //         klines.push(kline);
-    //     }
-    //
-    //     let repo = SqliteDomainRepository::new(pool.clone());
-    //
-    //     for (idx, ticker) in tickers.iter().enumerate() {
-    //         repo.create_klines(ticker, &klines[idx]).await.unwrap();
-    //     }
-    //
-    //     let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) from klines_us")
-    //         .fetch_one(&pool)
-    //         .await
-    //         .unwrap();
-    //
-    //     assert_eq!(count, 24000);
-    //
-    //     // Check for clearing function.
-    //     for (idx, ticker) in tickers.iter().enumerate() {
-    //         repo.create_klines(ticker, &klines[idx]).await.unwrap();
-    //     }
-    //
-    //     let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) from klines_us")
-    //         .fetch_one(&pool)
-    //         .await
-    //         .unwrap();
-    //
-    //     assert_eq!(count, 24000);
-    //
-    //     let klines_aapl = repo.get_klines("105.AAPL").await.unwrap();
-    //
-    //     assert_eq!(klines_aapl.len(), 8000);
-    //     assert_eq!(klines_aapl[0].k_ticker, "105.AAPL");
-    //     assert_eq!(klines_aapl[1].k_ticker, "105.AAPL");
-    //     assert_eq!(klines_aapl[0].k_date, 20200101);
-    //     assert_eq!(klines_aapl[1].k_date, 20200102);
-    // }
+        Ok(optimisation)
+    }
 
-    #[tokio::test]
-    async fn test_create_ml_sector() {
-        let pool = setup_test_db().await.unwrap();
+    async fn get_optimisation_runs(&self, id: i64) -> Result<Vec<BacktestRun>, anyhow::Error> {
+        let runs = sqlx::query_as::<_, BacktestRun>(
+            r#"
+            SELECT id, ticker, strategy, config, metrics, optimisation_id, created_at
+            FROM backtest_runs
+            WHERE optimisation_id = ?
+            ORDER BY id ASC
+        "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
 
-        let repo = SqliteDomainRepository::new(pool.clone());
+        Ok(runs)
+    }
 
-        const RAW_MONEYFLOW_SECTOR_EASTMONEY: &str = r#"jQuery112301083078708820121_1761565635682({"rc":0,"rt":6,"svr":181669432,"lt":1,"full":1,"dlmkts":"","data":{"total":86,"diff":[{"f1":2,"f2":35063.87,"f3":1.9,"f12":"BK0459","f13":90,"f14":"电子元件","f62":2672467968.0,"f66":2872657664.0,"f69":2.02,"f72":-200189696.0,"f75":-0.14,"f78":-1933110528.0,"f81":-1.36,"f84":-768382720.0,"f87":-0.54,"f124":1761550782,"f184":1.88,"f204":"胜宏科技","f205":"300476","f206":0},{"f1":2,"f2":18444.16,"f3":1.56,"f12":"BK0448","f13":90,"f14":"通信设备","f62":2602910976.0,"f66":3761489408.0,"f69":2.88,"f72":-1158578432.0,"f75":-0.89,"f78":-1900750336.0,"f81":-1.45,"f84":-711574272.0,"f87":-0.54,"f124":1761550782,"f184":1.99,"f204":"恒宝股份","f205":"002104","f206":0},{"f1":2,"f2":1987.6,"f3":1.54,"f12":"BK1037","f13":90,"f14":"消费电子","f62":2465783296.0,"f66":1903972096.0,"f69":1.94,"f72":561811200.0,"f75":0.57,"f78":-1500482304.0,"f81":-1.53,"f84":-998753792.0,"f87":-1.02,"f124":1761550782,"f184":2.52,"f204":"工业富联","f205":"601138","f206":1},{"f1":2,"f2":3109.46,"f3":2.39,"f12":"BK1027","f13":90,"f14":"小金属","f62":2056189696.0,"f66":2037394432.0,"f69":4.12,"f72":18795264.0,"f75":0.04,"f78":-770170368.0,"f81":-1.56,"f84":-1281168128.0,"f87":-2.59,"f124":1761550782,"f184":4.16,"f204":"厦门钨业","f205":"600549","f206":1},{"f1":2,"f2":2027.18,"f3":2.13,"f12":"BK1036","f13":90,"f14":"半导体","f62":1888555776.0,"f66":2031833856.0,"f69":0.83,"f72":-143278080.0,"f75":-0.06,"f78":-980680704.0,"f81":-0.4,"f84":-914381824.0,"f87":-0.38,"f124":1761550782,"f184":0.78,"f204":"兆易创新","f205":"603986","f206":1},{"f1":2,"f2":38790.99,"f3":0.83,"f12":"BK0465","f13":90,"f14":"化学制药","f62":1096540608.0,"f66":999148992.0,"f69":2.37,"f72":97391616.0,"f75":0.23,"f78":-981284352.0,"f81":-2.33,"f84":-143647744.0,"f87":-0.34,"f124":1761550782,"f184":2.6,"f204":"向日葵","f205":"300111","f206":0},{"f1":2,"f2":18347.21,"f3":1.36,"f12":"BK0428","f13":90,"f14":"电力行业","f62":1026856192.0,"f66":849184512.0,"f69":2.29,"f72":177671680.0,"f75":0.48,"f78":-1001579264.0,"f81":-2.7,"f84":-25276928.0,"f87":-0.07,"f124":1761550782,"f184":2.77,"f204":"中国核电","f205":"601985","f206":1},{"f1":2,"f2":9024.85,"f3":1.92,"f12":"BK0479","f13":90,"f14":"钢铁行业","f62":997690944.0,"f66":1004895040.0,"f69":5.74,"f72":-7204096.0,"f75":-0.04,"f78":-414445312.0,"f81":-2.37,"f84":-578170880.0,"f87":-3.3,"f124":1761550782,"f184":5.7,"f204":"新兴铸管","f205":"000778","f206":0},{"f1":2,"f2":33663.93,"f3":0.89,"f12":"BK0457","f13":90,"f14":"电网设备","f62":697530368.0,"f66":628551680.0,"f69":1.33,"f72":68978688.0,"f75":0.15,"f78":-144529664.0,"f81":-0.3,"f84":-603417088.0,"f87":-1.27,"f124":1761550782,"f184":1.47,"f204":"中国西电","f205":"601179","f206":1},{"f1":2,"f2":3500.02,"f3":0.93,"f12":"BK0910","f13":90,"f14":"专用设备","f62":645576192.0,"f66":684069888.0,"f69":0.86,"f72":-38493696.0,"f75":-0.05,"f78":-413467904.0,"f81":-0.52,"f84":-281076992.0,"f87":-0.35,"f124":1761550782,"f184":0.81,"f204":"英维克","f205":"002837","f206":0},{"f1":2,"f2":28302.66,"f3":0.58,"f12":"BK0425","f13":90,"f14":"工程建设","f62":616678496.0,"f66":506754144.0,"f69":1.8,"f72":109924352.0,"f75":0.39,"f78":-155837440.0,"f81":-0.55,"f84":-463444992.0,"f87":-1.65,"f124":1761550782,"f184":2.19,"f204":"中国电建","f205":"601669","f206":1},{"f1":2,"f2":1506.72,"f3":0.82,"f12":"BK0474","f13":90,"f14":"保险","f62":502335744.0,"f66":388679376.0,"f69":4.07,"f72":113656368.0,"f75":1.19,"f78":-406719424.0,"f81":-4.26,"f84":-95616336.0,"f87":-1.0,"f124":1761550782,"f184":5.26,"f204":"中国平安","f205":"601318","f206":1},{"f1":2,"f2":11688.06,"f3":1.27,"f12":"BK0484","f13":90,"f14":"贸易行业","f62":371387936.0,"f66":285093952.0,"f69":2.37,"f72":86293984.0,"f75":0.72,"f78":-119965136.0,"f81":-1.0,"f84":-258886784.0,"f87":-2.15,"f124":1761550782,"f184":3.09,"f204":"中电港","f205":"001287","f206":0},{"f1":2,"f2":47942.24,"f3":1.41,"f12":"BK0546","f13":90,"f14":"玻璃玻纤","f62":293145184.0,"f66":168121440.0,"f69":2.3,"f72":125023744.0,"f75":1.71,"f78":-94673024.0,"f81":-1.3,"f84":-179209440.0,"f87":-2.45,"f124":1761550782,"f184":4.01,"f204":"中材科技","f205":"002080","f206":0},{"f1":2,"f2":12567.57,"f3":0.51,"f12":"BK0539","f13":90,"f14":"综合行业","f62":284617232.0,"f66":321809104.0,"f69":4.11,"f72":-37191872.0,"f75":-0.47,"f78":-37467264.0,"f81":-0.48,"f84":-247149968.0,"f87":-3.15,"f124":1761550782,"f184":3.63,"f204":"东阳光","f205":"600673","f206":1},{"f1":2,"f2":12772.6,"f3":0.9,"f12":"BK0437","f13":90,"f14":"煤炭行业","f62":272668576.0,"f66":288082608.0,"f69":1.53,"f72":-15414032.0,"f75":-0.08,"f78":-38485504.0,"f81":-0.2,"f84":-234182912.0,"f87":-1.24,"f124":1761550782,"f184":1.45,"f204":"郑州煤电","f205":"600121","f206":1},{"f1":2,"f2":1087.13,"f3":2.34,"f12":"BK0729","f13":90,"f14":"船舶制造","f62":257691648.0,"f66":266805024.0,"f69":3.05,"f72":-9113376.0,"f75":-0.1,"f78":-195964128.0,"f81":-2.24,"f84":-61727520.0,"f87":-0.71,"f124":1761550782,"f184":2.94,"f204":"中国船舶","f205":"600150","f206":1},{"f1":2,"f2":2196.43,"f3":3.08,"f12":"BK1039","f13":90,"f14":"电子化学品","f62":255217344.0,"f66":585644992.0,"f69":2.33,"f72":-330427648.0,"f75":-1.31,"f78":-314919168.0,"f81":-1.25,"f84":59701760.0,"f87":0.24,"f124":1761550782,"f184":1.01,"f204":"晶瑞电材","f205":"300655","f206":0},{"f1":2,"f2":1350.48,"f3":0.97,"f12":"BK0730","f13":90,"f14":"农药兽药","f62":206520080.0,"f66":77003872.0,"f69":0.83,"f72":129516208.0,"f75":1.4,"f78":39617120.0,"f81":0.43,"f84":-255658768.0,"f87":-2.76,"f124":1761550782,"f184":2.23,"f204":"联化科技","f205":"002250","f206":0},{"f1":2,"f2":23845.93,"f3":1.06,"f12":"BK0478","f13":90,"f14":"有色金属","f62":189264384.0,"f66":111172096.0,"f69":0.18,"f72":78092288.0,"f75":0.12,"f78":443991040.0,"f81":0.71,"f84":-640158976.0,"f87":-1.02,"f124":1761550782,"f184":0.3,"f204":"紫金矿业","f205":"601899","f206":1},{"f1":2,"f2":15733.54,"f3":0.79,"f12":"BK0433","f13":90,"f14":"农牧饲渔","f62":184318400.0,"f66":299851392.0,"f69":1.79,"f72":-115532992.0,"f75":-0.69,"f78":-91829760.0,"f81":-0.55,"f84":-106748672.0,"f87":-0.64,"f124":1761550782,"f184":1.1,"f204":"牧原股份","f205":"002714","f206":0},{"f1":2,"f2":30942.83,"f3":1.0,"f12":"BK0538","f13":90,"f14":"化学制品","f62":141105904.0,"f66":212118000.0,"f69":0.49,"f72":-71012096.0,"f75":-0.16,"f78":-174632704.0,"f81":-0.4,"f84":47441152.0,"f87":0.11,"f124":1761550782,"f184":0.33,"f204":"天赐材料","f205":"002709","f206":0},{"f1":2,"f2":7908.95,"f3":0.17,"f12":"BK0422","f13":90,"f14":"物流行业","f62":134002704.0,"f66":189793424.0,"f69":1.76,"f72":-55790720.0,"f75":-0.52,"f78":-103790080.0,"f81":-0.96,"f84":-33989888.0,"f87":-0.32,"f124":1761550782,"f184":1.25,"f204":"胜通能源","f205":"001331","f206":0},{"f1":2,"f2":30530.19,"f3":0.97,"f12":"BK0424","f13":90,"f14":"水泥建材","f62":106185504.0,"f66":49538272.0,"f69":0.8,"f72":56647232.0,"f75":0.92,"f78":-48269104.0,"f81":-0.78,"f84":-57916384.0,"f87":-0.94,"f124":1761550782,"f184":1.72,"f204":"海螺水泥","f205":"600585","f206":1},{"f1":2,"f2":1285.58,"f3":1.16,"f12":"BK0727","f13":90,"f14":"医疗服务","f62":93195760.0,"f66":327629040.0,"f69":1.67,"f72":-234433280.0,"f75":-1.19,"f78":-302119168.0,"f81":-1.54,"f84":208923648.0,"f87":1.06,"f124":1761550782,"f184":0.47,"f204":"药明康德","f205":"603259","f206":1},{"f1":2,"f2":1024.65,"f3":0.29,"f12":"BK1041","f13":90,"f14":"医疗器械","f62":88950640.0,"f66":-221685904.0,"f69":-0.99,"f72":310636544.0,"f75":1.38,"f78":17168640.0,"f81":0.08,"f84":-114670592.0,"f87":-0.51,"f124":1761550782,"f184":0.4,"f204":"福瑞股份","f205":"300049","f206":0},{"f1":2,"f2":1128.72,"f3":1.07,"f12":"BK1016","f13":90,"f14":"汽车服务","f62":70827612.0,"f66":125612796.0,"f69":7.37,"f72":-54785184.0,"f75":-3.21,"f78":-76294032.0,"f81":-4.48,"f84":521312.0,"f87":0.03,"f124":1761550782,"f184":4.16,"f204":"漳州发展","f205":"000753","f206":0},{"f1":2,"f2":15298.11,"f3":-0.17,"f12":"BK0451","f13":90,"f14":"房地产开发","f62":58891120.0,"f66":-36992144.0,"f69":-0.16,"f72":95883264.0,"f75":0.41,"f78":-26480384.0,"f81":-0.11,"f84":-32410624.0,"f87":-0.14,"f124":1761550782,"f184":0.25,"f204":"首开股份","f205":"600376","f206":1},{"f1":2,"f2":1635.22,"f3":1.39,"f12":"BK0731","f13":90,"f14":"化肥行业","f62":46755136.0,"f66":212334640.0,"f69":2.25,"f72":-165579504.0,"f75":-1.76,"f78":-253192816.0,"f81":-2.68,"f84":206437680.0,"f87":2.19,"f124":1761550782,"f184":0.5,"f204":"盐湖股份","f205":"000792","f206":0},{"f1":2,"f2":483.59,"f3":0.49,"f12":"BK0734","f13":90,"f14":"珠宝首饰","f62":45087792.0,"f66":-30447968.0,"f69":-1.42,"f72":75535760.0,"f75":3.51,"f78":-2759760.0,"f81":-0.13,"f84":-42328016.0,"f87":-1.97,"f124":1761550782,"f184":2.1,"f204":"潮宏基","f205":"002345","f206":0},{"f1":2,"f2":693.44,"f3":0.33,"f12":"BK0738","f13":90,"f14":"多元金融","f62":41639344.0,"f66":86104544.0,"f69":1.26,"f72":-44465200.0,"f75":-0.65,"f78":-74005520.0,"f81":-1.08,"f84":32366160.0,"f87":0.47,"f124":1761550782,"f184":0.61,"f204":"中油资本","f205":"000617","f206":0},{"f1":2,"f2":715.47,"f3":2.17,"f12":"BK1015","f13":90,"f14":"能源金属","f62":36233600.0,"f66":38813056.0,"f69":0.16,"f72":-2579456.0,"f75":-0.01,"f78":-186657280.0,"f81":-0.78,"f84":150423552.0,"f87":0.63,"f124":1761550782,"f184":0.15,"f204":"华友钴业","f205":"603799","f206":1},{"f1":2,"f2":1759.77,"f3":0.83,"f12":"BK1020","f13":90,"f14":"非金属材料","f62":36116352.0,"f66":161883776.0,"f69":0.8,"f72":-125767424.0,"f75":-0.62,"f78":-47178752.0,"f81":-0.23,"f84":-10470656.0,"f87":-0.05,"f124":1761550782,"f184":0.18,"f204":"菲利华","f205":"300395","f206":0},{"f1":2,"f2":2632.39,"f3":0.34,"f12":"BK1030","f13":90,"f14":"电机","f62":27743792.0,"f66":182289472.0,"f69":1.05,"f72":-154545680.0,"f75":-0.89,"f78":-322093056.0,"f81":-1.85,"f84":232761344.0,"f87":1.34,"f124":1761550782,"f184":0.16,"f204":"卧龙电驱","f205":"600580","f206":1},{"f1":2,"f2":692.49,"f3":0.59,"f12":"BK0740","f13":90,"f14":"教育","f62":20278388.0,"f66":7033636.0,"f69":0.31,"f72":13244752.0,"f75":0.59,"f78":-39129168.0,"f81":-1.73,"f84":18850768.0,"f87":0.83,"f124":1761550782,"f184":0.9,"f204":"中公教育","f205":"002607","f206":0},{"f1":2,"f2":19150.0,"f3":0.8,"f12":"BK0454","f13":90,"f14":"塑料制品","f62":16523088.0,"f66":-34563472.0,"f69":-0.21,"f72":51086560.0,"f75":0.31,"f78":165054208.0,"f81":1.01,"f84":-211354368.0,"f87":-1.29,"f124":1761550782,"f184":0.1,"f204":"佛塑科技","f205":"000973","f206":0},{"f1":2,"f2":1235.4,"f3":0.4,"f12":"BK0733","f13":90,"f14":"包装材料","f62":12034057.0,"f66":471449.0,"f69":0.01,"f72":11562608.0,"f75":0.31,"f78":-27117552.0,"f81":-0.73,"f84":12477664.0,"f87":0.34,"f124":1761550782,"f184":0.33,"f204":"王子新材","f205":"002735","f206":0},{"f1":2,"f2":18673.43,"f3":0.44,"f12":"BK0482","f13":90,"f14":"商业百货","f62":-32728768.0,"f66":73189520.0,"f69":0.65,"f72":-105918288.0,"f75":-0.94,"f78":9347488.0,"f81":0.08,"f84":23381264.0,"f87":0.21,"f124":1761550782,"f184":-0.29,"f204":"国光连锁","f205":"605188","f206":1},{"f1":2,"f2":1128.47,"f3":0.61,"f12":"BK1042","f13":90,"f14":"医药商业","f62":-43339544.0,"f66":-4437864.0,"f69":-0.11,"f72":-38901680.0,"f75":-0.94,"f78":-34779520.0,"f81":-0.84,"f84":78119072.0,"f87":1.89,"f124":1761550782,"f184":-1.05,"f204":"九州通","f205":"600998","f206":1},{"f1":2,"f2":10602.94,"f3":0.23,"f12":"BK0470","f13":90,"f14":"造纸印刷","f62":-46049120.0,"f66":-80690000.0,"f69":-1.38,"f72":34640880.0,"f75":0.59,"f78":79723008.0,"f81":1.37,"f84":-42126336.0,"f87":-0.72,"f124":1761550782,"f184":-0.79,"f204":"中顺洁柔","f205":"002511","f206":0},{"f1":2,"f2":533.72,"f3":0.2,"f12":"BK0728","f13":90,"f14":"环保行业","f62":-67351584.0,"f66":-20234016.0,"f69":-0.1,"f72":-47117568.0,"f75":-0.24,"f78":142688768.0,"f81":0.72,"f84":-81471232.0,"f87":-0.41,"f124":1761550782,"f184":-0.34,"f204":"高能环境","f205":"603588","f206":1},{"f1":2,"f2":6204.4,"f3":0.2,"f12":"BK0464","f13":90,"f14":"石油行业","f62":-99684464.0,"f66":242880368.0,"f69":3.3,"f72":-342564832.0,"f75":-4.66,"f78":70292512.0,"f81":0.96,"f84":29391968.0,"f87":0.4,"f124":1761550782,"f184":-1.36,"f204":"中国石化","f205":"600028","f206":1},{"f1":2,"f2":1715.29,"f3":0.88,"f12":"BK1018","f13":90,"f14":"橡胶制品","f62":-117049728.0,"f66":-45177648.0,"f69":-0.6,"f72":-71872080.0,"f75":-0.95,"f78":-7609728.0,"f81":-0.1,"f84":86729680.0,"f87":1.15,"f124":1761550782,"f184":-1.55,"f204":"科创新源","f205":"300731","f206":0},{"f1":2,"f2":1223.82,"f3":-0.47,"f12":"BK1045","f13":90,"f14":"房地产服务","f62":-122941878.0,"f66":-69009094.0,"f69":-2.69,"f72":-53932784.0,"f75":-2.11,"f78":-15535600.0,"f81":-0.61,"f84":138477472.0,"f87":5.41,"f124":1761550782,"f184":-4.8,"f204":"我爱我家","f205":"000560","f206":0},{"f1":2,"f2":9115.6,"f3":0.01,"f12":"BK0427","f13":90,"f14":"公用事业","f62":-132585264.0,"f66":-82856112.0,"f69":-2.36,"f72":-49729152.0,"f75":-1.42,"f78":29656448.0,"f81":0.84,"f84":102928816.0,"f87":2.93,"f124":1761550782,"f184":-3.77,"f204":"海天股份","f205":"603759","f206":1},{"f1":2,"f2":16999.7,"f3":0.11,"f12":"BK0471","f13":90,"f14":"化纤行业","f62":-132674803.0,"f66":-112412947.0,"f69":-2.54,"f72":-20261856.0,"f75":-0.46,"f78":34904880.0,"f81":0.79,"f84":92093280.0,"f87":2.08,"f124":1761550782,"f184":-3.0,"f204":"恒力石化","f205":"600346","f206":1},{"f1":2,"f2":754.04,"f3":0.58,"f12":"BK0725","f13":90,"f14":"装修装饰","f62":-159712048.0,"f66":-180949088.0,"f69":-2.99,"f72":21237040.0,"f75":0.35,"f78":144701184.0,"f81":2.39,"f84":55498560.0,"f87":0.92,"f124":1761550782,"f184":-2.64,"f204":"中铁装配","f205":"300374","f206":0},{"f1":2,"f2":1880.31,"f3":0.25,"f12":"BK1028","f13":90,"f14":"燃气","f62":-159932336.0,"f66":-177324928.0,"f69":-2.51,"f72":17392592.0,"f75":0.25,"f78":-31213168.0,"f81":-0.44,"f84":182760304.0,"f87":2.59,"f124":1761550782,"f184":-2.27,"f204":"首华燃气","f205":"300483","f206":0},{"f1":2,"f2":11887.93,"f3":1.16,"f12":"BK0429","f13":90,"f14":"交运设备","f62":-173683152.0,"f66":-154603040.0,"f69":-1.47,"f72":-19080112.0,"f75":-0.18,"f78":75509008.0,"f81":0.72,"f84":94802976.0,"f87":0.9,"f124":1761550782,"f184":-1.65,"f204":"宗申动力","f205":"001696","f206":0},{"f1":2,"f2":5272.9,"f3":0.05,"f12":"BK0420","f13":90,"f14":"航空机场","f62":-186943328.0,"f66":-205286688.0,"f69":-4.64,"f72":18343360.0,"f75":0.41,"f78":56613232.0,"f81":1.28,"f84":130330080.0,"f87":2.94,"f124":1761550782,"f184":-4.22,"f204":"南方航空","f205":"600029","f206":1}]}});"#;
+    async fn set_walk_forward_report(
+        &self,
+        id: i64,
+        report: &WalkForwardReport,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query("UPDATE optimisations SET report = ? WHERE id = ?")
+            .bind(serde_json::to_string(report)?)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
 
-        let raw_moneyflow: RawMoneyflowEastmoney =
-            parse_raw_eastmoney(RAW_MONEYFLOW_SECTOR_EASTMONEY).unwrap();
-        let moneyflow = create_moneyflow(raw_moneyflow);
+        Ok(())
+    }
 
-        let total = 40;
-        let mut moneyflow_batch: Vec<Vec<MoneyflowEastmoney>> = vec![];
-        for day in 1..total {
-            let mut mf_copy = moneyflow.clone();
-            for x in mf_copy.iter_mut() {
-                let mut new_dt = NaiveDate::parse_from_str(&x.date_time, "%Y-%m-%d").unwrap();
-                new_dt += Duration::days(day);
-                x.date_time = new_dt.to_string();
-            }
-            moneyflow_batch.push(mf_copy);
-        }
+    async fn get_walk_forward_report(
+        &self,
+        id: i64,
+    ) -> Result<Option<WalkForwardReport>, anyhow::Error> {
+        let report: Option<Option<String>> =
+            sqlx::query_scalar("SELECT report FROM optimisations WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
 
-        for mf in moneyflow_batch {
-            repo.create_mf_sector(&mf).await.unwrap();
+        match report.flatten() {
+            Some(report) => Ok(Some(serde_json::from_str(&report)?)),
+            None => Ok(None),
         }
+    }
+
+    async fn create_watchlist(
+        &self,
+        name: &str,
+        notes: Option<&str>,
+    ) -> Result<i64, anyhow::Error> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO watchlists (name, notes) VALUES (?, ?) RETURNING id",
+        )
+        .bind(name)
+        .bind(notes)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_watchlists(&self) -> Result<Vec<Watchlist>, anyhow::Error> {
+        let mut watchlists =
+            sqlx::query_as::<_, Watchlist>("SELECT * FROM watchlists ORDER BY name")
+                .fetch_all(&self.pool)
+                .await?;
+
+        for watchlist in &mut watchlists {
+            watchlist.items = self.get_watchlist_items(watchlist.id).await?;
+        }
+
+        Ok(watchlists)
+    }
+
+    async fn get_watchlist(&self, id: i64) -> Result<Option<Watchlist>, anyhow::Error> {
+        let watchlist = sqlx::query_as::<_, Watchlist>("SELECT * FROM watchlists WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(mut watchlist) = watchlist else {
+            return Ok(None);
+        };
+        watchlist.items = self.get_watchlist_items(id).await?;
+
+        Ok(Some(watchlist))
+    }
+
+    async fn update_watchlist(
+        &self,
+        id: i64,
+        name: Option<&str>,
+        notes: Option<&str>,
+    ) -> Result<bool, anyhow::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE watchlists SET
+                name = COALESCE(?, name),
+                notes = COALESCE(?, notes)
+            WHERE id = ?
+        "#,
+        )
+        .bind(name)
+        .bind(notes)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_watchlist(&self, id: i64) -> Result<bool, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM watchlist_items WHERE watchlist_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM watchlists WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn add_watchlist_tickers(
+        &self,
+        id: i64,
+        tickers: &[Ticker],
+    ) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for ticker in tickers {
+            sqlx::query(
+                r#"
+                INSERT INTO watchlist_items (watchlist_id, ticker, position)
+                SELECT ?, ?, COALESCE(MAX(position) + 1, 0)
+                FROM watchlist_items WHERE watchlist_id = ?
+                ON CONFLICT (watchlist_id, ticker) DO NOTHING
+            "#,
+            )
+            .bind(id)
+            .bind(ticker)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn remove_watchlist_ticker(
+        &self,
+        id: i64,
+        ticker: &Ticker,
+    ) -> Result<bool, anyhow::Error> {
+        let result =
+            sqlx::query("DELETE FROM watchlist_items WHERE watchlist_id = ? AND ticker = ?")
+                .bind(id)
+                .bind(ticker)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_watchlist_item_notes(
+        &self,
+        id: i64,
+        ticker: &Ticker,
+        notes: Option<&str>,
+    ) -> Result<bool, anyhow::Error> {
+        let result = sqlx::query(
+            "UPDATE watchlist_items SET notes = ? WHERE watchlist_id = ? AND ticker = ?",
+        )
+        .bind(notes)
+        .bind(id)
+        .bind(ticker)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn reorder_watchlist(&self, id: i64, tickers: &[Ticker]) -> Result<(), anyhow::Error> {
+        let current = self.get_watchlist_tickers(id).await?;
+        let order = tickers
+            .iter()
+            .filter(|t| current.contains(t))
+            .chain(current.iter().filter(|t| !tickers.contains(t)));
+
+        let mut tx = self.pool.begin().await?;
+
+        for (position, ticker) in order.enumerate() {
+            sqlx::query(
+                "UPDATE watchlist_items SET position = ? WHERE watchlist_id = ? AND ticker = ?",
+            )
+            .bind(position as i64)
+            .bind(id)
+            .bind(ticker)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_watchlist_tickers(&self, id: i64) -> Result<Vec<Ticker>, anyhow::Error> {
+        let tickers = sqlx::query_scalar::<_, String>(
+            "SELECT ticker FROM watchlist_items WHERE watchlist_id = ? ORDER BY position",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(parse_stored(tickers))
+    }
+
+    async fn create_transaction(&self, tx: &Transaction) -> Result<i64, anyhow::Error> {
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO transactions
+                (account, trade_date, kind, ticker, quantity, price, amount, fee, currency, notes)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+        "#,
+        )
+        .bind(&tx.account)
+        .bind(tx.trade_date)
+        .bind(tx.kind)
+        .bind(&tx.ticker)
+        .bind(tx.quantity)
+        .bind(tx.price)
+        .bind(tx.amount)
+        .bind(tx.fee)
+        .bind(tx.currency)
+        .bind(&tx.notes)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_transaction(&self, id: i64) -> Result<Option<Transaction>, anyhow::Error> {
+        let tx = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(tx)
+    }
+
+    async fn get_transactions(&self, account: &str) -> Result<Vec<Transaction>, anyhow::Error> {
+        let txs = sqlx::query_as::<_, Transaction>(
+            "SELECT * FROM transactions WHERE account = ? ORDER BY trade_date, id",
+        )
+        .bind(account)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(txs)
+    }
+
+    async fn delete_transaction(&self, id: i64) -> Result<bool, anyhow::Error> {
+        let result = sqlx::query("DELETE FROM transactions WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_accounts(&self) -> Result<Vec<String>, anyhow::Error> {
+        let accounts = sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT account FROM transactions ORDER BY account",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accounts)
+    }
+
+    async fn get_fx_rates(&self) -> Result<Vec<FxRate>, anyhow::Error> {
+        let rates = sqlx::query_as::<_, FxRate>("SELECT * FROM fx_rates ORDER BY currency")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rates)
+    }
+
+    async fn set_fx_rate(&self, currency: Currency, cny_rate: f64) -> Result<(), anyhow::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO fx_rates (currency, cny_rate)
+            VALUES (?, ?)
+            ON CONFLICT (currency) DO UPDATE SET
+                cny_rate = excluded.cny_rate,
+                updated_at = CURRENT_TIMESTAMP
+        "#,
+        )
+        .bind(currency)
+        .bind(cny_rate)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_risk_report(
+        &self,
+        account: &str,
+        base: Currency,
+        report: &RiskReport,
+    ) -> Result<i64, anyhow::Error> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO risk_reports (account, base_currency, report) VALUES (?, ?, ?) RETURNING id",
+        )
+        .bind(account)
+        .bind(base)
+        .bind(serde_json::to_string(report)?)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_risk_report(&self, account: &str) -> Result<Option<RiskRun>, anyhow::Error> {
+        let run = sqlx::query_as::<_, RiskRun>(
+            "SELECT * FROM risk_reports WHERE account = ? ORDER BY id DESC LIMIT 1",
+        )
+        .bind(account)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(run)
+    }
+
+    async fn get_tracked_sectors(&self) -> Result<Vec<TrackedSector>, anyhow::Error> {
+        let sectors =
+            sqlx::query_as::<_, TrackedSector>("SELECT * FROM tracked_sectors ORDER BY position")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(sectors)
+    }
+
+    async fn add_tracked_sector(
+        &self,
+        ticker: &Ticker,
+        realname: &str,
+    ) -> Result<bool, anyhow::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO tracked_sectors (ticker, realname, position)
+            VALUES (?, ?, (SELECT COALESCE(MAX(position) + 1, 0) FROM tracked_sectors))
+        "#,
+        )
+        .bind(ticker)
+        .bind(realname)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_tracked_sector(&self, ticker: &Ticker) -> Result<bool, anyhow::Error> {
+        let result = sqlx::query("DELETE FROM tracked_sectors WHERE ticker = ?")
+            .bind(ticker)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_sector_members(
+        &self,
+        sector: &Ticker,
+        members: &[SectorMember],
+    ) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM sector_members WHERE sector = ?")
+            .bind(sector)
+            .execute(&mut *tx)
+            .await?;
+        for member in members {
+            sqlx::query(
+                "INSERT OR IGNORE INTO sector_members (sector, ticker, realname) VALUES (?, ?, ?)",
+            )
+            .bind(sector)
+            .bind(&member.ticker)
+            .bind(&member.realname)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_sector_members(
+        &self,
+        sector: &Ticker,
+    ) -> Result<Vec<SectorMember>, anyhow::Error> {
+        let members = sqlx::query_as::<_, SectorMember>(
+            "SELECT * FROM sector_members WHERE sector = ? ORDER BY ticker",
+        )
+        .bind(sector)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    async fn get_stock_sectors(&self, ticker: &Ticker) -> Result<Vec<SectorMember>, anyhow::Error> {
+        let members = sqlx::query_as::<_, SectorMember>(
+            "SELECT * FROM sector_members WHERE ticker = ? ORDER BY sector",
+        )
+        .bind(ticker)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    async fn get_sector_membership(&self) -> Result<HashMap<String, Vec<String>>, anyhow::Error> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT ticker, sector FROM sector_members ORDER BY ticker, sector",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut membership: HashMap<String, Vec<String>> = HashMap::new();
+        for (ticker, sector) in rows {
+            membership.entry(ticker).or_default().push(sector);
+        }
+
+        Ok(membership)
+    }
+
+    async fn create_mf_sector(&self, flows: &[MoneyflowEastmoney]) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for flow in flows.iter() {
+            sqlx::query(
+                r#"
+                INSERT INTO moneyflow_sector (trade_date, ticker, realname, lead_value, lead_share, super_value, super_share, large_value, large_share, mid_value, mid_share, small_value, small_share)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (trade_date, ticker) DO UPDATE SET
+                    realname = excluded.realname,
+                    lead_value = excluded.lead_value,
+                    lead_share = excluded.lead_share,
+                    super_value = excluded.super_value,
+                    super_share = excluded.super_share,
+                    large_value = excluded.large_value,
+                    large_share = excluded.large_share,
+                    mid_value = excluded.mid_value,
+                    mid_share = excluded.mid_share,
+                    small_value = excluded.small_value,
+                    small_share = excluded.small_share
+            "#,
+            )
+            .bind(&flow.trade_date)
+            .bind(&flow.ticker)
+            .bind(&flow.realname)
+            .bind(flow.lead_value)
+            .bind(flow.lead_share)
+            .bind(flow.super_value)
+            .bind(flow.super_share)
+            .bind(flow.large_value)
+            .bind(flow.large_share)
+            .bind(flow.mid_value)
+            .bind(flow.mid_share)
+            .bind(flow.small_value)
+            .bind(flow.small_share)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_mf_sector(&self) -> Result<Vec<MoneyflowEastmoney>, anyhow::Error> {
+        let flows = sqlx::query_as::<_, MoneyflowEastmoney>(
+            "SELECT * FROM moneyflow_sector ORDER BY trade_date, ticker",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flows)
+    }
+
+    async fn delete_mf_sector(&self) -> Result<(), anyhow::Error> {
+        sqlx::query!("DELETE FROM moneyflow_sector")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_mf_sector_before(&self, trade_date: &str) -> Result<u64, anyhow::Error> {
+        let result = sqlx::query("DELETE FROM moneyflow_sector WHERE trade_date < ?")
+            .bind(trade_date)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn create_mf_stock(&self, flows: &[MoneyflowStock]) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for flow in flows.iter() {
+            sqlx::query(
+                r#"
+                INSERT INTO moneyflow_stock (trade_date, ticker, lead_value, lead_share, super_value, super_share, large_value, large_share, mid_value, mid_share, small_value, small_share, close, change_pct)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (trade_date, ticker) DO UPDATE SET
+                    lead_value = excluded.lead_value,
+                    lead_share = excluded.lead_share,
+                    super_value = excluded.super_value,
+                    super_share = excluded.super_share,
+                    large_value = excluded.large_value,
+                    large_share = excluded.large_share,
+                    mid_value = excluded.mid_value,
+                    mid_share = excluded.mid_share,
+                    small_value = excluded.small_value,
+                    small_share = excluded.small_share,
+                    close = excluded.close,
+                    change_pct = excluded.change_pct
+            "#,
+            )
+            .bind(&flow.trade_date)
+            .bind(&flow.ticker)
+            .bind(flow.lead_value)
+            .bind(flow.lead_share)
+            .bind(flow.super_value)
+            .bind(flow.super_share)
+            .bind(flow.large_value)
+            .bind(flow.large_share)
+            .bind(flow.mid_value)
+            .bind(flow.mid_share)
+            .bind(flow.small_value)
+            .bind(flow.small_share)
+            .bind(flow.close)
+            .bind(flow.change_pct)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_mf_stock(
+        &self,
+        ticker: &Ticker,
+        start: Option<&str>,
+    ) -> Result<Vec<MoneyflowStock>, anyhow::Error> {
+        let flows = sqlx::query_as::<_, MoneyflowStock>(
+            r#"
+            SELECT * FROM moneyflow_stock
+            WHERE ticker = ? AND (? IS NULL OR trade_date >= ?)
+            ORDER BY trade_date
+        "#,
+        )
+        .bind(ticker)
+        .bind(start)
+        .bind(start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flows)
+    }
+
+    async fn delete_mf_stock_before(&self, trade_date: &str) -> Result<u64, anyhow::Error> {
+        let result = sqlx::query("DELETE FROM moneyflow_stock WHERE trade_date < ?")
+            .bind(trade_date)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn create_strength_signals(
+        &self,
+        signals: &[StrengthSignal],
+    ) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for s in signals.iter() {
+            sqlx::query(
+                r#"
+                INSERT INTO strength_signals (ticker, bar_date, benchmark, sector, ret_1w, ret_1m, ret_3m, ret_6m, rs_1w, rs_1m, rs_3m, rs_6m, sector_rs_1w, sector_rs_1m, sector_rs_3m, sector_rs_6m, score, rating)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (ticker, bar_date) DO UPDATE SET
+                    benchmark = excluded.benchmark,
+                    sector = excluded.sector,
+                    ret_1w = excluded.ret_1w,
+                    ret_1m = excluded.ret_1m,
+                    ret_3m = excluded.ret_3m,
+                    ret_6m = excluded.ret_6m,
+                    rs_1w = excluded.rs_1w,
+                    rs_1m = excluded.rs_1m,
+                    rs_3m = excluded.rs_3m,
+                    rs_6m = excluded.rs_6m,
+                    sector_rs_1w = excluded.sector_rs_1w,
+                    sector_rs_1m = excluded.sector_rs_1m,
+                    sector_rs_3m = excluded.sector_rs_3m,
+                    sector_rs_6m = excluded.sector_rs_6m,
+                    score = excluded.score,
+                    rating = excluded.rating
+            "#,
+            )
+            .bind(&s.ticker)
+            .bind(s.bar_date)
+            .bind(&s.benchmark)
+            .bind(&s.sector)
+            .bind(s.ret_1w)
+            .bind(s.ret_1m)
+            .bind(s.ret_3m)
+            .bind(s.ret_6m)
+            .bind(s.rs_1w)
+            .bind(s.rs_1m)
+            .bind(s.rs_3m)
+            .bind(s.rs_6m)
+            .bind(s.sector_rs_1w)
+            .bind(s.sector_rs_1m)
+            .bind(s.sector_rs_3m)
+            .bind(s.sector_rs_6m)
+            .bind(s.score)
+            .bind(s.rating)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_strength_latest(&self) -> Result<Vec<StrengthSignal>, anyhow::Error> {
+        let signals = sqlx::query_as::<_, StrengthSignal>(
+            r#"
+            SELECT s.*
+            FROM strength_signals s
+            JOIN (
+                SELECT ticker, MAX(bar_date) AS bar_date
+                FROM strength_signals
+                GROUP BY ticker
+            ) latest ON s.ticker = latest.ticker AND s.bar_date = latest.bar_date
+            ORDER BY s.rating IS NULL, s.rating DESC, s.ticker
+        "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(signals)
+    }
+
+    async fn get_strength_series(
+        &self,
+        ticker: &Ticker,
+    ) -> Result<Vec<StrengthSignal>, anyhow::Error> {
+        let signals = sqlx::query_as::<_, StrengthSignal>(
+            r#"
+            SELECT * FROM strength_signals
+            WHERE ticker = ?
+            ORDER BY bar_date
+        "#,
+        )
+        .bind(ticker)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(signals)
+    }
+
+    async fn create_quote_snapshots(&self, quotes: &[Quote]) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for q in quotes.iter() {
+            sqlx::query(
+                r#"
+                INSERT INTO quote_snapshots (ticker, quoted_at, realname, last, bid, ask, volume, change_pct)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (ticker, quoted_at) DO UPDATE SET
+                    realname = excluded.realname,
+                    last = excluded.last,
+                    bid = excluded.bid,
+                    ask = excluded.ask,
+                    volume = excluded.volume,
+                    change_pct = excluded.change_pct
+            "#,
+            )
+            .bind(&q.ticker)
+            .bind(q.quoted_at)
+            .bind(&q.realname)
+            .bind(q.last)
+            .bind(q.bid)
+            .bind(q.ask)
+            .bind(q.volume)
+            .bind(q.change_pct)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_quote_snapshots(
+        &self,
+        ticker: &Ticker,
+        since: Option<i64>,
+    ) -> Result<Vec<Quote>, anyhow::Error> {
+        let quotes = sqlx::query_as::<_, Quote>(
+            r#"
+            SELECT * FROM quote_snapshots
+            WHERE ticker = ? AND (? IS NULL OR quoted_at >= ?)
+            ORDER BY quoted_at
+        "#,
+        )
+        .bind(ticker)
+        .bind(since)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(quotes)
+    }
+
+    async fn delete_quote_snapshots_before(&self, quoted_at: i64) -> Result<u64, anyhow::Error> {
+        let result = sqlx::query("DELETE FROM quote_snapshots WHERE quoted_at < ?")
+            .bind(quoted_at)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn get_sector_tickers(&self) -> Result<Vec<Ticker>, anyhow::Error> {
+        let tickers =
+            sqlx::query_scalar::<_, String>("SELECT ticker FROM stocks WHERE ticker LIKE ?")
+                .bind(sector_pattern())
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(parse_stored(tickers))
+    }
+
+    async fn get_stock_tickers(&self) -> Result<Vec<Ticker>, anyhow::Error> {
+        let tickers =
+            sqlx::query_scalar::<_, String>("SELECT ticker FROM stocks WHERE ticker NOT LIKE ?")
+                .bind(sector_pattern())
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(parse_stored(tickers))
+    }
+}
+
+/// Matches the tickers of sector boards, e.g. `90.%`.
+fn sector_pattern() -> String {
+    format!("{SECTOR_MARKET}.%")
+}
+
+/// Tickers stored before validation that no longer parse are skipped.
+fn parse_stored(tickers: Vec<String>) -> Vec<Ticker> {
+    tickers.into_iter().filter_map(|t| t.parse().ok()).collect()
+}
+
+/// Monday and Sunday (yyyymmdd) of the ISO week of `date`.
+fn week_of(date: i64) -> Option<(i64, i64)> {
+    let monday = date_from_i64(date)?.week(Weekday::Mon).first_day();
+    let yyyymmdd = |d: NaiveDate| d.format("%Y%m%d").to_string().parse::<i64>().ok();
+
+    Some((yyyymmdd(monday)?, yyyymmdd(monday + Duration::days(6))?))
+}
+
+/// Klines of the global indexes and the US markets (100 to 110) live in `klines_us`.
+fn is_us(ticker: &Ticker) -> bool {
+    (100..=110).contains(&ticker.market().code)
+}
+
+// FIX: more test coverage no need network call.
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use crate::{
+        domain::{
+            backtest::{
+                BacktestConfig,
+                optimize::{Objective, ParamRange, ParamSpace, SearchMethod, walk_forward},
+                run_backtest,
+                strategy::StrategyConfig,
+            },
+            model::{
+                Currency, FundamentalsSnapshot, Kline, OptimisationMode, Quote, SectorMember,
+                Signal, SignalPeriod, Stock, Transaction, TransactionKind,
+            },
+            repository::DomainRepository,
+            service_market::{Isin, Ticker},
+            service_risk::{RiskParams, RiskPosition, compute_risk},
+            service_strength::StrengthSignal,
+        },
+        infra::{
+            data::{
+                moneyflow::{
+                    MoneyflowEastmoney, MoneyflowStock, RawMoneyflowEastmoney, create_moneyflow,
+                },
+                service::parse_raw_eastmoney,
+            },
+            storage::repo_domain_sqlite::SqliteDomainRepository,
+        },
+    };
+
+    async fn setup_test_db() -> Result<SqlitePool, sqlx::Error> {
+        // Using a unique database URL for each test run enhances isolation.
+        // In a real scenario, you might generate a unique name or use an in-memory database.
+        let database_url = "sqlite::memory:";
+        let pool = SqlitePoolOptions::new().connect(database_url).await?;
+        sqlx::migrate!("./migrations").run(&pool).await?;
+        Ok(pool)
+    }
+
+    fn ticker(s: &str) -> Ticker {
+        s.parse().unwrap()
+    }
+
+    pub fn generate_sequential_klines(count: usize, ticker: &str, start_date: i64) -> Vec<Kline> {
+        let mut klines = Vec::with_capacity(count);
+
+        for i in 0..count {
+            let base_price = 100.0 + (i as f64 * 0.1); // Slowly increasing base price
+            let open = base_price;
+            let close = open - (i as f64 * 0.2);
+
+            let high = open.max(close);
+            let low = open.min(close);
+
+            let volume = 100000.0 + (i as f64 * 0.1);
+            let value = volume * (open + close) / 2.0;
+
+            klines.push(Kline {
+                k_ticker: ticker.to_string(),
+                k_date: start_date + i as i64,
+                k_open: open,
+                k_high: high,
+                k_low: low,
+                k_close: close,
+                k_volume: volume,
+                k_value: value,
+            });
+        }
+
+        klines
+    }
+
+    #[tokio::test]
+    async fn test_create_klines() {
+        let pool = setup_test_db().await.unwrap();
+
+        let tickers = vec![ticker("105.AAPL"), ticker("105.GOOGL"), ticker("105.MSFT")];
+        let mut klines: Vec<Vec<Kline>> = vec![];
+        for ticker in &tickers {
+            let kline = generate_sequential_klines(8000, ticker, 20200101);
+            klines.push(kline);
+        }
+
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        for (idx, ticker) in tickers.iter().enumerate() {
+            repo.create_klines(ticker, &klines[idx]).await.unwrap();
+        }
+
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) from klines_us")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(count, 24000);
+
+        // Check for clearing function.
+        for (idx, ticker) in tickers.iter().enumerate() {
+            repo.create_klines(ticker, &klines[idx]).await.unwrap();
+        }
+
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) from klines_us")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(count, 24000);
+
+        let klines_aapl = repo.get_klines(&ticker("105.AAPL")).await.unwrap();
+
+        assert_eq!(klines_aapl.len(), 8000);
+        assert_eq!(klines_aapl[0].k_ticker, "105.AAPL");
+        assert_eq!(klines_aapl[1].k_ticker, "105.AAPL");
+        assert_eq!(klines_aapl[0].k_date, 20200101);
+        assert_eq!(klines_aapl[1].k_date, 20200102);
+    }
+
+    #[tokio::test]
+    async fn test_create_ml_sector() {
+        let pool = setup_test_db().await.unwrap();
+
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        const RAW_MONEYFLOW_SECTOR_EASTMONEY: &str = r#"jQuery112301083078708820121_1761565635682({"rc":0,"rt":6,"svr":181669432,"lt":1,"full":1,"dlmkts":"","data":{"total":86,"diff":[{"f1":2,"f2":35063.87,"f3":1.9,"f12":"BK0459","f13":90,"f14":"电子元件","f62":2672467968.0,"f66":2872657664.0,"f69":2.02,"f72":-200189696.0,"f75":-0.14,"f78":-1933110528.0,"f81":-1.36,"f84":-768382720.0,"f87":-0.54,"f124":1761550782,"f184":1.88,"f204":"胜宏科技","f205":"300476","f206":0},{"f1":2,"f2":18444.16,"f3":1.56,"f12":"BK0448","f13":90,"f14":"通信设备","f62":2602910976.0,"f66":3761489408.0,"f69":2.88,"f72":-1158578432.0,"f75":-0.89,"f78":-1900750336.0,"f81":-1.45,"f84":-711574272.0,"f87":-0.54,"f124":1761550782,"f184":1.99,"f204":"恒宝股份","f205":"002104","f206":0},{"f1":2,"f2":1987.6,"f3":1.54,"f12":"BK1037","f13":90,"f14":"消费电子","f62":2465783296.0,"f66":1903972096.0,"f69":1.94,"f72":561811200.0,"f75":0.57,"f78":-1500482304.0,"f81":-1.53,"f84":-998753792.0,"f87":-1.02,"f124":1761550782,"f184":2.52,"f204":"工业富联","f205":"601138","f206":1},{"f1":2,"f2":3109.46,"f3":2.39,"f12":"BK1027","f13":90,"f14":"小金属","f62":2056189696.0,"f66":2037394432.0,"f69":4.12,"f72":18795264.0,"f75":0.04,"f78":-770170368.0,"f81":-1.56,"f84":-1281168128.0,"f87":-2.59,"f124":1761550782,"f184":4.16,"f204":"厦门钨业","f205":"600549","f206":1},{"f1":2,"f2":2027.18,"f3":2.13,"f12":"BK1036","f13":90,"f14":"半导体","f62":1888555776.0,"f66":2031833856.0,"f69":0.83,"f72":-143278080.0,"f75":-0.06,"f78":-980680704.0,"f81":-0.4,"f84":-914381824.0,"f87":-0.38,"f124":1761550782,"f184":0.78,"f204":"兆易创新","f205":"603986","f206":1},{"f1":2,"f2":38790.99,"f3":0.83,"f12":"BK0465","f13":90,"f14":"化学制药","f62":1096540608.0,"f66":999148992.0,"f69":2.37,"f72":97391616.0,"f75":0.23,"f78":-981284352.0,"f81":-2.33,"f84":-143647744.0,"f87":-0.34,"f124":1761550782,"f184":2.6,"f204":"向日葵","f205":"300111","f206":0},{"f1":2,"f2":18347.21,"f3":1.36,"f12":"BK0428","f13":90,"f14":"电力行业","f62":1026856192.0,"f66":849184512.0,"f69":2.29,"f72":177671680.0,"f75":0.48,"f78":-1001579264.0,"f81":-2.7,"f84":-25276928.0,"f87":-0.07,"f124":1761550782,"f184":2.77,"f204":"中国核电","f205":"601985","f206":1},{"f1":2,"f2":9024.85,"f3":1.92,"f12":"BK0479","f13":90,"f14":"钢铁行业","f62":997690944.0,"f66":1004895040.0,"f69":5.74,"f72":-7204096.0,"f75":-0.04,"f78":-414445312.0,"f81":-2.37,"f84":-578170880.0,"f87":-3.3,"f124":1761550782,"f184":5.7,"f204":"新兴铸管","f205":"000778","f206":0},{"f1":2,"f2":33663.93,"f3":0.89,"f12":"BK0457","f13":90,"f14":"电网设备","f62":697530368.0,"f66":628551680.0,"f69":1.33,"f72":68978688.0,"f75":0.15,"f78":-144529664.0,"f81":-0.3,"f84":-603417088.0,"f87":-1.27,"f124":1761550782,"f184":1.47,"f204":"中国西电","f205":"601179","f206":1},{"f1":2,"f2":3500.02,"f3":0.93,"f12":"BK0910","f13":90,"f14":"专用设备","f62":645576192.0,"f66":684069888.0,"f69":0.86,"f72":-38493696.0,"f75":-0.05,"f78":-413467904.0,"f81":-0.52,"f84":-281076992.0,"f87":-0.35,"f124":1761550782,"f184":0.81,"f204":"英维克","f205":"002837","f206":0},{"f1":2,"f2":28302.66,"f3":0.58,"f12":"BK0425","f13":90,"f14":"工程建设","f62":616678496.0,"f66":506754144.0,"f69":1.8,"f72":109924352.0,"f75":0.39,"f78":-155837440.0,"f81":-0.55,"f84":-463444992.0,"f87":-1.65,"f124":1761550782,"f184":2.19,"f204":"中国电建","f205":"601669","f206":1},{"f1":2,"f2":1506.72,"f3":0.82,"f12":"BK0474","f13":90,"f14":"保险","f62":502335744.0,"f66":388679376.0,"f69":4.07,"f72":113656368.0,"f75":1.19,"f78":-406719424.0,"f81":-4.26,"f84":-95616336.0,"f87":-1.0,"f124":1761550782,"f184":5.26,"f204":"中国平安","f205":"601318","f206":1},{"f1":2,"f2":11688.06,"f3":1.27,"f12":"BK0484","f13":90,"f14":"贸易行业","f62":371387936.0,"f66":285093952.0,"f69":2.37,"f72":86293984.0,"f75":0.72,"f78":-119965136.0,"f81":-1.0,"f84":-258886784.0,"f87":-2.15,"f124":1761550782,"f184":3.09,"f204":"中电港","f205":"001287","f206":0},{"f1":2,"f2":47942.24,"f3":1.41,"f12":"BK0546","f13":90,"f14":"玻璃玻纤","f62":293145184.0,"f66":168121440.0,"f69":2.3,"f72":125023744.0,"f75":1.71,"f78":-94673024.0,"f81":-1.3,"f84":-179209440.0,"f87":-2.45,"f124":1761550782,"f184":4.01,"f204":"中材科技","f205":"002080","f206":0},{"f1":2,"f2":12567.57,"f3":0.51,"f12":"BK0539","f13":90,"f14":"综合行业","f62":284617232.0,"f66":321809104.0,"f69":4.11,"f72":-37191872.0,"f75":-0.47,"f78":-37467264.0,"f81":-0.48,"f84":-247149968.0,"f87":-3.15,"f124":1761550782,"f184":3.63,"f204":"东阳光","f205":"600673","f206":1},{"f1":2,"f2":12772.6,"f3":0.9,"f12":"BK0437","f13":90,"f14":"煤炭行业","f62":272668576.0,"f66":288082608.0,"f69":1.53,"f72":-15414032.0,"f75":-0.08,"f78":-38485504.0,"f81":-0.2,"f84":-234182912.0,"f87":-1.24,"f124":1761550782,"f184":1.45,"f204":"郑州煤电","f205":"600121","f206":1},{"f1":2,"f2":1087.13,"f3":2.34,"f12":"BK0729","f13":90,"f14":"船舶制造","f62":257691648.0,"f66":266805024.0,"f69":3.05,"f72":-9113376.0,"f75":-0.1,"f78":-195964128.0,"f81":-2.24,"f84":-61727520.0,"f87":-0.71,"f124":1761550782,"f184":2.94,"f204":"中国船舶","f205":"600150","f206":1},{"f1":2,"f2":2196.43,"f3":3.08,"f12":"BK1039","f13":90,"f14":"电子化学品","f62":255217344.0,"f66":585644992.0,"f69":2.33,"f72":-330427648.0,"f75":-1.31,"f78":-314919168.0,"f81":-1.25,"f84":59701760.0,"f87":0.24,"f124":1761550782,"f184":1.01,"f204":"晶瑞电材","f205":"300655","f206":0},{"f1":2,"f2":1350.48,"f3":0.97,"f12":"BK0730","f13":90,"f14":"农药兽药","f62":206520080.0,"f66":77003872.0,"f69":0.83,"f72":129516208.0,"f75":1.4,"f78":39617120.0,"f81":0.43,"f84":-255658768.0,"f87":-2.76,"f124":1761550782,"f184":2.23,"f204":"联化科技","f205":"002250","f206":0},{"f1":2,"f2":23845.93,"f3":1.06,"f12":"BK0478","f13":90,"f14":"有色金属","f62":189264384.0,"f66":111172096.0,"f69":0.18,"f72":78092288.0,"f75":0.12,"f78":443991040.0,"f81":0.71,"f84":-640158976.0,"f87":-1.02,"f124":1761550782,"f184":0.3,"f204":"紫金矿业","f205":"601899","f206":1},{"f1":2,"f2":15733.54,"f3":0.79,"f12":"BK0433","f13":90,"f14":"农牧饲渔","f62":184318400.0,"f66":299851392.0,"f69":1.79,"f72":-115532992.0,"f75":-0.69,"f78":-91829760.0,"f81":-0.55,"f84":-106748672.0,"f87":-0.64,"f124":1761550782,"f184":1.1,"f204":"牧原股份","f205":"002714","f206":0},{"f1":2,"f2":30942.83,"f3":1.0,"f12":"BK0538","f13":90,"f14":"化学制品","f62":141105904.0,"f66":212118000.0,"f69":0.49,"f72":-71012096.0,"f75":-0.16,"f78":-174632704.0,"f81":-0.4,"f84":47441152.0,"f87":0.11,"f124":1761550782,"f184":0.33,"f204":"天赐材料","f205":"002709","f206":0},{"f1":2,"f2":7908.95,"f3":0.17,"f12":"BK0422","f13":90,"f14":"物流行业","f62":134002704.0,"f66":189793424.0,"f69":1.76,"f72":-55790720.0,"f75":-0.52,"f78":-103790080.0,"f81":-0.96,"f84":-33989888.0,"f87":-0.32,"f124":1761550782,"f184":1.25,"f204":"胜通能源","f205":"001331","f206":0},{"f1":2,"f2":30530.19,"f3":0.97,"f12":"BK0424","f13":90,"f14":"水泥建材","f62":106185504.0,"f66":49538272.0,"f69":0.8,"f72":56647232.0,"f75":0.92,"f78":-48269104.0,"f81":-0.78,"f84":-57916384.0,"f87":-0.94,"f124":1761550782,"f184":1.72,"f204":"海螺水泥","f205":"600585","f206":1},{"f1":2,"f2":1285.58,"f3":1.16,"f12":"BK0727","f13":90,"f14":"医疗服务","f62":93195760.0,"f66":327629040.0,"f69":1.67,"f72":-234433280.0,"f75":-1.19,"f78":-302119168.0,"f81":-1.54,"f84":208923648.0,"f87":1.06,"f124":1761550782,"f184":0.47,"f204":"药明康德","f205":"603259","f206":1},{"f1":2,"f2":1024.65,"f3":0.29,"f12":"BK1041","f13":90,"f14":"医疗器械","f62":88950640.0,"f66":-221685904.0,"f69":-0.99,"f72":310636544.0,"f75":1.38,"f78":17168640.0,"f81":0.08,"f84":-114670592.0,"f87":-0.51,"f124":1761550782,"f184":0.4,"f204":"福瑞股份","f205":"300049","f206":0},{"f1":2,"f2":1128.72,"f3":1.07,"f12":"BK1016","f13":90,"f14":"汽车服务","f62":70827612.0,"f66":125612796.0,"f69":7.37,"f72":-54785184.0,"f75":-3.21,"f78":-76294032.0,"f81":-4.48,"f84":521312.0,"f87":0.03,"f124":1761550782,"f184":4.16,"f204":"漳州发展","f205":"000753","f206":0},{"f1":2,"f2":15298.11,"f3":-0.17,"f12":"BK0451","f13":90,"f14":"房地产开发","f62":58891120.0,"f66":-36992144.0,"f69":-0.16,"f72":95883264.0,"f75":0.41,"f78":-26480384.0,"f81":-0.11,"f84":-32410624.0,"f87":-0.14,"f124":1761550782,"f184":0.25,"f204":"首开股份","f205":"600376","f206":1},{"f1":2,"f2":1635.22,"f3":1.39,"f12":"BK0731","f13":90,"f14":"化肥行业","f62":46755136.0,"f66":212334640.0,"f69":2.25,"f72":-165579504.0,"f75":-1.76,"f78":-253192816.0,"f81":-2.68,"f84":206437680.0,"f87":2.19,"f124":1761550782,"f184":0.5,"f204":"盐湖股份","f205":"000792","f206":0},{"f1":2,"f2":483.59,"f3":0.49,"f12":"BK0734","f13":90,"f14":"珠宝首饰","f62":45087792.0,"f66":-30447968.0,"f69":-1.42,"f72":75535760.0,"f75":3.51,"f78":-2759760.0,"f81":-0.13,"f84":-42328016.0,"f87":-1.97,"f124":1761550782,"f184":2.1,"f204":"潮宏基","f205":"002345","f206":0},{"f1":2,"f2":693.44,"f3":0.33,"f12":"BK0738","f13":90,"f14":"多元金融","f62":41639344.0,"f66":86104544.0,"f69":1.26,"f72":-44465200.0,"f75":-0.65,"f78":-74005520.0,"f81":-1.08,"f84":32366160.0,"f87":0.47,"f124":1761550782,"f184":0.61,"f204":"中油资本","f205":"000617","f206":0},{"f1":2,"f2":715.47,"f3":2.17,"f12":"BK1015","f13":90,"f14":"能源金属","f62":36233600.0,"f66":38813056.0,"f69":0.16,"f72":-2579456.0,"f75":-0.01,"f78":-186657280.0,"f81":-0.78,"f84":150423552.0,"f87":0.63,"f124":1761550782,"f184":0.15,"f204":"华友钴业","f205":"603799","f206":1},{"f1":2,"f2":1759.77,"f3":0.83,"f12":"BK1020","f13":90,"f14":"非金属材料","f62":36116352.0,"f66":161883776.0,"f69":0.8,"f72":-125767424.0,"f75":-0.62,"f78":-47178752.0,"f81":-0.23,"f84":-10470656.0,"f87":-0.05,"f124":1761550782,"f184":0.18,"f204":"菲利华","f205":"300395","f206":0},{"f1":2,"f2":2632.39,"f3":0.34,"f12":"BK1030","f13":90,"f14":"电机","f62":27743792.0,"f66":182289472.0,"f69":1.05,"f72":-154545680.0,"f75":-0.89,"f78":-322093056.0,"f81":-1.85,"f84":232761344.0,"f87":1.34,"f124":1761550782,"f184":0.16,"f204":"卧龙电驱","f205":"600580","f206":1},{"f1":2,"f2":692.49,"f3":0.59,"f12":"BK0740","f13":90,"f14":"教育","f62":20278388.0,"f66":7033636.0,"f69":0.31,"f72":13244752.0,"f75":0.59,"f78":-39129168.0,"f81":-1.73,"f84":18850768.0,"f87":0.83,"f124":1761550782,"f184":0.9,"f204":"中公教育","f205":"002607","f206":0},{"f1":2,"f2":19150.0,"f3":0.8,"f12":"BK0454","f13":90,"f14":"塑料制品","f62":16523088.0,"f66":-34563472.0,"f69":-0.21,"f72":51086560.0,"f75":0.31,"f78":165054208.0,"f81":1.01,"f84":-211354368.0,"f87":-1.29,"f124":1761550782,"f184":0.1,"f204":"佛塑科技","f205":"000973","f206":0},{"f1":2,"f2":1235.4,"f3":0.4,"f12":"BK0733","f13":90,"f14":"包装材料","f62":12034057.0,"f66":471449.0,"f69":0.01,"f72":11562608.0,"f75":0.31,"f78":-27117552.0,"f81":-0.73,"f84":12477664.0,"f87":0.34,"f124":1761550782,"f184":0.33,"f204":"王子新材","f205":"002735","f206":0},{"f1":2,"f2":18673.43,"f3":0.44,"f12":"BK0482","f13":90,"f14":"商业百货","f62":-32728768.0,"f66":73189520.0,"f69":0.65,"f72":-105918288.0,"f75":-0.94,"f78":9347488.0,"f81":0.08,"f84":23381264.0,"f87":0.21,"f124":1761550782,"f184":-0.29,"f204":"国光连锁","f205":"605188","f206":1},{"f1":2,"f2":1128.47,"f3":0.61,"f12":"BK1042","f13":90,"f14":"医药商业","f62":-43339544.0,"f66":-4437864.0,"f69":-0.11,"f72":-38901680.0,"f75":-0.94,"f78":-34779520.0,"f81":-0.84,"f84":78119072.0,"f87":1.89,"f124":1761550782,"f184":-1.05,"f204":"九州通","f205":"600998","f206":1},{"f1":2,"f2":10602.94,"f3":0.23,"f12":"BK0470","f13":90,"f14":"造纸印刷","f62":-46049120.0,"f66":-80690000.0,"f69":-1.38,"f72":34640880.0,"f75":0.59,"f78":79723008.0,"f81":1.37,"f84":-42126336.0,"f87":-0.72,"f124":1761550782,"f184":-0.79,"f204":"中顺洁柔","f205":"002511","f206":0},{"f1":2,"f2":533.72,"f3":0.2,"f12":"BK0728","f13":90,"f14":"环保行业","f62":-67351584.0,"f66":-20234016.0,"f69":-0.1,"f72":-47117568.0,"f75":-0.24,"f78":142688768.0,"f81":0.72,"f84":-81471232.0,"f87":-0.41,"f124":1761550782,"f184":-0.34,"f204":"高能环境","f205":"603588","f206":1},{"f1":2,"f2":6204.4,"f3":0.2,"f12":"BK0464","f13":90,"f14":"石油行业","f62":-99684464.0,"f66":242880368.0,"f69":3.3,"f72":-342564832.0,"f75":-4.66,"f78":70292512.0,"f81":0.96,"f84":29391968.0,"f87":0.4,"f124":1761550782,"f184":-1.36,"f204":"中国石化","f205":"600028","f206":1},{"f1":2,"f2":1715.29,"f3":0.88,"f12":"BK1018","f13":90,"f14":"橡胶制品","f62":-117049728.0,"f66":-45177648.0,"f69":-0.6,"f72":-71872080.0,"f75":-0.95,"f78":-7609728.0,"f81":-0.1,"f84":86729680.0,"f87":1.15,"f124":1761550782,"f184":-1.55,"f204":"科创新源","f205":"300731","f206":0},{"f1":2,"f2":1223.82,"f3":-0.47,"f12":"BK1045","f13":90,"f14":"房地产服务","f62":-122941878.0,"f66":-69009094.0,"f69":-2.69,"f72":-53932784.0,"f75":-2.11,"f78":-15535600.0,"f81":-0.61,"f84":138477472.0,"f87":5.41,"f124":1761550782,"f184":-4.8,"f204":"我爱我家","f205":"000560","f206":0},{"f1":2,"f2":9115.6,"f3":0.01,"f12":"BK0427","f13":90,"f14":"公用事业","f62":-132585264.0,"f66":-82856112.0,"f69":-2.36,"f72":-49729152.0,"f75":-1.42,"f78":29656448.0,"f81":0.84,"f84":102928816.0,"f87":2.93,"f124":1761550782,"f184":-3.77,"f204":"海天股份","f205":"603759","f206":1},{"f1":2,"f2":16999.7,"f3":0.11,"f12":"BK0471","f13":90,"f14":"化纤行业","f62":-132674803.0,"f66":-112412947.0,"f69":-2.54,"f72":-20261856.0,"f75":-0.46,"f78":34904880.0,"f81":0.79,"f84":92093280.0,"f87":2.08,"f124":1761550782,"f184":-3.0,"f204":"恒力石化","f205":"600346","f206":1},{"f1":2,"f2":754.04,"f3":0.58,"f12":"BK0725","f13":90,"f14":"装修装饰","f62":-159712048.0,"f66":-180949088.0,"f69":-2.99,"f72":21237040.0,"f75":0.35,"f78":144701184.0,"f81":2.39,"f84":55498560.0,"f87":0.92,"f124":1761550782,"f184":-2.64,"f204":"中铁装配","f205":"300374","f206":0},{"f1":2,"f2":1880.31,"f3":0.25,"f12":"BK1028","f13":90,"f14":"燃气","f62":-159932336.0,"f66":-177324928.0,"f69":-2.51,"f72":17392592.0,"f75":0.25,"f78":-31213168.0,"f81":-0.44,"f84":182760304.0,"f87":2.59,"f124":1761550782,"f184":-2.27,"f204":"首华燃气","f205":"300483","f206":0},{"f1":2,"f2":11887.93,"f3":1.16,"f12":"BK0429","f13":90,"f14":"交运设备","f62":-173683152.0,"f66":-154603040.0,"f69":-1.47,"f72":-19080112.0,"f75":-0.18,"f78":75509008.0,"f81":0.72,"f84":94802976.0,"f87":0.9,"f124":1761550782,"f184":-1.65,"f204":"宗申动力","f205":"001696","f206":0},{"f1":2,"f2":5272.9,"f3":0.05,"f12":"BK0420","f13":90,"f14":"航空机场","f62":-186943328.0,"f66":-205286688.0,"f69":-4.64,"f72":18343360.0,"f75":0.41,"f78":56613232.0,"f81":1.28,"f84":130330080.0,"f87":2.94,"f124":1761550782,"f184":-4.22,"f204":"南方航空","f205":"600029","f206":1}]}});"#;
+
+        let raw_moneyflow: RawMoneyflowEastmoney =
+            parse_raw_eastmoney(RAW_MONEYFLOW_SECTOR_EASTMONEY).unwrap();
+        let tracked: Vec<String> = repo
+            .get_tracked_sectors()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.ticker)
+            .collect();
+        let moneyflow = create_moneyflow(raw_moneyflow, &tracked);
+
+        let total = 40;
+        let mut moneyflow_batch: Vec<Vec<MoneyflowEastmoney>> = vec![];
+        for day in 1..total {
+            let mut mf_copy = moneyflow.clone();
+            for x in mf_copy.iter_mut() {
+                let mut new_dt = NaiveDate::parse_from_str(&x.trade_date, "%Y-%m-%d").unwrap();
+                new_dt += Duration::days(day);
+                x.trade_date = new_dt.to_string();
+            }
+            moneyflow_batch.push(mf_copy);
+        }
+
+        for mf in &moneyflow_batch {
+            repo.create_mf_sector(mf).await.unwrap();
+        }
+        // Re-running a day updates its rows in place.
+        repo.create_mf_sector(moneyflow_batch.last().unwrap())
+            .await
+            .unwrap();
 
         let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) from moneyflow_sector")
             .fetch_one(&pool)
             .await
             .unwrap();
 
-        assert_eq!(count, 930);
+        // No row cap, 31 tracked sectors over 39 days.
+        assert_eq!(count, 1209);
 
         let ticker_excluded = "90.BK0420";
         let records = repo.get_mf_sector().await.unwrap();
 
-        assert_eq!(records.len(), 930);
+        assert_eq!(records.len(), 1209);
+        assert!(
+            records
+                .windows(2)
+                .all(|w| w[0].trade_date <= w[1].trade_date)
+        );
+
+        let cutoff = &moneyflow_batch[9][0].trade_date;
+        let deleted = repo.delete_mf_sector_before(cutoff).await.unwrap();
+        assert_eq!(deleted, 9 * 31);
+        assert_eq!(repo.get_mf_sector().await.unwrap().len(), 30 * 31);
 
         let filtered: Vec<MoneyflowEastmoney> = records
             .into_iter()
@@ -534,4 +1737,735 @@ mod tests {
 
         assert_eq!(filtered.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_signals_history() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let signal = |ticker: &str, bar_date: i64, kdj_k: f64| Signal {
+            ticker: ticker.to_string(),
+            period: SignalPeriod::Day,
+            bar_date,
+            kdj_k,
+            kdj_d: 50.0,
+            boll_dist: 1.0,
+        };
+
+        for (date, k) in [(20251103, 10.0), (20251104, 20.0), (20251105, 30.0)] {
+            repo.create_signal(signal("1.600635", date, k))
+                .await
+                .unwrap();
+        }
+        repo.create_signal(signal("90.BK0475", 20251105, 40.0))
+            .await
+            .unwrap();
+
+        // Re-run on the same bar overwrites.
+        repo.create_signal(signal("1.600635", 20251105, 35.0))
+            .await
+            .unwrap();
+
+        let series = repo
+            .get_signal_series(&ticker("1.600635"), SignalPeriod::Day, None, None)
+            .await
+            .unwrap();
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[2].kdj_k, 35.0);
+
+        let series = repo
+            .get_signal_series(
+                &ticker("1.600635"),
+                SignalPeriod::Day,
+                Some(20251104),
+                Some(20251104),
+            )
+            .await
+            .unwrap();
+        assert_eq!(series.len(), 1);
+
+        let stocks = repo
+            .get_signals_latest(SignalPeriod::Day, false)
+            .await
+            .unwrap();
+        assert_eq!(stocks.len(), 1);
+        assert_eq!(stocks[0].bar_date, 20251105);
+
+        let sectors = repo
+            .get_signals_latest(SignalPeriod::Day, true)
+            .await
+            .unwrap();
+        assert_eq!(sectors.len(), 1);
+        assert_eq!(sectors[0].ticker, "90.BK0475");
+
+        let weekly = repo
+            .get_signals_latest(SignalPeriod::Week, false)
+            .await
+            .unwrap();
+        assert!(weekly.is_empty());
+
+        let recent = repo
+            .get_signals_recent(SignalPeriod::Day, false, 2)
+            .await
+            .unwrap();
+        let dates: Vec<i64> = recent.iter().map(|s| s.bar_date).collect();
+        assert_eq!(dates, vec![20251104, 20251105]);
+
+        let tail = repo
+            .get_signals_tail(&ticker("1.600635"), SignalPeriod::Day, 2)
+            .await
+            .unwrap();
+        let dates: Vec<i64> = tail.iter().map(|s| s.bar_date).collect();
+        assert_eq!(dates, vec![20251104, 20251105]);
+
+        repo.delete_signals_before(&ticker("1.600635"), SignalPeriod::Day, 20251105)
+            .await
+            .unwrap();
+        let series = repo
+            .get_signal_series(&ticker("1.600635"), SignalPeriod::Day, None, None)
+            .await
+            .unwrap();
+        assert_eq!(series.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_weekly_signal_per_week() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let signal = |bar_date: i64, kdj_k: f64| Signal {
+            ticker: "1.600635".to_string(),
+            period: SignalPeriod::Week,
+            bar_date,
+            kdj_k,
+            kdj_d: 50.0,
+            boll_dist: 1.0,
+        };
+
+        // Friday of the prior week, then Tuesday and Wednesday of a week in progress.
+        for (date, k) in [(20251031, 10.0), (20251104, 20.0), (20251105, 30.0)] {
+            repo.create_signal(signal(date, k)).await.unwrap();
+        }
+        // Daily signals of the same week are untouched.
+        repo.create_signal(Signal {
+            period: SignalPeriod::Day,
+            ..signal(20251104, 40.0)
+        })
+        .await
+        .unwrap();
+
+        let weekly = repo
+            .get_signal_series(&ticker("1.600635"), SignalPeriod::Week, None, None)
+            .await
+            .unwrap();
+        let dates: Vec<(i64, f64)> = weekly.iter().map(|s| (s.bar_date, s.kdj_k)).collect();
+        assert_eq!(dates, vec![(20251031, 10.0), (20251105, 30.0)]);
+
+        let daily = repo
+            .get_signal_series(&ticker("1.600635"), SignalPeriod::Day, None, None)
+            .await
+            .unwrap();
+        assert_eq!(daily.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_screener_rules() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        repo.create_screener_rule("oversold", "kdj_k < 20", None, false)
+            .await
+            .unwrap();
+        repo.create_screener_rule("oversold", "kdj_k < 10", Some("kdj_k"), true)
+            .await
+            .unwrap();
+
+        let rules = repo.get_screener_rules().await.unwrap();
+        assert_eq!(rules.len(), 1);
+
+        let rule = repo.get_screener_rule("oversold").await.unwrap().unwrap();
+        assert_eq!(rule.expression, "kdj_k < 10");
+        assert_eq!(rule.rank_by.as_deref(), Some("kdj_k"));
+        assert!(rule.descending);
+
+        repo.delete_screener_rule("oversold").await.unwrap();
+        assert!(repo.get_screener_rule("oversold").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_backtest_runs() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let klines = generate_sequential_klines(60, "1.600635", 20250101);
+        let strategy = StrategyConfig::default();
+        let config = BacktestConfig::default();
+        let report = run_backtest(&klines, strategy.build().as_mut(), &config);
+
+        let id = repo
+            .create_backtest_run(&ticker("1.600635"), &strategy, &config, &report, None)
+            .await
+            .unwrap();
+
+        let runs = repo
+            .get_backtest_runs(Some(&ticker("1.600635")))
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].id, id);
+        assert_eq!(runs[0].strategy, strategy);
+        assert!(
+            repo.get_backtest_runs(Some(&ticker("1.688981")))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(repo.get_backtest_runs(None).await.unwrap().len(), 1);
+
+        let stored = repo.get_backtest_report(id).await.unwrap().unwrap();
+        assert_eq!(stored.equity.len(), 60);
+        assert_eq!(stored.metrics, report.metrics);
+        assert!(repo.get_backtest_report(id + 1).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_optimisations() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let strategy = StrategyConfig::default();
+        let space = ParamSpace::from([(
+            "buy_below".to_string(),
+            ParamRange {
+                start: 10.0,
+                end: 30.0,
+                step: 10.0,
+            },
+        )]);
+        let id = repo
+            .create_optimisation(
+                &ticker("1.600635"),
+                OptimisationMode::Search,
+                &strategy,
+                &space,
+                &SearchMethod::Grid,
+                Objective::Sharpe,
+            )
+            .await
+            .unwrap();
+
+        let stored = repo.get_optimisation(id).await.unwrap().unwrap();
+        assert_eq!(stored.mode, OptimisationMode::Search);
+        assert_eq!(stored.space.len(), 1);
+        assert_eq!(repo.get_optimisations().await.unwrap().len(), 1);
+
+        // Only runs linked to the optimisation are its trials.
+        let klines = generate_sequential_klines(60, "1.600635", 20250101);
+        let config = BacktestConfig::default();
+        let report = run_backtest(&klines, strategy.build().as_mut(), &config);
+        repo.create_backtest_run(&ticker("1.600635"), &strategy, &config, &report, Some(id))
+            .await
+            .unwrap();
+        repo.create_backtest_run(&ticker("1.600635"), &strategy, &config, &report, None)
+            .await
+            .unwrap();
+        assert_eq!(repo.get_optimisation_runs(id).await.unwrap().len(), 1);
+
+        assert!(repo.get_walk_forward_report(id).await.unwrap().is_none());
+        let wf = walk_forward(&klines, &[strategy], 30, 10, Objective::Sharpe, &config);
+        repo.set_walk_forward_report(id, &wf).await.unwrap();
+        let stored = repo.get_walk_forward_report(id).await.unwrap().unwrap();
+        assert_eq!(stored.windows.len(), wf.windows.len());
+    }
+
+    #[tokio::test]
+    async fn test_watchlists() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let id = repo.create_watchlist("banks", None).await.unwrap();
+        assert!(repo.create_watchlist("banks", None).await.is_err());
+
+        let tickers: Vec<Ticker> = ["1.600036", "0.000001", "1.601398"]
+            .into_iter()
+            .map(ticker)
+            .collect();
+        repo.add_watchlist_tickers(id, &tickers).await.unwrap();
+        // Existing tickers keep their place.
+        repo.add_watchlist_tickers(id, &[ticker("1.600036"), ticker("1.601988")])
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_watchlist_tickers(id).await.unwrap(),
+            vec!["1.600036", "0.000001", "1.601398", "1.601988"]
+        );
+
+        repo.reorder_watchlist(id, &[ticker("1.601988"), ticker("1.601398")])
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_watchlist_tickers(id).await.unwrap(),
+            vec!["1.601988", "1.601398", "1.600036", "0.000001"]
+        );
+
+        assert!(
+            repo.remove_watchlist_ticker(id, &ticker("0.000001"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !repo
+                .remove_watchlist_ticker(id, &ticker("0.000001"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            repo.set_watchlist_item_notes(id, &ticker("1.600036"), Some("CMB"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            repo.update_watchlist(id, Some("big banks"), Some("state owned"))
+                .await
+                .unwrap()
+        );
+
+        let watchlist = repo.get_watchlist(id).await.unwrap().unwrap();
+        assert_eq!(watchlist.name, "big banks");
+        assert_eq!(watchlist.notes.as_deref(), Some("state owned"));
+        assert_eq!(watchlist.items.len(), 3);
+        assert_eq!(watchlist.items[2].notes.as_deref(), Some("CMB"));
+        assert_eq!(repo.get_watchlists().await.unwrap().len(), 1);
+
+        assert!(repo.delete_watchlist(id).await.unwrap());
+        assert!(repo.get_watchlist(id).await.unwrap().is_none());
+        assert!(repo.get_watchlist_tickers(id).await.unwrap().is_empty());
+        assert!(!repo.update_watchlist(id, Some("x"), None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_transactions() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let mut tx = Transaction {
+            id: 0,
+            account: "main".to_string(),
+            trade_date: 20250103,
+            kind: TransactionKind::Buy,
+            ticker: Some("116.00700".to_string()),
+            quantity: 100.0,
+            price: 400.0,
+            amount: 0.0,
+            fee: 10.0,
+            currency: Currency::Hkd,
+            notes: None,
+            created_at: String::new(),
+        };
+        let first = repo.create_transaction(&tx).await.unwrap();
+        tx.trade_date = 20250102;
+        let second = repo.create_transaction(&tx).await.unwrap();
+
+        let txs = repo.get_transactions("main").await.unwrap();
+        assert_eq!(
+            txs.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![second, first]
+        );
+        assert_eq!(txs[0].currency, Currency::Hkd);
+        assert_eq!(txs[0].kind, TransactionKind::Buy);
+        assert_eq!(repo.get_accounts().await.unwrap(), vec!["main"]);
+
+        assert!(repo.delete_transaction(first).await.unwrap());
+        assert!(repo.get_transaction(first).await.unwrap().is_none());
+        assert_eq!(repo.get_transactions("main").await.unwrap().len(), 1);
+
+        // Seeded by the migration.
+        assert_eq!(repo.get_fx_rates().await.unwrap().len(), 3);
+        repo.set_fx_rate(Currency::Usd, 7.3).await.unwrap();
+        let rates = repo.get_fx_rates().await.unwrap();
+        let usd = rates.iter().find(|r| r.currency == Currency::Usd).unwrap();
+        assert_eq!(usd.cny_rate, 7.3);
+    }
+
+    #[tokio::test]
+    async fn test_risk_reports() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let positions = vec![RiskPosition {
+            ticker: "1.600635".to_string(),
+            market_value: 1_000.0,
+            closes: vec![(20250101, 10.0), (20250102, 10.5), (20250103, 10.2)],
+            sectors: vec![],
+        }];
+        let report = compute_risk(&positions, None, &RiskParams::default());
+
+        assert!(repo.get_risk_report("main").await.unwrap().is_none());
+        repo.create_risk_report("main", Currency::Cny, &report)
+            .await
+            .unwrap();
+        let id = repo
+            .create_risk_report("main", Currency::Usd, &report)
+            .await
+            .unwrap();
+
+        let latest = repo.get_risk_report("main").await.unwrap().unwrap();
+        assert_eq!(latest.id, id);
+        assert_eq!(latest.base_currency, Currency::Usd);
+        assert_eq!(latest.report, report);
+    }
+
+    #[tokio::test]
+    async fn test_sector_members() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let member = |sector: &str, ticker: &str| SectorMember {
+            sector: sector.to_string(),
+            ticker: ticker.to_string(),
+            realname: ticker.to_string(),
+            updated_at: String::new(),
+        };
+        repo.set_sector_members(
+            &ticker("90.BK1036"),
+            &[
+                member("90.BK1036", "1.600584"),
+                member("90.BK1036", "0.002371"),
+            ],
+        )
+        .await
+        .unwrap();
+        repo.set_sector_members(&ticker("90.BK0459"), &[member("90.BK0459", "1.600584")])
+            .await
+            .unwrap();
+
+        let members = repo.get_sector_members(&ticker("90.BK1036")).await.unwrap();
+        assert_eq!(
+            members
+                .iter()
+                .map(|m| m.ticker.as_str())
+                .collect::<Vec<_>>(),
+            vec!["0.002371", "1.600584"]
+        );
+        let sectors = repo.get_stock_sectors(&ticker("1.600584")).await.unwrap();
+        assert_eq!(
+            sectors
+                .iter()
+                .map(|m| m.sector.as_str())
+                .collect::<Vec<_>>(),
+            vec!["90.BK0459", "90.BK1036"]
+        );
+
+        // A refresh replaces the previous constituents.
+        repo.set_sector_members(&ticker("90.BK1036"), &[member("90.BK1036", "0.002371")])
+            .await
+            .unwrap();
+        let membership = repo.get_sector_membership().await.unwrap();
+        assert_eq!(membership["1.600584"], vec!["90.BK0459"]);
+        assert_eq!(membership["0.002371"], vec!["90.BK1036"]);
+    }
+
+    #[tokio::test]
+    async fn test_tracked_sectors() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        // Seeded by the migration.
+        let seeded = repo.get_tracked_sectors().await.unwrap();
+        assert_eq!(seeded.len(), 55);
+        assert_eq!(seeded[0].ticker, "90.BK0475");
+        assert_eq!(seeded[0].realname, "银行");
+
+        assert!(
+            repo.add_tracked_sector(&ticker("90.BK0420"), "航空机场")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !repo
+                .add_tracked_sector(&ticker("90.BK0420"), "航空机场")
+                .await
+                .unwrap()
+        );
+        let sectors = repo.get_tracked_sectors().await.unwrap();
+        assert_eq!(sectors.len(), 56);
+        assert_eq!(sectors.last().unwrap().ticker, "90.BK0420");
+        assert_eq!(sectors.last().unwrap().position, 55);
+
+        assert!(
+            repo.delete_tracked_sector(&ticker("90.BK0475"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !repo
+                .delete_tracked_sector(&ticker("90.BK0475"))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            repo.get_tracked_sectors().await.unwrap()[0].ticker,
+            "90.BK1036"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mf_stock() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let flow = |trade_date: &str, lead_value: f64| MoneyflowStock {
+            trade_date: trade_date.to_string(),
+            ticker: "1.600635".to_string(),
+            lead_value,
+            lead_share: 1.0,
+            super_value: lead_value,
+            super_share: 1.0,
+            large_value: 0.0,
+            large_share: 0.0,
+            mid_value: 0.0,
+            mid_share: 0.0,
+            small_value: -lead_value,
+            small_share: -1.0,
+            close: 5.0,
+            change_pct: 0.0,
+        };
+        repo.create_mf_stock(&[flow("2025-11-05", 2.0), flow("2025-11-04", 1.0)])
+            .await
+            .unwrap();
+        // A re-crawl overlaps the stored days.
+        repo.create_mf_stock(&[flow("2025-11-05", 3.0), flow("2025-11-06", 4.0)])
+            .await
+            .unwrap();
+
+        let flows = repo.get_mf_stock(&ticker("1.600635"), None).await.unwrap();
+        assert_eq!(
+            flows.iter().map(|f| f.lead_value).collect::<Vec<_>>(),
+            vec![1.0, 3.0, 4.0]
+        );
+        let recent = repo
+            .get_mf_stock(&ticker("1.600635"), Some("2025-11-05"))
+            .await
+            .unwrap();
+        assert_eq!(recent.len(), 2);
+        assert!(
+            repo.get_mf_stock(&ticker("1.600000"), None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert_eq!(repo.delete_mf_stock_before("2025-11-05").await.unwrap(), 1);
+        assert_eq!(
+            repo.get_mf_stock(&ticker("1.600635"), None)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strength_signals() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let signal = |ticker: &str, bar_date: i64, rating: Option<i64>| StrengthSignal {
+            ticker: ticker.to_string(),
+            bar_date,
+            benchmark: "1.000300".to_string(),
+            sector: Some("90.BK1036".to_string()),
+            ret_1w: Some(0.01),
+            ret_1m: Some(0.05),
+            ret_3m: None,
+            ret_6m: None,
+            rs_1w: Some(1.01),
+            rs_1m: Some(1.02),
+            rs_3m: None,
+            rs_6m: None,
+            sector_rs_1w: Some(0.99),
+            sector_rs_1m: None,
+            sector_rs_3m: None,
+            sector_rs_6m: None,
+            score: rating.map(|r| r as f64 / 100.0),
+            rating,
+        };
+
+        repo.create_strength_signals(&[
+            signal("1.600635", 20251105, Some(10)),
+            signal("1.600000", 20251105, Some(50)),
+        ])
+        .await
+        .unwrap();
+        repo.create_strength_signals(&[
+            signal("1.600635", 20251106, Some(90)),
+            signal("1.600000", 20251106, None),
+        ])
+        .await
+        .unwrap();
+
+        let latest = repo.get_strength_latest().await.unwrap();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0], signal("1.600635", 20251106, Some(90)));
+        assert_eq!(latest[1].rating, None);
+
+        // Re-runs replace the values of a date.
+        repo.create_strength_signals(&[signal("1.600635", 20251106, Some(80))])
+            .await
+            .unwrap();
+        let series = repo.get_strength_series(&ticker("1.600635")).await.unwrap();
+        assert_eq!(
+            series.iter().map(|s| s.rating).collect::<Vec<_>>(),
+            vec![Some(10), Some(80)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fundamentals_snapshots() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let stock = |pe: f64| Stock {
+            ticker: "1.600635".to_string(),
+            realname: "大众公用".to_string(),
+            market: 1,
+            total_cap: Some(1e10),
+            pe: Some(pe),
+            pb: Some(1.2),
+            revenue: None,
+            net: None,
+            margin: None,
+            debt: None,
+        };
+
+        // Refreshing a stock replaces its row.
+        repo.create_stock(stock(10.0)).await.unwrap();
+        repo.create_stock(stock(12.0)).await.unwrap();
+        assert_eq!(
+            repo.get_stock(&ticker("1.600635")).await.unwrap().pe,
+            Some(12.0)
+        );
+
+        repo.create_fundamentals_snapshot(&FundamentalsSnapshot::new(&stock(10.0), "2025-11-05"))
+            .await
+            .unwrap();
+        repo.create_fundamentals_snapshot(&FundamentalsSnapshot::new(&stock(11.0), "2025-11-06"))
+            .await
+            .unwrap();
+        repo.create_fundamentals_snapshot(&FundamentalsSnapshot::new(&stock(12.0), "2025-11-06"))
+            .await
+            .unwrap();
+
+        let series = repo
+            .get_fundamentals(&ticker("1.600635"), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            series.iter().map(|s| s.pe).collect::<Vec<_>>(),
+            vec![Some(10.0), Some(12.0)]
+        );
+        let recent = repo
+            .get_fundamentals(&ticker("1.600635"), Some("2025-11-06"))
+            .await
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+
+        repo.delete_stocks(&[ticker("1.600635")]).await.unwrap();
+        assert!(
+            repo.get_fundamentals(&ticker("1.600635"), None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stock_isins() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let tesla: Isin = "US88160R1014".parse().unwrap();
+        repo.create_stock(Stock {
+            ticker: "105.TSLA".to_string(),
+            realname: "特斯拉".to_string(),
+            market: 105,
+            total_cap: None,
+            pe: None,
+            pb: None,
+            revenue: None,
+            net: None,
+            margin: None,
+            debt: None,
+        })
+        .await
+        .unwrap();
+        repo.create_stock_isin(&ticker("105.TSLA"), &tesla)
+            .await
+            .unwrap();
+        // Links of tickers that are not stored stocks do not resolve.
+        repo.create_stock_isin(&ticker("106.TSLA"), &tesla)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.get_isin_tickers(&tesla).await.unwrap(),
+            vec![ticker("105.TSLA")]
+        );
+
+        repo.delete_stocks(&[ticker("105.TSLA")]).await.unwrap();
+        assert!(repo.get_isin_tickers(&tesla).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_quote_snapshots() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteDomainRepository::new(pool.clone());
+
+        let quote = |ticker: &str, quoted_at: i64, last: f64| Quote {
+            ticker: ticker.to_string(),
+            realname: ticker.to_string(),
+            last: Some(last),
+            bid: Some(last - 0.01),
+            ask: Some(last),
+            volume: Some(1000.0),
+            change_pct: None,
+            quoted_at,
+        };
+
+        repo.create_quote_snapshots(&[
+            quote("1.600635", 100, 5.0),
+            quote("1.600635", 160, 5.1),
+            quote("0.000001", 160, 11.0),
+        ])
+        .await
+        .unwrap();
+        // The same exchange quote is stored once.
+        repo.create_quote_snapshots(&[quote("1.600635", 160, 5.2)])
+            .await
+            .unwrap();
+
+        let all = repo
+            .get_quote_snapshots(&ticker("1.600635"), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            all,
+            vec![quote("1.600635", 100, 5.0), quote("1.600635", 160, 5.2)]
+        );
+        let since = repo
+            .get_quote_snapshots(&ticker("1.600635"), Some(150))
+            .await
+            .unwrap();
+        assert_eq!(since.len(), 1);
+
+        let deleted = repo.delete_quote_snapshots_before(150).await.unwrap();
+        assert_eq!(deleted, 1);
+        assert_eq!(
+            repo.get_quote_snapshots(&ticker("0.000001"), None)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
 }
@@ -0,0 +1,300 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use crate::domain::{
+    model::{
+        BalanceSheet, CashflowStatement, FinancialRatios, FinancialStatements, IncomeStatement,
+    },
+    repository::FinancialRepository,
+    service_market::Ticker,
+};
+
+#[derive(Clone)]
+pub struct SqliteFinancialRepository {
+    pub pool: SqlitePool,
+}
+
+impl SqliteFinancialRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl FinancialRepository for SqliteFinancialRepository {
+    async fn create_financials(
+        &self,
+        statements: &FinancialStatements,
+        ratios: &[FinancialRatios],
+    ) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for s in statements.income.iter() {
+            sqlx::query(
+                r#"
+                INSERT INTO income_statements (ticker, period, report_type, revenue, operating_cost, operating_profit, total_profit, net_profit, parent_net_profit, eps)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (ticker, period, report_type) DO UPDATE SET
+                    revenue = excluded.revenue,
+                    operating_cost = excluded.operating_cost,
+                    operating_profit = excluded.operating_profit,
+                    total_profit = excluded.total_profit,
+                    net_profit = excluded.net_profit,
+                    parent_net_profit = excluded.parent_net_profit,
+                    eps = excluded.eps
+            "#,
+            )
+            .bind(&s.ticker)
+            .bind(&s.period)
+            .bind(s.report_type)
+            .bind(s.revenue)
+            .bind(s.operating_cost)
+            .bind(s.operating_profit)
+            .bind(s.total_profit)
+            .bind(s.net_profit)
+            .bind(s.parent_net_profit)
+            .bind(s.eps)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for s in statements.balance.iter() {
+            sqlx::query(
+                r#"
+                INSERT INTO balance_sheets (ticker, period, report_type, total_assets, total_liabilities, total_equity, parent_equity, cash, current_assets, current_liabilities)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (ticker, period, report_type) DO UPDATE SET
+                    total_assets = excluded.total_assets,
+                    total_liabilities = excluded.total_liabilities,
+                    total_equity = excluded.total_equity,
+                    parent_equity = excluded.parent_equity,
+                    cash = excluded.cash,
+                    current_assets = excluded.current_assets,
+                    current_liabilities = excluded.current_liabilities
+            "#,
+            )
+            .bind(&s.ticker)
+            .bind(&s.period)
+            .bind(s.report_type)
+            .bind(s.total_assets)
+            .bind(s.total_liabilities)
+            .bind(s.total_equity)
+            .bind(s.parent_equity)
+            .bind(s.cash)
+            .bind(s.current_assets)
+            .bind(s.current_liabilities)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for s in statements.cashflow.iter() {
+            sqlx::query(
+                r#"
+                INSERT INTO cashflow_statements (ticker, period, report_type, operating_cf, investing_cf, financing_cf, capex)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (ticker, period, report_type) DO UPDATE SET
+                    operating_cf = excluded.operating_cf,
+                    investing_cf = excluded.investing_cf,
+                    financing_cf = excluded.financing_cf,
+                    capex = excluded.capex
+            "#,
+            )
+            .bind(&s.ticker)
+            .bind(&s.period)
+            .bind(s.report_type)
+            .bind(s.operating_cf)
+            .bind(s.investing_cf)
+            .bind(s.financing_cf)
+            .bind(s.capex)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for r in ratios.iter() {
+            sqlx::query(
+                r#"
+                INSERT INTO financial_ratios (ticker, period, report_type, roe, roa, gross_margin, fcf, debt_to_equity, revenue_yoy, net_profit_yoy)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (ticker, period, report_type) DO UPDATE SET
+                    roe = excluded.roe,
+                    roa = excluded.roa,
+                    gross_margin = excluded.gross_margin,
+                    fcf = excluded.fcf,
+                    debt_to_equity = excluded.debt_to_equity,
+                    revenue_yoy = excluded.revenue_yoy,
+                    net_profit_yoy = excluded.net_profit_yoy
+            "#,
+            )
+            .bind(&r.ticker)
+            .bind(&r.period)
+            .bind(r.report_type)
+            .bind(r.roe)
+            .bind(r.roa)
+            .bind(r.gross_margin)
+            .bind(r.fcf)
+            .bind(r.debt_to_equity)
+            .bind(r.revenue_yoy)
+            .bind(r.net_profit_yoy)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_financials(&self, ticker: &Ticker) -> Result<FinancialStatements, anyhow::Error> {
+        let income = sqlx::query_as::<_, IncomeStatement>(
+            "SELECT * FROM income_statements WHERE ticker = ? ORDER BY period",
+        )
+        .bind(ticker)
+        .fetch_all(&self.pool)
+        .await?;
+        let balance = sqlx::query_as::<_, BalanceSheet>(
+            "SELECT * FROM balance_sheets WHERE ticker = ? ORDER BY period",
+        )
+        .bind(ticker)
+        .fetch_all(&self.pool)
+        .await?;
+        let cashflow = sqlx::query_as::<_, CashflowStatement>(
+            "SELECT * FROM cashflow_statements WHERE ticker = ? ORDER BY period",
+        )
+        .bind(ticker)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(FinancialStatements {
+            income,
+            balance,
+            cashflow,
+        })
+    }
+
+    async fn get_financial_ratios(
+        &self,
+        ticker: &Ticker,
+    ) -> Result<Vec<FinancialRatios>, anyhow::Error> {
+        let ratios = sqlx::query_as::<_, FinancialRatios>(
+            "SELECT * FROM financial_ratios WHERE ticker = ? ORDER BY period",
+        )
+        .bind(ticker)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ratios)
+    }
+
+    async fn get_financial_ratios_latest(&self) -> Result<Vec<FinancialRatios>, anyhow::Error> {
+        let ratios = sqlx::query_as::<_, FinancialRatios>(
+            r#"
+            SELECT r.*
+            FROM financial_ratios r
+            JOIN (
+                SELECT ticker, MAX(period) AS period
+                FROM financial_ratios
+                GROUP BY ticker
+            ) latest ON r.ticker = latest.ticker AND r.period = latest.period
+        "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ratios)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use crate::{
+        domain::{
+            model::{FinancialStatements, IncomeStatement, ReportType},
+            repository::FinancialRepository,
+            service_financials::compute_ratios,
+        },
+        infra::storage::repo_financial_sqlite::SqliteFinancialRepository,
+    };
+
+    async fn setup_test_db() -> Result<SqlitePool, sqlx::Error> {
+        let database_url = "sqlite::memory:";
+        let pool = SqlitePoolOptions::new().connect(database_url).await?;
+        sqlx::migrate!("./migrations").run(&pool).await?;
+        Ok(pool)
+    }
+
+    fn income(ticker: &str, period: &str, revenue: f64) -> IncomeStatement {
+        IncomeStatement {
+            ticker: ticker.to_string(),
+            period: period.to_string(),
+            report_type: ReportType::from_period(period).unwrap(),
+            revenue: Some(revenue),
+            operating_cost: Some(revenue / 2.0),
+            operating_profit: None,
+            total_profit: None,
+            net_profit: None,
+            parent_net_profit: None,
+            eps: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_financials() {
+        let pool = setup_test_db().await.unwrap();
+        let repo = SqliteFinancialRepository::new(pool.clone());
+
+        let statements = FinancialStatements {
+            income: vec![
+                income("1.600635", "2024-12-31", 100.0),
+                income("1.600635", "2025-03-31", 30.0),
+            ],
+            ..Default::default()
+        };
+        repo.create_financials(&statements, &compute_ratios(&statements))
+            .await
+            .unwrap();
+
+        // Restated figures replace the stored period.
+        let restated = FinancialStatements {
+            income: vec![income("1.600635", "2025-03-31", 40.0)],
+            ..Default::default()
+        };
+        repo.create_financials(&restated, &compute_ratios(&restated))
+            .await
+            .unwrap();
+        let other = FinancialStatements {
+            income: vec![income("0.000001", "2024-12-31", 10.0)],
+            ..Default::default()
+        };
+        repo.create_financials(&other, &compute_ratios(&other))
+            .await
+            .unwrap();
+
+        let stored = repo
+            .get_financials(&"1.600635".parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(stored.income.len(), 2);
+        assert_eq!(stored.income[1].revenue, Some(40.0));
+        assert_eq!(stored.income[1].report_type, ReportType::Q1);
+        assert!(stored.balance.is_empty());
+
+        let ratios = repo
+            .get_financial_ratios(&"1.600635".parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(ratios.len(), 2);
+        assert_eq!(ratios[1].gross_margin, Some(0.5));
+
+        let mut latest = repo.get_financial_ratios_latest().await.unwrap();
+        latest.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+        assert_eq!(
+            latest
+                .iter()
+                .map(|r| (r.ticker.as_str(), r.period.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("0.000001", "2024-12-31"), ("1.600635", "2025-03-31")]
+        );
+    }
+}
@@ -161,7 +161,6 @@ export interface components {
             log_timestamp: string;
         };
         MoneyflowEastmoney: {
-            date_time: string;
             /** Format: double */
             large_share: number;
             /** Format: double */
@@ -184,6 +183,8 @@ export interface components {
             /** Format: double */
             super_value: number;
             ticker: string;
+            /** @description Shanghai trading date, YYYY-MM-DD. */
+            trade_date: string;
         };
         Signal: {
             /** Format: double */